@@ -0,0 +1,108 @@
+//! ABI stabile tra l'ambiente di fault injection e i target caricati come plugin.
+//!
+//! Un plugin è una libreria `cdylib` che esporta il simbolo [`ENTRY_SYMBOL`]: una funzione
+//! `extern "C"` che restituisce un puntatore a un [`PluginDescriptor`] statico. Tutti i tipi
+//! che attraversano il confine sono `#[repr(C)]` o interi, quindi host e plugin possono essere
+//! compilati con versioni diverse del compilatore.
+//!
+//! Il protocollo ricalca quello dei runner interni:
+//!   - lo stato del target (le variabili iniettabili, ognuna in doppia copia) è creato da
+//!     `create` a partire dal vettore di input e descritto dal registro delle variabili
+//!     (`var_count` / `var_info`);
+//!   - `run` esegue l'algoritmo e, dopo ogni istruzione, invoca la callback di step ricevuta
+//!     dall'host, che ritorna solo quando l'iniettore ha concesso il passo successivo;
+//!   - `flip` inverte un bit di una delle due copie di una variabile, la stessa che `output`
+//!     restituisce: l'host la invoca solo mentre `run` è fermo dentro la callback di step,
+//!     quindi non ci sono accessi concorrenti.
+//!     Durante la callback `run` non deve però tenere riferimenti esclusivi allo stato, che
+//!     `flip` modifica proprio in quel momento.
+//!
+//! Le funzioni del plugin non devono mai propagare panic attraverso il confine `extern "C"`.
+
+use std::ffi::{c_char, c_void};
+
+///Versione dell'ABI: l'host rifiuta i plugin compilati per una versione diversa
+pub const ABI_VERSION: u32 = 2;
+
+///Nome (terminato da NUL) della funzione di ingresso esportata dal plugin
+pub const ENTRY_SYMBOL: &[u8] = b"fault_plugin_entry\0";
+
+///Esito di un'operazione del plugin: `STATUS_OK` oppure il tipo di incoerenza rilevata,
+/// con la stessa classificazione usata dall'host per i target interni
+pub type Status = u32;
+
+pub const STATUS_OK: Status = 0;
+pub const STATUS_ASSIGN_FAIL: Status = 1;
+pub const STATUS_ADD_FAIL: Status = 2;
+pub const STATUS_SUB_FAIL: Status = 3;
+pub const STATUS_MUL_FAIL: Status = 4;
+pub const STATUS_DIV_FAIL: Status = 5;
+pub const STATUS_BIT_FAIL: Status = 6;
+pub const STATUS_INDEX_MUT_FAIL: Status = 7;
+pub const STATUS_INDEX_FAIL: Status = 8;
+pub const STATUS_ORD_FAIL: Status = 9;
+pub const STATUS_PARTIAL_ORD_FAIL: Status = 10;
+pub const STATUS_PARTIAL_EQ_FAIL: Status = 11;
+pub const STATUS_INNER_FAIL: Status = 12;
+pub const STATUS_DEPTH_FAIL: Status = 13;
+///Panic catturato dal plugin prima di attraversare il confine `extern "C"`
+pub const STATUS_PANIC: Status = 14;
+///Richiesta non valida (es. indice di variabile o di elemento fuori dal registro)
+pub const STATUS_INVALID: Status = 255;
+
+///Descrizione di una variabile iniettabile
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct VarInfo {
+    ///Nome della variabile, terminato da NUL e valido finché il plugin resta caricato
+    pub name: *const c_char,
+    ///Ampiezza in bit di un elemento (es. 32 per i32, 64 per usize)
+    pub width: u32,
+    ///Numero di elementi: 0 per una variabile scalare
+    pub len: usize,
+    ///true per gli interi con segno (es. i32), false per gli interi senza segno e i booleani
+    pub signed: bool,
+}
+
+impl VarInfo {
+    ///Descrizione restituita da `var_info` per un indice fuori dal registro
+    pub const NONE: VarInfo = VarInfo { name: std::ptr::null(), width: 0, len: 0, signed: false };
+}
+
+///Callback invocata dal plugin dopo ogni istruzione eseguita: `ctx` è il contesto passato
+/// dall'host a `run`, `label` il numero dell'istruzione (solo a scopo diagnostico)
+pub type StepFn = unsafe extern "C" fn(ctx: *mut c_void, label: u32);
+
+///Funzione di ingresso esportata dal plugin con il nome [`ENTRY_SYMBOL`]
+pub type EntryFn = unsafe extern "C" fn() -> *const PluginDescriptor;
+
+///Tabella delle funzioni di un target
+#[repr(C)]
+pub struct PluginDescriptor {
+    pub abi_version: u32,
+    ///Nome del target, terminato da NUL (es. "plugin_bubble_sort")
+    pub name: *const c_char,
+    ///Crea lo stato del target a partire dal vettore di input
+    pub create: unsafe extern "C" fn(input: *const i32, len: usize) -> *mut c_void,
+    ///Libera lo stato creato da `create`
+    pub destroy: unsafe extern "C" fn(state: *mut c_void),
+    ///Numero di variabili del registro
+    pub var_count: unsafe extern "C" fn(state: *const c_void) -> usize,
+    ///Descrizione della variabile di indice `index`: [`VarInfo::NONE`] se `index >= var_count`
+    pub var_info: unsafe extern "C" fn(state: *const c_void, index: usize) -> VarInfo,
+    ///Inverte il bit `bit` dell'elemento `element` (0 per gli scalari) della variabile `index`,
+    /// nella stessa copia restituita da `output`
+    pub flip: unsafe extern "C" fn(state: *mut c_void, index: usize, element: usize, bit: u32) -> Status,
+    ///Esegue la versione irrobustita dell'algoritmo
+    pub run: unsafe extern "C" fn(state: *mut c_void, step: StepFn, ctx: *mut c_void) -> Status,
+    ///Copia l'output dell'ultima `run` in `out` (al più `cap` elementi) e ne restituisce la
+    /// lunghezza complessiva. Di ogni elemento si copia la copia su cui agisce `flip`, come i
+    /// target interni restituiscono la copia iniettata: un fault su un elemento mai riletto
+    /// produce un output errato ma non rilevato
+    pub output: unsafe extern "C" fn(state: *const c_void, out: *mut i32, cap: usize) -> usize,
+    ///Esegue la versione non irrobustita (golden run) con la stessa convenzione di `output`
+    pub golden: unsafe extern "C" fn(input: *const i32, len: usize, out: *mut i32, cap: usize) -> usize,
+}
+
+//Il descrittore contiene solo puntatori a dati e funzioni statiche del plugin, mai modificati
+unsafe impl Sync for PluginDescriptor {}
@@ -0,0 +1,216 @@
+//! Plugin di riferimento: il bubble sort irrobustito riscritto sull'interfaccia di
+//! `fault_plugin_api`. Le istruzioni e le variabili iniettabili sono le stesse del target
+//! interno "bubble_sort", quindi a parità di input il numero di step coincide.
+//!
+//! Mentre `run` è fermo nella callback di step l'host può invocare `flip` sullo stesso stato:
+//! per questo nessuna delle due tiene un riferimento esclusivo allo stato, e le variabili sono
+//! celle modificate solo con letture e scritture puntuali.
+
+use std::cell::Cell;
+use std::ffi::{c_void, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+use fault_plugin_api::*;
+
+///Valore in doppia copia: la seconda copia è quella su cui l'host inietta i fault
+#[derive(Clone, Copy)]
+struct Dup<T> {
+    cp0: T,
+    cp1: T,
+}
+
+impl<T: Copy + PartialEq> Dup<T> {
+    fn new(val: T) -> Self {
+        Dup { cp0: val, cp1: val }
+    }
+
+    fn inner(&self) -> Result<T, Status> {
+        if self.cp0 == self.cp1 { Ok(self.cp0) } else { Err(STATUS_INNER_FAIL) }
+    }
+}
+
+///Applica 'f' alla seconda copia della variabile
+fn update<T: Copy>(var: &Cell<Dup<T>>, f: impl FnOnce(T) -> T) {
+    let mut val = var.get();
+    val.cp1 = f(val.cp1);
+    var.set(val);
+}
+
+struct BubbleSort {
+    vet: Vec<Cell<Dup<i32>>>,
+    n: Cell<Dup<usize>>,
+    i: Cell<Dup<usize>>,
+    j: Cell<Dup<usize>>,
+    swapped: Cell<Dup<bool>>,
+}
+
+///Registro delle variabili (nome, bit di un elemento, con segno): l'ordine definisce
+/// l'indice usato da `flip`
+const VARS: [(&CStr, u32, bool); 5] = [
+    (c"vet", i32::BITS, true),
+    (c"n", usize::BITS, false),
+    (c"i", usize::BITS, false),
+    (c"j", usize::BITS, false),
+    (c"swapped", 1, false),
+];
+
+///Confronto tra due valori irrobustiti: un'incoerenza viene classificata come nel PartialOrd
+/// dei target interni
+fn lt(a: Dup<usize>, b: Dup<usize>) -> Result<bool, Status> {
+    Ok(a.inner().map_err(|_| STATUS_PARTIAL_ORD_FAIL)? < b.inner().map_err(|_| STATUS_PARTIAL_ORD_FAIL)?)
+}
+
+fn sort(state: &BubbleSort, step: &mut dyn FnMut(u32)) -> Result<(), Status> {
+    let get = |vet: &[Cell<Dup<i32>>], index: usize| vet.get(index).ok_or(STATUS_INDEX_FAIL)?.get().inner();
+
+    state.n.set(Dup::new(state.vet.len()));
+    step(1);
+    state.i.set(Dup::new(0));
+    step(2);
+
+    while lt(state.i.get(), state.n.get())? {
+        step(3);
+        state.swapped.set(Dup::new(false));
+        step(4);
+        state.j.set(Dup::new(0));
+        step(5);
+
+        //n - i - 1 non va in underflow perché i < n
+        while lt(state.j.get(), Dup::new(state.n.get().inner().map_err(|_| STATUS_SUB_FAIL)? - state.i.get().inner().map_err(|_| STATUS_SUB_FAIL)? - 1))? {
+            step(6);
+            let j = state.j.get().inner().map_err(|_| STATUS_INDEX_FAIL)?;
+            if get(&state.vet, j)? > get(&state.vet, j + 1)? {
+                step(7);
+                //entrambi gli indici sono stati appena letti con successo
+                state.vet[j].swap(&state.vet[j + 1]);
+                step(8);
+                state.swapped.set(Dup::new(true));
+                step(9);
+            }
+            state.j.set(Dup::new(state.j.get().inner().map_err(|_| STATUS_ADD_FAIL)? + 1));
+            step(10);
+        }
+
+        if !state.swapped.get().inner()? {
+            step(11);
+            break;
+        }
+        state.i.set(Dup::new(state.i.get().inner().map_err(|_| STATUS_ADD_FAIL)? + 1));
+        step(12);
+    }
+    Ok(())
+}
+
+unsafe extern "C" fn create(input: *const i32, len: usize) -> *mut c_void {
+    let input = if len == 0 { &[][..] } else { slice::from_raw_parts(input, len) };
+    let state = BubbleSort {
+        vet: input.iter().map(|&val| Cell::new(Dup::new(val))).collect(),
+        n: Cell::new(Dup::new(0)),
+        i: Cell::new(Dup::new(0)),
+        j: Cell::new(Dup::new(0)),
+        swapped: Cell::new(Dup::new(false)),
+    };
+    Box::into_raw(Box::new(state)) as *mut c_void
+}
+
+unsafe extern "C" fn destroy(state: *mut c_void) {
+    drop(Box::from_raw(state as *mut BubbleSort));
+}
+
+unsafe extern "C" fn var_count(_state: *const c_void) -> usize {
+    VARS.len()
+}
+
+unsafe extern "C" fn var_info(state: *const c_void, index: usize) -> VarInfo {
+    let state = &*(state as *const BubbleSort);
+    //un panic non può attraversare il confine extern "C": fuori dal registro si restituisce
+    //la descrizione nulla
+    let Some(&(name, width, signed)) = VARS.get(index) else {
+        return VarInfo::NONE;
+    };
+    VarInfo {
+        name: name.as_ptr(),
+        width,
+        len: if index == 0 { state.vet.len() } else { 0 },
+        signed,
+    }
+}
+
+unsafe extern "C" fn flip(state: *mut c_void, index: usize, element: usize, bit: u32) -> Status {
+    //riferimento condiviso: `run` può essere fermo nella callback di step con lo stesso stato
+    let state = &*(state as *const BubbleSort);
+    match (index, VARS.get(index)) {
+        //come nei target interni, un flip su un booleano ne inverte il valore qualunque sia il bit
+        (4, _) => { update(&state.swapped, |val| !val); STATUS_OK }
+        (_, Some(&(_, width, _))) if bit >= width => STATUS_INVALID,
+        (0, _) => match state.vet.get(element) {
+            Some(el) => {
+                update(el, |val| val ^ (1 << bit));
+                STATUS_OK
+            }
+            None => STATUS_INVALID,
+        },
+        (1, _) => { update(&state.n, |val| val ^ (1 << bit)); STATUS_OK }
+        (2, _) => { update(&state.i, |val| val ^ (1 << bit)); STATUS_OK }
+        (3, _) => { update(&state.j, |val| val ^ (1 << bit)); STATUS_OK }
+        _ => STATUS_INVALID,
+    }
+}
+
+unsafe extern "C" fn run(state: *mut c_void, step: StepFn, ctx: *mut c_void) -> Status {
+    let state = &*(state as *const BubbleSort);
+    let mut step = |label: u32| step(ctx, label);
+    //un panic non può attraversare il confine extern "C"
+    match panic::catch_unwind(AssertUnwindSafe(|| sort(state, &mut step))) {
+        Ok(Ok(())) => STATUS_OK,
+        Ok(Err(status)) => status,
+        Err(_) => STATUS_PANIC,
+    }
+}
+
+unsafe extern "C" fn output(state: *const c_void, out: *mut i32, cap: usize) -> usize {
+    let state = &*(state as *const BubbleSort);
+    for (k, el) in state.vet.iter().take(cap).enumerate() {
+        *out.add(k) = el.get().cp1;
+    }
+    state.vet.len()
+}
+
+unsafe extern "C" fn golden(input: *const i32, len: usize, out: *mut i32, cap: usize) -> usize {
+    let mut vet = if len == 0 { Vec::new() } else { slice::from_raw_parts(input, len).to_vec() };
+    let n = vet.len();
+    for i in 0..n {
+        let mut swapped = false;
+        for j in 0..n - i - 1 {
+            if vet[j] > vet[j + 1] {
+                vet.swap(j, j + 1);
+                swapped = true;
+            }
+        }
+        if !swapped {
+            break;
+        }
+    }
+    for (k, &val) in vet.iter().take(cap).enumerate() {
+        *out.add(k) = val;
+    }
+    n
+}
+
+static DESCRIPTOR: PluginDescriptor = PluginDescriptor {
+    abi_version: ABI_VERSION,
+    name: c"plugin_bubble_sort".as_ptr(),
+    create,
+    destroy,
+    var_count,
+    var_info,
+    flip,
+    run,
+    output,
+    golden,
+};
+
+#[no_mangle]
+pub extern "C" fn fault_plugin_entry() -> *const PluginDescriptor {
+    &DESCRIPTOR
+}
@@ -1,11 +1,18 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::sync::mpsc::{Receiver};
 use std::time::Instant;
 use serde::{Deserialize, Serialize};
 use crate::fault_env::Data;
-use crate::fault_list_manager::file_fault_list::{bubble_sort, matrix_multiplication, selection_sort};
-use crate::hardened::{bubble_sort_hardened, matrix_multiplication_hardened, selection_sort_hardened, Hardened, IncoherenceError, IntoNestedVec};
-use crate::injector::TestResult;
+use crate::fault_list_manager::file_fault_list::{bubble_sort, matrix_multiplication, selection_sort, quick_sort, merge_sort, bfs, dijkstra, dot_product, fir_filter, jacobi, crc32, kmp, par_matrix_multiplication};
+use crate::hardened::{bubble_sort_hardened, matrix_multiplication_hardened, selection_sort_hardened, quick_sort_hardened, merge_sort_hardened, bfs_hardened, dijkstra_hardened, Hardened, IncoherenceError, IntoNestedVec};
+use crate::hardened::{dot_product_hardened, fir_filter_hardened, jacobi_hardened, crc32_hardened, kmp_hardened, par_matrix_multiplication_hardened};
+use crate::hardened::par_matrix_multiplication_hardened::PAR_WORKERS;
+use crate::fault_list_manager::{target_function, FaultListEntry, FaultListMetadata};
+use crate::injector::{label_trace, Output, TestResult};
+use crate::injector::labels::{runner_labels, RunnerLabels};
+use crate::fault_list_manager::static_analysis::SourceSpan;
+use crate::plugin;
 use crate::{pdf_generator, VERBOSE};
 
 #[derive(Serialize,Deserialize,Debug,Clone)]
@@ -15,13 +22,21 @@ pub struct Faults{
     pub(crate) n_inner_fault: usize,
     pub(crate) n_sub_fault: usize,
     pub(crate) n_mul_fault: usize,
+    pub(crate) n_div_fault: usize,
+    pub(crate) n_bit_fault: usize,
     pub(crate) n_add_fault: usize,
     pub(crate) n_indexmut_fault: usize,
     pub(crate) n_index_fault: usize,
     pub(crate) n_ord_fault: usize,
     pub(crate) n_partialord_fault: usize,
     pub(crate) n_partialeq_fault: usize,
+    pub(crate) n_depth_fault: usize,
     pub(crate) n_fatal_fault: usize,
+    pub(crate) n_tolerated_fault: usize,    //output reale diverso da quello atteso ma entro la tolleranza
+    //target paralleli: fault rilevati da un thread diverso da quello che aveva scritto il dato,
+    //oppure dallo stesso thread (non sono conteggiati nel totale)
+    pub(crate) n_cross_thread_detected: usize,
+    pub(crate) n_same_thread_detected: usize,
     pub(crate) total_fault: usize,
 }
 pub struct FaultsIter<'a> {
@@ -58,29 +73,151 @@ impl<'a> Iterator for FaultsIter<'a> {
         result
     }
 }
+///Frazioni degli esiti stimate sullo spazio dei fault: ogni esito è pesato con il peso di
+/// importanza della sua variabile, che compensa la strategia con cui le variabili sono state
+/// scelte (vedi FaultListMetadata::site_weights)
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq)]
+pub struct SpaceEstimate{
+    pub(crate) detected: f64,
+    pub(crate) silent: f64,         //output corretto o entro la tolleranza
+    pub(crate) corrupted: f64,
+}
+
+impl SpaceEstimate{
+    fn new(detected: f64, silent: f64, corrupted: f64) -> Option<Self>{
+        let total = detected + silent + corrupted;
+        (total > 0.0).then(|| SpaceEstimate{ detected: detected / total, silent: silent / total, corrupted: corrupted / total })
+    }
+}
+
+///Esiti delle iniezioni avvenute mentre era in esecuzione un'istruzione di una riga del sorgente
+/// (conteggi pesati come quelli di Faults). Le entry morte eliminate dalla fault list non hanno
+/// un'esecuzione e non sono riportate
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Default)]
+pub struct SourceLine{
+    pub(crate) line: usize,
+    pub(crate) code: String,
+    pub(crate) detected: usize,
+    pub(crate) silent: usize,
+    pub(crate) corrupted: usize,
+}
+
+///Righe del sorgente a cui si riportano gli istanti della fault list: all'istante t il runner ha
+/// appena inviato l'etichetta trace[t-1], e la tabella delle etichette del target indica
+/// l'istruzione dell'analisi statica appena eseguita
+struct LineMap<'a>{
+    labels: Option<RunnerLabels>,
+    trace: Vec<&'static str>,
+    statements: &'a [SourceSpan],
+    lines: BTreeMap<usize, SourceLine>,
+}
+
+impl<'a> LineMap<'a>{
+    fn new(target: &str, trace: Vec<&'static str>, statements: &'a [SourceSpan])->Self{
+        LineMap{ labels: runner_labels(target), trace, statements, lines: BTreeMap::new() }
+    }
+
+    fn at(&mut self, time: usize)->Option<&mut SourceLine>{
+        let label = self.trace.get(time.checked_sub(1)?)?;
+        let k = self.labels?.statement(label)?;
+        let span = self.statements.get(k.checked_sub(1)?)?;
+        Some(self.lines.entry(span.line).or_insert_with(|| SourceLine{ line: span.line, code: span.code.clone(), ..SourceLine::default() }))
+    }
+
+    fn into_lines(self)->Vec<SourceLine>{
+        self.lines.into_values().collect()
+    }
+}
+
+///Output atteso del caso di studio, calcolato dalla versione non irrobustita: a differenza di
+/// Data contiene un solo valore
+#[derive(Serialize,Deserialize,Debug,Clone)]
+pub enum GoldenOutput{
+    Vector(Vec<i32>),       //anche CRC (reinterpretato come i32) e posizioni delle occorrenze
+    Matrix(Vec<Vec<i32>>),
+    Reals(Vec<f64>),        //kernel numerici
+}
+
+impl From<Vec<i32>> for GoldenOutput {
+    fn from(vec: Vec<i32>) -> Self {
+        GoldenOutput::Vector(vec)
+    }
+}
+
+impl From<Vec<Vec<i32>>> for GoldenOutput {
+    fn from(matrix: Vec<Vec<i32>>) -> Self {
+        GoldenOutput::Matrix(matrix)
+    }
+}
+
+impl From<Vec<f64>> for GoldenOutput {
+    fn from(reals: Vec<f64>) -> Self {
+        GoldenOutput::Reals(reals)
+    }
+}
+
+impl GoldenOutput{
+    pub fn into_vector(self) ->Vec<i32>{
+        match self{
+            GoldenOutput::Vector(ris) => ris,
+            _ => panic!("Not a vector output!"),
+        }
+    }
+
+    pub fn into_matrix(self) ->Vec<Vec<i32>>{
+        match self{
+            GoldenOutput::Matrix(ris) => ris,
+            _ => panic!("Not a matrix output!"),
+        }
+    }
+
+    pub fn into_reals(self) ->Vec<f64>{
+        match self{
+            GoldenOutput::Reals(ris) => ris,
+            _ => panic!("Not a reals output!"),
+        }
+    }
+}
+
 #[derive(Serialize,Deserialize,Debug,Clone)]
 pub struct Analyzer{
     pub(crate) n_esecuzione: i8,
     pub(crate) faults: Faults,
     pub(crate) input: Data<i32>,
-    pub(crate) output: Data<i32>,
+    pub(crate) output: GoldenOutput,
     pub(crate) time_experiment: f64,
     pub(crate) time_alg_hardened: f64,
     pub(crate) time_alg_not_hardened: f64,
     pub(crate) byte_hardened: f64,
     pub(crate) byte_not_hardened: f64,
     pub(crate) target_program: String,
+    //intestazione della fault list (assente per le fault list senza metadati)
+    #[serde(default)]
+    pub(crate) fault_list_info: Option<FaultListMetadata>,
+    //presente solo se le variabili non sono state scelte in modo uniforme sullo spazio dei fault
+    #[serde(default)]
+    pub(crate) space_estimate: Option<SpaceEstimate>,
+    //esiti per riga del sorgente, ordinati per riga (vuoto se la fault list non riporta la
+    //posizione delle istruzioni)
+    #[serde(default)]
+    pub(crate) source_lines: Vec<SourceLine>,
 }
 
 impl Analyzer{
     pub(crate) fn new(faults: Faults, time_exp:f64, n_esecuzione:i8, target: String) -> Self{
         let input:Data<i32> = match target.as_str() {
-            "matrix_multiplication" => Data::Matrices(Vec::new(),Vec::new()),
+            "matrix_multiplication" | "par_matrix_multiplication" => Data::Matrices(Vec::new(),Vec::new()),
+            "bfs" | "dijkstra" => Data::Graph(Vec::new(), 0),
+            "dot_product" | "fir_filter" => Data::Reals(Vec::new(), Vec::new()),
+            "jacobi" => Data::LinearSystem(Vec::new(), Vec::new()),
+            "crc32" => Data::Bytes(Vec::new()),
+            "kmp" => Data::Text(String::new(), String::new()),
             _ => Data::Vector(Vec::new())
         };
-        let output:Data<i32> = match target.as_str() {
-            "matrix_multiplication" => Data::Matrices(Vec::new(),Vec::new()),
-            _ => Data::Vector(Vec::new())
+        let output = match target.as_str() {
+            "matrix_multiplication" | "par_matrix_multiplication" => GoldenOutput::Matrix(Vec::new()),
+            "dot_product" | "fir_filter" | "jacobi" => GoldenOutput::Reals(Vec::new()),
+            _ => GoldenOutput::Vector(Vec::new())
         };
 
         Analyzer{
@@ -93,10 +230,25 @@ impl Analyzer{
             time_alg_not_hardened: 0.0,
             byte_hardened: 0.0,
             byte_not_hardened: 0.0,
-            target_program: target
+            target_program: target,
+            fault_list_info: None,
+            space_estimate: None,
+            source_lines: Vec::new(),
         }
     }
 
+    ///Righe da cui hanno origine più fault con output errato (SDC), con la loro quota sul totale
+    /// degli SDC riportati al sorgente: al più 'n', dalla più frequente
+    pub(crate) fn sdc_origins(&self, n: usize)->Vec<(f64, &SourceLine)>{
+        let total: usize = self.source_lines.iter().map(|line| line.corrupted).sum();
+        let mut origins: Vec<(f64, &SourceLine)> = self.source_lines.iter()
+            .filter(|line| line.corrupted > 0)
+            .map(|line| (line.corrupted as f64 / total as f64, line))
+            .collect();
+        origins.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.line.cmp(&b.1.line)));
+        origins.truncate(n);
+        origins
+    }
 }
 
 impl Faults{
@@ -107,68 +259,104 @@ impl Faults{
             n_inner_fault: 0,
             n_sub_fault:0,
             n_mul_fault: 0,
+            n_div_fault: 0,
+            n_bit_fault: 0,
             n_add_fault: 0,
             n_indexmut_fault: 0,
             n_index_fault: 0,
             n_ord_fault: 0,
             n_partialord_fault: 0,
             n_partialeq_fault: 0,
+            n_depth_fault: 0,
             n_fatal_fault:0,
+            n_tolerated_fault: 0,
+            n_cross_thread_detected: 0,
+            n_same_thread_detected: 0,
             total_fault: 0,
         }
     }
 }
 
 pub fn run_analyzer(rx_chan_inj_anl: Receiver<TestResult>, file_path:String, data: Data<i32>,
-                target:String, n_esecuzione:i8, time_experiment:f64) {
+                target:String, n_esecuzione:i8, timer: Instant, fault_list_info: Option<FaultListMetadata>) {
     let mut vec_result = Vec::new();
     let mut faults = Faults::new();
     while let Ok(test_result) = rx_chan_inj_anl.recv() {
         vec_result.push(test_result);
     }
+    //gli stage lavorano in parallelo: la campagna termina con l'ultimo risultato ricevuto
+    let time_experiment = timer.elapsed().as_millis() as f64;
 
 
     let mut v_ok = Vec::new();
     let mut fault_list_ok = Vec::new();
+    //peso di importanza di una entry, per la stima sullo spazio dei fault
+    let site_weight = |entry: &FaultListEntry| entry.weight as f64 * fault_list_info.as_ref().map_or(1.0, |info| info.site_weight(&entry.var));
+    let mut detected_weight = 0.0;
+    //la traccia del run senza fault serve solo se la fault list riporta le posizioni nel sorgente,
+    //e le etichette del runner si riferiscono solo alla funzione predefinita del target
+    let statements = fault_list_info.as_ref()
+        .filter(|info| info.params.as_ref().is_none_or(|params| params.function(&target) == target_function(&target)))
+        .map_or(&[][..], |info| info.statements.as_slice());
+    let trace = if statements.is_empty() { Vec::new() } else { label_trace(&target, data.clone()) };
+    let mut line_map = LineMap::new(&target, trace, statements);
     for test_result in &vec_result {
 
         let res = test_result.get_result();
-
+        //una entry può rappresentare più fault equivalenti (vedi fault_list_manager::liveness)
+        let weight = test_result.get_fault_list_entry().weight;
+        if res.is_err() {
+            detected_weight += site_weight(&test_result.get_fault_list_entry());
+            if let Some(line) = line_map.at(test_result.get_fault_list_entry().time) {
+                line.detected += weight;
+            }
+        }
 
         if res.is_ok() {
-            faults.n_silent_fault += 1;
+            faults.n_silent_fault += weight;
             v_ok.push(res.unwrap());
             fault_list_ok.push(test_result.get_fault_list_entry());
         } else {
+            match test_result.get_detection() {
+                Some(detection) if detection.is_cross_thread() => faults.n_cross_thread_detected += weight,
+                Some(_) => faults.n_same_thread_detected += weight,
+                None => {}
+            }
             match res.err().unwrap() {
-                IncoherenceError::AssignFail => faults.n_assign_fault += 1,
-                IncoherenceError::AddFail => faults.n_add_fault += 1,
-                IncoherenceError::MulFail => faults.n_mul_fault += 1,
-                IncoherenceError::InnerFail => faults.n_inner_fault += 1,
-                IncoherenceError::SubFail => faults.n_sub_fault += 1,
-                IncoherenceError::IndexMutFail => faults.n_indexmut_fault += 1,
-                IncoherenceError::IndexFail => faults.n_index_fault += 1,
-                IncoherenceError::OrdFail => faults.n_ord_fault += 1,
-                IncoherenceError::PartialOrdFail => faults.n_partialord_fault += 1,
-                IncoherenceError::PartialEqFail => faults.n_partialeq_fault += 1
+                IncoherenceError::AssignFail => faults.n_assign_fault += weight,
+                IncoherenceError::AddFail => faults.n_add_fault += weight,
+                IncoherenceError::MulFail => faults.n_mul_fault += weight,
+                IncoherenceError::DivFail => faults.n_div_fault += weight,
+                IncoherenceError::BitFail => faults.n_bit_fault += weight,
+                IncoherenceError::InnerFail => faults.n_inner_fault += weight,
+                IncoherenceError::SubFail => faults.n_sub_fault += weight,
+                IncoherenceError::IndexMutFail => faults.n_indexmut_fault += weight,
+                IncoherenceError::IndexFail => faults.n_index_fault += weight,
+                IncoherenceError::OrdFail => faults.n_ord_fault += weight,
+                IncoherenceError::PartialOrdFail => faults.n_partialord_fault += weight,
+                IncoherenceError::PartialEqFail => faults.n_partialeq_fault += weight,
+                IncoherenceError::DepthFail => faults.n_depth_fault += weight
             }
         }
     }
+    //le entry morte eliminate dalla fault list sono fault silenti con output corretto
+    faults.n_silent_fault += fault_list_info.as_ref().map_or(0, |info| info.dead_faults);
     faults.total_fault =  faults.n_silent_fault + faults.n_assign_fault + faults.n_add_fault +
-                            faults.n_mul_fault + faults.n_inner_fault + faults.n_sub_fault +
+                            faults.n_mul_fault + faults.n_div_fault + faults.n_bit_fault + faults.n_inner_fault + faults.n_sub_fault +
                             faults.n_indexmut_fault + faults.n_index_fault + faults.n_ord_fault +
-                            faults.n_partialord_fault + faults.n_partialeq_fault;
+                            faults.n_partialord_fault + faults.n_partialeq_fault +
+                            faults.n_depth_fault;
 
 
     let mut analyzer = Analyzer::new(faults,time_experiment, n_esecuzione,target);
     analyzer.input = data;
+    analyzer.fault_list_info = fault_list_info.clone();
     get_data_for_dimension_table(&mut analyzer).unwrap();
     get_data_for_time_table(&mut analyzer).unwrap();
 
-    let correct_ouput = match analyzer.target_program.as_str() {
-        "matrix_multiplication" => {analyzer.output.clone().into_matrices().0.into_iter().flatten().collect::<Vec<i32>>()}
-        _ => {analyzer.output.clone().into_vector()}
-    };
+    let mut silent_weight: f64 = analyzer.fault_list_info.as_ref()
+        .map_or(0.0, |info| info.dead_by_var.iter().map(|(var, &dead)| dead as f64 * info.site_weight(var)).sum());
+    let mut corrupted_weight = 0.0;
     let mut i = 0;
     if VERBOSE {
         println!("##########################################################################");
@@ -176,14 +364,38 @@ pub fn run_analyzer(rx_chan_inj_anl: Receiver<TestResult>, file_path:String, dat
         println!("##########################################################################");
     }
     for v in v_ok{
-        if correct_ouput != v.into_nested_vec() {
-            if VERBOSE {
-                println!("Fault #{} {:?}", analyzer.faults.n_fatal_fault, fault_list_ok[i]);
+        let class = classify_output(&analyzer, v);
+        match class {
+            OutputClass::Correct => silent_weight += site_weight(&fault_list_ok[i]),
+            OutputClass::WithinTolerance => {
+                analyzer.faults.n_tolerated_fault += fault_list_ok[i].weight;
+                silent_weight += site_weight(&fault_list_ok[i]);
+            }
+            OutputClass::Corrupted => {
+                if VERBOSE {
+                    println!("Fault #{} {:?}", analyzer.faults.n_fatal_fault, fault_list_ok[i]);
+                }
+                analyzer.faults.n_fatal_fault += fault_list_ok[i].weight;
+                corrupted_weight += site_weight(&fault_list_ok[i]);
+            }
+        }
+        if let Some(line) = line_map.at(fault_list_ok[i].time) {
+            match class {
+                OutputClass::Corrupted => line.corrupted += fault_list_ok[i].weight,
+                _ => line.silent += fault_list_ok[i].weight,
             }
-            analyzer.faults.n_fatal_fault += 1;
         }
         i=i+1;
     }
+    analyzer.source_lines = line_map.into_lines();
+    if VERBOSE {
+        for (share, line) in analyzer.sdc_origins(3) {
+            println!("{:.0}% degli SDC ha origine alla riga {} `{}`", share * 100.0, line.line, line.code);
+        }
+    }
+    if analyzer.fault_list_info.as_ref().is_some_and(|info| !info.site_weights.is_empty()) {
+        analyzer.space_estimate = SpaceEstimate::new(detected_weight, silent_weight, corrupted_weight);
+    }
 
     let json_path = "results/tmp.json";
     // 1. Leggi il contenuto esistente del file (o array vuoto se è stato appena creato)
@@ -218,11 +430,108 @@ pub fn run_analyzer(rx_chan_inj_anl: Receiver<TestResult>, file_path:String, dat
     }
 
 }
+///Tolleranza relativa entro cui l'output di un kernel numerico è considerato accettabile
+const FLOAT_TOLERANCE: f64 = 1e-6;
+
+///Esito del confronto tra l'output di un'esecuzione non rilevata e quello atteso
+#[derive(Debug, PartialEq)]
+enum OutputClass {
+    Correct,
+    WithinTolerance,    //solo per output reali: diverso da quello atteso, ma entro FLOAT_TOLERANCE
+    Corrupted,
+}
+
+fn classify_output(analyzer: &Analyzer, output: Output) -> OutputClass {
+    let correct = match (analyzer.target_program.as_str(), output) {
+        //Per i grafi l'output si valida controllando le distanze sugli archi
+        ("bfs" | "dijkstra", Output::Int(v)) => {
+            let (graph, source) = analyzer.input.clone().into_graph();
+            check_distances(&graph, source, &v.into_nested_vec(), analyzer.target_program == "bfs")
+        }
+        ("matrix_multiplication" | "par_matrix_multiplication", Output::Int(v)) => {
+            analyzer.output.clone().into_matrix().into_iter().flatten().collect::<Vec<i32>>() == v.into_nested_vec()
+        }
+        (_, Output::Int(v)) => analyzer.output.clone().into_vector() == v.into_nested_vec(),
+        //Il CRC e le posizioni delle occorrenze sono memorizzati nel vettore di i32 dell'output
+        //atteso: il confronto si fa nel tipo prodotto dal runner
+        (_, Output::Word(v)) => {
+            analyzer.output.clone().into_vector().iter().map(|&e| e as u32).collect::<Vec<u32>>() == v.into_nested_vec()
+        }
+        (_, Output::Index(v)) => {
+            analyzer.output.clone().into_vector().iter().map(|&e| e as usize).collect::<Vec<usize>>() == v.into_nested_vec()
+        }
+        (_, Output::Real(v)) => return classify_reals(&analyzer.output.clone().into_reals(), &v.into_nested_vec()),
+    };
+    if correct { OutputClass::Correct } else { OutputClass::Corrupted }
+}
+
+///Un output reale è corretto se coincide bit a bit con quello atteso. Altrimenti è entro la
+/// tolleranza se ogni elemento differisce al più di FLOAT_TOLERANCE in termini relativi
+/// (assoluti per valori attesi minori di 1): è il caso tipico dei flip nei bit meno
+/// significativi della mantissa.
+fn classify_reals(expected: &[f64], output: &[f64]) -> OutputClass {
+    if expected.len() != output.len() {
+        return OutputClass::Corrupted;
+    }
+    if expected.iter().zip(output).all(|(e, o)| e.to_bits() == o.to_bits()) {
+        return OutputClass::Correct;
+    }
+    //il confronto con '<=' è falso per NaN: un output NaN non è mai entro la tolleranza
+    if expected.iter().zip(output).all(|(e, o)| (e - o).abs() <= FLOAT_TOLERANCE * e.abs().max(1.0)) {
+        OutputClass::WithinTolerance
+    } else {
+        OutputClass::Corrupted
+    }
+}
+
+///Verifica che 'dist' contenga le distanze minime dalla sorgente, senza confrontarlo con l'output
+/// dell'esecuzione golden: la sorgente ha distanza 0, nessun arco può essere ancora rilassato e
+/// ogni nodo raggiunto ha un predecessore che ne giustifica la distanza. Con pesi strettamente
+/// positivi queste condizioni individuano un'unica soluzione.
+/// Con 'unit_weights' ogni arco pesa 1 (distanze della BFS).
+fn check_distances(graph: &[Vec<(usize, i32)>], source: usize, dist: &[i32], unit_weights: bool) -> bool {
+    if dist.len() != graph.len() || dist.get(source) != Some(&0) {
+        return false;
+    }
+    let weight = |w: i32| if unit_weights { 1 } else { w as i64 };
+    let mut justified = vec![false; dist.len()];
+    justified[source] = true;
+    for (u, edges) in graph.iter().enumerate() {
+        if dist[u] == i32::MAX {
+            continue;
+        }
+        for &(v, w) in edges {
+            let alt = dist[u] as i64 + weight(w);
+            if (dist[v] as i64) > alt {
+                return false;
+            }
+            if (dist[v] as i64) == alt {
+                justified[v] = true;
+            }
+        }
+    }
+    dist.iter().zip(justified).all(|(&d, ok)| d == i32::MAX || ok)
+}
+
 fn get_data_for_dimension_table(analyzer: &mut Analyzer) -> Result<(),String>{
+    //Dei plugin si conosce solo la libreria compilata: la tabella delle dimensioni resta vuota
+    if plugin::get(&analyzer.target_program).is_some() {
+        return Ok(());
+    }
     let file_path_nothardened = match analyzer.target_program.as_str() {
         "sel_sort" => "src/fault_list_manager/file_fault_list/selection_sort/mod.rs",
         "bubble_sort" => "src/fault_list_manager/file_fault_list/bubble_sort/mod.rs",
         "matrix_multiplication" => "src/fault_list_manager/file_fault_list/matrix_multiplication/mod.rs",
+        "quick_sort" => "src/fault_list_manager/file_fault_list/quick_sort/mod.rs",
+        "merge_sort" => "src/fault_list_manager/file_fault_list/merge_sort/mod.rs",
+        "bfs" => "src/fault_list_manager/file_fault_list/bfs/mod.rs",
+        "dijkstra" => "src/fault_list_manager/file_fault_list/dijkstra/mod.rs",
+        "dot_product" => "src/fault_list_manager/file_fault_list/dot_product/mod.rs",
+        "fir_filter" => "src/fault_list_manager/file_fault_list/fir_filter/mod.rs",
+        "jacobi" => "src/fault_list_manager/file_fault_list/jacobi/mod.rs",
+        "crc32" => "src/fault_list_manager/file_fault_list/crc32/mod.rs",
+        "kmp" => "src/fault_list_manager/file_fault_list/kmp/mod.rs",
+        "par_matrix_multiplication" => "src/fault_list_manager/file_fault_list/par_matrix_multiplication/mod.rs",
         _ => "",
     };
     let metadata_not_hard = fs::metadata(file_path_nothardened);
@@ -231,6 +540,16 @@ fn get_data_for_dimension_table(analyzer: &mut Analyzer) -> Result<(),String>{
         "sel_sort" => "src/hardened/selection_sort_hardened/mod.rs",
         "bubble_sort" => "src/hardened/bubble_sort_hardened/mod.rs",
         "matrix_multiplication" => "src/hardened/matrix_multiplication_hardened/mod.rs",
+        "quick_sort" => "src/hardened/quick_sort_hardened/mod.rs",
+        "merge_sort" => "src/hardened/merge_sort_hardened/mod.rs",
+        "bfs" => "src/hardened/bfs_hardened/mod.rs",
+        "dijkstra" => "src/hardened/dijkstra_hardened/mod.rs",
+        "dot_product" => "src/hardened/dot_product_hardened/mod.rs",
+        "fir_filter" => "src/hardened/fir_filter_hardened/mod.rs",
+        "jacobi" => "src/hardened/jacobi_hardened/mod.rs",
+        "crc32" => "src/hardened/crc32_hardened/mod.rs",
+        "kmp" => "src/hardened/kmp_hardened/mod.rs",
+        "par_matrix_multiplication" => "src/hardened/par_matrix_multiplication_hardened/mod.rs",
         _ => "",
     };
     let metadata_hard = fs::metadata(file_path_hardened);
@@ -263,7 +582,73 @@ fn get_data_for_time_table(analyzer: &mut Analyzer) -> Result<(),String>{
             analyzer.output = matrix_multiplication::matrix_multiplication(matrices.0,matrices.1).into();
             (start_mat_multiplication.elapsed().as_nanos() as f64)/1000.0
         },
-        _ => return Err("Indice non valido".to_string()),
+        "quick_sort" => {
+            let start_quick_sort = Instant::now();
+            analyzer.output = quick_sort::quick_sort(data.into_vector()).into();
+            (start_quick_sort.elapsed().as_nanos() as f64)/1000.0
+        },
+        "merge_sort" => {
+            let start_merge_sort = Instant::now();
+            analyzer.output = merge_sort::merge_sort(data.into_vector()).into();
+            (start_merge_sort.elapsed().as_nanos() as f64)/1000.0
+        },
+        "bfs" => {
+            let start_bfs = Instant::now();
+            let (graph, source) = data.into_graph();
+            analyzer.output = bfs::bfs(graph, source).into();
+            (start_bfs.elapsed().as_nanos() as f64)/1000.0
+        },
+        "dijkstra" => {
+            let start_dijkstra = Instant::now();
+            let (graph, source) = data.into_graph();
+            analyzer.output = dijkstra::dijkstra(graph, source).into();
+            (start_dijkstra.elapsed().as_nanos() as f64)/1000.0
+        },
+        //L'output dei kernel numerici è un vettore reale
+        "dot_product" => {
+            let start_dot_product = Instant::now();
+            let (x, y) = data.into_reals();
+            analyzer.output = vec![dot_product::dot_product(x, y)].into();
+            (start_dot_product.elapsed().as_nanos() as f64)/1000.0
+        },
+        "fir_filter" => {
+            let start_fir_filter = Instant::now();
+            let (x, h) = data.into_reals();
+            analyzer.output = fir_filter::fir_filter(x, h).into();
+            (start_fir_filter.elapsed().as_nanos() as f64)/1000.0
+        },
+        "jacobi" => {
+            let start_jacobi = Instant::now();
+            let (a, b) = data.into_linear_system();
+            analyzer.output = jacobi::jacobi(a, b).into();
+            (start_jacobi.elapsed().as_nanos() as f64)/1000.0
+        },
+        "par_matrix_multiplication" => {
+            let start_par_mat_multiplication = Instant::now();
+            let (a, b) = data.into_matrices();
+            analyzer.output = par_matrix_multiplication::par_matrix_multiplication(a, b, PAR_WORKERS).into();
+            (start_par_mat_multiplication.elapsed().as_nanos() as f64)/1000.0
+        },
+        //Il CRC (reinterpretato come i32) e le posizioni delle occorrenze finiscono in un GoldenOutput::Vector
+        "crc32" => {
+            let start_crc32 = Instant::now();
+            analyzer.output = vec![crc32::crc32(data.into_bytes()) as i32].into();
+            (start_crc32.elapsed().as_nanos() as f64)/1000.0
+        },
+        "kmp" => {
+            let start_kmp = Instant::now();
+            let (text, pattern) = data.into_text();
+            analyzer.output = kmp::kmp(text.into_bytes(), pattern.into_bytes()).into_iter().map(|p| p as i32).collect::<Vec<i32>>().into();
+            (start_kmp.elapsed().as_nanos() as f64)/1000.0
+        },
+        name => match plugin::get(name) {
+            Some(plugin) => {
+                let start_plugin = Instant::now();
+                analyzer.output = plugin.golden(&data.into_vector()).into();
+                (start_plugin.elapsed().as_nanos() as f64)/1000.0
+            },
+            None => return Err("Indice non valido".to_string()),
+        },
     };
     analyzer.time_alg_hardened= match analyzer.target_program.as_str() {
         "sel_sort" => {
@@ -282,20 +667,92 @@ fn get_data_for_time_table(analyzer: &mut Analyzer) -> Result<(),String>{
             matrix_multiplication_hardened::matrix_multiplication(&mut Hardened::from_mat(matrices.0),&mut Hardened::from_mat(matrices.1)).unwrap();
             (start_mat_multiplication.elapsed().as_nanos() as f64)/1000.0
         },
-        _ => return Err("Indice non valido".to_string()),
+        "quick_sort" => {
+            let start_quick_sort = Instant::now();
+            quick_sort_hardened::quick_sort(&mut Hardened::from_vec(data_hard.into_vector())).unwrap();
+            (start_quick_sort.elapsed().as_nanos() as f64)/1000.0
+        },
+        "merge_sort" => {
+            let start_merge_sort = Instant::now();
+            merge_sort_hardened::merge_sort(&mut Hardened::from_vec(data_hard.into_vector())).unwrap();
+            (start_merge_sort.elapsed().as_nanos() as f64)/1000.0
+        },
+        "bfs" => {
+            let start_bfs = Instant::now();
+            let (graph, source) = data_hard.into_graph();
+            bfs_hardened::bfs(&graph, source).unwrap();
+            (start_bfs.elapsed().as_nanos() as f64)/1000.0
+        },
+        "dijkstra" => {
+            let start_dijkstra = Instant::now();
+            let (graph, source) = data_hard.into_graph();
+            dijkstra_hardened::dijkstra(&graph, source).unwrap();
+            (start_dijkstra.elapsed().as_nanos() as f64)/1000.0
+        },
+        "dot_product" => {
+            let start_dot_product = Instant::now();
+            let (x, y) = data_hard.into_reals();
+            dot_product_hardened::dot_product(&Hardened::from_vec(x), &Hardened::from_vec(y)).unwrap();
+            (start_dot_product.elapsed().as_nanos() as f64)/1000.0
+        },
+        "fir_filter" => {
+            let start_fir_filter = Instant::now();
+            let (x, h) = data_hard.into_reals();
+            fir_filter_hardened::fir_filter(&Hardened::from_vec(x), &Hardened::from_vec(h)).unwrap();
+            (start_fir_filter.elapsed().as_nanos() as f64)/1000.0
+        },
+        "jacobi" => {
+            let start_jacobi = Instant::now();
+            let (a, b) = data_hard.into_linear_system();
+            jacobi_hardened::jacobi(&Hardened::from_mat(a), &Hardened::from_vec(b), jacobi_hardened::JACOBI_ITERATIONS).unwrap();
+            (start_jacobi.elapsed().as_nanos() as f64)/1000.0
+        },
+        "par_matrix_multiplication" => {
+            let start_par_mat_multiplication = Instant::now();
+            let (a, b) = data_hard.into_matrices();
+            par_matrix_multiplication_hardened::par_matrix_multiplication(&Hardened::from_mat(a), &Hardened::from_mat(b), PAR_WORKERS).unwrap();
+            (start_par_mat_multiplication.elapsed().as_nanos() as f64)/1000.0
+        },
+        "crc32" => {
+            let start_crc32 = Instant::now();
+            crc32_hardened::crc32(&Hardened::from_vec(data_hard.into_bytes())).unwrap();
+            (start_crc32.elapsed().as_nanos() as f64)/1000.0
+        },
+        "kmp" => {
+            let start_kmp = Instant::now();
+            let (text, pattern) = data_hard.into_text();
+            kmp_hardened::kmp(&Hardened::from_vec(text.into_bytes()), &Hardened::from_vec(pattern.into_bytes())).unwrap();
+            (start_kmp.elapsed().as_nanos() as f64)/1000.0
+        },
+        name => match plugin::get(name) {
+            Some(plugin) => {
+                let instance = plugin.instance(&data_hard.into_vector());
+                let start_plugin = Instant::now();
+                instance.run(|| {}).unwrap();
+                (start_plugin.elapsed().as_nanos() as f64)/1000.0
+            },
+            None => return Err("Indice non valido".to_string()),
+        },
     };
     Ok(())
 }
 #[cfg(test)]
 mod tests{
     use rand::Rng;
-    use crate::analyzer::{get_data_for_dimension_table, get_data_for_time_table, Analyzer, Faults};
+    use std::env::temp_dir;
+    use crate::analyzer::{check_distances, classify_reals, OutputClass, get_data_for_dimension_table, get_data_for_time_table, Analyzer, Faults, LineMap, SourceLine, SpaceEstimate};
+    use crate::fault_env::Data;
+    use crate::fault_list_manager::static_analysis::{generate_analysis_file, load_analysis};
+    use crate::fault_list_manager::type_size::PointerWidth;
+    use crate::injector::label_trace;
     #[test]
     fn try_get_execution_times(){
         let faults = Faults {
             n_silent_fault: 1,
             n_assign_fault: 2,
             n_mul_fault: 3,
+            n_div_fault: 0,
+            n_bit_fault: 0,
             n_inner_fault: 3,
             n_sub_fault: 8,
             n_add_fault: 5,
@@ -304,7 +761,11 @@ mod tests{
             n_ord_fault: 8,
             n_partialord_fault: 9,
             n_partialeq_fault: 10,
+            n_depth_fault: 0,
             n_fatal_fault: 22,
+            n_tolerated_fault: 0,
+            n_cross_thread_detected: 0,
+            n_same_thread_detected: 0,
             total_fault: 55,
         };
         let mut analyzer = Analyzer::new(faults,389.0,1,"sel_sort".to_string());
@@ -324,6 +785,8 @@ mod tests{
             n_silent_fault: 1,
             n_assign_fault: 2,
             n_mul_fault: 3,
+            n_div_fault: 0,
+            n_bit_fault: 0,
             n_inner_fault: 3,
             n_sub_fault: 8,
             n_add_fault: 5,
@@ -332,7 +795,11 @@ mod tests{
             n_ord_fault: 8,
             n_partialord_fault: 9,
             n_partialeq_fault: 10,
+            n_depth_fault: 0,
             n_fatal_fault: 22,
+            n_tolerated_fault: 0,
+            n_cross_thread_detected: 0,
+            n_same_thread_detected: 0,
             total_fault: 55,
         };
         let mut analyzer = Analyzer::new(faults,389.0,1,"sel_sort".to_string());
@@ -350,6 +817,8 @@ mod tests{
             n_silent_fault: 1,
             n_assign_fault: 2,
             n_mul_fault: 3,
+            n_div_fault: 0,
+            n_bit_fault: 0,
             n_inner_fault: 3,
             n_sub_fault: 8,
             n_add_fault: 5,
@@ -358,7 +827,11 @@ mod tests{
             n_ord_fault: 8,
             n_partialord_fault: 9,
             n_partialeq_fault: 10,
+            n_depth_fault: 0,
             n_fatal_fault: 22,
+            n_tolerated_fault: 0,
+            n_cross_thread_detected: 0,
+            n_same_thread_detected: 0,
             total_fault: 55,
         };
         let ref_iter = &faults;
@@ -366,5 +839,72 @@ mod tests{
             println!("{}: {}", name, value.to_string());
         }
     }
-}
 
+    #[test]
+    fn try_check_distances(){
+        let graph = vec![vec![(1, 4), (2, 1)], vec![(3, 1)], vec![(1, 2), (3, 5)], vec![], vec![(0, 1)]];
+        assert!(check_distances(&graph, 0, &[0, 3, 1, 4, i32::MAX], false));
+        assert!(check_distances(&graph, 0, &[0, 1, 1, 2, i32::MAX], true));
+        //arco 2->1 non rilassato
+        assert!(!check_distances(&graph, 0, &[0, 4, 1, 5, i32::MAX], false));
+        //distanza troppo piccola: nessun predecessore la giustifica
+        assert!(!check_distances(&graph, 0, &[0, 2, 1, 3, i32::MAX], false));
+        //nodo raggiungibile marcato come irraggiungibile
+        assert!(!check_distances(&graph, 0, &[0, 3, 1, i32::MAX, i32::MAX], false));
+        //sorgente non a distanza 0
+        assert!(!check_distances(&graph, 4, &[1, 4, 2, 5, 1], false));
+    }
+
+    #[test]
+    fn try_classify_reals(){
+        let expected = vec![1.5, -0.25, 1000.0];
+        assert_eq!(classify_reals(&expected, &expected.clone()), OutputClass::Correct);
+        //flip del bit meno significativo della mantissa
+        let lsb = vec![1.5, f64::from_bits((-0.25f64).to_bits() ^ 1), 1000.0];
+        assert_eq!(classify_reals(&expected, &lsb), OutputClass::WithinTolerance);
+        //-0.0 e 0.0 sono uguali per IEEE ma non bit a bit
+        assert_eq!(classify_reals(&[0.0], &[-0.0]), OutputClass::WithinTolerance);
+        //flip di un bit dell'esponente
+        let exp = vec![1.5, -0.25, f64::from_bits(1000.0f64.to_bits() ^ (1 << 55))];
+        assert_eq!(classify_reals(&expected, &exp), OutputClass::Corrupted);
+        assert_eq!(classify_reals(&expected, &[1.5, f64::NAN, 1000.0]), OutputClass::Corrupted);
+    }
+
+    #[test]
+    fn try_space_estimate(){
+        //due esiti rilevati su una variabile sovra-campionata (peso 0.5) e uno silente su una
+        //sotto-campionata (peso 2): sullo spazio dei fault pesano allo stesso modo
+        let estimate = SpaceEstimate::new(2.0 * 0.5, 2.0, 0.0).unwrap();
+        assert_eq!(estimate, SpaceEstimate{ detected: 1.0 / 3.0, silent: 2.0 / 3.0, corrupted: 0.0 });
+        assert_eq!(SpaceEstimate::new(0.0, 0.0, 0.0), None);
+    }
+
+    #[test]
+    fn try_sdc_origins(){
+        let dest = temp_dir().join("bubble_sort_lines_test.json").to_string_lossy().to_string();
+        generate_analysis_file("src/fault_list_manager/file_fault_list/bubble_sort/mod.rs".to_string(), dest.clone(), PointerWidth::HOST).unwrap();
+        let statements = load_analysis(&dest, "bubble_sort").unwrap().statements;
+        //traccia su [2, 1]: i1..i10, i12, i3, i4, i5, i11. i12 è "i += 1" (riga 19): il break
+        //della riga 17 non invia etichette
+        let trace = label_trace("bubble_sort", Data::Vector(vec![2, 1]));
+        let mut all = LineMap::new("bubble_sort", trace.clone(), &statements);
+        let lines: Vec<usize> = (1..=trace.len()).map(|t| all.at(t).unwrap().line).collect();
+        assert_eq!(lines, vec![2, 3, 5, 6, 7, 9, 10, 11, 12, 14, 19, 5, 6, 7, 16]);
+
+        let mut line_map = LineMap::new("bubble_sort", trace, &statements);
+        line_map.at(8).unwrap().corrupted += 3;          //i8: vet.swap(j, j + 1)
+        line_map.at(10).unwrap().corrupted += 1;         //i10: j += 1
+        line_map.at(14).unwrap().detected += 2;          //i5: let mut j = 0
+        line_map.at(8).unwrap().silent += 1;
+        assert!(line_map.at(0).is_none() && line_map.at(16).is_none());
+
+        let mut analyzer = Analyzer::new(Faults::new(), 0.0, 0, "bubble_sort".to_string());
+        analyzer.source_lines = line_map.into_lines();
+        assert_eq!(analyzer.source_lines.iter().map(|line| line.line).collect::<Vec<_>>(), vec![7, 11, 14]);
+        assert_eq!(analyzer.source_lines[0], SourceLine{ line: 7, code: "let mut j = 0;".to_string(), detected: 2, ..SourceLine::default() });
+        let origins = analyzer.sdc_origins(3);
+        assert_eq!(origins.iter().map(|(share, line)| (*share, line.line)).collect::<Vec<_>>(), vec![(0.75, 11), (0.25, 14)]);
+        assert_eq!(origins[0].1.code, "vet.swap(j, j + 1);");
+        assert!(analyzer.sdc_origins(1).len() == 1);
+    }
+}
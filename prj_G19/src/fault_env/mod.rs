@@ -1,38 +1,49 @@
-use std::sync::mpsc::channel;
+use std::sync::mpsc::sync_channel;
+use std::thread;
 use std::time::Instant;
 use serde::{Deserialize, Serialize};
 use crate::analyzer::run_analyzer;
-use crate::fault_list_manager::fault_manager;
+use crate::fault_list_manager::{fault_manager, FaultSource};
 use crate::injector::injector_manager;
 
 //Al fine di generalizzare passo dei dati anziché un vec specifico
 #[derive(Serialize,Deserialize,Debug,Clone)]
-pub enum Data<T>{
+pub enum Data<T, R = f64>{
     Vector(Vec<T>),
-    Matrices(Vec<Vec<T>>, Vec<Vec<T>>)
+    Matrices(Vec<Vec<T>>, Vec<Vec<T>>),
+    Graph(AdjacencyList<T>, usize),     //liste di adiacenza e nodo sorgente
+    //I kernel numerici lavorano in virgola mobile: il loro tipo R è indipendente da T
+    Reals(Vec<R>, Vec<R>),              //due vettori reali (segnale e coefficienti)
+    LinearSystem(Vec<Vec<R>>, Vec<R>),  //matrice dei coefficienti e termini noti
+    //I casi di studio su stringhe e byte lavorano sempre su u8
+    Bytes(Vec<u8>),                     //messaggio di cui calcolare il CRC32
+    Text(String, String)                //testo e pattern da cercare
 }
 
+///Per ogni nodo, la lista degli archi uscenti (nodo destinazione, peso)
+pub type AdjacencyList<T> = Vec<Vec<(usize, T)>>;
+
 //Converte da Vec<T> a Data<T>
-impl<T> From<Vec<T>> for Data<T> {
+impl<T, R> From<Vec<T>> for Data<T, R> {
     fn from(vec: Vec<T>) -> Self {
         Data::Vector(vec)
     }
 }
 
 //Converte da (Vec<Vec<T>>,Vec<Vec<T>>) a Data<T>
-impl<T> From<(Vec<Vec<T>>, Vec<Vec<T>>)> for Data<T> {
+impl<T, R> From<(Vec<Vec<T>>, Vec<Vec<T>>)> for Data<T, R> {
     fn from(matrices: (Vec<Vec<T>>, Vec<Vec<T>>)) -> Self {
         Data::Matrices(matrices.0, matrices.1)
     }
 }
 
 //Converte da Vec<Vec<T>> a Data<T>
-impl<T> From<Vec<Vec<T>>> for Data<T> {
+impl<T, R> From<Vec<Vec<T>>> for Data<T, R> {
     fn from(matrix: Vec<Vec<T>>) -> Self {
         Data::Matrices(matrix, Vec::new())
     }
 }
-impl<T> Data<T>{
+impl<T, R> Data<T, R>{
     pub fn into_vector(self) ->Vec<T>{
         match self{
             Data::Vector(ris) =>{
@@ -55,9 +66,67 @@ impl<T> Data<T>{
 
         }
     }
+
+    pub fn into_reals(self) ->(Vec<R>, Vec<R>){
+        match self{
+            Data::Reals(x, y)=>{
+                (x, y)
+            },
+            _=>{
+                panic!("Not a reals variant");
+            }
+        }
+    }
+
+    pub fn into_linear_system(self) ->(Vec<Vec<R>>, Vec<R>){
+        match self{
+            Data::LinearSystem(a, b)=>{
+                (a, b)
+            },
+            _=>{
+                panic!("Not a linear system variant");
+            }
+        }
+    }
+
+    pub fn into_bytes(self) ->Vec<u8>{
+        match self{
+            Data::Bytes(bytes)=>{
+                bytes
+            },
+            _=>{
+                panic!("Not a bytes variant");
+            }
+        }
+    }
+
+    pub fn into_text(self) ->(String, String){
+        match self{
+            Data::Text(text, pattern)=>{
+                (text, pattern)
+            },
+            _=>{
+                panic!("Not a text variant");
+            }
+        }
+    }
+
+    pub fn into_graph(self) ->(AdjacencyList<T>, usize){
+        match self{
+            Data::Graph(adj, source)=>{
+                (adj, source)
+            },
+            _=>{
+                panic!("Not a graph variant");
+            }
+        }
+    }
 }
 
-pub fn fault_injection_env(fault_list: String,      // nome file fault-list
+///Capacità dei canali tra gli stage della pipeline
+pub const PIPELINE_BOUND: usize = 256;
+
+pub fn fault_injection_env(fault_list: impl Into<FaultSource>,     // fault list: file o entry generate
                            target: String,          // nome programma target
                            file_path: String,       // nome file report
                            data: Data<i32>,
@@ -65,12 +134,30 @@ pub fn fault_injection_env(fault_list: String,      // nome file fault-list
                            esecuzione:i8)
 {       // dati del problema
 
-    let (tx_chan_fm_inj, rx_chan_fm_inj) = channel();
-    let (tx_chan_inj_anl, rx_chan_inj_anl) = channel();
-    fault_manager(tx_chan_fm_inj,fault_list);
-    injector_manager(rx_chan_fm_inj, tx_chan_inj_anl, target.clone(), data.clone());
-    let execution_time = timer.elapsed().as_millis()as f64;
-    run_analyzer(rx_chan_inj_anl,file_path,data,target,esecuzione,execution_time);
+    //canali limitati: uno stage più veloce del successivo si ferma invece di accumulare entry
+    //e risultati in memoria
+    let (tx_chan_fm_inj, rx_chan_fm_inj) = sync_channel(PIPELINE_BOUND);
+    let (tx_chan_inj_anl, rx_chan_inj_anl) = sync_channel(PIPELINE_BOUND);
+    //una fault list generata per un altro target, con uno schema diverso o con entry fuori dallo
+    //spazio dei fault invaliderebbe l'intera campagna
+    let (fault_list_info, entries) = match fault_list.into().open(&target) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Fault list non valida: {}", e);
+            return;
+        }
+    };
+    //i tre stage lavorano in parallelo: l'iniettore parte dalla prima entry ricevuta e
+    //l'analyzer raccoglie i risultati man mano che le iniezioni terminano
+    thread::scope(|s| {
+        let fault_manager = s.spawn(move || fault_manager(tx_chan_fm_inj, entries));
+        s.spawn(|| injector_manager(rx_chan_fm_inj, tx_chan_inj_anl, target.clone(), data.clone()));
+        run_analyzer(rx_chan_inj_anl,file_path,data.clone(),target.clone(),esecuzione,timer,fault_list_info);
+        //un errore a metà fault list interrompe l'invio: il report copre solo le entry precedenti
+        if let Err(e) = fault_manager.join().unwrap() {
+            eprintln!("Fault list non valida, campagna interrotta: {}", e);
+        }
+    });
 /*
     sleep(Duration::from_secs(10));
     let target = vec![-32, -9, 1, 3, 10, 15, 16, 19, 20, 27];
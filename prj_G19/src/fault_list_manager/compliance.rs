@@ -0,0 +1,400 @@
+//! Controllo statico delle regole di irrobustimento su un file sorgente già irrobustito
+//! (es. "src/hardened/bubble_sort_hardened/mod.rs").
+//!
+//! Le violazioni cercate sono quelle che il compilatore non può segnalare:
+//! - Regola 1: variabili locali di tipo scalare non incapsulate in ```Hardened<T>```
+//!   (dichiarazioni "let" e indici dei cicli "for");
+//! - Regola 2: riassegnazione con '=' di un valore ```Hardened<T>``` invece di ```assign()```,
+//!   che non controlla la coerenza del valore assegnato;
+//! - Regola 3: accesso diretto a una copia (```x.cp1```, ```x["cp1"]```) che salta il controllo
+//!   di coerenza, e indici di ```swap()``` non estratti con ```inner()```.
+//!
+//! I blocchi impl di ```Hardened<T>``` sono l'implementazione delle regole stesse e non vengono
+//! controllati.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Expr, File, GenericArgument, ItemImpl, Lit, Pat, PathArguments, Type};
+use thiserror::Error;
+
+///Violazione cercata dal controllo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Violation{
+    PlainLocal,
+    DirectAssign,
+    CopyAccess,
+    UncheckedIndex,
+}
+
+impl Violation{
+    ///Regola di irrobustimento violata
+    pub fn rule(self)->usize{
+        match self {
+            Violation::PlainLocal => 1,
+            Violation::DirectAssign => 2,
+            Violation::CopyAccess | Violation::UncheckedIndex => 3,
+        }
+    }
+}
+
+///Violazione trovata: riga e colonna (da 1) del costrutto che la causa
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding{
+    pub violation: Violation,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for Finding{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: Regola {} ({:?}): {}", self.line, self.column, self.violation.rule(), self.violation, self.message)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ComplianceError{
+    #[error("ComplianceError::Io: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("ComplianceError::Parse: {0}")]
+    Parse(#[from] syn::Error),
+}
+
+const SCALARS: &[&str] = &["bool", "char", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64",
+    "u128", "usize", "f32", "f64"];
+
+///Numero di indicizzazioni che separano il tipo da un valore ```Hardened<T>```
+/// (0 per Hardened<usize>, 1 per Vec<Hardened<i32>> o &[Hardened<u8>], ...)
+fn hardened_depth(ty: &Type)->Option<usize>{
+    match ty {
+        Type::Reference(reference) => hardened_depth(&reference.elem),
+        Type::Paren(paren) => hardened_depth(&paren.elem),
+        Type::Slice(slice) => hardened_depth(&slice.elem).map(|depth| depth + 1),
+        Type::Array(array) => hardened_depth(&array.elem).map(|depth| depth + 1),
+        Type::Path(path) => {
+            let segment = path.path.segments.last()?;
+            match segment.ident.to_string().as_str() {
+                "Hardened" => Some(0),
+                "Vec" => match &segment.arguments {
+                    PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                        GenericArgument::Type(elem) => hardened_depth(elem).map(|depth| depth + 1),
+                        _ => None,
+                    }),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_scalar(ty: &Type)->bool{
+    matches!(ty, Type::Path(path) if path.path.get_ident().is_some_and(|ident| SCALARS.contains(&ident.to_string().as_str())))
+}
+
+///Nome della variabile legata da un pattern semplice ("x", "mut x", "x: T")
+fn binding(pat: &Pat)->Option<(String, Option<&Type>)>{
+    match pat {
+        Pat::Ident(ident) => Some((ident.ident.to_string(), None)),
+        Pat::Type(typed) => binding(&typed.pat).map(|(name, _)| (name, Some(&*typed.ty))),
+        _ => None,
+    }
+}
+
+///Toglie l'operatore '?', le parentesi e unwrap()/expect() che propagano l'errore
+fn strip(expr: &Expr)->&Expr{
+    match expr {
+        Expr::Try(inner) => strip(&inner.expr),
+        Expr::Paren(inner) => strip(&inner.expr),
+        Expr::Group(inner) => strip(&inner.expr),
+        Expr::MethodCall(call) if call.method == "unwrap" || call.method == "expect" => strip(&call.receiver),
+        _ => expr,
+    }
+}
+
+///Valore estratto con il controllo di coerenza
+fn is_checked(expr: &Expr)->bool{
+    matches!(strip(expr), Expr::MethodCall(call) if call.method == "inner")
+}
+
+///Espressione che produce uno scalare non irrobustito: letterali, lunghezze, valori già estratti
+/// con inner() e cast
+fn is_plain(expr: &Expr)->bool{
+    match strip(expr) {
+        Expr::Lit(lit) => matches!(lit.lit, Lit::Int(_) | Lit::Float(_) | Lit::Bool(_) | Lit::Char(_)),
+        Expr::MethodCall(call) => ["len", "inner", "count"].contains(&call.method.to_string().as_str()),
+        Expr::Cast(_) => true,
+        Expr::Unary(unary) => is_plain(&unary.expr),
+        Expr::Binary(binary) => is_plain(&binary.left) && is_plain(&binary.right),
+        _ => false,
+    }
+}
+
+///Variabile alla base di una posizione (vet[i][j] -> vet) e numero di indicizzazioni
+fn place(expr: &Expr)->Option<(String, usize)>{
+    match expr {
+        Expr::Path(path) => path.path.get_ident().map(|ident| (ident.to_string(), 0)),
+        Expr::Index(index) => place(&index.expr).map(|(name, depth)| (name, depth + 1)),
+        Expr::Unary(unary) => place(&unary.expr),
+        Expr::Paren(paren) => place(&paren.expr),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct Checker{
+    //variabili irrobustite della funzione corrente, con la profondità di hardened_depth()
+    hardened: HashMap<String, usize>,
+    findings: Vec<Finding>,
+}
+
+impl Checker{
+    fn report(&mut self, violation: Violation, span: Span, message: String){
+        let start = span.start();
+        self.findings.push(Finding{ violation, line: start.line, column: start.column + 1, message });
+    }
+
+    ///Profondità del valore prodotto dall'inizializzatore, se è irrobustito
+    fn init_depth(&self, expr: &Expr)->Option<usize>{
+        match strip(expr) {
+            Expr::Call(call) => match &*call.func {
+                Expr::Path(path) if path.path.segments.first().is_some_and(|s| s.ident == "Hardened") =>
+                    match path.path.segments.last()?.ident.to_string().as_str() {
+                        "from_vec" => Some(1),
+                        "from_mat" => Some(2),
+                        _ => Some(0),
+                    },
+                _ => None,
+            },
+            Expr::Macro(mac) if mac.mac.path.is_ident("vec") => {
+                //vec![Hardened::from(0); n]: basta che l'elemento sia irrobustito
+                let first = syn::parse2::<Expr>(mac.mac.tokens.clone().into_iter()
+                    .take_while(|token| !matches!(token, proc_macro2::TokenTree::Punct(p) if p.as_char() == ';' || p.as_char() == ','))
+                    .collect()).ok()?;
+                self.init_depth(&first).map(|depth| depth + 1)
+            }
+            Expr::Binary(binary) => self.init_depth(&binary.left).or_else(|| self.init_depth(&binary.right))
+                .filter(|&depth| depth == 0),
+            Expr::Reference(reference) => self.init_depth(&reference.expr),
+            inner => place(inner).and_then(|(name, indices)| self.hardened.get(&name)?.checked_sub(indices)),
+        }
+    }
+
+    fn declare(&mut self, name: String, depth: Option<usize>){
+        match depth {
+            Some(depth) => self.hardened.insert(name, depth),
+            None => self.hardened.remove(&name),
+        };
+    }
+}
+
+impl<'ast> Visit<'ast> for Checker{
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        if hardened_depth(&node.self_ty) == Some(0) {
+            return;
+        }
+        syn::visit::visit_item_impl(self, node);
+    }
+
+    fn visit_signature(&mut self, node: &'ast syn::Signature) {
+        //ogni funzione ha le proprie variabili
+        self.hardened.clear();
+        for input in &node.inputs {
+            if let syn::FnArg::Typed(arg) = input {
+                if let Some((name, _)) = binding(&arg.pat) {
+                    self.declare(name, hardened_depth(&arg.ty));
+                }
+            }
+        }
+        syn::visit::visit_signature(self, node);
+    }
+
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        //l'inizializzatore è valutato prima che la variabile esista
+        if let Some(init) = &node.init {
+            self.visit_expr(&init.expr);
+        }
+        if let Some((name, ty)) = binding(&node.pat) {
+            let init = node.init.as_ref().map(|init| &*init.expr);
+            let depth = match ty {
+                Some(ty) => hardened_depth(ty),
+                None => init.and_then(|init| self.init_depth(init)),
+            };
+            if ty.is_some_and(is_scalar) || (ty.is_none() && init.is_some_and(is_plain)) {
+                self.report(Violation::PlainLocal, node.pat.span(),
+                            format!("la variabile '{}' è uno scalare senza copia: va dichiarata Hardened", name));
+            }
+            self.declare(name, depth);
+        }
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'ast syn::ExprForLoop) {
+        if matches!(strip(&node.expr), Expr::Range(_)) {
+            if let Some((name, _)) = binding(&node.pat) {
+                self.report(Violation::PlainLocal, node.pat.span(),
+                            format!("l'indice '{}' del ciclo for non è irrobustito: va usato un ciclo while su un Hardened", name));
+                self.declare(name, None);
+            }
+        }
+        syn::visit::visit_expr_for_loop(self, node);
+    }
+
+    fn visit_expr_assign(&mut self, node: &'ast syn::ExprAssign) {
+        if let Some((name, indices)) = place(&node.left) {
+            if self.hardened.get(&name) == Some(&indices) {
+                let left = &node.left;
+                let target = quote::quote!(#left).to_string().replace(' ', "");
+                self.report(Violation::DirectAssign, node.span(),
+                            format!("'{}' è Hardened: va riassegnato con {}.assign(...) che controlla il valore", target, target));
+            }
+        }
+        syn::visit::visit_expr_assign(self, node);
+    }
+
+    fn visit_expr_field(&mut self, node: &'ast syn::ExprField) {
+        if let syn::Member::Named(member) = &node.member {
+            if member == "cp1" || member == "cp2" {
+                self.report(Violation::CopyAccess, node.span(),
+                            format!("accesso diretto a '{}' senza controllo di coerenza: va usato inner()", member));
+            }
+        }
+        syn::visit::visit_expr_field(self, node);
+    }
+
+    fn visit_expr_index(&mut self, node: &'ast syn::ExprIndex) {
+        if let Expr::Lit(syn::ExprLit{ lit: Lit::Str(copy), .. }) = &*node.index {
+            if copy.value() == "cp1" || copy.value() == "cp2" {
+                self.report(Violation::CopyAccess, node.span(),
+                            format!("accesso diretto a [\"{}\"] senza controllo di coerenza: va usato inner()", copy.value()));
+            }
+        }
+        syn::visit::visit_expr_index(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if node.method == "swap" {
+            for arg in node.args.iter().filter(|arg| !is_checked(arg)) {
+                let index = quote::quote!(#arg).to_string();
+                self.report(Violation::UncheckedIndex, arg.span(),
+                            format!("l'indice '{}' di swap() non è estratto con inner()", index));
+            }
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+///Violazioni nel sorgente, nell'ordine in cui compaiono
+pub fn check_source(code: &str)->Result<Vec<Finding>, ComplianceError>{
+    let file: File = syn::parse_str(code)?;
+    let mut checker = Checker::default();
+    checker.visit_file(&file);
+    checker.findings.sort_by_key(|finding| (finding.line, finding.column));
+    Ok(checker.findings)
+}
+
+pub const USAGE: &str = "uso:
+    lint <file.rs> [<file.rs> ...]";
+
+///Sottocomando "lint": il comando fallisce se almeno un file viola le regole
+pub fn run_command(args: &[String])->Result<String, String>{
+    if args.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    let mut report = Vec::new();
+    let mut total = 0;
+    for path in args {
+        let code = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, ComplianceError::from(e)))?;
+        let findings = check_source(&code).map_err(|e| format!("{}: {}", path, e))?;
+        let lines: Vec<&str> = code.lines().collect();
+        for finding in &findings {
+            report.push(format!("{}:{}", path, finding));
+            report.push(format!("    {}", lines.get(finding.line - 1).map_or("", |line| line.trim())));
+        }
+        total += findings.len();
+    }
+    if total > 0 {
+        return Err(format!("{} violazioni delle regole di irrobustimento\n{}", total, report.join("\n")));
+    }
+    Ok(format!("{} file conformi alle regole di irrobustimento", args.len()))
+}
+
+#[cfg(test)]
+mod tests{
+    use std::fs;
+    use crate::fault_list_manager::compliance::{check_source, Finding, Violation};
+
+    fn check_file(path: &str)->Vec<Finding>{
+        check_source(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    fn violations(code: &str)->Vec<(Violation, usize)>{
+        check_source(code).unwrap().iter().map(|finding| (finding.violation, finding.line)).collect()
+    }
+
+    #[test]
+    fn test_hardened_sources(){
+        //tutti i sorgenti irrobustiti: l'unica violazione è nel bubble sort, dove 'swapped' viene
+        //riassegnato con '='
+        let mut checked = 0;
+        for dir in fs::read_dir("src/hardened").unwrap() {
+            let path = dir.unwrap().path().join("mod.rs");
+            if !path.is_file() {
+                continue;
+            }
+            checked += 1;
+            let findings = check_file(path.to_str().unwrap());
+            if path.starts_with("src/hardened/bubble_sort_hardened") {
+                assert_eq!(findings.iter().map(|f| (f.violation, f.line, f.column)).collect::<Vec<_>>(),
+                           vec![(Violation::DirectAssign, 15, 17)]);
+                assert!(findings[0].message.contains("swapped.assign"));
+            } else {
+                assert!(findings.is_empty(), "{}: {:?}", path.display(), findings);
+            }
+        }
+        assert_eq!(checked, 13);
+    }
+
+    #[test]
+    fn test_violations(){
+        let code = "
+fn sort(vet: &mut Vec<Hardened<i32>>, mat: Vec<Vec<Hardened<i32>>>, k: usize) -> Result<(), IncoherenceError> {
+    let n = vet.len();
+    let mut i: usize = 0;
+    let mut j = Hardened::from(0);
+    let copy = vet[j];
+    for h in 0..n {}
+    j = Hardened::from(1);
+    vet[j] = copy;
+    mat[0][1] = copy;
+    let m = vet[0].cp1;
+    vet[1][\"cp2\"] = 3;
+    vet.swap(j.inner()?, i);
+    vet.swap((j + 1)?.inner().unwrap(), j[\"cp1\"]);
+    let mut rows = Vec::new();
+    rows = Vec::new();
+    Ok(())
+}
+impl<T> Hardened<T> {
+    fn get(&self) -> T { self.cp1 }
+}";
+        assert_eq!(violations(code), vec![
+            (Violation::PlainLocal, 3),
+            (Violation::PlainLocal, 4),
+            (Violation::PlainLocal, 7),
+            (Violation::DirectAssign, 8),
+            (Violation::DirectAssign, 9),
+            (Violation::DirectAssign, 10),
+            (Violation::CopyAccess, 11),
+            (Violation::CopyAccess, 12),
+            (Violation::UncheckedIndex, 13),
+            (Violation::UncheckedIndex, 14),
+            (Violation::CopyAccess, 14),
+        ]);
+        assert!(check_source("fn f( {").is_err());
+    }
+}
@@ -0,0 +1,628 @@
+//! Grafo di controllo di flusso (CFG) di ogni funzione di un file sorgente.
+//!
+//! I nodi sono blocchi base: sequenze di istruzioni eseguite sempre tutte, dalla prima all'ultima.
+//! Le istruzioni sono numerate da 1 come in StatementCounter (nel DOT l'istruzione k è "iK"); le
+//! etichette inviate dai runner non seguono sempre questa numerazione, e la corrispondenza è
+//! nelle tabelle di injector::labels. Un'istruzione può comparire in più blocchi: un if o un
+//! match valutano la condizione nel blocco corrente e si riuniscono in uno nuovo, un operatore ?
+//! chiude il blocco perché può uscire dalla funzione.
+//! Ogni ciclo (while, for, loop) inizia con un blocco di testa che contiene l'istruzione del
+//! ciclo; anche il corpo di una closure è una testa, perché può essere eseguito più volte.
+//! Ingresso e uscita della funzione sono due blocchi vuoti, 0 e 1.
+//!
+//! Il grafo si esporta in JSON o in DOT (Graphviz) con il sottocomando "cfg":
+//! ```dot -Tpng bubble_sort.dot -o bubble_sort.png``` lo disegna.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::mem;
+use serde::{Deserialize, Serialize};
+use syn::{Block, Expr, ExprClosure, ExprForLoop, ExprIf, ExprLoop, ExprMatch, ExprWhile, File, Item, Signature, Stmt};
+use syn::visit::{self, Visit};
+use itertools::Itertools;
+use crate::fault_list_manager::static_analysis::{visit_functions, AnalysisError, SourceSpan};
+
+pub const ENTRY: usize = 0;
+pub const EXIT: usize = 1;
+
+///Blocco base: istruzioni (da 1) nell'ordine di esecuzione e blocchi successori
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BasicBlock{
+    pub id: usize,
+    pub statements: Vec<usize>,
+    pub succ: Vec<usize>,
+    #[serde(default)]
+    pub loop_header: bool,
+}
+
+///Grafo di una funzione: blocks[0] è l'ingresso, blocks[1] l'uscita; statements[k-1] è la
+/// posizione nel sorgente dell'istruzione k
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ControlFlowGraph{
+    pub blocks: Vec<BasicBlock>,
+    pub statements: Vec<SourceSpan>,
+}
+
+impl ControlFlowGraph{
+    pub fn build(block: &Block)->Self{
+        let mut builder = Builder{ blocks: vec![BasicBlock::default(), BasicBlock::default()], ..Builder::default() };
+        builder.cur = builder.block(&[ENTRY]);
+        builder.visit_block(block);
+        builder.edge(builder.cur, EXIT);
+        ControlFlowGraph{ blocks: simplify(builder.blocks), statements: builder.statements }
+    }
+
+    ///Archi all'indietro: da un blocco del corpo di un ciclo alla sua testa. I blocchi sono
+    /// numerati nell'ordine del sorgente, quindi il corpo segue sempre la testa
+    pub fn back_edges(&self)->Vec<(usize, usize)>{
+        self.blocks.iter()
+            .flat_map(|block| block.succ.iter().map(move |&succ| (block.id, succ)))
+            .filter(|&(from, to)| self.blocks[to].loop_header && from >= to)
+            .collect()
+    }
+
+    ///true se l'istruzione k chiude un blocco con più successori (condizione di un if, di un
+    /// ciclo o di un match)
+    pub fn branches(&self, k: usize)->bool{
+        self.blocks.iter().any(|block| block.statements.last() == Some(&k) && block.succ.len() > 1)
+    }
+
+    ///Istruzioni eseguite dopo 'from' fino a 'to' compresa, lungo il percorso più breve che
+    /// attraversa solo istruzioni per cui 'silent' è vero. 'from' None è l'ingresso della
+    /// funzione, 'to' None l'uscita (che non compare nel risultato). Se un'istruzione prosegue in
+    /// più blocchi la si conta una volta sola, e 'to' si esegue di nuovo solo dal primo blocco
+    /// che la contiene. None se non esiste un percorso
+    pub fn path(&self, from: Option<usize>, to: Option<usize>, silent: impl Fn(usize)->bool)->Option<Vec<usize>>{
+        //nodo: (blocco, posizione); la posizione oltre l'ultima istruzione è la fine del blocco
+        let starts: Vec<(usize, usize)> = match from {
+            None => vec![(ENTRY, 0)],
+            Some(k) => self.blocks.iter()
+                .flat_map(|block| block.statements.iter().positions(move |&s| s == k).map(move |i| (block.id, i + 1)))
+                .collect(),
+        };
+        let first = to.and_then(|k| self.blocks.iter().position(|block| block.statements.contains(&k)));
+        let mut parent: BTreeMap<(usize, usize), Option<(usize, usize)>> = starts.iter().map(|&node| (node, None)).collect();
+        let mut queue: std::collections::VecDeque<(usize, usize)> = starts.into();
+        let mut found = None;
+        while let Some((b, i)) = queue.pop_front() {
+            let block = &self.blocks[b];
+            let next: Vec<(usize, usize)> = match block.statements.get(i) {
+                Some(&k) if Some(k) == to && Some(b) == first => { found = Some((b, i)); break; }
+                Some(&k) if Some(k) == from || silent(k) => vec![(b, i + 1)],
+                Some(_) => Vec::new(),
+                None if b == EXIT && to.is_none() => { found = Some((b, i)); break; }
+                None => block.succ.iter().map(|&succ| (succ, 0)).collect(),
+            };
+            for node in next {
+                if let std::collections::btree_map::Entry::Vacant(e) = parent.entry(node) {
+                    e.insert(Some((b, i)));
+                    queue.push_back(node);
+                }
+            }
+        }
+        let mut node = Some(found?);
+        let mut statements = Vec::new();
+        while let Some((b, i)) = node {
+            statements.extend(self.blocks[b].statements.get(i).copied());
+            node = parent[&(b, i)];
+        }
+        statements.reverse();
+        statements.dedup();
+        Some(statements.into_iter().skip_while(|&k| Some(k) == from).collect())
+    }
+
+    //nodi e archi in sintassi DOT; 'prefix' distingue i nodi di funzioni diverse
+    fn write_dot(&self, prefix: &str, out: &mut Vec<String>){
+        let back_edges = self.back_edges();
+        for block in &self.blocks {
+            let label = match block.id {
+                ENTRY => "ingresso".to_string(),
+                EXIT => "uscita".to_string(),
+                _ => block.statements.iter()
+                    .map(|&k| format!("i{}: {}\\l", k, escape(&self.statements[k - 1].to_string())))
+                    .join(""),
+            };
+            let shape = match block.id {
+                ENTRY | EXIT => ", shape=oval",
+                _ if block.loop_header => ", peripheries=2",
+                _ => "",
+            };
+            out.push(format!("        {}b{} [label=\"{}\"{}];", prefix, block.id, label, shape));
+        }
+        for block in &self.blocks {
+            for &succ in &block.succ {
+                let style = if back_edges.contains(&(block.id, succ)) { " [style=dashed]" } else { "" };
+                out.push(format!("        {}b{} -> {}b{}{};", prefix, block.id, prefix, succ, style));
+            }
+        }
+    }
+}
+
+fn escape(text: &str)->String{
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+///Grafi di tutte le funzioni in un unico documento DOT, un riquadro per funzione. Le teste dei
+/// cicli hanno il bordo doppio, gli archi all'indietro sono tratteggiati
+pub fn to_dot(graphs: &BTreeMap<String, ControlFlowGraph>)->String{
+    let mut out = vec!["digraph cfg {".to_string(), "    node [shape=box, fontname=\"monospace\"];".to_string()];
+    for (n, (path, graph)) in graphs.iter().enumerate() {
+        out.push(format!("    subgraph cluster_{} {{", n));
+        out.push(format!("        label=\"{}\";", escape(path)));
+        graph.write_dot(&format!("f{}_", n), &mut out);
+        out.push("    }".to_string());
+    }
+    out.push("}".to_string());
+    out.join("\n")
+}
+
+///Grafi delle funzioni del sorgente, indicizzati per percorso come nel file di analisi
+pub fn control_flow_graphs(code: &str)->Result<BTreeMap<String, ControlFlowGraph>, AnalysisError>{
+    let file: File = syn::parse_str(code).map_err(|e| AnalysisError::Parse(e.to_string()))?;
+    let mut graphs = BTreeMap::new();
+    visit_functions(&file.items, "", &mut |path, _: &Signature, block| {
+        graphs.insert(path, ControlFlowGraph::build(block));
+    });
+    Ok(graphs)
+}
+
+//Elimina i blocchi irraggiungibili e quelli vuoti di passaggio, poi unisce le catene di blocchi
+//senza diramazioni. I blocchi rimasti vengono rinumerati nell'ordine di creazione
+fn simplify(mut blocks: Vec<BasicBlock>)->Vec<BasicBlock>{
+    //un blocco vuoto con un solo successore (es. il punto in cui si riuniscono i rami) viene
+    //sostituito dal successore
+    let passthrough = |blocks: &[BasicBlock], id: usize| {
+        let block = &blocks[id];
+        id > EXIT && block.statements.is_empty() && !block.loop_header && block.succ.len() == 1 && block.succ[0] != id
+    };
+    for id in 0..blocks.len() {
+        let succ = blocks[id].succ.iter()
+            .map(|&succ| {
+                let mut target = succ;
+                for _ in 0..blocks.len() {
+                    if !passthrough(&blocks, target) { break; }
+                    target = blocks[target].succ[0];
+                }
+                target
+            })
+            .unique()
+            .collect();
+        blocks[id].succ = succ;
+    }
+
+    let mut alive = reachable(&blocks);
+    alive[EXIT] = true;
+    let preds = |blocks: &[BasicBlock], alive: &[bool], id: usize| (0..blocks.len())
+        .filter(|&pred| alive[pred] && blocks[pred].succ.contains(&id))
+        .count();
+    for id in EXIT + 1..blocks.len() {
+        while alive[id] {
+            let [next] = blocks[id].succ[..] else { break };
+            if next <= EXIT || next == id || blocks[next].loop_header || preds(&blocks, &alive, next) != 1 {
+                break;
+            }
+            let next_block = mem::take(&mut blocks[next]);
+            for k in next_block.statements {
+                if blocks[id].statements.last() != Some(&k) {
+                    blocks[id].statements.push(k);
+                }
+            }
+            blocks[id].succ = next_block.succ;
+            alive[next] = false;
+        }
+    }
+
+    let ids: Vec<usize> = (0..blocks.len()).filter(|&id| alive[id]).collect();
+    let renumber: BTreeMap<usize, usize> = ids.iter().enumerate().map(|(new, &old)| (old, new)).collect();
+    ids.iter()
+        .map(|&old| {
+            let block = mem::take(&mut blocks[old]);
+            BasicBlock{ id: renumber[&old], succ: block.succ.iter().map(|succ| renumber[succ]).collect(), ..block }
+        })
+        .collect()
+}
+
+fn reachable(blocks: &[BasicBlock])->Vec<bool>{
+    let mut alive = vec![false; blocks.len()];
+    let mut stack = vec![ENTRY];
+    while let Some(id) = stack.pop() {
+        if !mem::replace(&mut alive[id], true) {
+            stack.extend(blocks[id].succ.iter().copied());
+        }
+    }
+    alive
+}
+
+//Ciclo in corso di visita: destinazione di continue e blocchi da cui si esce con break
+struct Loop{
+    label: Option<String>,
+    head: usize,
+    breaks: Vec<usize>,
+}
+
+#[derive(Default)]
+struct Builder{
+    blocks: Vec<BasicBlock>,
+    statements: Vec<SourceSpan>,
+    owner: Option<usize>,   //istruzione in corso di visita
+    cur: usize,             //blocco a cui si aggiungono le istruzioni
+    loops: Vec<Loop>,
+    exit: Option<usize>,    //destinazione dei return (None: uscita della funzione)
+}
+
+impl Builder{
+    fn block(&mut self, preds: &[usize])->usize{
+        let id = self.blocks.len();
+        self.blocks.push(BasicBlock{ id, ..BasicBlock::default() });
+        for &pred in preds {
+            self.edge(pred, id);
+        }
+        id
+    }
+
+    fn edge(&mut self, from: usize, to: usize){
+        if !self.blocks[from].succ.contains(&to) {
+            self.blocks[from].succ.push(to);
+        }
+    }
+
+    fn push(&mut self, k: usize){
+        if self.blocks[self.cur].statements.last() != Some(&k) {
+            self.blocks[self.cur].statements.push(k);
+        }
+    }
+
+    //dopo break, continue e return il codice successivo non è raggiungibile da qui
+    fn unreachable(&mut self){
+        self.cur = self.block(&[]);
+    }
+
+    //nuova istruzione contata, nel blocco corrente
+    fn statement(&mut self, span: SourceSpan)->Option<usize>{
+        self.statements.push(span);
+        let k = self.statements.len();
+        self.push(k);
+        self.owner.replace(k)
+    }
+
+    //il codice successivo prosegue in un nuovo blocco, successore di 'preds'
+    fn split(&mut self, preds: &[usize]){
+        self.cur = self.block(preds);
+    }
+
+    //come split, ma nel nuovo blocco prosegue anche l'istruzione corrente
+    fn resume(&mut self, preds: &[usize]){
+        self.split(preds);
+        if let Some(k) = self.owner {
+            self.push(k);
+        }
+    }
+
+    //testa di un ciclo: l'istruzione del ciclo si sposta dal blocco corrente alla testa
+    fn header(&mut self)->usize{
+        let block = &mut self.blocks[self.cur];
+        if self.owner.is_some() && block.statements.last() == self.owner.as_ref() {
+            block.statements.pop();
+        }
+        self.resume(&[self.cur]);
+        self.blocks[self.cur].loop_header = true;
+        self.cur
+    }
+
+    //corpo di un ciclo a partire da 'from': alla fine del corpo si torna alla testa
+    fn loop_body(&mut self, label: Option<String>, head: usize, from: usize, body: &Block)->Vec<usize>{
+        self.loops.push(Loop{ label, head, breaks: Vec::new() });
+        self.cur = self.block(&[from]);
+        self.visit_block(body);
+        self.edge(self.cur, head);
+        self.loops.pop().unwrap().breaks
+    }
+
+    fn find_loop(&mut self, label: Option<&syn::Lifetime>)->Option<&mut Loop>{
+        let label = label.map(|label| label.ident.to_string());
+        self.loops.iter_mut().rev().find(|l| label.is_none() || l.label == label)
+    }
+}
+
+impl<'ast> Visit<'ast> for Builder{
+    fn visit_stmt(&mut self, stmt: &'ast Stmt){
+        if let Stmt::Item(_) = stmt {
+            return;
+        }
+        let owner = self.statement(SourceSpan::of_stmt(stmt));
+        match stmt {
+            //il ramo else di un let-else non prosegue
+            Stmt::Local(syn::Local{ init: Some(syn::LocalInit{ expr, diverge: Some((_, diverge)), .. }), .. }) => {
+                self.visit_expr(expr);
+                let after = self.cur;
+                self.cur = self.block(&[after]);
+                self.visit_expr(diverge);
+                self.resume(&[after]);
+            }
+            _ => visit::visit_stmt(self, stmt),
+        }
+        self.owner = owner;
+    }
+
+    fn visit_item(&mut self, _item: &'ast Item){}
+
+    fn visit_expr_if(&mut self, expr_if: &'ast ExprIf){
+        self.visit_expr(&expr_if.cond);
+        let cond = self.cur;
+        self.cur = self.block(&[cond]);
+        self.visit_block(&expr_if.then_branch);
+        let mut outs = vec![self.cur];
+        match &expr_if.else_branch {
+            Some((_, else_branch)) => {
+                self.cur = self.block(&[cond]);
+                self.visit_expr(else_branch);
+                outs.push(self.cur);
+            }
+            None => outs.push(cond),
+        }
+        self.split(&outs);
+    }
+
+    fn visit_expr_while(&mut self, expr_while: &'ast ExprWhile){
+        let head = self.header();
+        self.visit_expr(&expr_while.cond);
+        let cond = self.cur;
+        let label = expr_while.label.as_ref().map(|label| label.name.ident.to_string());
+        let mut outs = self.loop_body(label, head, cond, &expr_while.body);
+        outs.push(cond);
+        self.split(&outs);
+    }
+
+    fn visit_expr_for_loop(&mut self, expr_for: &'ast ExprForLoop){
+        self.visit_expr(&expr_for.expr);
+        let head = self.header();
+        let label = expr_for.label.as_ref().map(|label| label.name.ident.to_string());
+        let mut outs = self.loop_body(label, head, head, &expr_for.body);
+        outs.push(head);
+        self.split(&outs);
+    }
+
+    fn visit_expr_loop(&mut self, expr_loop: &'ast ExprLoop){
+        let head = self.header();
+        let label = expr_loop.label.as_ref().map(|label| label.name.ident.to_string());
+        let outs = self.loop_body(label, head, head, &expr_loop.body);
+        self.split(&outs);
+    }
+
+    fn visit_expr_match(&mut self, expr_match: &'ast ExprMatch){
+        self.visit_expr(&expr_match.expr);
+        let scrutinee = self.cur;
+        let mut outs = Vec::new();
+        for arm in &expr_match.arms {
+            let owner = self.owner;
+            self.cur = self.block(&[scrutinee]);
+            //un braccio senza blocco è un'istruzione a sé
+            if !matches!(&*arm.body, Expr::Block(_)) {
+                self.statement(SourceSpan::of(&arm.body));
+            }
+            if let Some((_, guard)) = &arm.guard {
+                self.visit_expr(guard);
+            }
+            self.visit_expr(&arm.body);
+            outs.push(self.cur);
+            self.owner = owner;
+        }
+        self.split(&outs);
+    }
+
+    fn visit_expr_break(&mut self, expr_break: &'ast syn::ExprBreak){
+        if let Some(expr) = &expr_break.expr {
+            self.visit_expr(expr);
+        }
+        let cur = self.cur;
+        if let Some(target) = self.find_loop(expr_break.label.as_ref()) {
+            target.breaks.push(cur);
+        }
+        self.unreachable();
+    }
+
+    fn visit_expr_continue(&mut self, expr_continue: &'ast syn::ExprContinue){
+        if let Some(head) = self.find_loop(expr_continue.label.as_ref()).map(|target| target.head) {
+            self.edge(self.cur, head);
+        }
+        self.unreachable();
+    }
+
+    fn visit_expr_return(&mut self, expr_return: &'ast syn::ExprReturn){
+        if let Some(expr) = &expr_return.expr {
+            self.visit_expr(expr);
+        }
+        self.edge(self.cur, self.exit.unwrap_or(EXIT));
+        self.unreachable();
+    }
+
+    //l'operatore ? può uscire dalla funzione: chiude il blocco
+    fn visit_expr_try(&mut self, expr_try: &'ast syn::ExprTry){
+        self.visit_expr(&expr_try.expr);
+        let cur = self.cur;
+        self.edge(cur, self.exit.unwrap_or(EXIT));
+        self.resume(&[cur]);
+    }
+
+    //il corpo di una closure può essere eseguito un numero qualunque di volte, anche nessuna
+    fn visit_expr_closure(&mut self, closure: &'ast ExprClosure){
+        let before = self.cur;
+        let after = self.block(&[before]);
+        let exit = self.exit.replace(after);
+        let loops = mem::take(&mut self.loops);
+        let owner = self.owner;
+        self.cur = self.block(&[before]);
+        self.blocks[self.cur].loop_header = true;
+        let body = self.cur;
+        if !matches!(&*closure.body, Expr::Block(_)) {
+            self.statement(SourceSpan::of(&*closure.body));
+        }
+        self.visit_expr(&closure.body);
+        self.edge(self.cur, body);
+        self.edge(self.cur, after);
+        self.owner = owner;
+        self.loops = loops;
+        self.exit = exit;
+        self.cur = after;
+        if let Some(k) = owner {
+            self.push(k);
+        }
+    }
+}
+
+pub const USAGE: &str = "uso:
+    cfg <file.rs> -o <dest.json|dest.dot> [--function <nome>]";
+
+///Sottocomando "cfg": salva i grafi delle funzioni del file (o della sola 'nome', per percorso
+/// completo o solo nome) in JSON o in DOT, secondo l'estensione della destinazione
+pub fn run_command(args: &[String])->Result<String, String>{
+    let mut src = None;
+    let mut dest = None;
+    let mut function = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("manca il valore di {}", arg));
+        match arg.as_str() {
+            "-o" | "--output" => dest = Some(value()?.clone()),
+            "--function" => function = Some(value()?.clone()),
+            _ if arg.starts_with('-') => return Err(format!("opzione sconosciuta: {}\n{}", arg, USAGE)),
+            _ if src.is_none() => src = Some(arg.clone()),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let src = src.ok_or(USAGE.to_string())?;
+    let dest = dest.ok_or(format!("manca il file di destinazione (-o)\n{}", USAGE))?;
+    let code = fs::read_to_string(&src).map_err(|e| format!("{}: {}", src, AnalysisError::from(e)))?;
+    let mut graphs = control_flow_graphs(&code).map_err(|e| format!("{}: {}", src, e))?;
+    if let Some(name) = function {
+        graphs.retain(|path, _| *path == name || path.rsplit("::").next() == Some(name.as_str()));
+        if graphs.is_empty() {
+            return Err(AnalysisError::UnknownFunction{ name, available: control_flow_graphs(&code).unwrap().keys().join(", ") }.to_string());
+        }
+    }
+    let content = match dest.ends_with(".dot") {
+        true => to_dot(&graphs),
+        false => serde_json::to_string_pretty(&graphs).map_err(|e| e.to_string())?,
+    };
+    fs::write(&dest, content).map_err(|e| format!("{}: {}", dest, e))?;
+    let blocks: usize = graphs.values().map(|graph| graph.blocks.len()).sum();
+    Ok(format!("{} funzioni, {} blocchi base scritti in {}", graphs.len(), blocks, dest))
+}
+
+#[cfg(test)]
+mod tests{
+    use std::collections::BTreeMap;
+    use std::fs;
+    use crate::fault_list_manager::control_flow::{control_flow_graphs, run_command, to_dot, ControlFlowGraph};
+    use crate::fault_list_manager::static_analysis::{analyze_function, visit_functions, FileContext};
+
+    fn graph(code: &str)->ControlFlowGraph{
+        control_flow_graphs(code).unwrap().into_values().next().unwrap()
+    }
+
+    //(istruzioni, successori, testa di un ciclo) di ogni blocco
+    fn blocks(graph: &ControlFlowGraph)->Vec<(Vec<usize>, Vec<usize>, bool)>{
+        graph.blocks.iter().map(|block| (block.statements.clone(), block.succ.clone(), block.loop_header)).collect()
+    }
+
+    #[test]
+    fn test_bubble_sort(){
+        let graph = graph(&fs::read_to_string("src/fault_list_manager/file_fault_list/bubble_sort/mod.rs").unwrap());
+        assert_eq!(blocks(&graph), vec![
+            (vec![], vec![2], false), (vec![], vec![], false),
+            (vec![1, 2], vec![3], false),
+            (vec![3], vec![4, 12], true),           //while i < n
+            (vec![4, 5], vec![5], false),
+            (vec![6], vec![6, 9], true),            //while j < n - i - 1
+            (vec![7], vec![7, 8], false),
+            (vec![8, 9], vec![8], false),
+            (vec![10], vec![5], false),
+            (vec![11], vec![10, 11], false),
+            (vec![12], vec![12], false),            //break
+            (vec![13], vec![3], false),
+            (vec![14], vec![1], false),
+        ]);
+        assert_eq!(graph.back_edges(), vec![(8, 5), (11, 3)]);
+        assert_eq!(graph.statements[5].to_string(), "riga 9 `while j < n - i - 1 {`");
+    }
+
+    #[test]
+    fn test_closures_try_and_continue(){
+        let graph = graph(r#"
+            fn f(v: Vec<i32>)->Option<i32>{
+                let mut acc = 0;                                            //1
+                for e in v.iter() {                                         //2
+                    let Some(x) = e.checked_mul(2) else { continue; };      //3 4
+                    acc = match x {                                         //5
+                        0 => acc,                                           //6
+                        _ => acc + x,                                       //7
+                    };
+                }
+                let g = |y: i32| y.checked_add(acc);                        //8 9
+                g(acc)?;                                                    //10
+                Some(acc)                                                   //11
+            }
+        "#);
+        assert_eq!(blocks(&graph), vec![
+            (vec![], vec![2], false), (vec![], vec![], false),
+            (vec![1], vec![3], false),
+            (vec![2], vec![4, 9], true),
+            (vec![3], vec![5, 6], false),
+            (vec![4], vec![3], false),              //continue
+            (vec![3, 5], vec![7, 8], false),        //il let-else prosegue con il match
+            (vec![6], vec![3], false),
+            (vec![7], vec![3], false),
+            (vec![8], vec![10, 11], false),
+            (vec![8, 10], vec![1, 12], false),      //dopo la closure, fino al ?
+            (vec![9], vec![11, 10], true),          //corpo della closure
+            (vec![10, 11], vec![1], false),
+        ]);
+        assert_eq!(graph.back_edges(), vec![(5, 3), (7, 3), (8, 3), (11, 11)]);
+    }
+
+    #[test]
+    fn test_statements_match_counter(){
+        //le istruzioni del grafo sono quelle contate dall'analisi statica, anche nelle versioni
+        //irrobustite, e ogni istruzione sta in almeno un blocco raggiungibile
+        let targets = fs::read_dir("src/fault_list_manager/file_fault_list").unwrap()
+            .chain(fs::read_dir("src/hardened").unwrap())
+            .map(|entry| entry.unwrap().path().join("mod.rs"));
+        for path in targets {
+            let Ok(code) = fs::read_to_string(&path) else { continue };
+            let graphs = control_flow_graphs(&code).unwrap();
+            let file: syn::File = syn::parse_str(&code).unwrap();
+            visit_functions(&file.items, "", &mut |name, sig, block| {
+                let graph = &graphs[&name];
+                assert_eq!(graph.statements.len(), analyze_function(sig, block, &FileContext::default()).num_inst, "{:?} {}", path, name);
+                let mut placed: Vec<usize> = graph.blocks.iter().flat_map(|block| block.statements.iter().copied()).collect();
+                placed.sort();
+                placed.dedup();
+                assert_eq!(placed, (1..=graph.statements.len()).collect::<Vec<_>>(), "{:?} {}", path, name);
+            });
+        }
+    }
+
+    #[test]
+    fn test_export(){
+        let dir = std::env::temp_dir();
+        let src = "src/fault_list_manager/file_fault_list/bubble_sort/mod.rs";
+        let json = dir.join("cfg_test.json").to_string_lossy().to_string();
+        let dot = dir.join("cfg_test.dot").to_string_lossy().to_string();
+
+        run_command(&[src.to_string(), "-o".to_string(), json.clone()]).unwrap();
+        let graphs: BTreeMap<String, ControlFlowGraph> = serde_json::from_str(&fs::read_to_string(&json).unwrap()).unwrap();
+        assert_eq!(blocks(&graphs["bubble_sort"]), blocks(&graph(&fs::read_to_string(src).unwrap())));
+
+        run_command(&[src.to_string(), "-o".to_string(), dot.clone(), "--function".to_string(), "bubble_sort".to_string()]).unwrap();
+        let dot = fs::read_to_string(&dot).unwrap();
+        assert!(dot.starts_with("digraph cfg {") && dot.contains("label=\"bubble_sort\";"));
+        assert!(dot.contains("f0_b5 [label=\"i6: riga 9 `while j < n - i - 1 {`\\l\", peripheries=2];"));
+        assert!(dot.contains("f0_b11 -> f0_b3 [style=dashed];") && dot.contains("f0_b3 -> f0_b12;"));
+        //le virgolette del codice sono protette
+        let graphs = control_flow_graphs("fn h(x: i32){ println!(\"{}\", x); }").unwrap();
+        assert!(to_dot(&graphs).contains("i1: riga 1 `println!(\\\"{}\\\", x);`\\l"));
+
+        assert!(run_command(&[src.to_string(), "-o".to_string(), json.clone(), "--function".to_string(), "quick_sort".to_string()]).unwrap_err().contains("UnknownFunction"));
+        assert!(run_command(&[src.to_string()]).is_err());
+    }
+}
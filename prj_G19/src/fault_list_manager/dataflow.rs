@@ -0,0 +1,533 @@
+//! Analisi def-use e liveness statica del corpo di una funzione.
+//!
+//! Il corpo viene tradotto in un grafo di flusso i cui nodi appartengono alle istruzioni contate
+//! dall'analisi statica, numerate da 1 nello stesso ordine di StatementCounter. Un'istruzione
+//! può avere più nodi: quello di ingresso, la testa di un ciclo, l'inizio di ogni ramo e il punto
+//! in cui i rami si riuniscono. Ingresso e uscita della funzione sono due nodi a parte.
+//! Su questo grafo si calcolano, per ogni variabile:
+//!     - le istruzioni che la scrivono (anche un solo elemento) e quelle che la leggono
+//!     - le istruzioni prima delle quali il suo valore è morto: non viene letto lungo nessun
+//!       percorso prima di essere sovrascritto, quindi un bit-flip iniettato lì non ha effetto
+//! Come nella potatura dinamica (vedi liveness), la scrittura di un elemento o una chiamata che
+//! modifica un vettore contano come letture dell'intero vettore e non lo sovrascrivono.
+
+use std::collections::{BTreeSet, HashMap};
+use std::mem;
+use proc_macro2::{TokenStream, TokenTree};
+use syn::{Block, Expr, ExprClosure, ExprForLoop, ExprIf, ExprLoop, ExprMatch, ExprWhile, FnArg, Item, Macro, Pat, PatIdent, Signature, Stmt, Type};
+use syn::visit::{self, Visit};
+
+//metodi che modificano il ricevente: contano come scrittura parziale
+const MUTATING_METHODS: &[&str] = &["swap", "push", "pop", "insert", "remove", "clear", "sort",
+    "sort_unstable", "reverse", "truncate", "extend", "fill", "append", "retain", "dedup", "resize",
+    "drain", "push_str", "iter_mut", "get_mut", "last_mut", "first_mut", "as_mut"];
+
+const ENTRY: usize = 0;
+const EXIT: usize = 1;
+
+#[derive(Default)]
+struct Node{
+    stmt: Option<usize>,            //istruzione a cui appartiene il nodo (None: ingresso/uscita)
+    defs: BTreeSet<String>,         //variabili sovrascritte interamente
+    partial: BTreeSet<String>,      //variabili scritte solo in parte (elementi, campi, &mut)
+    uses: BTreeSet<String>,         //variabili lette
+    succ: Vec<usize>,
+}
+
+///Accessi a una variabile: istruzioni che la scrivono, che la leggono e prima delle quali è morta
+#[derive(Debug, Default, PartialEq)]
+pub struct Accesses{
+    pub defs: Vec<usize>,
+    pub uses: Vec<usize>,
+    pub dead: Vec<usize>,
+}
+
+///Grafo di flusso della funzione con l'insieme delle variabili vive all'ingresso di ogni nodo
+pub struct FlowGraph{
+    nodes: Vec<Node>,
+    entries: HashMap<usize, usize>,     //istruzione --> nodo di ingresso
+    live_in: Vec<BTreeSet<String>>,
+    pub num_stmts: usize,
+}
+
+impl FlowGraph{
+    pub fn build(sig: &Signature, block: &Block)->Self{
+        let mut builder = Builder{ nodes: vec![Node::default(), Node::default()], ..Builder::default() };
+        //i parametri sono definiti all'ingresso; quelli passati per riferimento mutabile sono
+        //visibili al chiamante e quindi letti all'uscita
+        for param in &sig.inputs {
+            if let FnArg::Typed(pat_type) = param {
+                let mut names = Vec::new();
+                bindings(&pat_type.pat, &mut names);
+                if let Type::Reference(reference) = &*pat_type.ty {
+                    if reference.mutability.is_some() {
+                        builder.nodes[EXIT].uses.extend(names.iter().cloned());
+                    }
+                }
+                builder.nodes[ENTRY].defs.extend(names);
+            }
+        }
+        builder.cur = builder.node(&[ENTRY]);
+        builder.visit_block(block);
+        builder.edge(builder.cur, EXIT);
+
+        let Builder{ nodes, entries, count, .. } = builder;
+        let mut graph = FlowGraph{ live_in: vec![BTreeSet::new(); nodes.len()], nodes, entries, num_stmts: count };
+        graph.solve();
+        graph
+    }
+
+    //live_in(n) = uses(n) ∪ partial(n) ∪ (live_out(n) - defs(n)), fino al punto fisso
+    fn solve(&mut self){
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for n in (0..self.nodes.len()).rev() {
+                let node = &self.nodes[n];
+                let mut live: BTreeSet<String> = node.succ.iter()
+                    .flat_map(|&succ| self.live_in[succ].iter().cloned())
+                    .filter(|name| !node.defs.contains(name))
+                    .collect();
+                live.extend(node.uses.iter().cloned());
+                live.extend(node.partial.iter().cloned());
+                if live != self.live_in[n] {
+                    self.live_in[n] = live;
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    ///Accessi alla variabile 'name'; le istruzioni in cui è morta partono da 'start', quella
+    /// da cui la variabile è iniettabile
+    pub fn accesses(&self, name: &str, start: usize)->Accesses{
+        Accesses{
+            defs: self.stmts(|node| node.defs.contains(name) || node.partial.contains(name)),
+            uses: self.stmts(|node| node.uses.contains(name)),
+            dead: (start.max(1)..=self.num_stmts)
+                .filter(|stmt| self.entries.get(stmt).is_some_and(|&n| !self.live_in[n].contains(name)))
+                .collect(),
+        }
+    }
+
+    //istruzioni (ordinate, senza ripetizioni) con almeno un nodo che soddisfa 'filter'
+    fn stmts(&self, filter: impl Fn(&Node)->bool)->Vec<usize>{
+        self.nodes.iter()
+            .filter(|node| filter(node))
+            .filter_map(|node| node.stmt)
+            .collect::<BTreeSet<usize>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+//Nomi introdotti da un pattern (let, for, bracci dei match, argomenti delle closure)
+fn bindings(pat: &Pat, names: &mut Vec<String>){
+    struct Bindings<'a>(&'a mut Vec<String>);
+    impl<'ast> Visit<'ast> for Bindings<'_>{
+        fn visit_pat_ident(&mut self, pat: &'ast PatIdent){
+            self.0.push(pat.ident.to_string());
+            visit::visit_pat_ident(self, pat);
+        }
+    }
+    Bindings(names).visit_pat(pat);
+}
+
+//Variabile a cui appartiene un luogo di memoria: "v" per v[i][j], v.campo, *v, v.iter_mut()
+fn root(expr: &Expr)->Option<String>{
+    match expr {
+        Expr::Path(path) if path.qself.is_none() => path.path.get_ident().map(|ident| ident.to_string()),
+        Expr::Index(index) => root(&index.expr),
+        Expr::Field(field) => root(&field.base),
+        Expr::Paren(paren) => root(&paren.expr),
+        Expr::Unary(unary) => root(&unary.expr),
+        Expr::MethodCall(call) => root(&call.receiver),
+        _ => None,
+    }
+}
+
+//Identificatori nei token di una macro: sono le variabili che la macro può leggere
+fn macro_idents(tokens: TokenStream, names: &mut BTreeSet<String>){
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => { names.insert(ident.to_string()); }
+            TokenTree::Group(group) => macro_idents(group.stream(), names),
+            _ => {}
+        }
+    }
+}
+
+//Ciclo in corso di visita: destinazione di continue e nodi da cui si esce con break
+struct Loop{
+    label: Option<String>,
+    head: usize,
+    breaks: Vec<usize>,
+}
+
+#[derive(Default)]
+struct Builder{
+    nodes: Vec<Node>,
+    entries: HashMap<usize, usize>,
+    count: usize,           //istruzioni contate finora, come in StatementCounter
+    owner: Option<usize>,   //istruzione in corso di visita
+    cur: usize,             //nodo a cui si aggiungono gli accessi
+    loops: Vec<Loop>,
+    exit: Option<usize>,    //destinazione dei return (None: uscita della funzione)
+}
+
+impl Builder{
+    fn node(&mut self, preds: &[usize])->usize{
+        let id = self.nodes.len();
+        self.nodes.push(Node{ stmt: self.owner, ..Node::default() });
+        for &pred in preds {
+            self.edge(pred, id);
+        }
+        id
+    }
+
+    fn edge(&mut self, from: usize, to: usize){
+        if !self.nodes[from].succ.contains(&to) {
+            self.nodes[from].succ.push(to);
+        }
+    }
+
+    //dopo break, continue e return il codice successivo non è raggiungibile da qui
+    fn unreachable(&mut self){
+        self.cur = self.node(&[]);
+    }
+
+    //nuova istruzione contata: il suo nodo di ingresso segue quello corrente
+    fn statement(&mut self)->Option<usize>{
+        self.count += 1;
+        let owner = self.owner.replace(self.count);
+        self.cur = self.node(&[self.cur]);
+        self.entries.insert(self.count, self.cur);
+        owner
+    }
+
+    fn bind(&mut self, pat: &Pat){
+        let mut names = Vec::new();
+        bindings(pat, &mut names);
+        self.nodes[self.cur].defs.extend(names);
+    }
+
+    //scrittura dell'intera variabile o, per elementi e campi, di una sua parte
+    fn write(&mut self, place: &Expr){
+        if let Expr::Path(path) = place {
+            if let Some(ident) = path.path.get_ident() {
+                self.nodes[self.cur].defs.insert(ident.to_string());
+                return;
+            }
+        }
+        self.visit_expr(place);
+        if let Some(name) = root(place) {
+            self.nodes[self.cur].partial.insert(name);
+        }
+    }
+
+    //corpo di un ciclo a partire da 'head': si torna alla testa alla fine del corpo
+    fn loop_body(&mut self, label: Option<String>, head: usize, from: usize, body: &Block)->Vec<usize>{
+        self.loops.push(Loop{ label, head, breaks: Vec::new() });
+        self.cur = self.node(&[from]);
+        self.visit_block(body);
+        self.edge(self.cur, head);
+        self.loops.pop().unwrap().breaks
+    }
+
+    fn find_loop(&mut self, label: Option<&syn::Lifetime>)->Option<&mut Loop>{
+        let label = label.map(|label| label.ident.to_string());
+        self.loops.iter_mut().rev().find(|l| label.is_none() || l.label == label)
+    }
+}
+
+impl<'ast> Visit<'ast> for Builder{
+    fn visit_stmt(&mut self, stmt: &'ast Stmt){
+        if let Stmt::Item(_) = stmt {
+            return;
+        }
+        let owner = self.statement();
+        match stmt {
+            Stmt::Local(local) => {
+                if let Some(init) = &local.init {
+                    self.visit_expr(&init.expr);
+                    //il ramo else di un let-else non prosegue
+                    if let Some((_, diverge)) = &init.diverge {
+                        let after = self.cur;
+                        self.cur = self.node(&[after]);
+                        self.visit_expr(diverge);
+                        self.cur = after;
+                    }
+                }
+                self.bind(&local.pat);
+            }
+            _ => visit::visit_stmt(self, stmt),
+        }
+        self.owner = owner;
+    }
+
+    fn visit_item(&mut self, _item: &'ast Item){}
+
+    fn visit_expr_path(&mut self, path: &'ast syn::ExprPath){
+        if let (None, Some(ident)) = (&path.qself, path.path.get_ident()) {
+            self.nodes[self.cur].uses.insert(ident.to_string());
+        }
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'ast syn::ExprAssign){
+        self.visit_expr(&assign.right);
+        self.write(&assign.left);
+    }
+
+    fn visit_expr_binary(&mut self, binary: &'ast syn::ExprBinary){
+        use syn::BinOp::*;
+        match binary.op {
+            AddAssign(_) | SubAssign(_) | MulAssign(_) | DivAssign(_) | RemAssign(_) | BitXorAssign(_)
+            | BitAndAssign(_) | BitOrAssign(_) | ShlAssign(_) | ShrAssign(_) => {
+                self.visit_expr(&binary.right);
+                self.visit_expr(&binary.left);
+                self.write(&binary.left);
+            }
+            _ => visit::visit_expr_binary(self, binary),
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall){
+        visit::visit_expr_method_call(self, call);
+        if MUTATING_METHODS.contains(&call.method.to_string().as_str()) {
+            if let Some(name) = root(&call.receiver) {
+                self.nodes[self.cur].partial.insert(name);
+            }
+        }
+    }
+
+    fn visit_expr_reference(&mut self, reference: &'ast syn::ExprReference){
+        self.visit_expr(&reference.expr);
+        if let (Some(_), Some(name)) = (reference.mutability, root(&reference.expr)) {
+            self.nodes[self.cur].partial.insert(name);
+        }
+    }
+
+    fn visit_expr_let(&mut self, expr_let: &'ast syn::ExprLet){
+        self.visit_expr(&expr_let.expr);
+        self.bind(&expr_let.pat);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast Macro){
+        macro_idents(mac.tokens.clone(), &mut self.nodes[self.cur].uses);
+    }
+
+    fn visit_expr_if(&mut self, expr_if: &'ast ExprIf){
+        self.visit_expr(&expr_if.cond);
+        let cond = self.cur;
+        self.cur = self.node(&[cond]);
+        self.visit_block(&expr_if.then_branch);
+        let mut outs = vec![self.cur];
+        match &expr_if.else_branch {
+            Some((_, else_branch)) => {
+                self.cur = self.node(&[cond]);
+                self.visit_expr(else_branch);
+                outs.push(self.cur);
+            }
+            None => outs.push(cond),
+        }
+        self.cur = self.node(&outs);
+    }
+
+    fn visit_expr_while(&mut self, expr_while: &'ast ExprWhile){
+        let head = self.node(&[self.cur]);
+        self.cur = head;
+        self.visit_expr(&expr_while.cond);
+        let cond = self.cur;
+        let label = expr_while.label.as_ref().map(|label| label.name.ident.to_string());
+        let mut outs = self.loop_body(label, head, cond, &expr_while.body);
+        outs.push(cond);
+        self.cur = self.node(&outs);
+    }
+
+    fn visit_expr_for_loop(&mut self, expr_for: &'ast ExprForLoop){
+        self.visit_expr(&expr_for.expr);
+        let head = self.node(&[self.cur]);
+        self.cur = head;
+        self.bind(&expr_for.pat);
+        let label = expr_for.label.as_ref().map(|label| label.name.ident.to_string());
+        let mut outs = self.loop_body(label, head, head, &expr_for.body);
+        outs.push(head);
+        self.cur = self.node(&outs);
+    }
+
+    fn visit_expr_loop(&mut self, expr_loop: &'ast ExprLoop){
+        let head = self.node(&[self.cur]);
+        let label = expr_loop.label.as_ref().map(|label| label.name.ident.to_string());
+        let outs = self.loop_body(label, head, head, &expr_loop.body);
+        self.cur = self.node(&outs);
+    }
+
+    fn visit_expr_match(&mut self, expr_match: &'ast ExprMatch){
+        self.visit_expr(&expr_match.expr);
+        let scrutinee = self.cur;
+        let mut outs = Vec::new();
+        for arm in &expr_match.arms {
+            //un braccio senza blocco è un'istruzione a sé
+            let bare = !matches!(&*arm.body, Expr::Block(_));
+            let owner = self.owner;
+            self.cur = scrutinee;
+            if bare {
+                self.statement();
+            } else {
+                self.cur = self.node(&[scrutinee]);
+            }
+            self.bind(&arm.pat);
+            if let Some((_, guard)) = &arm.guard {
+                self.visit_expr(guard);
+            }
+            self.visit_expr(&arm.body);
+            outs.push(self.cur);
+            self.owner = owner;
+        }
+        self.cur = self.node(&outs);
+    }
+
+    fn visit_expr_break(&mut self, expr_break: &'ast syn::ExprBreak){
+        if let Some(expr) = &expr_break.expr {
+            self.visit_expr(expr);
+        }
+        let cur = self.cur;
+        if let Some(target) = self.find_loop(expr_break.label.as_ref()) {
+            target.breaks.push(cur);
+        }
+        self.unreachable();
+    }
+
+    fn visit_expr_continue(&mut self, expr_continue: &'ast syn::ExprContinue){
+        if let Some(head) = self.find_loop(expr_continue.label.as_ref()).map(|target| target.head) {
+            self.edge(self.cur, head);
+        }
+        self.unreachable();
+    }
+
+    fn visit_expr_return(&mut self, expr_return: &'ast syn::ExprReturn){
+        if let Some(expr) = &expr_return.expr {
+            self.visit_expr(expr);
+        }
+        self.edge(self.cur, self.exit.unwrap_or(EXIT));
+        self.unreachable();
+    }
+
+    fn visit_expr_try(&mut self, expr_try: &'ast syn::ExprTry){
+        self.visit_expr(&expr_try.expr);
+        //l'operatore ? può uscire dalla funzione
+        self.edge(self.cur, self.exit.unwrap_or(EXIT));
+    }
+
+    //il corpo di una closure può essere eseguito un numero qualunque di volte, anche nessuna
+    fn visit_expr_closure(&mut self, closure: &'ast ExprClosure){
+        let before = self.cur;
+        let after = self.node(&[before]);
+        let exit = self.exit.replace(after);
+        let loops = mem::take(&mut self.loops);
+        let owner = self.owner;
+        if matches!(&*closure.body, Expr::Block(_)) {
+            self.cur = self.node(&[before]);
+        } else {
+            self.statement();
+        }
+        let body = self.cur;
+        for input in &closure.inputs {
+            self.bind(input);
+        }
+        self.visit_expr(&closure.body);
+        self.edge(self.cur, body);
+        self.edge(self.cur, after);
+        self.owner = owner;
+        self.loops = loops;
+        self.exit = exit;
+        self.cur = after;
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use syn::ItemFn;
+    use crate::fault_list_manager::dataflow::{Accesses, FlowGraph};
+    use crate::fault_list_manager::static_analysis::{analyze_function, FileContext};
+
+    fn graph(code: &str)->FlowGraph{
+        let func: ItemFn = syn::parse_str(code).unwrap();
+        FlowGraph::build(&func.sig, &func.block)
+    }
+
+    fn accesses(defs: &[usize], uses: &[usize], dead: &[usize])->Accesses{
+        Accesses{ defs: defs.to_vec(), uses: uses.to_vec(), dead: dead.to_vec() }
+    }
+
+    #[test]
+    fn test_def_use_and_dead_statements(){
+        let graph = graph(r#"
+            fn f(mut v: Vec<i32>, out: &mut Vec<i32>)->i32{
+                let n = v.len();                    //1
+                let mut i = 0;                      //2
+                let mut x = 5;                      //3
+                while i < n {                       //4
+                    x = v[i];                       //5
+                    v[i] = 0;                       //6
+                    if x > 3 { break; }             //7 8
+                    i += 1;                         //9
+                }
+                out.push(x);                        //10
+                println!("{}", n);                  //11
+                i                                   //12
+            }
+        "#);
+        assert_eq!(graph.num_stmts, 12);
+        //il valore iniziale di x è letto solo se il ciclo non viene eseguito
+        assert_eq!(graph.accesses("x", 3), accesses(&[3, 5], &[7, 10], &[3, 5, 11, 12]));
+        assert_eq!(graph.accesses("n", 1), accesses(&[1], &[4, 11], &[1, 12]));
+        //la scrittura di un elemento non sovrascrive il vettore, che dopo il ciclo non serve più
+        assert_eq!(graph.accesses("v", 1), accesses(&[6], &[1, 5, 6], &[8, 10, 11, 12]));
+        //un parametro &mut è visibile al chiamante fino all'uscita
+        assert_eq!(graph.accesses("out", 1), accesses(&[10], &[10], &[]));
+        assert_eq!(graph.accesses("i", 2), accesses(&[2, 9], &[4, 5, 6, 9, 12], &[2]));
+    }
+
+    #[test]
+    fn test_match_closures_and_loops(){
+        let graph = graph(r#"
+            fn f(v: Vec<i32>)->i32{
+                let mut acc = 0;                            //1
+                let mut k = 0;                              //2
+                for e in v.iter() {                         //3
+                    acc = match e {                         //4
+                        0 => acc,                           //5
+                        _ => { k = *e; acc + k }            //6 7
+                    };
+                }
+                let g = |y: i32| y + k;                     //8 9
+                loop {                                      //10
+                    acc -= 1;                               //11
+                    if acc < 0 { return g(acc); }           //12 13
+                }
+            }
+        "#);
+        assert_eq!(graph.num_stmts, 13);
+        assert_eq!(graph.accesses("acc", 1), accesses(&[1, 4, 11], &[5, 7, 11, 12, 13], &[1]));
+        //k è letto dalla closure, creata dopo il ciclo
+        assert_eq!(graph.accesses("k", 2), accesses(&[2, 6], &[7, 9], &[2, 6, 10, 11, 12, 13]));
+        assert_eq!(graph.accesses("e", 3), accesses(&[3], &[4, 6], &[3, 5, 7, 8, 9, 10, 11, 12, 13]));
+    }
+
+    #[test]
+    fn test_statements_match_counter(){
+        //le istruzioni del grafo sono quelle contate dall'analisi statica, per ogni target
+        for entry in std::fs::read_dir("src/fault_list_manager/file_fault_list").unwrap() {
+            let path = entry.unwrap().path().join("mod.rs");
+            let Ok(code) = std::fs::read_to_string(&path) else { continue };
+            let file: syn::File = syn::parse_str(&code).unwrap();
+            for item in file.items {
+                if let syn::Item::Fn(func) = item {
+                    let counted = analyze_function(&func.sig, &func.block, &FileContext::default()).num_inst;
+                    assert_eq!(FlowGraph::build(&func.sig, &func.block).num_stmts, counted, "{:?}", path);
+                }
+            }
+        }
+    }
+}
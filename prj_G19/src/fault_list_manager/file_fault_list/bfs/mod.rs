@@ -0,0 +1,26 @@
+pub fn bfs(graph: Vec<Vec<(usize, i32)>>, source: usize) -> Vec<i32> {
+    let n: usize = graph.len();
+    let mut dist: Vec<i32> = vec![i32::MAX; n];
+    let mut visited: Vec<bool> = vec![false; n];
+    let mut queue: Vec<usize> = Vec::new();
+    let mut head: usize = 0;
+
+    dist[source] = 0;
+    visited[source] = true;
+    queue.push(source);
+    while head < queue.len() {
+        let u: usize = queue[head];
+        head += 1;
+        let mut e: usize = 0;
+        while e < graph[u].len() {
+            let v: usize = graph[u][e].0;
+            if !visited[v] {
+                visited[v] = true;
+                dist[v] = dist[u] + 1;
+                queue.push(v);
+            }
+            e += 1;
+        }
+    }
+    dist
+}
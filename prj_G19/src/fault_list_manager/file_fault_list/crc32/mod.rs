@@ -0,0 +1,30 @@
+pub fn crc32(data: Vec<u8>) -> u32 {
+    let mut table: Vec<u32> = vec![0; 256];
+    let mut n: usize = 0;
+
+    //Costruzione della tabella di lookup (polinomio riflesso 0xEDB88320)
+    while n < 256 {
+        let mut c: u32 = n as u32;
+        let mut k: usize = 0;
+        while k < 8 {
+            if c & 1 == 1 {
+                c = 0xEDB88320 ^ (c >> 1);
+            } else {
+                c >>= 1;
+            }
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+
+    let mut crc: u32 = 0xFFFFFFFF;
+    let mut i: usize = 0;
+    while i < data.len() {
+        let byte: u8 = data[i];
+        let idx: usize = ((crc ^ byte as u32) & 0xFF) as usize;
+        crc = table[idx] ^ (crc >> 8);
+        i += 1;
+    }
+    crc ^ 0xFFFFFFFF
+}
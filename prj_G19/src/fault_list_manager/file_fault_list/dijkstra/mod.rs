@@ -0,0 +1,41 @@
+pub fn dijkstra(graph: Vec<Vec<(usize, i32)>>, source: usize) -> Vec<i32> {
+    let n: usize = graph.len();
+    let mut dist: Vec<i32> = vec![i32::MAX; n];
+    let mut visited: Vec<bool> = vec![false; n];
+    //Coda di priorità: coppie (distanza, nodo) memorizzate in due vettori paralleli
+    let mut pq_dist: Vec<i32> = Vec::new();
+    let mut pq_node: Vec<usize> = Vec::new();
+
+    dist[source] = 0;
+    pq_dist.push(0);
+    pq_node.push(source);
+    while !pq_node.is_empty() {
+        //Estrazione del minimo
+        let mut best: usize = 0;
+        let mut idx: usize = 1;
+        while idx < pq_node.len() {
+            if pq_dist[idx] < pq_dist[best] {
+                best = idx;
+            }
+            idx += 1;
+        }
+        let d: i32 = pq_dist.swap_remove(best);
+        let u: usize = pq_node.swap_remove(best);
+
+        if !visited[u] {
+            visited[u] = true;
+            let mut e: usize = 0;
+            while e < graph[u].len() {
+                let v: usize = graph[u][e].0;
+                let alt: i32 = d + graph[u][e].1;
+                if alt < dist[v] {
+                    dist[v] = alt;
+                    pq_dist.push(alt);
+                    pq_node.push(v);
+                }
+                e += 1;
+            }
+        }
+    }
+    dist
+}
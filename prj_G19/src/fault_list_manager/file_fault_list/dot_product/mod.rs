@@ -0,0 +1,11 @@
+pub fn dot_product(x: Vec<f64>, y: Vec<f64>) -> f64 {
+    let n: usize = x.len();
+    let mut acc: f64 = 0.0;
+    let mut i: usize = 0;
+
+    while i < n {
+        acc += x[i] * y[i];
+        i += 1;
+    }
+    acc
+}
@@ -0,0 +1,18 @@
+pub fn fir_filter(x: Vec<f64>, h: Vec<f64>) -> Vec<f64> {
+    let n: usize = x.len();
+    let m: usize = h.len();
+    let mut y: Vec<f64> = vec![0.0; n];
+    let mut i: usize = 0;
+
+    while i < n {
+        let mut acc: f64 = 0.0;
+        let mut k: usize = 0;
+        while k < m && k <= i {
+            acc += h[k] * x[i - k];
+            k += 1;
+        }
+        y[i] = acc;
+        i += 1;
+    }
+    y
+}
@@ -0,0 +1,27 @@
+pub fn jacobi(a: Vec<Vec<f64>>, b: Vec<f64>) -> Vec<f64> {
+    let n: usize = b.len();
+    //Numero fisso di iterazioni: la durata dell'esecuzione non dipende dai fault
+    let iterations: usize = 25;
+    let mut x: Vec<f64> = vec![0.0; n];
+    let mut x_new: Vec<f64> = vec![0.0; n];
+    let mut it: usize = 0;
+
+    while it < iterations {
+        let mut i: usize = 0;
+        while i < n {
+            let mut sigma: f64 = 0.0;
+            let mut j: usize = 0;
+            while j < n {
+                if j != i {
+                    sigma += a[i][j] * x[j];
+                }
+                j += 1;
+            }
+            x_new[i] = (b[i] - sigma) / a[i][i];
+            i += 1;
+        }
+        x = x_new.clone();
+        it += 1;
+    }
+    x
+}
@@ -0,0 +1,40 @@
+pub fn kmp(text: Vec<u8>, pattern: Vec<u8>) -> Vec<usize> {
+    let n: usize = text.len();
+    let m: usize = pattern.len();
+    if m == 0 {
+        return Vec::new();
+    }
+    //fail[q]: lunghezza del più lungo prefisso proprio di pattern[..=q] che ne è anche suffisso
+    let mut fail: Vec<usize> = vec![0; m];
+    let mut k: usize = 0;
+    let mut q: usize = 1;
+
+    while q < m {
+        while k > 0 && pattern[k] != pattern[q] {
+            k = fail[k - 1];
+        }
+        if pattern[k] == pattern[q] {
+            k += 1;
+        }
+        fail[q] = k;
+        q += 1;
+    }
+
+    let mut matches: Vec<usize> = Vec::new();
+    let mut j: usize = 0;
+    let mut i: usize = 0;
+    while i < n {
+        while j > 0 && pattern[j] != text[i] {
+            j = fail[j - 1];
+        }
+        if pattern[j] == text[i] {
+            j += 1;
+        }
+        if j == m {
+            matches.push(i + 1 - m);
+            j = fail[j - 1];
+        }
+        i += 1;
+    }
+    matches
+}
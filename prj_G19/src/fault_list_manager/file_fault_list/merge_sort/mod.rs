@@ -0,0 +1,37 @@
+pub fn merge_sort(vet: Vec<i32>) -> Vec<i32> {
+    let n: usize = vet.len();
+    if n < 2 {
+        return vet;
+    }
+    let aux: Vec<i32> = vet.clone();
+    merge_sort_rec(vet, aux, 0, n - 1).0
+}
+
+//Ordina il sotto-vettore [lo, hi] usando 'aux' come vettore di appoggio per la fusione
+pub fn merge_sort_rec(mut vet: Vec<i32>, mut aux: Vec<i32>, lo: usize, hi: usize) -> (Vec<i32>, Vec<i32>) {
+    if lo < hi {
+        let mid: usize = (lo + hi) / 2;
+        (vet, aux) = merge_sort_rec(vet, aux, lo, mid);
+        (vet, aux) = merge_sort_rec(vet, aux, mid + 1, hi);
+
+        let mut i: usize = lo;
+        let mut j: usize = mid + 1;
+        let mut k: usize = lo;
+        while k <= hi {
+            if j > hi || (i <= mid && vet[i] <= vet[j]) {
+                aux[k] = vet[i];
+                i += 1;
+            } else {
+                aux[k] = vet[j];
+                j += 1;
+            }
+            k += 1;
+        }
+        k = lo;
+        while k <= hi {
+            vet[k] = aux[k];
+            k += 1;
+        }
+    }
+    (vet, aux)
+}
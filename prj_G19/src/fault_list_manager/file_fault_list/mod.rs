@@ -1,3 +1,13 @@
 pub mod bubble_sort;
 pub mod selection_sort;
-pub mod matrix_multiplication;
\ No newline at end of file
+pub mod matrix_multiplication;
+pub mod quick_sort;
+pub mod merge_sort;
+pub mod bfs;
+pub mod dijkstra;
+pub mod dot_product;
+pub mod fir_filter;
+pub mod jacobi;
+pub mod crc32;
+pub mod kmp;
+pub mod par_matrix_multiplication;
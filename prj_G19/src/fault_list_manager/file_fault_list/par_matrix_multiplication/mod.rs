@@ -0,0 +1,42 @@
+use std::sync::Mutex;
+use std::thread;
+
+pub fn par_matrix_multiplication(a: Vec<Vec<i32>>, b: Vec<Vec<i32>>, workers: usize) -> Vec<Vec<i32>> {
+    let size: usize = a.len();
+    //Stato condiviso: la matrice risultato e l'indice della prossima riga da calcolare
+    let result = Mutex::new(vec![vec![0; size]; size]);
+    let next_row = Mutex::new(0);
+
+    thread::scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| worker(&a, &b, &result, &next_row));
+        }
+    });
+    result.into_inner().unwrap()
+}
+
+//Il corpo di ogni thread: le sue variabili sono locali al worker, 'result' e 'next_row' sono condivise
+fn worker(a: &[Vec<i32>], b: &[Vec<i32>], result: &Mutex<Vec<Vec<i32>>>, next_row: &Mutex<usize>) {
+    let size: usize = a.len();
+    loop {
+        let row: usize = {
+            let mut next = next_row.lock().unwrap();
+            if *next >= size {
+                break;
+            }
+            *next += 1;
+            *next - 1
+        };
+        let mut j: usize = 0;
+        while j < size {
+            let mut acc: i32 = 0;
+            let mut k: usize = 0;
+            while k < size {
+                acc += a[row][k] * b[k][j];
+                k += 1;
+            }
+            result.lock().unwrap()[row][j] = acc;
+            j += 1;
+        }
+    }
+}
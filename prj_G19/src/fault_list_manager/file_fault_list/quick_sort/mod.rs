@@ -0,0 +1,30 @@
+pub fn quick_sort(vet: Vec<i32>) -> Vec<i32> {
+    let n: usize = vet.len();
+    if n < 2 {
+        return vet;
+    }
+    quick_sort_rec(vet, 0, n - 1)
+}
+
+//Partizionamento di Lomuto sul sotto-vettore [lo, hi]
+pub fn quick_sort_rec(mut vet: Vec<i32>, lo: usize, hi: usize) -> Vec<i32> {
+    let pivot: i32 = vet[hi];
+    let mut i: usize = lo;
+    let mut j: usize = lo;
+
+    while j < hi {
+        if vet[j] < pivot {
+            vet.swap(i, j);
+            i += 1;
+        }
+        j += 1;
+    }
+    vet.swap(i, hi);
+    if i > lo + 1 {
+        vet = quick_sort_rec(vet, lo, i - 1);
+    }
+    if i + 1 < hi {
+        vet = quick_sort_rec(vet, i + 1, hi);
+    }
+    vet
+}
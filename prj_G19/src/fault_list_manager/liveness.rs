@@ -0,0 +1,272 @@
+//! Potatura della fault list con le informazioni def-use dell'analisi statica.
+//!
+//! Un bit-flip iniettato all'istante t ha effetto solo se la variabile viene letta prima di
+//! essere sovrascritta: in caso contrario l'iniezione è morta e il suo esito (fault silente)
+//! è noto senza eseguirla. Due iniezioni sulla stessa variabile e sullo stesso bit, tra le
+//! quali la variabile non viene mai acceduta, producono lo stesso stato alla lettura
+//! successiva e quindi lo stesso esito: si esegue un solo rappresentante con un peso pari al
+//! numero di entry equivalenti, e l'analyzer pesa i conteggi di conseguenza.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use syn::{FnArg, Pat, Type};
+use crate::fault_list_manager::{read_fault_list, site_of, target_function, write_fault_list, FaultList, FaultListEntry};
+use crate::fault_list_manager::control_flow::ControlFlowGraph;
+use crate::fault_list_manager::static_analysis::{load_analysis, visit_functions, ResultAnalysis, Variable};
+use crate::injector::labels::runner_labels;
+
+///Esito di un'iniezione rispetto agli accessi successivi alla variabile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Liveness{
+    Dead,           //la variabile è sovrascritta (o non più letta) prima di ogni lettura
+    Live(usize),    //indice nella traccia del primo step che accede alla variabile
+}
+
+//Accessi della funzione analizzata lungo l'esecuzione senza fault
+struct Model{
+    //istruzioni eseguite prima di ogni etichetta della traccia, più quelle eseguite dopo l'ultima
+    //fino all'uscita; None se il percorso non è noto
+    steps: Vec<Option<Vec<usize>>>,
+    vars: HashMap<String, Variable>,
+    //parametri passati per riferimento mutabile: il chiamante li legge dopo l'uscita
+    outputs: Vec<String>,
+}
+
+impl Model{
+    //Serve la tabella delle etichette del runner e il sorgente della funzione. I target paralleli
+    //alternano le etichette di più thread e in quelli ricorsivi le etichette di servizio separano
+    //i frame: in entrambi i casi non c'è un'unica esecuzione della funzione da seguire
+    fn new(case: &str, analysis: &ResultAnalysis, trace: &[&'static str])->Option<Self>{
+        let labels = runner_labels(case).filter(|labels| !labels.interleaved && labels.statements.iter().all(|(_, k)| k.is_some()))?;
+        let code = fs::read_to_string(&analysis.source).ok()?;
+        let file: syn::File = syn::parse_str(&code).ok()?;
+        let function = target_function(case);
+        let mut found = None;
+        visit_functions(&file.items, "", &mut |path, sig, block| {
+            if path == function {
+                let outputs = sig.inputs.iter()
+                    .filter_map(|param| match param {
+                        FnArg::Typed(pat_type) => Some(pat_type),
+                        FnArg::Receiver(_) => None,
+                    })
+                    .filter(|pat_type| matches!(&*pat_type.ty, Type::Reference(reference) if reference.mutability.is_some()))
+                    .filter_map(|pat_type| match &*pat_type.pat {
+                        Pat::Ident(ident) => Some(ident.ident.to_string()),
+                        _ => None,
+                    })
+                    .collect();
+                found = Some((ControlFlowGraph::build(block), outputs));
+            }
+        });
+        let (cfg, outputs) = found?;
+
+        let mut steps = Vec::with_capacity(trace.len() + 1);
+        //istruzione dell'etichetta precedente: Some(None) è l'ingresso, None un punto sconosciuto
+        let mut prev = Some(None);
+        for label in trace {
+            let k = labels.statement(label);
+            steps.push(prev.zip(k).and_then(|(from, to)| labels.path(&cfg, from, Some(to))));
+            prev = k.map(Some);
+        }
+        steps.push(prev.and_then(|from| labels.path(&cfg, from, None)));
+        let vars = analysis.vars.iter().map(|var| (var.name.clone(), var.clone())).collect();
+        Some(Model{ steps, vars, outputs })
+    }
+}
+
+///Informazioni def-use di un'esecuzione senza fault: la sequenza delle etichette inviate dal
+/// runner e, se disponibili per il target, gli accessi di ogni step ricavati dall'analisi statica
+/// (defs, uses e dead delle variabili) e dal percorso nel grafo di controllo di flusso tra le
+/// istruzioni di due etichette consecutive
+pub struct DefUse{
+    model: Option<Model>,
+    trace: Vec<&'static str>,
+}
+
+impl DefUse{
+    ///'analysis' è l'analisi statica della funzione predefinita del target (vedi target_function)
+    pub fn new(case: &str, analysis: Option<&ResultAnalysis>, trace: Vec<&'static str>)->Self{
+        let model = analysis.and_then(|analysis| Model::new(case, analysis, &trace));
+        DefUse{ model, trace }
+    }
+
+    ///Liveness di un bit-flip sulla variabile 'var' ("j", "vet[3]", "result[0][1]") iniettato
+    /// all'istante 'time', cioè subito dopo lo step 'time' della traccia
+    pub fn liveness(&self, var: &str, time: usize)->Liveness{
+        //il contatore dell'iniettore parte da 1: all'istante 0 il fault non viene mai iniettato
+        if time == 0 {
+            return Liveness::Dead;
+        }
+        //per vettori e matrici gli accessi sono tracciati sull'intera variabile; i nomi con il
+        //frame ("lo@0") e le variabili fuori dall'analisi non hanno informazioni def-use
+        let name = var.split('[').next().unwrap();
+        let Some((model, variable)) = self.model.as_ref().and_then(|model| Some((model, model.vars.get(name)?))) else {
+            //ogni step può accedere alla variabile: si riuniscono solo i duplicati
+            return Liveness::Live(time);
+        };
+        for (index, step) in model.steps.iter().enumerate().skip(time) {
+            let Some(statements) = step else {
+                return Liveness::Live(index);
+            };
+            for k in statements {
+                if variable.dead.contains(k) {
+                    return Liveness::Dead;
+                }
+                if variable.uses.contains(k) || variable.defs.contains(k) {
+                    return Liveness::Live(index);
+                }
+            }
+        }
+        if model.outputs.contains(&variable.name) {
+            Liveness::Live(self.trace.len())
+        } else {
+            Liveness::Dead
+        }
+    }
+}
+
+///Elimina le entry morte e riunisce quelle equivalenti (stessa variabile, stesso bit e stesso
+/// primo accesso successivo) in un'unica entry con la somma dei pesi. Restituisce le entry
+/// rimaste, nell'ordine della loro prima occorrenza, e il peso complessivo delle entry morte.
+pub fn collapse(entries: Vec<FaultListEntry>, def_use: &DefUse)->(Vec<FaultListEntry>, usize){
+    let mut collapsed: Vec<FaultListEntry> = Vec::new();
+    let mut classes: HashMap<(String, usize, Liveness), usize> = HashMap::new();
+    let mut dead = 0;
+    for entry in entries {
+        match def_use.liveness(&entry.var, entry.time) {
+            Liveness::Dead => dead += entry.weight,
+            live => {
+                let key = (entry.var.clone(), entry.flipped_bit, live);
+                match classes.get(&key) {
+                    Some(&index) => collapsed[index].weight += entry.weight,
+                    None => {
+                        classes.insert(key, collapsed.len());
+                        collapsed.push(entry);
+                    }
+                }
+            }
+        }
+    }
+    (collapsed, dead)
+}
+
+///Applica 'collapse' alla fault list salvata in 'path' e la riscrive: nell'intestazione restano
+/// il numero di fault rappresentati e si registrano le entry morte e le esecuzioni necessarie.
+/// Le fault list senza intestazione non hanno dove registrare le entry morte e restano invariate.
+/// 'analysis' è il file di analisi da cui è stata generata la fault list: se questa riguarda una
+/// funzione diversa da quella predefinita del target, le etichette del runner non vi corrispondono
+/// e si riuniscono solo le entry duplicate.
+pub fn prune_fault_list(path: &str, case: &str, analysis: &str, trace: Vec<&'static str>){
+    let (Some(mut metadata), entries) = read_fault_list(path).unwrap() else {
+        return;
+    };
+    let function = metadata.params.as_ref().map_or(target_function(case), |params| params.function(case));
+    let analysis = if function == target_function(case) { load_analysis(analysis, function).ok() } else { None };
+    //le entry morte di ogni variabile sono la differenza tra i pesi prima e dopo la potatura
+    let weight_by_var = |entries: &[FaultListEntry]| entries.iter()
+        .fold(BTreeMap::new(), |mut weights: BTreeMap<String, usize>, entry| {
+            *weights.entry(site_of(&entry.var).to_string()).or_default() += entry.weight;
+            weights
+        });
+    let before = weight_by_var(&entries);
+    let (entries, dead) = collapse(entries, &DefUse::new(case, analysis.as_ref(), trace));
+    let after = weight_by_var(&entries);
+    for (var, weight) in before {
+        let var_dead = weight - after.get(&var).copied().unwrap_or(0);
+        if var_dead > 0 {
+            *metadata.dead_by_var.entry(var).or_default() += var_dead;
+        }
+    }
+    metadata.dead_faults += dead;
+    metadata.runs = Some(entries.len());
+    write_fault_list(path, &FaultList{ metadata, entries }).unwrap();
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::fault_list_manager::type_size::PointerWidth;
+    use std::env::temp_dir;
+    use crate::fault_env::Data;
+    use crate::fault_list_manager::{create_fault_list, read_fault_list, static_analysis, DimData, FaultListEntry};
+    use crate::fault_list_manager::static_analysis::{load_analysis, ResultAnalysis};
+    use crate::fault_list_manager::liveness::{collapse, prune_fault_list, DefUse, Liveness};
+    use crate::injector::label_trace;
+
+    fn entry(var: &str, time: usize, flipped_bit: usize)->FaultListEntry{
+        FaultListEntry{ var: var.to_string(), time, flipped_bit, weight: 1 }
+    }
+
+    fn bubble_sort_analysis()->ResultAnalysis{
+        let dest = temp_dir().join("bubble_sort_ris_liveness_unit_test.json").to_string_lossy().to_string();
+        static_analysis::generate_analysis_file(
+            "src/fault_list_manager/file_fault_list/bubble_sort/mod.rs".to_string(), dest.clone(), PointerWidth::HOST).unwrap();
+        load_analysis(&dest, "bubble_sort").unwrap()
+    }
+
+    //Traccia del bubble sort su [2, 1]: una passata con scambio, poi una senza scambi
+    fn bubble_sort_trace()->Vec<&'static str>{
+        vec!["i1", "i2", "i3", "i4", "i5", "i6", "i7", "i8", "i9", "i10", "i12",
+             "i3", "i4", "i5", "i11"]
+    }
+
+    #[test]
+    fn test_liveness_bubble_sort(){
+        let def_use = DefUse::new("bubble_sort", Some(&bubble_sort_analysis()), bubble_sort_trace());
+        //j viene riassegnato a 0 (i5) prima di essere letto di nuovo
+        assert_eq!(def_use.liveness("j", 12), Liveness::Dead);
+        //j viene letto dalla condizione del ciclo interno (i6, indice 5 della traccia)
+        assert_eq!(def_use.liveness("j", 5), Liveness::Live(5));
+        //swapped è letto dall'if !swapped, eseguito senza etichetta prima di i12, poi riscritto
+        //a false (i4) all'inizio della passata successiva
+        assert_eq!(def_use.liveness("swapped", 10), Liveness::Live(10));
+        assert_eq!(def_use.liveness("swapped", 11), Liveness::Dead);
+        //vet è sempre letto alla fine, come output
+        assert_eq!(def_use.liveness("vet[1]", 14), Liveness::Live(15));
+        assert_eq!(def_use.liveness("n", 0), Liveness::Dead);
+    }
+
+    #[test]
+    fn test_collapse_equivalent_entries(){
+        let def_use = DefUse::new("bubble_sort", Some(&bubble_sort_analysis()), bubble_sort_trace());
+        let entries = vec![
+            entry("vet[0]", 10, 3), entry("vet[0]", 12, 3), entry("vet[0]", 14, 3),   //nessun accesso a vet dopo i10
+            entry("vet[0]", 12, 4),                                                   //bit diverso
+            entry("j", 12, 1), entry("j", 13, 1),                                     //morte: riassegnata da i5
+            entry("i", 3, 2), entry("i", 3, 2),                                       //duplicato
+        ];
+        let (collapsed, dead) = collapse(entries, &def_use);
+        assert_eq!(dead, 2);
+        let weights: Vec<(&str, usize, usize)> = collapsed.iter().map(|e| (e.var.as_str(), e.flipped_bit, e.weight)).collect();
+        assert_eq!(weights, vec![("vet[0]", 3, 3), ("vet[0]", 4, 1), ("i", 2, 2)]);
+    }
+
+    #[test]
+    fn test_prune_fault_list(){
+        let analysis = temp_dir().join("bubble_sort_ris_prune_test.json").to_string_lossy().to_string();
+        let dest = temp_dir().join("bubble_sort_FL_prune_test.json").to_string_lossy().to_string();
+        static_analysis::generate_analysis_file(
+            "src/fault_list_manager/file_fault_list/bubble_sort/mod.rs".to_string(), analysis.clone(), PointerWidth::HOST).unwrap();
+        let data = Data::Vector(vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32]);
+        let trace = label_trace("bubble_sort", data.clone());
+        create_fault_list(3000, "bubble_sort".to_string(), analysis.clone(), DimData::Vector(10), dest.clone(), trace.len(), 7).unwrap();
+
+        prune_fault_list(&dest, "bubble_sort", &analysis, trace);
+        let (Some(metadata), entries) = read_fault_list(&dest).unwrap() else { panic!("intestazione mancante") };
+        //le entry rimaste più quelle morte rappresentano ancora tutti i 3000 fault
+        let represented: usize = entries.iter().map(|e| e.weight).sum();
+        assert_eq!(represented + metadata.dead_faults, 3000);
+        assert_eq!(metadata.num_faults, 3000);
+        assert_eq!(metadata.runs, Some(entries.len()));
+        assert_eq!(metadata.dead_by_var.values().sum::<usize>(), metadata.dead_faults);
+        assert!(entries.len() < 3000);
+    }
+
+    #[test]
+    fn test_collapse_without_table(){
+        //senza informazioni def-use si riuniscono soltanto le entry identiche
+        let def_use = DefUse::new("quick_sort", None, Vec::new());
+        let (collapsed, dead) = collapse(vec![entry("lo@0", 4, 1), entry("lo@0", 5, 1), entry("lo@0", 4, 1)], &def_use);
+        assert_eq!(dead, 0);
+        assert_eq!(collapsed.iter().map(|e| e.weight).collect::<Vec<_>>(), vec![2, 1]);
+    }
+}
@@ -1,142 +1,1003 @@
-use core::mem::size_of;
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::SyncSender;
 use std::fs;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
-use std::fs::OpenOptions;
-use std::io::Write;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
 use serde_json;
-use crate::static_analysis::{ResultAnalysis,Variable};
+use thiserror::Error;
+use regex::Regex;
+use crate::static_analysis::{AnalysisError, SourceSpan, Variable};
+use crate::fault_list_manager::type_size::TypeSize;
+use crate::fault_list_manager::validation::{validate_fault_list, Registry};
 use rand::prelude::*;
+use rand::distributions::WeightedIndex;
+use rand_chacha::ChaCha8Rng;
 
 
 pub mod static_analysis;
+pub mod dataflow;
+pub mod type_inference;
+pub mod type_size;
+pub mod liveness;
+pub mod validation;
+pub mod set_ops;
+pub mod compliance;
+pub mod control_flow;
 pub(crate) mod file_fault_list;
 
 //mod file_fault_list;
 
 //Dimensioni di array/matrici per la generazione della fault list
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub enum DimData{
     Vector(usize),              //caso vettore
-    Matrices((usize,usize))       //caso matrice
+    Matrices((usize,usize)),      //caso matrice
+    Frames(usize, Vec<usize>),  //caso ricorsivo: dimensione del vettore e numero di frame
+                                //attivi dopo ogni istruzione eseguita
+    Graph(usize, usize),        //caso grafo: numero di nodi e numero di archi
+    Lengths(HashMap<String, usize>),//kernel numerici: lunghezza di ogni vettore (lato per le
+                                    //matrici quadrate), indicizzata per nome della variabile
+    Workers((usize,usize), usize)   //caso parallelo: dimensioni delle matrici e numero di worker
 }
 
 impl DimData{
-    fn into_tuple(self)->(usize,usize){
+    fn to_tuple(&self)->(usize,usize){
         match self{
             DimData::Matrices((a,b)) => {
-                (a,b)
+                (*a,*b)
             }
             _ => { panic!("not expected a tuple here") }
         }
     }
-    fn into_n(self)->usize{
-        if let DimData::Vector(a)=self{
-            return a;
+    fn to_n(&self)->usize{
+        match self{
+            DimData::Vector(a) | DimData::Frames(a, _) => *a,
+            _ => panic!("Not possible!")
+        }
+    }
+    ///Numero di elementi del vettore 'name' che possono essere iniettati
+    fn vector_len(&self, case: &str, name: &str)->usize{
+        match self{
+            //vettore accessorio usato dall'algoritmo di matrix_multiplication
+            DimData::Matrices((n, _)) if case=="matrix_multiplication" => *n,
+            //la coda di priorità di dijkstra contiene al più un elemento per ogni
+            //rilassamento riuscito, più la sorgente
+            DimData::Graph(_, m) if name.starts_with("pq_") => *m + 1,
+            DimData::Graph(n, _) => *n,
+            DimData::Lengths(lengths) => lengths[name],
+            _ => self.to_n()
+        }
+    }
+    ///Numero di righe e di colonne della matrice 'name'
+    fn matrix_dims(&self, name: &str)->(usize,usize){
+        match self{
+            DimData::Lengths(lengths) => (lengths[name], lengths[name]),
+            DimData::Workers(dims, _) => *dims,
+            _ => self.to_tuple()
+        }
+    }
+}
+
+///Tipi scalari che l'iniettore sa corrompere
+const SCALARS: &[&str] = &["bool", "char", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64",
+    "u128", "usize", "f32", "f64"];
+
+///Scalare che l'iniettore sa corrompere; i plugin dichiarano interi di larghezza arbitraria (u12)
+fn is_scalar(ty: &str)->bool{
+    SCALARS.contains(&ty) || ty.strip_prefix('u').is_some_and(|width| width.parse::<u32>().is_ok())
+}
+
+///Vettore monodimensionale (es. "Vec < i32 >", "Vec < bool >")
+fn is_vector(ty: &str)->bool{
+    ty.starts_with("Vec <") && !ty.starts_with("Vec < Vec")
+}
+
+///Tipo degli elementi di vettori e matrici; per gli scalari il tipo stesso
+fn element_type(ty: &str)->&str{
+    let mut ty = ty;
+    while let Some(inner) = ty.strip_prefix("Vec < ").and_then(|ty| ty.strip_suffix(" >")) {
+        ty = inner;
+    }
+    ty
+}
+
+///Numero di bit di un elemento della variabile: per vettori e matrici conta il tipo degli
+/// elementi, per i booleani l'unico bit significativo (l'iniettore ne inverte il valore)
+fn bit_width(var: &Variable)->usize{
+    match element_type(&var.ty) {
+        "bool" => 1,
+        _ => var.size.element_bytes().unwrap() * 8
+    }
+}
+
+///Distribuzione della posizione del bit invertito, per gli studi di sensibilità per bit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitDistribution{
+    #[default]
+    Uniform,        //ogni bit con la stessa probabilità
+    MsbWeighted,    //probabilità proporzionale a bit+1: il bit più significativo è il più probabile
+    LsbOnly,        //solo il bit meno significativo
+}
+
+impl BitDistribution{
+    ///Numero di posizioni che possono essere estratte in una variabile di 'width' bit
+    fn support(&self, width: usize)->usize{
+        match self {
+            BitDistribution::LsbOnly => width.min(1),
+            _ => width
+        }
+    }
+
+    ///Peso relativo della posizione 'bit' (usato dal campionamento statistico)
+    fn weight(&self, bit: usize)->f64{
+        match self {
+            BitDistribution::MsbWeighted => (bit + 1) as f64,
+            _ => 1.0
+        }
+    }
+
+    ///Estrae la posizione del bit da invertire in una variabile di 'width' bit
+    fn sample(&self, width: usize, rng: &mut impl Rng)->usize{
+        match self {
+            BitDistribution::Uniform => rng.gen_range(0..width),
+            BitDistribution::MsbWeighted => WeightedIndex::new(1..=width).unwrap().sample(rng),
+            BitDistribution::LsbOnly => 0,
+        }
+    }
+}
+
+///Scelta della variabile di ogni entry casuale
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SiteSelection{
+    #[default]
+    PerVariable,                    //ogni variabile con la stessa probabilità, scalare o vettore che sia
+    Area,                           //proporzionale ai bit di stato vivo: uniforme sullo spazio dei fault
+    Lifetime,                       //proporzionale al numero di istanti in cui la variabile è viva
+    Custom(BTreeMap<String, f64>),  //pesi indicati dall'utente; le variabili non elencate non sono scelte
+}
+
+impl SiteSelection{
+    ///Peso relativo di ogni variabile di 'vars' nello spazio 'space'
+    fn weights(&self, vars: &[Variable], space: &FaultSpace)->Vec<f64>{
+        match self {
+            SiteSelection::PerVariable => vec![1.0; vars.len()],
+            SiteSelection::Area => space.var_sizes(vars.len()).into_iter().map(|size| size as f64).collect(),
+            SiteSelection::Lifetime => space.var_lifetimes(vars.len()).into_iter().map(|times| times as f64).collect(),
+            SiteSelection::Custom(weights) => vars.iter().map(|var| weights.get(&var.name).copied().unwrap_or(0.0)).collect(),
+        }
+    }
+}
+
+///Variabile a cui appartiene l'elemento 'var' di una entry ("vet[3]" -> "vet", "x@2" -> "x")
+pub fn site_of(var: &str)->&str{
+    var.split(['[', '@']).next().unwrap_or_default()
+}
+
+///Strategia di generazione della fault list
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sampling{
+    Random(usize),                              //numero di entry estratte a caso
+    Exhaustive,                                 //ogni variabile, elemento, istante e bit
+    Statistical{margin: f64, confidence: f64},  //campione dimensionato con la formula di Leveugle
+}
+
+impl From<i32> for Sampling{
+    fn from(num_faults: i32) -> Self {
+        Sampling::Random(num_faults.max(0) as usize)
+    }
+}
+
+impl Sampling{
+    pub fn with_bits(self, bits: BitDistribution)->FaultListParams{
+        FaultListParams{ sampling: self, bits, sites: SiteSelection::default(), function: None }
+    }
+}
+
+///Parametri di generazione della fault list: strategia di campionamento, distribuzione dei bit
+/// e scelta delle variabili (solo per l'estrazione casuale: gli altri modi sono già uniformi
+/// sullo spazio dei fault)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaultListParams{
+    pub sampling: Sampling,
+    pub bits: BitDistribution,
+    #[serde(default)]
+    pub sites: SiteSelection,
+    //funzione del file di analisi da cui prendere le variabili (None: quella del target, vedi
+    //target_function)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+}
+
+impl FaultListParams{
+    pub fn with_sites(self, sites: SiteSelection)->Self{
+        FaultListParams{ sites, ..self }
+    }
+
+    ///Funzione analizzata per il target 'case'
+    pub fn function<'a>(&'a self, case: &'a str)->&'a str{
+        self.function.as_deref().unwrap_or(target_function(case))
+    }
+}
+
+///Funzione del sorgente di un target che contiene le variabili iniettate dal runner: di solito
+/// quella con lo stesso nome, ma negli algoritmi ricorsivi è la funzione ricorsiva e nei target
+/// paralleli il corpo dei worker
+pub fn target_function(case: &str)->&str{
+    match case {
+        "sel_sort" => "selection_sort",
+        "quick_sort" => "quick_sort_rec",
+        "merge_sort" => "merge_sort_rec",
+        "par_matrix_multiplication" => "worker",
+        _ => case,
+    }
+}
+
+impl From<Sampling> for FaultListParams{
+    fn from(sampling: Sampling) -> Self {
+        sampling.with_bits(BitDistribution::Uniform)
+    }
+}
+
+impl From<i32> for FaultListParams{
+    fn from(num_faults: i32) -> Self {
+        Sampling::from(num_faults).into()
+    }
+}
+
+///Profilo di un'esecuzione senza fault del runner: numero di istruzioni eseguite e quante volte
+/// è stata eseguita ogni etichetta. L'istante t di una entry segue l'istruzione t-esima, quindi
+/// gli istanti estratti in modo uniforme cadono su ogni etichetta in proporzione all'istogramma
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExecutionProfile{
+    pub steps: usize,
+    pub histogram: BTreeMap<String, usize>,
+}
+
+impl ExecutionProfile{
+    pub fn from_trace(trace: &[&str])->Self{
+        let mut histogram = BTreeMap::new();
+        trace.iter().for_each(|label| *histogram.entry(label.to_string()).or_default() += 1);
+        ExecutionProfile{ steps: trace.len(), histogram }
+    }
+}
+
+//Solo numero di istruzioni, per le fault list generate senza eseguire il target
+impl From<usize> for ExecutionProfile{
+    fn from(steps: usize) -> Self {
+        ExecutionProfile{ steps, histogram: BTreeMap::new() }
+    }
+}
+
+///Stream del generatore dedicati alle due sorgenti di casualità di una campagna: a parità di
+/// seed la scelta dell'input e l'estrazione della fault list restano indipendenti
+pub const INPUT_STREAM: u64 = 0;
+pub const FAULT_LIST_STREAM: u64 = 1;
+
+///Generatore pseudo-casuale di una campagna. ChaCha8 produce la stessa sequenza su ogni
+/// piattaforma e versione di rand_chacha, quindi dal seed si rigenera l'intera campagna
+pub fn campaign_rng(seed: u64, stream: u64)->ChaCha8Rng{
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(stream);
+    rng
+}
+
+///Livello di confidenza con cui si riporta il margine di errore delle fault list casuali
+pub const DEFAULT_CONFIDENCE: f64 = 0.95;
+
+///Valore critico della normale standard per i livelli di confidenza supportati (None per gli
+/// altri livelli)
+pub fn z_score(confidence: f64)->Option<f64>{
+    match (confidence * 1000.0).round() as u32 {
+        900 => Some(1.645),
+        950 => Some(1.96),
+        980 => Some(2.326),
+        990 => Some(2.576),
+        999 => Some(3.291),
+        _ => None
+    }
+}
+
+///Dimensione del campione secondo Leveugle et al. (DATE 2009):
+///     n = N / (1 + e^2 * (N - 1) / (t^2 * p * (1 - p)))
+/// con N dimensione dello spazio dei fault, e margine di errore, t valore critico della
+/// confidenza e p = 0.5 (caso peggiore). None se la confidenza non è supportata da z_score
+pub fn sample_size(space_size: usize, margin: f64, confidence: f64)->Option<usize>{
+    let (n, t) = (space_size as f64, z_score(confidence)?);
+    let sample = n / (1.0 + margin * margin * (n - 1.0) / (t * t * 0.25));
+    Some((sample.ceil() as usize).min(space_size))
+}
+
+///Margine di errore ottenuto con un campione di 'sample' fault, invertendo la formula di
+/// sample_size (con il fattore di correzione per popolazione finita). None se la confidenza non è
+/// supportata da z_score
+pub fn error_margin(space_size: usize, sample: usize, confidence: f64)->Option<f64>{
+    let t = z_score(confidence)?;
+    if sample == 0 {
+        return Some(1.0);
+    }
+    if sample >= space_size {
+        return Some(0.0);
+    }
+    let (n, s) = (space_size as f64, sample as f64);
+    Some(t * (0.25 / s * (n - s) / (n - 1.0)).sqrt())
+}
+
+///Versione dello schema dei file di fault list: va incrementata a ogni modifica
+/// incompatibile dell'intestazione o delle entry
+pub const SCHEMA_VERSION: u32 = 1;
+
+///Intestazione della fault list: come è stata generata e con quale margine di errore
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FaultListMetadata{
+    //0 nelle fault list generate prima del versionamento dello schema
+    #[serde(default)]
+    pub schema_version: u32,
+    //target per cui è stata generata la fault list
+    #[serde(default)]
+    pub target: String,
+    pub mode: String,           //"random", "exhaustive", "statistical" o "derived" (vedi set_ops)
+    pub space_size: usize,      //numero di fault possibili
+    pub num_faults: usize,
+    pub confidence: f64,
+    pub error_margin: f64,
+    //seed della campagna (assente nelle fault list generate prima della sua introduzione)
+    #[serde(default)]
+    pub seed: Option<u64>,
+    //entry eliminate perché sovrascritte prima di essere lette: sono fault silenti
+    #[serde(default)]
+    pub dead_faults: usize,
+    //esecuzioni necessarie dopo la potatura (assente se la fault list non è stata potata)
+    #[serde(default)]
+    pub runs: Option<usize>,
+    //parametri del generatore (assenti nelle fault list senza versione dello schema)
+    #[serde(default)]
+    pub params: Option<FaultListParams>,
+    //peso di importanza di ogni variabile: rapporto tra la sua quota dello spazio dei fault e la
+    //probabilità con cui è stata scelta. Vuoto se le entry sono già uniformi sullo spazio
+    #[serde(default)]
+    pub site_weights: BTreeMap<String, f64>,
+    //entry morte per variabile, per pesarle come le altre nelle stime dell'analyzer
+    #[serde(default)]
+    pub dead_by_var: BTreeMap<String, usize>,
+    //esecuzioni di ogni etichetta nel run senza fault da cui è stata ricavata la durata; solo
+    //descrittivo, gli istanti sono estratti in modo uniforme sulla durata
+    #[serde(default)]
+    pub label_histogram: BTreeMap<String, usize>,
+    //posizione nel sorgente di ogni istruzione del target (vedi ResultAnalysis::statements),
+    //con cui l'analyzer riporta gli esiti alle righe del sorgente
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub statements: Vec<SourceSpan>,
+}
+
+impl FaultListMetadata{
+    ///Peso di importanza di una entry sulla variabile 'var' (1 se la selezione è uniforme)
+    pub fn site_weight(&self, var: &str)->f64{
+        if self.site_weights.is_empty() {
+            return 1.0;
         }
-        else{
-            panic!("Not possible!");
+        self.site_weights.get(site_of(var)).copied().unwrap_or(0.0)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FaultList{
+    pub metadata: FaultListMetadata,
+    pub entries: Vec<FaultListEntry>,
+}
+
+//Le fault list generate prima dell'intestazione sono un semplice array di entry
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FaultListFile{
+    WithMetadata(Box<FaultList>),
+    Entries(Vec<FaultListEntry>),
+}
+
+#[derive(Error, Debug)]
+pub enum FaultListError{
+    #[error("FaultListError::Io: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("FaultListError::Parse: riga {line}: {message}")]
+    Parse{ line: usize, message: String },
+    #[error("FaultListError::Schema: il file usa lo schema {found}, l'host il {expected}")]
+    Schema{ found: u32, expected: u32 },
+    #[error("FaultListError::Target: la fault list è stata generata per '{found}', la campagna è su '{expected}'")]
+    Target{ found: String, expected: String },
+    #[error("FaultListError::Invalid: {} problemi{}", .0.len(), .0.iter().map(|problem| format!("\n    {}", problem)).collect::<String>())]
+    Invalid(Vec<validation::EntryProblem>),
+    #[error("FaultListError::MissingHeader: {0} non ha un'intestazione da conservare")]
+    MissingHeader(String),
+    #[error("FaultListError::Incompatible: le fault list differiscono in {0}")]
+    Incompatible(String),
+    #[error("FaultListError::Analysis: {0}")]
+    Analysis(#[from] AnalysisError),
+    #[error("FaultListError::UnknownSites: pesi assegnati a variabili che il target non ha: {} (variabili: {available})", .names.join(", "))]
+    UnknownSites{ names: Vec<String>, available: String },
+    #[error("FaultListError::NoSites: nessuna variabile iniettabile ha un peso positivo")]
+    NoSites,
+    #[error("FaultListError::Confidence: livello di confidenza {0} non supportato (90, 95, 98, 99 o 99.9 %)")]
+    Confidence(f64),
+}
+
+///Formato del file della fault list, dedotto dall'estensione:
+///     - .jsonl: intestazione sulla prima riga, poi una entry JSON per riga
+///     - .csv: intestazione JSON su una riga di commento ('# '), riga con i nomi delle
+///       colonne e una entry per riga
+///     - altrimenti un unico documento JSON con intestazione ed entry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FaultListFormat{
+    Json,
+    JsonLines,
+    Csv,
+}
+
+const CSV_COLUMNS: &str = "var,time,flipped_bit,weight";
+
+impl FaultListFormat{
+    pub fn from_path(path: &str)->Self{
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("jsonl") => FaultListFormat::JsonLines,
+            Some("csv") => FaultListFormat::Csv,
+            _ => FaultListFormat::Json,
+        }
+    }
+
+    pub fn extension(&self)->&'static str{
+        match self {
+            FaultListFormat::Json => "json",
+            FaultListFormat::JsonLines => "jsonl",
+            FaultListFormat::Csv => "csv",
         }
     }
+
+    ///'path' con l'estensione del formato
+    pub fn apply(&self, path: &str)->String{
+        Path::new(path).with_extension(self.extension()).to_string_lossy().to_string()
+    }
+}
+
+impl FaultListEntry{
+    fn to_csv(&self)->String{
+        format!("{},{},{},{}", self.var, self.time, self.flipped_bit, self.weight)
+    }
+
+    //il peso è facoltativo come nel formato JSON
+    fn from_csv(row: &str)->Result<Self, String>{
+        let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+        if fields.len() != 3 && fields.len() != 4 {
+            return Err(format!("attesi 3 o 4 campi, trovati {}", fields.len()));
+        }
+        let number = |field: &str, name: &str| field.parse::<usize>()
+            .map_err(|_| format!("valore non valido per {}: '{}'", name, field));
+        Ok(FaultListEntry{
+            var: fields[0].to_string(),
+            time: number(fields[1], "time")?,
+            flipped_bit: number(fields[2], "flipped_bit")?,
+            weight: match fields.get(3) {
+                Some(weight) => number(weight, "weight")?,
+                None => unit_weight(),
+            },
+        })
+    }
+}
+
+///Serializzazione della fault list nel formato dedotto dall'estensione di 'path'
+pub fn write_fault_list(path: &str, fault_list: &FaultList)->Result<(), FaultListError>{
+    let mut writer = FaultListWriter::create(path, &fault_list.metadata)?;
+    for entry in &fault_list.entries {
+        writer.write(entry)?;
+    }
+    writer.finish()
+}
+
+///Scrittura incrementale di una fault list: l'intestazione all'apertura, poi una entry alla
+/// volta. Nel formato JSON ogni entry occupa una riga e il file viene chiuso da finish()
+pub struct FaultListWriter{
+    out: BufWriter<File>,
+    format: FaultListFormat,
+    num_entries: usize,
+}
+
+impl FaultListWriter{
+    pub fn create(path: &str, metadata: &FaultListMetadata)->Result<Self, FaultListError>{
+        let mut out = BufWriter::new(File::create(path)?);
+        let format = FaultListFormat::from_path(path);
+        let header = serde_json::to_string(metadata).unwrap();
+        match format {
+            FaultListFormat::Json => write!(out, "{{\n\"metadata\": {},\n\"entries\": [", header)?,
+            FaultListFormat::JsonLines => write!(out, "{}", header)?,
+            FaultListFormat::Csv => write!(out, "# {}\n{}", header, CSV_COLUMNS)?,
+        }
+        Ok(FaultListWriter{ out, format, num_entries: 0 })
+    }
+
+    pub fn write(&mut self, entry: &FaultListEntry)->Result<(), FaultListError>{
+        let separator = if self.format == FaultListFormat::Json && self.num_entries > 0 { "," } else { "" };
+        let line = match self.format {
+            FaultListFormat::Csv => entry.to_csv(),
+            _ => serde_json::to_string(entry).unwrap(),
+        };
+        write!(self.out, "{}\n{}", separator, line)?;
+        self.num_entries += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self)->Result<(), FaultListError>{
+        if self.format == FaultListFormat::Json {
+            write!(self.out, "\n]\n}}")?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+///Entry della fault list insieme alla riga del file in cui si trova
+type NumberedEntry = Result<(usize, FaultListEntry), FaultListError>;
+
+///Lettore di una fault list: l'intestazione è letta all'apertura, le entry dei formati a
+/// righe una alla volta, senza caricare l'intero file
+pub struct FaultListReader{
+    header: Option<FaultListMetadata>,
+    entries: Box<dyn Iterator<Item = NumberedEntry> + Send>,
+}
+
+//Nel formato JSON una entry può occupare più righe: la sua riga è quella del campo "var"
+fn json_entry_lines(content: &str)->Vec<usize>{
+    let var_field = Regex::new(r#""var"\s*:"#).unwrap();
+    content.lines().enumerate()
+        .flat_map(|(i, line)| std::iter::repeat_n(i + 1, var_field.find_iter(line).count()))
+        .collect()
+}
+
+impl FaultListReader{
+    pub fn open(path: &str)->Result<Self, FaultListError>{
+        let format = FaultListFormat::from_path(path);
+        if format == FaultListFormat::Json {
+            let flist_string = fs::read_to_string(path)?;
+            let (header, entries) = match serde_json::from_str(&flist_string)
+                .map_err(|e| FaultListError::Parse{ line: e.line(), message: e.to_string() })? {
+                FaultListFile::WithMetadata(fault_list) => (Some(fault_list.metadata), fault_list.entries),
+                FaultListFile::Entries(entries) => (None, entries),
+            };
+            let lines = json_entry_lines(&flist_string);
+            let entries = entries.into_iter().enumerate()
+                .map(move |(i, entry)| Ok((lines.get(i).copied().unwrap_or(0), entry)));
+            return Ok(FaultListReader{ header, entries: Box::new(entries) });
+        }
+
+        //numero di riga (da 1) insieme al contenuto, saltando le righe vuote
+        let mut lines = BufReader::new(File::open(path)?).lines().enumerate()
+            .map(|(i, line)| line.map(|line| (i + 1, line)))
+            .filter(|line| !matches!(line, Ok((_, l)) if l.trim().is_empty()));
+        let (line, first) = lines.next().transpose()?
+            .ok_or(FaultListError::Parse{ line: 1, message: "intestazione mancante".to_string() })?;
+        let header = match format {
+            FaultListFormat::Csv => first.strip_prefix('#')
+                .ok_or(FaultListError::Parse{ line, message: "intestazione mancante".to_string() })?,
+            _ => first.as_str(),
+        };
+        let header: FaultListMetadata = serde_json::from_str(header.trim())
+            .map_err(|e| FaultListError::Parse{ line, message: e.to_string() })?;
+        if format == FaultListFormat::Csv {
+            match lines.next().transpose()? {
+                Some((_, columns)) if columns.trim() == CSV_COLUMNS => {}
+                Some((line, _)) => return Err(FaultListError::Parse{ line, message: format!("colonne attese: {}", CSV_COLUMNS) }),
+                None => {}
+            }
+        }
+        let entries = lines.map(move |line| {
+            let (line, content) = line?;
+            match format {
+                FaultListFormat::Csv => FaultListEntry::from_csv(&content),
+                _ => serde_json::from_str(&content).map_err(|e| e.to_string()),
+            }.map(|entry| (line, entry)).map_err(|message| FaultListError::Parse{ line, message })
+        });
+        Ok(FaultListReader{ header: Some(header), entries: Box::new(entries) })
+    }
+
+    pub fn header(&self)->Option<&FaultListMetadata>{
+        self.header.as_ref()
+    }
+
+    ///Entry con il numero della riga del file in cui si trovano
+    pub fn numbered(self)->impl Iterator<Item = NumberedEntry>{
+        self.entries
+    }
+
+    ///Una fault list è utilizzabile in una campagna se ha lo schema corrente ed è stata
+    /// generata per lo stesso target; quelle senza intestazione sono accettate così come sono
+    pub fn check(&self, target: &str)->Result<(), FaultListError>{
+        let Some(header) = &self.header else { return Ok(()) };
+        if header.schema_version != SCHEMA_VERSION {
+            return Err(FaultListError::Schema{ found: header.schema_version, expected: SCHEMA_VERSION });
+        }
+        if header.target != target {
+            return Err(FaultListError::Target{ found: header.target.clone(), expected: target.to_string() });
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for FaultListReader{
+    type Item = Result<FaultListEntry, FaultListError>;
+
+    fn next(&mut self)->Option<Self::Item>{
+        self.entries.next().map(|entry| entry.map(|(_, entry)| entry))
+    }
+}
+
+///Intestazione ed entry della fault list salvata in 'path'
+pub(crate) fn read_fault_list(path: &str)->Result<(Option<FaultListMetadata>, Vec<FaultListEntry>), FaultListError>{
+    let reader = FaultListReader::open(path)?;
+    let header = reader.header().cloned();
+    Ok((header, reader.collect::<Result<_, _>>()?))
+}
+
+
+///Blocco dello spazio dei fault: tutte le combinazioni di elemento, istante e bit di una variabile
+struct SpaceBlock{
+    var: usize,             //indice della variabile in 'vars'
+    names: Vec<String>,
+    times: Range<usize>,
+    bits: usize,
+}
+
+impl SpaceBlock{
+    fn size(&self)->usize{
+        self.names.len() * self.times.len() * self.bits
+    }
+}
+
+///Spazio dei fault indicizzabile: ogni indice in 0..size() corrisponde a un'unica entry
+pub struct FaultSpace{
+    blocks: Vec<SpaceBlock>,
+    offsets: Vec<usize>,    //indice della prima entry di ogni blocco
+    size: usize,
+}
+
+impl FaultSpace{
+    fn new(vars: &[Variable], dims: &DimData, case: &str, num_instr_eff: usize, bit_distribution: BitDistribution)->Self{
+        let mut blocks = Vec::new();
+        for (v, var) in vars.iter().enumerate() {
+            let name = &var.name;
+            let bits = bit_distribution.support(bit_width(var));
+            let times = var.start..num_instr_eff;
+            let names: Vec<String> = if is_vector(&var.ty) {
+                (0..dims.vector_len(case, name)).map(|i| format!("{}[{}]", name, i)).collect()
+            } else if var.ty.starts_with("Vec < Vec") {
+                let (n_r, n_c) = dims.matrix_dims(name);
+                (0..n_r).flat_map(|r| (0..n_c).map(move |c| format!("{}[{}][{}]", name, r, c))).collect()
+            } else if let (DimData::Frames(_, frames), true) = (dims, name != "depth") {
+                //i frame attivi cambiano nel tempo: un blocco per ogni istante
+                for t in times.filter(|&t| t > 0 && frames[t-1] > 0) {
+                    blocks.push(SpaceBlock{ var: v, names: (0..frames[t-1]).map(|f| format!("{}@{}", name, f)).collect(), times: t..t+1, bits });
+                }
+                continue;
+            } else if let (DimData::Workers(_, workers), true) = (dims, name != "next_row") {
+                (0..*workers).map(|w| format!("{}@{}", name, w)).collect()
+            } else {
+                vec![name.clone()]
+            };
+            blocks.push(SpaceBlock{ var: v, names, times, bits });
+        }
+        blocks.retain(|block| block.size() > 0);
+
+        let mut offsets = Vec::with_capacity(blocks.len());
+        let mut size = 0;
+        for block in &blocks {
+            offsets.push(size);
+            size += block.size();
+        }
+        FaultSpace{ blocks, offsets, size }
+    }
+
+    pub fn size(&self)->usize{
+        self.size
+    }
+
+    ///Numero di entry dello spazio per ognuna delle 'num_vars' variabili
+    fn var_sizes(&self, num_vars: usize)->Vec<usize>{
+        let mut sizes = vec![0; num_vars];
+        self.blocks.iter().for_each(|block| sizes[block.var] += block.size());
+        sizes
+    }
+
+    ///Numero di istanti in cui ognuna delle 'num_vars' variabili è iniettabile
+    fn var_lifetimes(&self, num_vars: usize)->Vec<usize>{
+        let mut lifetimes = vec![0; num_vars];
+        self.blocks.iter().for_each(|block| lifetimes[block.var] += block.times.len());
+        lifetimes
+    }
+
+    ///Posizione del bit invertito dalla entry di indice 'index'
+    fn bit(&self, index: usize)->usize{
+        let b = self.offsets.partition_point(|&offset| offset <= index) - 1;
+        (index - self.offsets[b]) % self.blocks[b].bits
+    }
+
+    fn entry(&self, index: usize)->FaultListEntry{
+        let b = self.offsets.partition_point(|&offset| offset <= index) - 1;
+        let block = &self.blocks[b];
+        let mut rest = index - self.offsets[b];
+        let flipped_bit = rest % block.bits;
+        rest /= block.bits;
+        let time = block.times.start + rest % block.times.len();
+        rest /= block.times.len();
+        FaultListEntry{ var: block.names[rest].clone(), time, flipped_bit, weight: 1 }
+    }
+}
+
+///Variabili iniettabili del target: quelle dell'analisi statica di dimensione nota, più lo
+/// stato che l'analisi non vede (profondità della ricorsione, stato condiviso dai worker)
+fn injectable_vars(mut vars: Vec<Variable>, dims: &DimData)->Vec<Variable>{
+    //Sono iniettabili solo le variabili i cui elementi sono scalari di dimensione nota: ne
+    //restano fuori ad esempio le liste di adiacenza dei grafi (vettori di tuple, un dato di sola
+    //lettura), gli array e le struct
+    vars.retain(|var| var.size != TypeSize::Unknown && is_scalar(element_type(&var.ty)));
+    //Negli algoritmi ricorsivi anche la profondità della ricorsione è iniettabile
+    if let DimData::Frames(..) = dims {
+        vars.push(Variable{
+            name: "depth".to_string(),
+            ty: "usize".to_string(),
+            size: static_analysis::type_size("usize"),
+            start: 1,
+            ..Variable::default()
+        });
+    }
+    //Nei target paralleli l'analisi statica vede solo la funzione eseguita dai worker, le cui
+    //variabili sono locali a ogni thread: lo stato condiviso viene aggiunto qui, al posto dei
+    //parametri con cui il worker vi accede
+    if let DimData::Workers(..) = dims {
+        const SHARED: [(&str, &str); 4] = [("next_row", "usize"), ("a", "Vec < Vec < i32 > >"),
+                                           ("b", "Vec < Vec < i32 > >"), ("result", "Vec < Vec < i32 > >")];
+        vars.retain(|var| SHARED.iter().all(|(name, _)| var.name != *name));
+        for (name, ty) in SHARED {
+            vars.push(Variable{
+                name: name.to_string(),
+                ty: ty.to_string(),
+                size: static_analysis::type_size(ty),
+                start: 1,
+                ..Variable::default()
+            });
+        }
+    }
+    vars
+}
+
+///Fault list generata ma non ancora materializzata: l'intestazione è nota subito, le entry
+/// vengono prodotte una alla volta, nello stesso ordine in cui create_fault_list le salva
+pub struct FaultListStream{
+    pub metadata: FaultListMetadata,
+    pub entries: Box<dyn Iterator<Item = FaultListEntry> + Send>,
 }
 
 ///Generazione della fault list:
-///     - generazione casuale di un certo numero di entry +
+///     - generazione casuale di un certo numero di entry, enumerazione esaustiva dello spazio
+///       dei fault oppure campione dimensionato per un margine di errore (vedi Sampling)
+///     - la posizione del bit invertito segue la distribuzione scelta (vedi BitDistribution);
+///       nell'enumerazione esaustiva la distribuzione determina solo quali bit sono enumerati
+///     - tutte le estrazioni dipendono solo da 'seed', che viene salvato nell'intestazione
+///     - gli istanti sono estratti in modo uniforme tra 1 e il numero di istruzioni del profilo,
+///       misurato eseguendo il runner senza fault, e cadono quindi su ogni etichetta in
+///       proporzione alle sue esecuzioni. L'istogramma delle etichette non interviene
+///       nell'estrazione: viene solo salvato nell'intestazione
+///     - fallisce se il file di analisi non è utilizzabile, se i pesi dell'utente non
+///       selezionano nessuna variabile del target o se la confidenza richiesta non è supportata
 ///
 /// path_raw_info
-pub fn create_fault_list(num_faults:i32, case: String, path_raw_info: String, dims: DimData, file_path_dest: String,
-                            num_instr_eff: usize)       //Number of actual instructions
-    ->Vec<FaultListEntry>{
+pub fn generate_fault_list(params: impl Into<FaultListParams>, case: String, path_raw_info: String, dims: DimData,
+                           profile: impl Into<ExecutionProfile>,   //esecuzione senza fault del runner
+                           seed: u64)
+    ->Result<FaultListStream, FaultListError>{
+    let ExecutionProfile{ steps: num_instr_eff, histogram: label_histogram } = profile.into();
+    let params: FaultListParams = params.into();
     //RETRIEVING INFORMAZIONI GREZZE
-    //Prendere il contenuto del file come stringa
-    let raw_info = fs::read_to_string(path_raw_info).unwrap();
-    //Unmarshaling (Stringa JSON --> Struttura Dati)
-    let info:ResultAnalysis =serde_json::from_str(&raw_info).unwrap();
+    //Unmarshaling (Stringa JSON --> Struttura Dati) della funzione scelta
+    let info = static_analysis::load_analysis(&path_raw_info, params.function(&case))?;
 
 
     //-----------------------Per Debug--------------------------
     //println!("Numero istruzioni: {}", info.num_inst);
 
-    let vars:Vec<Variable> = info.vars;
-    let num_vars=vars.len();
+    let statements = info.statements;
+    let vars = injectable_vars(info.vars, &dims);
     //----------------------------------------------------------
 
     //--------------------------GENERAZIONE DELLA FAULT LIST-----------------------------
-    let mut fault_list:Vec<FaultListEntry> = Vec::new();
-    //Ingrediente fondamentale: Generazione di numeri casuali
-    //Fonte utile:
-    //https://rust-lang-nursery.github.io/rust-cookbook/algorithms/randomness.html#generate-random-values
-
-    let mut rnd=rand::thread_rng();
-
-    for _ in 0..num_faults{
-        let what_var=rnd.gen_range(0..num_vars);
-        //Caso 'vettore'
-        if vars[what_var].ty==String::from("Vec < i32 >") {
-            #[allow(unused_assignments)]
-            let mut n=0;
-            //vettore accessorio usato dall'algoritmo di matrix_multiplication
-            if case=="matrix_multiplication"{
-                let tupla=dims.into_tuple();
-                n=tupla.0;
-            }
-            else{
-                n = dims.into_n();
+    let FaultListParams{ sampling, bits, .. } = params;
+    let space = FaultSpace::new(&vars, &dims, &case, num_instr_eff, bits);
+    let mut rng = campaign_rng(seed, FAULT_LIST_STREAM);
+    match sampling {
+        Sampling::Random(num_faults) => {
+            //le entry sono estratte con reinserimento: il margine è quello di un campione di
+            //pari dimensione senza ripetizioni
+            if let SiteSelection::Custom(custom) = &params.sites {
+                let names: Vec<String> = custom.keys().filter(|name| vars.iter().all(|var| &var.name != *name)).cloned().collect();
+                if !names.is_empty() {
+                    let available = vars.iter().map(|var| var.name.as_str()).collect::<Vec<_>>().join(", ");
+                    return Err(FaultListError::UnknownSites{ names, available });
+                }
             }
-            //Quale variabile del vettore voglio iniettare?
-            let what_el = rnd.gen_range(0..n);
-            let it = FaultListEntry {
-                var: format!("{}[{}]", vars[what_var].name, what_el),
-                time: rnd.gen_range(vars[what_var].start..num_instr_eff),
-                flipped_bit: rnd.gen_range(0..size_of::<i32>()),
-            };
-            fault_list.push(it);
-        }
-        //Caso 'matrice'
-        else if vars[what_var].ty==String::from("Vec < Vec < i32 > >"){
-            //Quale variabile del vettore voglio iniettare?
-            let (n_r, n_c) = dims.into_tuple();
-            //Genero un elemento a caso (riga/colonna)
-            let r = rnd.gen_range(0..n_r);       //Scelgo a caso la riga
-            let c = rnd.gen_range(0..n_c);       //Scelgo a caso la colonna
-
-            let it = FaultListEntry {
-                var: format!("{}[{}][{}]", vars[what_var].name, r,c),
-                time: rnd.gen_range(vars[what_var].start..num_instr_eff),
-                flipped_bit: rnd.gen_range(0..size_of::<i32>()),
-            };
-            fault_list.push(it);
-        }
-        //Caso 'non vettore', 'non matrice'
-        else {
-            let it = FaultListEntry {
-                var: vars[what_var].name.clone(),
-                time: rnd.gen_range(vars[what_var].start..num_instr_eff),
-                flipped_bit: rnd.gen_range(0..vars[what_var].size
-                    .parse::<usize>()
-                    .unwrap() *8),
-            };
-            fault_list.push(it);
+            let weights = params.sites.weights(&vars, &space);
+            let picker = SitePicker::new(&params.sites, &weights, &space.var_sizes(vars.len()), bits)?;
+            let metadata = FaultListMetadata{ mode: "random".to_string(), space_size: space.size(), num_faults,
+                confidence: DEFAULT_CONFIDENCE, error_margin: error_margin(space.size(), num_faults, DEFAULT_CONFIDENCE).expect("DEFAULT_CONFIDENCE supportata da z_score"), seed: Some(seed), dead_faults: 0, runs: None,
+                schema_version: SCHEMA_VERSION, target: case.clone(), site_weights: importance_weights(&vars, &space, &weights), dead_by_var: BTreeMap::new(), label_histogram, statements,
+                params: Some(params) };
+            let entries = (0..num_faults).map(move |_| random_entry(&case, &vars, &dims, num_instr_eff, &picker, &mut rng));
+            Ok(FaultListStream{ metadata, entries: Box::new(entries) })
+        }
+        Sampling::Exhaustive => {
+            let metadata = FaultListMetadata{ mode: "exhaustive".to_string(), space_size: space.size(), num_faults: space.size(),
+                confidence: 1.0, error_margin: 0.0, seed: Some(seed), dead_faults: 0, runs: None,
+                schema_version: SCHEMA_VERSION, target: case.clone(), site_weights: BTreeMap::new(), dead_by_var: BTreeMap::new(), label_histogram, statements,
+                params: Some(params.clone()) };
+            Ok(FaultListStream{ metadata, entries: Box::new((0..space.size()).map(move |i| space.entry(i))) })
+        }
+        Sampling::Statistical{margin, confidence} => {
+            let n = sample_size(space.size(), margin, confidence).ok_or(FaultListError::Confidence(confidence))?;
+            //con una distribuzione dei bit non uniforme il campione è estratto senza ripetizioni
+            //con probabilità proporzionali al peso del bit; il margine riportato è quello del
+            //campionamento uniforme
+            let mut indices = match bits {
+                BitDistribution::MsbWeighted => rand::seq::index::sample_weighted(&mut rng, space.size(), |i| bits.weight(space.bit(i)), n).unwrap(),
+                _ => rand::seq::index::sample(&mut rng, space.size(), n),
+            }.into_vec();
+            indices.sort_unstable();
+            let metadata = FaultListMetadata{ mode: "statistical".to_string(), space_size: space.size(), num_faults: n,
+                confidence, error_margin: error_margin(space.size(), n, confidence).ok_or(FaultListError::Confidence(confidence))?, seed: Some(seed), dead_faults: 0, runs: None,
+                schema_version: SCHEMA_VERSION, target: case.clone(), site_weights: BTreeMap::new(), dead_by_var: BTreeMap::new(), label_histogram, statements,
+                params: Some(params.clone()) };
+            Ok(FaultListStream{ metadata, entries: Box::new(indices.into_iter().map(move |i| space.entry(i))) })
         }
     }
+}
 
-
+///Genera la fault list (vedi generate_fault_list) e la salva in 'file_path_dest'
+pub fn create_fault_list(params: impl Into<FaultListParams>, case: String, path_raw_info: String, dims: DimData, file_path_dest: String,
+                            profile: impl Into<ExecutionProfile>,   //esecuzione senza fault del runner
+                            seed: u64)
+    ->Result<Vec<FaultListEntry>, FaultListError>{
+    let FaultListStream{ metadata, entries } = generate_fault_list(params, case, path_raw_info, dims, profile, seed)?;
+    let fault_list: Vec<FaultListEntry> = entries.collect();
 
     //SERIALIZZAZIONE (MARSHALLING) della fault list
 
-    //TODO: risolvere problema di errore su file
-    let mut fl= OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .append(false)
-        .create(true)
-        .open(file_path_dest)
-        .unwrap();
-    let ris_json = serde_json::to_string_pretty(&fault_list).unwrap();
-    fl.write_all(ris_json.as_bytes()).unwrap();
+    //il formato (JSON, JSON Lines o CSV) è dedotto dall'estensione del file
+    write_fault_list(&file_path_dest, &FaultList{ metadata, entries: fault_list.clone() })?;
+
+    Ok(fault_list)
+}
+
+///Peso di importanza di ogni variabile scelta con probabilità proporzionale a 'weights': la
+/// quota dello spazio dei fault (tra le variabili che possono essere scelte) divisa per la
+/// probabilità di sceglierla. Pesando ogni esito così, le percentuali stimate si riferiscono
+/// allo spazio dei fault e non alla strategia di selezione
+fn importance_weights(vars: &[Variable], space: &FaultSpace, weights: &[f64])->BTreeMap<String, f64>{
+    let sizes = space.var_sizes(vars.len());
+    let total_weight: f64 = weights.iter().sum();
+    let covered: usize = sizes.iter().zip(weights).filter(|(_, &w)| w > 0.0).map(|(size, _)| size).sum();
+    vars.iter().zip(sizes.iter().zip(weights))
+        .filter(|(_, (&size, &w))| size > 0 && w > 0.0)
+        .map(|(var, (&size, &w))| (var.name.clone(), (size as f64 / covered as f64) / (w / total_weight)))
+        .collect()
+}
 
-    return fault_list;
+///Scelta della variabile e del bit delle entry casuali
+struct SitePicker{
+    vars: Option<WeightedIndex<f64>>,   //None: variabili equiprobabili
+    available: Vec<bool>,               //variabili con almeno una entry nello spazio dei fault
+    bits: BitDistribution,
+}
+
+impl SitePicker{
+    ///Le variabili senza entry nello spazio dei fault (es. locali di una ricorsione che non
+    /// hanno mai un frame attivo dopo la dichiarazione) non vengono mai scelte
+    fn new(sites: &SiteSelection, weights: &[f64], sizes: &[usize], bits: BitDistribution)->Result<Self, FaultListError>{
+        let available: Vec<bool> = sizes.iter().map(|&size| size > 0).collect();
+        //con variabili equiprobabili si estrae come prima dell'introduzione delle strategie, in
+        //modo che gli stessi seed producano le stesse fault list: le variabili non disponibili
+        //vengono scartate ed estratte di nuovo
+        let vars = match sites {
+            SiteSelection::PerVariable if available.contains(&true) => None,
+            SiteSelection::PerVariable => return Err(FaultListError::NoSites),
+            _ => {
+                let weights: Vec<f64> = weights.iter().zip(&available).map(|(&w, &ok)| if ok { w } else { 0.0 }).collect();
+                Some(WeightedIndex::new(weights).map_err(|_| FaultListError::NoSites)?)
+            }
+        };
+        Ok(SitePicker{ vars, available, bits })
+    }
+
+    fn var(&self, rnd: &mut impl Rng)->usize{
+        match &self.vars {
+            Some(index) => index.sample(rnd),
+            None => loop {
+                let var = rnd.gen_range(0..self.available.len());
+                if self.available[var] {
+                    break var;
+                }
+            },
+        }
+    }
+}
+
+///Estrazione casuale di una entry: prima si sceglie la variabile, poi elemento, istante e bit
+fn random_entry(case: &str, vars: &[Variable], dims: &DimData, num_instr_eff: usize, picker: &SitePicker,
+                rnd: &mut impl Rng)->FaultListEntry{
+    let bits = picker.bits;
+    //Ingrediente fondamentale: Generazione di numeri casuali
+    //Fonte utile:
+    //https://rust-lang-nursery.github.io/rust-cookbook/algorithms/randomness.html#generate-random-values
+
+    let what_var=picker.var(rnd);
+    //Caso 'vettore'
+    if is_vector(&vars[what_var].ty) {
+        let n = dims.vector_len(case, &vars[what_var].name);
+        //Quale variabile del vettore voglio iniettare?
+        let what_el = rnd.gen_range(0..n);
+        FaultListEntry {
+            var: format!("{}[{}]", vars[what_var].name, what_el),
+            time: rnd.gen_range(vars[what_var].start..num_instr_eff),
+            flipped_bit: bits.sample(bit_width(&vars[what_var]), rnd),
+            weight: 1,
+        }
+    }
+    //Caso 'matrice'
+    else if vars[what_var].ty.starts_with("Vec < Vec"){
+        //Quale variabile del vettore voglio iniettare?
+        let (n_r, n_c) = dims.matrix_dims(&vars[what_var].name);
+        //Genero un elemento a caso (riga/colonna)
+        let r = rnd.gen_range(0..n_r);       //Scelgo a caso la riga
+        let c = rnd.gen_range(0..n_c);       //Scelgo a caso la colonna
+
+        FaultListEntry {
+            var: format!("{}[{}][{}]", vars[what_var].name, r,c),
+            time: rnd.gen_range(vars[what_var].start..num_instr_eff),
+            flipped_bit: bits.sample(bit_width(&vars[what_var]), rnd),
+            weight: 1,
+        }
+    }
+    //Caso 'variabile locale di un frame' (algoritmi ricorsivi): si sceglie un istante in cui
+    //almeno un frame è attivo e poi uno dei frame presenti in quell'istante
+    else if let (DimData::Frames(_, frames), true) = (dims, vars[what_var].name != "depth") {
+        //Il contatore dell'iniettore parte da 1: frames[t-1] è lo stato dopo l'istruzione t
+        let live_times:Vec<usize> = (vars[what_var].start..num_instr_eff)
+            .filter(|&t| t>0 && frames[t-1]>0)
+            .collect();
+        let time = live_times[rnd.gen_range(0..live_times.len())];
+        FaultListEntry {
+            var: format!("{}@{}", vars[what_var].name, rnd.gen_range(0..frames[time-1])),
+            time,
+            flipped_bit: bits.sample(bit_width(&vars[what_var]), rnd),
+            weight: 1,
+        }
+    }
+    //Caso 'variabile locale di un worker' (target paralleli): si sceglie il worker
+    else if let (DimData::Workers(_, workers), true) = (dims, vars[what_var].name != "next_row") {
+        FaultListEntry {
+            var: format!("{}@{}", vars[what_var].name, rnd.gen_range(0..*workers)),
+            time: rnd.gen_range(vars[what_var].start..num_instr_eff),
+            flipped_bit: bits.sample(bit_width(&vars[what_var]), rnd),
+            weight: 1,
+        }
+    }
+    //Caso 'non vettore', 'non matrice'
+    else {
+        FaultListEntry {
+            var: vars[what_var].name.clone(),
+            time: rnd.gen_range(vars[what_var].start..num_instr_eff),
+            flipped_bit: bits.sample(bit_width(&vars[what_var]), rnd),
+            weight: 1,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -144,23 +1005,554 @@ pub struct FaultListEntry{
     pub var: String,
     pub time: usize,
     pub flipped_bit: usize,
+    //numero di fault equivalenti rappresentati dalla entry (vedi liveness)
+    #[serde(default = "unit_weight")]
+    pub weight: usize,
+}
+
+fn unit_weight()->usize{
+    1
+}
+
+///Entry pronte per essere inviate all'iniettore
+pub type EntryStream = Box<dyn Iterator<Item = Result<FaultListEntry, FaultListError>> + Send>;
+
+///Sorgente delle entry della campagna
+pub enum FaultSource{
+    File(String, Registry),                         //fault list salvata in un file, con il registro
+                                                    //del target rispetto a cui validarla
+    Stream(Box<FaultListStream>, Option<String>),   //entry generate durante la campagna, copiate
+                                                    //nel file indicato man mano che vengono inviate
+}
+
+impl FaultSource{
+    ///Intestazione ed entry della sorgente. La sorgente viene rifiutata prima di produrre
+    /// qualsiasi entry se lo schema o il target non corrispondono alla campagna, oppure se una
+    /// entry del file non appartiene allo spazio dei fault del target (vedi validation). Le entry
+    /// generate durante la campagna vi appartengono per costruzione
+    pub fn open(self, target: &str)->Result<(Option<FaultListMetadata>, EntryStream), FaultListError>{
+        match self {
+            FaultSource::File(path, registry) => {
+                validate_fault_list(&path, target, &registry)?;
+                //Deserializzare (unmarshalling)) della fault list
+                let reader = FaultListReader::open(&path)?;
+                Ok((reader.header().cloned(), Box::new(reader)))
+            }
+            FaultSource::Stream(stream, tee) => {
+                let FaultListStream{ metadata, entries } = *stream;
+                if metadata.target != target {
+                    return Err(FaultListError::Target{ found: metadata.target, expected: target.to_string() });
+                }
+                let writer = tee.map(|path| FaultListWriter::create(&path, &metadata)).transpose()?;
+                Ok((Some(metadata), Box::new(Tee{ entries, writer })))
+            }
+        }
+    }
+}
+
+//Copia nel file ogni entry generata prima di restituirla; il file viene chiuso dopo l'ultima
+struct Tee{
+    entries: Box<dyn Iterator<Item = FaultListEntry> + Send>,
+    writer: Option<FaultListWriter>,
+}
+
+impl Iterator for Tee{
+    type Item = Result<FaultListEntry, FaultListError>;
+
+    fn next(&mut self)->Option<Self::Item>{
+        match (self.entries.next(), &mut self.writer) {
+            (Some(entry), Some(writer)) => Some(writer.write(&entry).map(|_| entry)),
+            (Some(entry), None) => Some(Ok(entry)),
+            (None, _) => self.writer.take().and_then(|writer| writer.finish().err()).map(Err),
+        }
+    }
 }
 
 //Stage della pipeline: Fault List Manager
-pub fn fault_manager(tx_chan_fm_inj: Sender<FaultListEntry>, fault_list:String){
-    //Deserializzare (unmarshalling)) della fault list
-    let flist_string = fs::read_to_string(fault_list).unwrap();
-    let flist:Vec<FaultListEntry>=serde_json::from_str(&flist_string.trim()).unwrap();
-    flist.into_iter().for_each(|el|tx_chan_fm_inj.send(el).unwrap());
-    drop(tx_chan_fm_inj);
+//Il canale verso l'iniettore è limitato: quando è pieno la lettura (o la generazione) delle
+//entry si ferma finché l'iniettore non ne preleva altre. Restituisce il numero di entry inviate
+pub fn fault_manager(tx_chan_fm_inj: SyncSender<FaultListEntry>, entries: EntryStream)
+    ->Result<usize, FaultListError>{
+    let mut sent = 0;
+    for entry in entries {
+        //l'iniettore ha chiuso il canale: le entry restanti non verrebbero eseguite
+        if tx_chan_fm_inj.send(entry?).is_err() {
+            break;
+        }
+        sent += 1;
+    }
+    Ok(sent)
 }
 
 #[cfg(test)]
 mod tests{
+    use crate::fault_list_manager::type_size::PointerWidth;
+    use std::collections::{BTreeMap, HashMap};
+    use std::env::temp_dir;
+    use crate::fault_env::Data;
+    use std::collections::HashSet;
+    use std::fs;
+    use std::sync::mpsc::sync_channel;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use crate::fault_list_manager::static_analysis::Variable;
+    use crate::fault_list_manager::{bit_width, create_fault_list, error_margin, generate_fault_list, BitDistribution, ExecutionProfile, FaultListEntry, FaultListMetadata, FaultListParams, FaultSource, fault_manager, read_fault_list, sample_size, site_of, z_score, SiteSelection, static_analysis, write_fault_list, DimData, FaultListError, FaultListFormat, Sampling, SCHEMA_VERSION};
+    use crate::fault_list_manager::validation::Registry;
+    use crate::injector::{frame_trace, label_trace};
+    use crate::hardened::par_matrix_multiplication_hardened::PAR_WORKERS;
+
+    //Invia le entry come lo stage della pipeline; il canale è limitato, quindi la ricezione
+    //avviene su un altro thread. Restituisce intestazione, entry ricevute ed esito dell'invio
+    type Delivered = (Option<FaultListMetadata>, usize, Result<usize, FaultListError>);
+    fn run_fault_manager(path: &str, target: &str, registry: Registry)->Result<Delivered, FaultListError>{
+        let (header, entries) = FaultSource::File(path.to_string(), registry).open(target)?;
+        let (tx, rx) = sync_channel(4);
+        let handle = thread::spawn(move || fault_manager(tx, entries));
+        let received = rx.iter().count();
+        Ok((header, received, handle.join().unwrap()))
+    }
+
+    //File di analisi del sorgente di 'case' in file_fault_list, diverso a ogni chiamata perché i
+    //test vengono eseguiti in parallelo
+    fn analysis_file(case: &str)->String{
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let analysis = temp_dir().join(format!("{}_ris_test_{}.json", case, NEXT.fetch_add(1, Ordering::Relaxed))).to_string_lossy().to_string();
+        static_analysis::generate_analysis_file(
+            format!("src/fault_list_manager/file_fault_list/{}/mod.rs", case), analysis.clone(), PointerWidth::HOST).unwrap();
+        analysis
+    }
+
     #[test]
     fn test_trivial(){
         assert_eq!(2,2);
     }
+
+    #[test]
+    fn test_fault_list_frames_are_live(){
+        let analysis = analysis_file("quick_sort");
+        let dest = temp_dir().join("quick_sort_FL_test.json").to_string_lossy().to_string();
+
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        let frames = frame_trace("quick_sort", Data::Vector(vet.clone()));
+        let num_instr = frames.len();
+        let fault_list = create_fault_list(500, "quick_sort".to_string(), analysis,
+                                           DimData::Frames(vet.len(), frames.clone()), dest, num_instr, 42).unwrap();
+
+        assert_eq!(fault_list.len(), 500);
+        for entry in fault_list.iter().filter(|e| e.var.contains('@')) {
+            let d = entry.var.split('@').nth(1).unwrap().parse::<usize>().unwrap();
+            assert!(d < frames[entry.time - 1], "{:?}", entry);
+        }
+        assert!(fault_list.iter().any(|e| e.var == "depth"));
+    }
+
+    #[test]
+    fn test_fault_list_frames_never_live(){
+        let analysis = analysis_file("quick_sort");
+        let dest = temp_dir().join("quick_sort_dead_frames_FL_test.json").to_string_lossy().to_string();
+
+        //un solo frame nei primi due istanti: le locali dichiarate dopo non sono mai iniettabili
+        let frames = [vec![1, 1], vec![0; 30]].concat();
+        let starts: HashMap<String, usize> = static_analysis::AnalysisDocument::load(&analysis).unwrap().functions.into_values()
+            .flat_map(|analysis| analysis.vars)
+            .map(|var| (var.name, var.start)).collect();
+        let fault_list = create_fault_list(200, "quick_sort".to_string(), analysis,
+                                           DimData::Frames(4, frames.clone()), dest, frames.len(), 7).unwrap();
+        assert_eq!(fault_list.len(), 200);
+        for entry in fault_list.iter().filter(|e| e.var.contains('@')) {
+            assert!(entry.time <= 2 && starts[site_of(&entry.var)] <= entry.time, "{:?}", entry);
+        }
+    }
+
+    #[test]
+    fn test_fault_list_graph(){
+        let analysis = analysis_file("dijkstra");
+        let dest = temp_dir().join("dijkstra_FL_test.json").to_string_lossy().to_string();
+
+        let graph = vec![vec![(1, 4), (2, 1)], vec![(3, 1)], vec![(1, 2), (3, 5)], vec![(4, 3)], vec![], vec![(0, 1)]];
+        let num_instr = label_trace("dijkstra", Data::Graph(graph.clone(), 0)).len();
+        let fault_list = create_fault_list(500, "dijkstra".to_string(), analysis,
+                                           DimData::Graph(6, 7), dest, num_instr, 42).unwrap();
+
+        //le liste di adiacenza non sono iniettabili
+        assert!(fault_list.iter().all(|e| !e.var.starts_with("graph")));
+        for entry in fault_list.iter().filter(|e| e.var.contains('[')) {
+            let index = entry.var.split(['[', ']']).nth(1).unwrap().parse::<usize>().unwrap();
+            let bound = if entry.var.starts_with("pq_") { 8 } else { 6 };
+            assert!(index < bound, "{:?}", entry);
+        }
+        assert!(fault_list.iter().any(|e| e.var.starts_with("visited[")));
+    }
+
+    #[test]
+    fn test_fault_list_real_kernel(){
+        let analysis = analysis_file("jacobi");
+        let dest = temp_dir().join("jacobi_FL_test.json").to_string_lossy().to_string();
+
+        let system = Data::LinearSystem(vec![vec![4.0, 1.0], vec![1.0, 5.0]], vec![5.0, 9.0]);
+        let lengths: HashMap<String, usize> = ["a", "b", "x", "x_new"].iter().map(|n| (n.to_string(), 2)).collect();
+        let fault_list = create_fault_list(500, "jacobi".to_string(), analysis,
+                                           DimData::Lengths(lengths), dest, label_trace("jacobi", system).len(), 42).unwrap();
+
+        for entry in fault_list.iter().filter(|e| e.var.contains('[')) {
+            let indices: Vec<usize> = entry.var.split(['[', ']']).filter_map(|i| i.parse().ok()).collect();
+            assert!(indices.iter().all(|&i| i < 2), "{:?}", entry);
+        }
+        assert!(fault_list.iter().any(|e| e.var.starts_with("a[")));
+        //sigma è un f64: i bit selezionabili sono 64
+        assert!(fault_list.iter().filter(|e| e.var == "sigma").all(|e| e.flipped_bit < 64));
+    }
+
+    #[test]
+    fn test_fault_list_bytes(){
+        let analysis = analysis_file("crc32");
+        let dest = temp_dir().join("crc32_FL_test.json").to_string_lossy().to_string();
+
+        let message = Data::Bytes(b"abc".to_vec());
+        let lengths = HashMap::from([("data".to_string(), 3), ("table".to_string(), 256)]);
+        let fault_list = create_fault_list(1000, "crc32".to_string(), analysis,
+                                           DimData::Lengths(lengths), dest, label_trace("crc32", message).len(), 42).unwrap();
+
+        //byte è un u8, c e crc sono u32
+        assert!(fault_list.iter().filter(|e| e.var == "byte").all(|e| e.flipped_bit < 8));
+        assert!(fault_list.iter().any(|e| e.var == "crc" && e.flipped_bit >= 8));
+        assert!(fault_list.iter().filter(|e| e.var == "c" || e.var == "crc").all(|e| e.flipped_bit < 32));
+        assert!(fault_list.iter().any(|e| e.var.starts_with("table[")));
+        assert!(fault_list.iter().filter(|e| e.var.starts_with("data[")).all(|e| e.var != "data[3]"));
+    }
+
+    #[test]
+    fn test_fault_list_workers(){
+        let analysis = analysis_file("par_matrix_multiplication");
+        let dest = temp_dir().join("par_matrix_mul_FL_test.json").to_string_lossy().to_string();
+
+        let matrices = Data::Matrices(vec![vec![1, 2], vec![3, 4]], vec![vec![5, 6], vec![7, 8]]);
+        let fault_list = create_fault_list(1000, "par_matrix_multiplication".to_string(), analysis,
+                                           DimData::Workers((2, 2), PAR_WORKERS), dest, label_trace("par_matrix_multiplication", matrices).len(), 42).unwrap();
+
+        //le variabili locali indicano il worker, lo stato condiviso no
+        for entry in fault_list.iter().filter(|e| e.var.contains('@')) {
+            let (name, w) = entry.var.split_once('@').unwrap();
+            assert!(["size", "row", "j", "k", "acc"].contains(&name), "{:?}", entry);
+            assert!(w.parse::<usize>().unwrap() < PAR_WORKERS);
+        }
+        assert!(fault_list.iter().any(|e| e.var == "next_row"));
+        assert!(fault_list.iter().any(|e| e.var.starts_with("result[")));
+        assert!((0..PAR_WORKERS).all(|w| fault_list.iter().any(|e| e.var.ends_with(&format!("@{}", w)))));
+    }
+
+    #[test]
+    fn test_fault_list_seed_reproducible(){
+        let analysis = analysis_file("bubble_sort");
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        let num_instr = label_trace("bubble_sort", Data::Vector(vet.clone())).len();
+
+        //a parità di seed il file della fault list è identico byte per byte, in ogni modalità casuale
+        let generate = |sampling: Sampling, seed: u64, dest: &str| {
+            let dest = temp_dir().join(dest).to_string_lossy().to_string();
+            create_fault_list(sampling, "bubble_sort".to_string(), analysis.clone(),
+                              DimData::Vector(vet.len()), dest.clone(), num_instr, seed).unwrap();
+            fs::read_to_string(dest).unwrap()
+        };
+        let statistical = Sampling::Statistical{ margin: 0.05, confidence: 0.95 };
+        for (sampling, name) in [(Sampling::Random(500), "random"), (statistical, "statistical")] {
+            let first = generate(sampling, 1234, &format!("bubble_sort_FL_seed_{}_a.json", name));
+            let second = generate(sampling, 1234, &format!("bubble_sort_FL_seed_{}_b.json", name));
+            let other = generate(sampling, 4321, &format!("bubble_sort_FL_seed_{}_c.json", name));
+            assert_eq!(first, second);
+            assert_ne!(first, other);
+        }
+        let dest = temp_dir().join("bubble_sort_FL_seed_random_a.json").to_string_lossy().to_string();
+        assert_eq!(read_fault_list(&dest).unwrap().0.unwrap().seed, Some(1234));
+    }
+
+    #[test]
+    fn test_bit_width_follows_type(){
+        let var = |ty: &str| Variable{ name: "x".to_string(), ty: ty.to_string(), size: static_analysis::type_size(ty), start: 0, ..Variable::default() };
+        assert_eq!(bit_width(&var("usize")), usize::BITS as usize);
+        assert_eq!(bit_width(&var("bool")), 1);
+        assert_eq!(bit_width(&var("Vec < bool >")), 1);
+        assert_eq!(bit_width(&var("Vec < i32 >")), 32);
+        assert_eq!(bit_width(&var("Vec < Vec < i32 > >")), 32);
+        assert_eq!(bit_width(&var("Vec < u8 >")), 8);
+        assert_eq!(bit_width(&var("Vec < Vec < f64 > >")), 64);
+    }
+
+    #[test]
+    fn test_bit_distributions(){
+        let analysis = analysis_file("bubble_sort");
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        let num_instr = label_trace("bubble_sort", Data::Vector(vet.clone())).len();
+        let generate = |params: FaultListParams, dest: &str| {
+            let dest = temp_dir().join(dest).to_string_lossy().to_string();
+            create_fault_list(params, "bubble_sort".to_string(), analysis.clone(), DimData::Vector(vet.len()), dest, num_instr, 3).unwrap()
+        };
+        //frazione delle entry che colpiscono la metà alta dei bit della variabile
+        let info = static_analysis::load_analysis(&analysis, "bubble_sort").unwrap();
+        let widths: HashMap<String, usize> = info.vars.iter().map(|var| (var.name.clone(), bit_width(var))).collect();
+        let upper_half = |fault_list: &[FaultListEntry]| {
+            let width = |var: &str| widths[var.split('[').next().unwrap()];
+            fault_list.iter().filter(|e| e.flipped_bit >= width(&e.var) / 2).count() as f64 / fault_list.len() as f64
+        };
+
+        let uniform = generate(Sampling::Random(3000).with_bits(BitDistribution::Uniform), "bubble_sort_FL_bits_uniform.json");
+        //i bit alti degli elementi del vettore vengono finalmente estratti
+        assert!(uniform.iter().any(|e| e.var.starts_with("vet") && e.flipped_bit >= 16));
+        assert!(uniform.iter().all(|e| e.var != "swapped" || e.flipped_bit == 0));
+        let msb = generate(Sampling::Random(3000).with_bits(BitDistribution::MsbWeighted), "bubble_sort_FL_bits_msb.json");
+        assert!(upper_half(&msb) > upper_half(&uniform) + 0.1);
+        let lsb = generate(Sampling::Random(3000).with_bits(BitDistribution::LsbOnly), "bubble_sort_FL_bits_lsb.json");
+        assert!(lsb.iter().all(|e| e.flipped_bit == 0));
+
+        //nello spazio esaustivo con solo LSB c'è una entry per variabile, elemento e istante
+        let lsb_space = generate(Sampling::Exhaustive.with_bits(BitDistribution::LsbOnly), "bubble_sort_FL_bits_lsb_exhaustive.json");
+        assert!(lsb_space.iter().all(|e| e.flipped_bit == 0));
+        let statistical = Sampling::Statistical{ margin: 0.05, confidence: 0.95 };
+        let msb_sample = generate(statistical.with_bits(BitDistribution::MsbWeighted), "bubble_sort_FL_bits_msb_statistical.json");
+        let uniform_sample = generate(statistical.into(), "bubble_sort_FL_bits_uniform_statistical.json");
+        assert_eq!(msb_sample.len(), uniform_sample.len());
+        assert!(upper_half(&msb_sample) > upper_half(&uniform_sample));
+    }
+
+    #[test]
+    fn test_leveugle_sample_size(){
+        //popolazione molto grande: si ritrova n = t^2 * p(1-p) / e^2 = 9604
+        assert_eq!(sample_size(1_000_000_000, 0.01, 0.95).unwrap(), 9604);
+        assert_eq!(sample_size(100, 0.05, 0.95).unwrap(), 80);
+        assert_eq!(sample_size(10, 0.0001, 0.99).unwrap(), 10);
+        for space in [50, 1_000, 250_000] {
+            let n = sample_size(space, 0.02, 0.99).unwrap();
+            assert!(error_margin(space, n, 0.99).unwrap() <= 0.02);
+            assert!(error_margin(space, n - 1, 0.99).unwrap() > 0.02);
+        }
+        assert_eq!(error_margin(100, 100, 0.95), Some(0.0));
+        //livelli di confidenza senza valore critico: nessun risultato invece di un panic
+        assert_eq!(z_score(0.5), None);
+        assert_eq!((sample_size(100, 0.05, 0.5), error_margin(100, 10, 0.5)), (None, None));
+    }
+
+    #[test]
+    fn test_fault_list_exhaustive(){
+        let analysis = analysis_file("bubble_sort");
+        let dest = temp_dir().join("bubble_sort_FL_exhaustive_test.json").to_string_lossy().to_string();
+
+        let vet = vec![3, -1, 2];
+        let num_instr = label_trace("bubble_sort", Data::Vector(vet.clone())).len();
+        let fault_list = create_fault_list(Sampling::Exhaustive, "bubble_sort".to_string(), analysis.clone(),
+                                           DimData::Vector(vet.len()), dest.clone(), num_instr, 42).unwrap();
+
+        //nessuna entry ripetuta e ogni elemento del vettore, istante e bit è presente
+        let distinct: HashSet<(String, usize, usize)> = fault_list.iter().map(|e| (e.var.clone(), e.time, e.flipped_bit)).collect();
+        assert_eq!(distinct.len(), fault_list.len());
+        for i in 0..vet.len() {
+            for time in 1..num_instr {
+                assert!((0..i32::BITS as usize).all(|bit| distinct.contains(&(format!("vet[{}]", i), time, bit))));
+            }
+        }
+        let metadata = read_fault_list(&dest).unwrap().0.unwrap();
+        assert_eq!((metadata.mode.as_str(), metadata.space_size, metadata.num_faults), ("exhaustive", fault_list.len(), fault_list.len()));
+        assert_eq!(metadata.error_margin, 0.0);
+
+        //il fault manager legge la fault list con l'intestazione
+        let registry = Registry::new(&analysis, "bubble_sort", &DimData::Vector(vet.len()), "bubble_sort", num_instr);
+        let (_, received, sent) = run_fault_manager(&dest, "bubble_sort", registry).unwrap();
+        assert_eq!((received, sent.unwrap()), (fault_list.len(), fault_list.len()));
+    }
+
+    #[test]
+    fn test_fault_list_statistical(){
+        let analysis = analysis_file("bubble_sort");
+        let dest = temp_dir().join("bubble_sort_FL_statistical_test.json").to_string_lossy().to_string();
+
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        let num_instr = label_trace("bubble_sort", Data::Vector(vet.clone())).len();
+        let sampling = Sampling::Statistical{ margin: 0.05, confidence: 0.95 };
+        let fault_list = create_fault_list(sampling, "bubble_sort".to_string(), analysis.clone(),
+                                           DimData::Vector(vet.len()), dest.clone(), num_instr, 42).unwrap();
+
+        let metadata = read_fault_list(&dest).unwrap().0.unwrap();
+        assert_eq!(metadata.mode, "statistical");
+        assert_eq!(fault_list.len(), sample_size(metadata.space_size, 0.05, 0.95).unwrap());
+        assert!(metadata.error_margin <= 0.05);
+        let distinct: HashSet<(String, usize, usize)> = fault_list.iter().map(|e| (e.var.clone(), e.time, e.flipped_bit)).collect();
+        assert_eq!(distinct.len(), fault_list.len());
+
+        //una confidenza senza valore critico è un errore della generazione
+        let unsupported = Sampling::Statistical{ margin: 0.05, confidence: 0.5 };
+        assert!(matches!(generate_fault_list(unsupported, "bubble_sort".to_string(), analysis, DimData::Vector(vet.len()), num_instr, 42),
+                         Err(FaultListError::Confidence(c)) if c == 0.5));
+    }
+
+    #[test]
+    fn test_site_selection(){
+        let analysis = analysis_file("bubble_sort");
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        let num_instr = label_trace("bubble_sort", Data::Vector(vet.clone())).len();
+        let generate = |sites: SiteSelection, dest: &str| {
+            let dest = temp_dir().join(dest).to_string_lossy().to_string();
+            let fault_list = create_fault_list(Sampling::Random(4000).with_bits(BitDistribution::Uniform).with_sites(sites.clone()), "bubble_sort".to_string(),
+                                               analysis.clone(), DimData::Vector(vet.len()), dest.clone(), num_instr, 11).unwrap();
+            let metadata = read_fault_list(&dest).unwrap().0.unwrap();
+            assert_eq!(metadata.params.as_ref().map(|params| &params.sites), Some(&sites));
+            (fault_list, metadata)
+        };
+        let share = |fault_list: &[FaultListEntry], var: &str| fault_list.iter().filter(|e| site_of(&e.var) == var).count() as f64 / fault_list.len() as f64;
+
+        //per variabile: il vettore riceve la stessa quota di uno scalare; il peso di importanza
+        //lo riporta alla sua quota dello spazio dei fault
+        let (per_variable, metadata) = generate(SiteSelection::PerVariable, "bubble_sort_FL_sites_var.json");
+        let num_vars = metadata.site_weights.len() as f64;
+        assert!((share(&per_variable, "vet") - 1.0 / num_vars).abs() < 0.03);
+        assert!(metadata.site_weight("vet[3]") > 1.0 && metadata.site_weight("swapped") < 1.0);
+        //la somma delle probabilità per i pesi di importanza è la somma delle quote dello spazio
+        assert!((metadata.site_weights.values().sum::<f64>() / num_vars - 1.0).abs() < 1e-9);
+
+        //per area: ogni variabile in proporzione ai suoi bit, e nessuna correzione da applicare
+        let (area, metadata) = generate(SiteSelection::Area, "bubble_sort_FL_sites_area.json");
+        //10 elementi i32 contro n, i e j (usize) e swapped (bool): 320 bit su 520
+        assert!((share(&area, "vet") - 320.0 / 520.0).abs() < 0.03);
+        assert!(metadata.site_weights.values().all(|&w| (w - 1.0).abs() < 1e-9));
+
+        let (lifetime, _) = generate(SiteSelection::Lifetime, "bubble_sort_FL_sites_lifetime.json");
+        assert!((share(&lifetime, "vet") - 1.0 / num_vars).abs() < 0.05);
+
+        //pesi dell'utente: le variabili senza peso non vengono mai scelte
+        let weights = BTreeMap::from([("vet".to_string(), 3.0), ("j".to_string(), 1.0)]);
+        let (custom, metadata) = generate(SiteSelection::Custom(weights), "bubble_sort_FL_sites_custom.json");
+        assert!(custom.iter().all(|e| ["vet", "j"].contains(&site_of(&e.var))));
+        assert!((share(&custom, "vet") - 0.75).abs() < 0.03);
+        assert_eq!(metadata.site_weights.keys().collect::<Vec<_>>(), vec!["j", "vet"]);
+
+        //pesi su variabili che il target non ha, o nessun peso positivo: errore e non panic
+        let custom = |weights: &[(&str, f64)]| create_fault_list(
+            Sampling::Random(10).with_bits(BitDistribution::Uniform).with_sites(SiteSelection::Custom(weights.iter().map(|&(var, w)| (var.to_string(), w)).collect())),
+            "bubble_sort".to_string(), analysis.clone(), DimData::Vector(vet.len()), temp_dir().join("bubble_sort_FL_sites_err.json").to_string_lossy().to_string(), num_instr, 11);
+        match custom(&[("vet", 1.0), ("matrice", 2.0), ("k", 1.0)]) {
+            Err(FaultListError::UnknownSites{ names, .. }) => assert_eq!(names, vec!["k", "matrice"]),
+            other => panic!("{:?}", other.map(|entries| entries.len())),
+        }
+        assert!(matches!(custom(&[("vet", 0.0)]), Err(FaultListError::NoSites)));
+    }
+
+    #[test]
+    fn test_execution_profile(){
+        let analysis = analysis_file("bubble_sort");
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        let trace = label_trace("bubble_sort", Data::Vector(vet.clone()));
+        let profile = ExecutionProfile::from_trace(&trace);
+        assert_eq!(profile.steps, trace.len());
+        assert_eq!(profile.histogram.values().sum::<usize>(), profile.steps);
+        //un input già ordinato termina dopo la prima passata
+        assert!(label_trace("bubble_sort", Data::Vector(vec![1, 2, 3])).len() < label_trace("bubble_sort", Data::Vector(vec![3, 2, 1])).len());
+
+        //l'istogramma finisce nell'intestazione, gli istanti restano entro l'esecuzione
+        let dest = temp_dir().join("bubble_sort_FL_profile_test.json").to_string_lossy().to_string();
+        let fault_list = create_fault_list(Sampling::Random(500), "bubble_sort".to_string(),
+                                           analysis, DimData::Vector(vet.len()), dest.clone(), profile.clone(), 3).unwrap();
+        assert!(fault_list.iter().all(|e| e.time < profile.steps));
+        let metadata = read_fault_list(&dest).unwrap().0.unwrap();
+        assert_eq!(metadata.label_histogram, profile.histogram);
+    }
+
+    #[test]
+    fn test_fault_list_formats(){
+        let analysis = analysis_file("bubble_sort");
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        let num_instr = label_trace("bubble_sort", Data::Vector(vet.clone())).len();
+
+        //le stesse entry e la stessa intestazione in ogni formato
+        let mut read_back = Vec::new();
+        for format in [FaultListFormat::Json, FaultListFormat::JsonLines, FaultListFormat::Csv] {
+            let dest = format.apply(&temp_dir().join("bubble_sort_FL_formats_test").to_string_lossy());
+            assert_eq!(FaultListFormat::from_path(&dest), format);
+            let fault_list = create_fault_list(Sampling::Random(200).with_bits(BitDistribution::MsbWeighted), "bubble_sort".to_string(),
+                                               analysis.clone(), DimData::Vector(vet.len()), dest.clone(), num_instr, 9).unwrap();
+            let (metadata, entries) = read_fault_list(&dest).unwrap();
+            let metadata = metadata.unwrap();
+            assert_eq!((metadata.schema_version, metadata.target.as_str()), (SCHEMA_VERSION, "bubble_sort"));
+            assert_eq!(metadata.params, Some(Sampling::Random(200).with_bits(BitDistribution::MsbWeighted)));
+            let key = |e: &FaultListEntry| (e.var.clone(), e.time, e.flipped_bit, e.weight);
+            assert_eq!(entries.iter().map(key).collect::<Vec<_>>(), fault_list.iter().map(key).collect::<Vec<_>>());
+
+            let registry = Registry::new(&analysis, "bubble_sort", &DimData::Vector(vet.len()), "bubble_sort", num_instr);
+            let (header, received, _) = run_fault_manager(&dest, "bubble_sort", registry).unwrap();
+            assert_eq!(header, Some(metadata.clone()));
+            assert_eq!(received, fault_list.len());
+            read_back.push(metadata);
+        }
+        assert!(read_back.windows(2).all(|w| w[0] == w[1]));
+    }
+
+    #[test]
+    fn test_fault_list_rejected(){
+        let analysis = analysis_file("bubble_sort");
+        let dest = temp_dir().join("bubble_sort_FL_rejected_test.jsonl").to_string_lossy().to_string();
+        create_fault_list(50, "bubble_sort".to_string(), analysis.clone(), DimData::Vector(10), dest.clone(), 100, 1).unwrap();
+        let registry = || Registry::new(&analysis, "bubble_sort", &DimData::Vector(10), "bubble_sort", 100);
+
+        //target diverso: nessuna entry viene inviata
+        assert!(matches!(run_fault_manager(&dest, "selection_sort", registry()), Err(FaultListError::Target{..})));
+
+        //schema di una versione diversa
+        let (metadata, entries) = read_fault_list(&dest).unwrap();
+        let mut metadata = metadata.unwrap();
+        metadata.schema_version = SCHEMA_VERSION + 1;
+        write_fault_list(&dest, &super::FaultList{ metadata, entries }).unwrap();
+        assert!(matches!(run_fault_manager(&dest, "bubble_sort", registry()),
+                         Err(FaultListError::Schema{ found, expected: SCHEMA_VERSION }) if found == SCHEMA_VERSION + 1));
+
+        //riga malformata in un CSV: la fault list viene rifiutata all'apertura, prima di inviare
+        //la prima entry, e l'errore riporta il numero di riga
+        let csv = temp_dir().join("bubble_sort_FL_rejected_test.csv").to_string_lossy().to_string();
+        let header = format!("# {{\"schema_version\":{},\"target\":\"bubble_sort\",\"mode\":\"random\",\"space_size\":10,\"num_faults\":2,\"confidence\":0.95,\"error_margin\":0.5}}", SCHEMA_VERSION);
+        fs::write(&csv, format!("{}\nvar,time,flipped_bit,weight\nvet[0],3,1,1\nvet[1],tre,1\n", header)).unwrap();
+        let Err(FaultListError::Invalid(problems)) = run_fault_manager(&csv, "bubble_sort", registry()) else {
+            panic!("riga malformata accettata");
+        };
+        assert_eq!(problems.iter().map(|problem| problem.line).collect::<Vec<_>>(), vec![4]);
+
+        //entry fuori dallo spazio dei fault del target: rifiutata anche senza passare da main
+        fs::write(&csv, format!("{}\nvar,time,flipped_bit,weight\nvet[0],3,1,1\nvet[12],3,1,1\n", header)).unwrap();
+        assert!(matches!(run_fault_manager(&csv, "bubble_sort", registry()), Err(FaultListError::Invalid(problems)) if problems[0].line == 4));
+    }
+
+    #[test]
+    fn test_fault_list_stream(){
+        let analysis = analysis_file("bubble_sort");
+        let key = |e: &FaultListEntry| (e.var.clone(), e.time, e.flipped_bit, e.weight);
+        for (sampling, format) in [(Sampling::Random(300), FaultListFormat::Json), (Sampling::Exhaustive, FaultListFormat::JsonLines),
+                                   (Sampling::Statistical{ margin: 0.05, confidence: 0.95 }, FaultListFormat::Csv)] {
+            let dest = format.apply(&temp_dir().join("bubble_sort_FL_stream_test").to_string_lossy());
+            let tee = format.apply(&temp_dir().join("bubble_sort_FL_stream_tee_test").to_string_lossy());
+            let fault_list = create_fault_list(sampling, "bubble_sort".to_string(), analysis.clone(), DimData::Vector(10), dest.clone(), 100, 4).unwrap();
+
+            //stesse entry e stessa intestazione di create_fault_list, senza materializzare la lista
+            let stream = generate_fault_list(sampling, "bubble_sort".to_string(), analysis.clone(), DimData::Vector(10), 100, 4).unwrap();
+            let (header, entries) = FaultSource::Stream(Box::new(stream), Some(tee.clone())).open("bubble_sort").unwrap();
+            let (tx, rx) = sync_channel(8);
+            let handle = thread::spawn(move || fault_manager(tx, entries));
+            let received: Vec<FaultListEntry> = rx.iter().collect();
+            assert_eq!(handle.join().unwrap().unwrap(), fault_list.len());
+            assert_eq!(received.iter().map(key).collect::<Vec<_>>(), fault_list.iter().map(key).collect::<Vec<_>>());
+            let saved = read_fault_list(&dest).unwrap().0.unwrap();
+            assert_eq!(header.map(|h| (h.mode, h.num_faults, h.params)), Some((saved.mode.clone(), saved.num_faults, saved.params.clone())));
+
+            //la copia su file è una fault list completa, identica a quella salvata
+            let (tee_header, tee_entries) = read_fault_list(&tee).unwrap();
+            assert_eq!(tee_header, Some(saved));
+            assert_eq!(tee_entries.iter().map(key).collect::<Vec<_>>(), fault_list.iter().map(key).collect::<Vec<_>>());
+        }
+
+        //un iniettore che smette di ricevere ferma la generazione
+        let stream = generate_fault_list(Sampling::Random(1000), "bubble_sort".to_string(), analysis, DimData::Vector(10), 100, 4).unwrap();
+        let (_, entries) = FaultSource::Stream(Box::new(stream), None).open("bubble_sort").unwrap();
+        let (tx, rx) = sync_channel(2);
+        let handle = thread::spawn(move || fault_manager(tx, entries));
+        let first: Vec<FaultListEntry> = rx.iter().take(5).collect();
+        drop(rx);
+        assert_eq!(first.len(), 5);
+        assert!(handle.join().unwrap().unwrap() < 1000);
+    }
 }
 
 /***    PUNTI SALIENTI REALIZZAZIONE PARTE CARLO
@@ -175,5 +1567,3 @@ mod tests{
     del punto precedente e, elemento per elemento, lo si manda nel canale allo stage successivo
     (Iniettore <-> Alex)
  */
-
-
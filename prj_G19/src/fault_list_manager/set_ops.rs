@@ -0,0 +1,361 @@
+//! Operazioni insiemistiche e filtri sulle fault list.
+//!
+//! Due entry rappresentano lo stesso fault se hanno uguali variabile, istante e bit: il peso non
+//! conta. Il risultato conserva l'ordine delle entry del primo operando, così una differenza con
+//! le entry già eseguite lascia intatta la parte ancora da eseguire. Differenza e intersezione
+//! conservano anche i pesi; l'unione somma i pesi delle entry che rappresentano lo stesso fault,
+//! perché il peso è il numero di fault estratti che l'entry rappresenta.
+//! L'intestazione del risultato è quella del primo operando, aggiornata al nuovo numero di fault
+//! (vedi result_header); le due fault list devono essere state generate allo stesso modo.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use regex::Regex;
+use crate::fault_list_manager::{error_margin, read_fault_list, site_of, write_fault_list, FaultList, FaultListEntry, FaultListError, FaultListMetadata, DEFAULT_CONFIDENCE};
+
+///Operazione tra due fault list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetOp{
+    Union,
+    Difference,
+    Intersection,
+}
+
+impl SetOp{
+    pub fn apply(self, a: &[FaultListEntry], b: &[FaultListEntry])->Vec<FaultListEntry>{
+        match self {
+            SetOp::Union => union(a, b),
+            SetOp::Difference => difference(a, b),
+            SetOp::Intersection => intersection(a, b),
+        }
+    }
+}
+
+fn key(entry: &FaultListEntry)->(&str, usize, usize){
+    (&entry.var, entry.time, entry.flipped_bit)
+}
+
+///Entry di 'a' seguite da quelle di 'b' che non compaiono in 'a' (una volta sola ciascuna). Il
+/// peso di una entry di 'b' già presente nel risultato si somma a quello della prima entry con lo
+/// stesso fault, così il peso totale dell'unione è la somma dei pesi dei due operandi
+pub fn union(a: &[FaultListEntry], b: &[FaultListEntry])->Vec<FaultListEntry>{
+    let mut result = a.to_vec();
+    let mut position: HashMap<_, _> = HashMap::new();
+    for (i, entry) in a.iter().enumerate() {
+        position.entry(key(entry)).or_insert(i);
+    }
+    for entry in b {
+        match position.get(&key(entry)) {
+            Some(&i) => result[i].weight += entry.weight,
+            None => {
+                position.insert(key(entry), result.len());
+                result.push(entry.clone());
+            }
+        }
+    }
+    result
+}
+
+///Entry di 'a' che non compaiono in 'b'
+pub fn difference(a: &[FaultListEntry], b: &[FaultListEntry])->Vec<FaultListEntry>{
+    let b: HashSet<_> = b.iter().map(key).collect();
+    filter(a, |entry| !b.contains(&key(entry)))
+}
+
+///Entry di 'a' che compaiono anche in 'b'
+pub fn intersection(a: &[FaultListEntry], b: &[FaultListEntry])->Vec<FaultListEntry>{
+    let b: HashSet<_> = b.iter().map(key).collect();
+    filter(a, |entry| b.contains(&key(entry)))
+}
+
+///Entry che soddisfano il predicato
+pub fn filter(entries: &[FaultListEntry], predicate: impl Fn(&FaultListEntry)->bool)->Vec<FaultListEntry>{
+    entries.iter().filter(|entry| predicate(entry)).cloned().collect()
+}
+
+///Criteri di selezione delle entry: un criterio assente accetta tutte le entry
+#[derive(Debug, Clone, Default)]
+pub struct EntryFilter{
+    //confrontata con l'intero nome ("vet[3]") o con la sola variabile ("vet")
+    pub var: Option<Regex>,
+    pub time: Option<Range<usize>>,
+    pub bits: Option<Range<usize>>,
+}
+
+impl EntryFilter{
+    pub fn matches(&self, entry: &FaultListEntry)->bool{
+        self.var.as_ref().is_none_or(|re| [entry.var.as_str(), site_of(&entry.var)].iter()
+            .any(|name| re.find(name).is_some_and(|m| m.range() == (0..name.len()))))
+            && self.time.as_ref().is_none_or(|time| time.contains(&entry.time))
+            && self.bits.as_ref().is_none_or(|bits| bits.contains(&entry.flipped_bit))
+    }
+}
+
+///Intestazione del risultato: i contatori descrivono le nuove entry. Le entry morte della
+/// potatura restano attribuite alle variabili che compaiono ancora nel risultato, mentre quelle
+/// delle variabili scartate non appartengono più alla fault list; come dopo la potatura,
+/// num_faults conta sia le entry rappresentate sia quelle morte. Il risultato non è più il
+/// campione estratto dal generatore: la modalità diventa "derived" e il margine di errore è
+/// ricalcolato sul nuovo numero di fault (alla confidenza predefinita se quella della fault list,
+/// come nell'enumerazione esaustiva, non ha un valore critico)
+fn result_header(mut metadata: FaultListMetadata, entries: &[FaultListEntry])->FaultListMetadata{
+    let vars: HashSet<_> = entries.iter().map(|entry| site_of(&entry.var)).collect();
+    metadata.dead_by_var.retain(|var, _| vars.contains(var.as_str()));
+    metadata.dead_faults = metadata.dead_by_var.values().sum();
+    metadata.num_faults = entries.iter().map(|entry| entry.weight).sum::<usize>() + metadata.dead_faults;
+    metadata.runs = metadata.runs.map(|_| entries.len());
+    metadata.mode = "derived".to_string();
+    if error_margin(metadata.space_size, metadata.num_faults, metadata.confidence).is_none() {
+        metadata.confidence = DEFAULT_CONFIDENCE;
+    }
+    metadata.error_margin = error_margin(metadata.space_size, metadata.num_faults, metadata.confidence)
+        .expect("DEFAULT_CONFIDENCE supportata da z_score");
+    metadata
+}
+
+///Intestazione comune di due fault list da combinare con 'op': devono avere stessi target,
+/// schema, parametri di generazione, confidenza e pesi di importanza (una variabile presente in
+/// una sola delle due mantiene il suo peso). Le entry morte di 'b' fanno parte solo dell'unione
+fn merge_headers(op: SetOp, mut a: FaultListMetadata, b: FaultListMetadata)->Result<FaultListMetadata, FaultListError>{
+    if a.target != b.target {
+        return Err(FaultListError::Target{ found: b.target, expected: a.target });
+    }
+    if a.schema_version != b.schema_version {
+        return Err(FaultListError::Incompatible(format!("versione dello schema ({} e {})", a.schema_version, b.schema_version)));
+    }
+    if a.params != b.params {
+        return Err(FaultListError::Incompatible(format!("parametri di generazione ({:?} e {:?})", a.params, b.params)));
+    }
+    if a.confidence != b.confidence {
+        return Err(FaultListError::Incompatible(format!("confidenza ({} e {})", a.confidence, b.confidence)));
+    }
+    //una mappa vuota indica pesi uniformi, quindi non si può unire a una mappa non vuota
+    let conflicts = a.site_weights.is_empty() != b.site_weights.is_empty()
+        || b.site_weights.iter().any(|(var, w)| a.site_weights.get(var).is_some_and(|w_a| w_a != w));
+    if conflicts {
+        return Err(FaultListError::Incompatible(format!("pesi di importanza ({:?} e {:?})", a.site_weights, b.site_weights)));
+    }
+    a.site_weights.extend(b.site_weights);
+    if op == SetOp::Union {
+        for (var, dead) in b.dead_by_var {
+            *a.dead_by_var.entry(var).or_default() += dead;
+        }
+    }
+    Ok(a)
+}
+
+///Applica 'op' alle fault list salvate in 'a' e 'b' e scrive il risultato in 'dest', nel formato
+/// dato dalla sua estensione. Le due fault list devono essere state generate per lo stesso
+/// target; restituisce il numero di entry del risultato
+pub fn combine_files(op: SetOp, a: &str, b: &str, dest: &str)->Result<usize, FaultListError>{
+    let (header_a, entries_a) = read_fault_list(a)?;
+    let (header_b, entries_b) = read_fault_list(b)?;
+    let header = match (header_a, header_b) {
+        (Some(header_a), Some(header_b)) => merge_headers(op, header_a, header_b)?,
+        (header_a, header_b) => header_a.or(header_b).ok_or(FaultListError::MissingHeader(a.to_string()))?,
+    };
+    let entries = op.apply(&entries_a, &entries_b);
+    let num_entries = entries.len();
+    write_fault_list(dest, &FaultList{ metadata: result_header(header, &entries), entries })?;
+    Ok(num_entries)
+}
+
+///Copia in 'dest' le entry della fault list 'src' accettate da 'entry_filter'. Le entry morte
+/// sono contate solo per variabile: con un filtro sugli istanti o sui bit non si sa quante
+/// cadano negli intervalli scelti, quindi non vengono riportate
+pub fn filter_file(src: &str, entry_filter: &EntryFilter, dest: &str)->Result<usize, FaultListError>{
+    let (header, entries) = read_fault_list(src)?;
+    let mut header = header.ok_or(FaultListError::MissingHeader(src.to_string()))?;
+    if entry_filter.time.is_some() || entry_filter.bits.is_some() {
+        header.dead_by_var.clear();
+    }
+    let entries = filter(&entries, |entry| entry_filter.matches(entry));
+    let num_entries = entries.len();
+    write_fault_list(dest, &FaultList{ metadata: result_header(header, &entries), entries })?;
+    Ok(num_entries)
+}
+
+//Intervallo "a..b" con estremi facoltativi ("..b", "a..")
+fn parse_range(arg: &str)->Result<Range<usize>, String>{
+    let (start, end) = arg.split_once("..").ok_or(format!("intervallo non valido: {} (atteso a..b)", arg))?;
+    let bound = |s: &str, default: usize| if s.is_empty() { Ok(default) } else {
+        s.parse::<usize>().map_err(|_| format!("intervallo non valido: {}", arg))
+    };
+    Ok(bound(start, 0)?..bound(end, usize::MAX)?)
+}
+
+pub const USAGE: &str = "uso:
+    fault-list union <a> <b> -o <dest>
+    fault-list difference <a> <b> -o <dest>
+    fault-list intersection <a> <b> -o <dest>
+    fault-list filter <src> [--var <regex>] [--time <a..b>] [--bits <a..b>] -o <dest>";
+
+///Sottocomando "fault-list": 'args' sono gli argomenti successivi al nome del sottocomando
+pub fn run_command(args: &[String])->Result<String, String>{
+    let (command, args) = args.split_first().ok_or(USAGE.to_string())?;
+    let mut files = Vec::new();
+    let mut dest = None;
+    let mut entry_filter = EntryFilter::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("manca il valore di {}", arg));
+        match arg.as_str() {
+            "-o" | "--output" => dest = Some(value()?.clone()),
+            "--var" => entry_filter.var = Some(Regex::new(value()?).map_err(|e| e.to_string())?),
+            "--time" => entry_filter.time = Some(parse_range(value()?)?),
+            "--bits" => entry_filter.bits = Some(parse_range(value()?)?),
+            _ if arg.starts_with('-') => return Err(format!("opzione sconosciuta: {}\n{}", arg, USAGE)),
+            _ => files.push(arg.clone()),
+        }
+    }
+    let dest = dest.ok_or(format!("manca il file di destinazione (-o)\n{}", USAGE))?;
+    let op = match command.as_str() {
+        "union" => SetOp::Union,
+        "difference" => SetOp::Difference,
+        "intersection" => SetOp::Intersection,
+        "filter" => {
+            let [src] = files.as_slice() else { return Err(USAGE.to_string()) };
+            let n = filter_file(src, &entry_filter, &dest).map_err(|e| e.to_string())?;
+            return Ok(format!("{} entry scritte in {}", n, dest));
+        }
+        _ => return Err(format!("sottocomando sconosciuto: {}\n{}", command, USAGE)),
+    };
+    let [a, b] = files.as_slice() else { return Err(USAGE.to_string()) };
+    let n = combine_files(op, a, b, &dest).map_err(|e| e.to_string())?;
+    Ok(format!("{} entry scritte in {}", n, dest))
+}
+
+#[cfg(test)]
+mod tests{
+    use std::collections::BTreeMap;
+    use std::env::temp_dir;
+    use regex::Regex;
+    use crate::fault_list_manager::{error_margin, read_fault_list, write_fault_list, FaultList, FaultListEntry, FaultListError, FaultListMetadata, Sampling, SCHEMA_VERSION};
+    use crate::fault_list_manager::set_ops::{combine_files, difference, intersection, run_command, union, EntryFilter, SetOp};
+
+    fn entry(var: &str, time: usize, flipped_bit: usize, weight: usize)->FaultListEntry{
+        FaultListEntry{ var: var.to_string(), time, flipped_bit, weight }
+    }
+
+    fn keys(entries: &[FaultListEntry])->Vec<(String, usize, usize, usize)>{
+        entries.iter().map(|e| (e.var.clone(), e.time, e.flipped_bit, e.weight)).collect()
+    }
+
+    fn metadata(target: &str)->FaultListMetadata{
+        serde_json::from_str(&format!("{{\"schema_version\":{},\"target\":\"{}\",\"mode\":\"random\",\"space_size\":100,\"num_faults\":3,\"confidence\":0.95,\"error_margin\":0.5,\"seed\":7,\"dead_faults\":2,\"runs\":3,\"dead_by_var\":{{\"vet\":1,\"k\":1}}}}",
+                                      SCHEMA_VERSION, target)).unwrap()
+    }
+
+    #[test]
+    fn test_set_operations(){
+        let a = vec![entry("vet[0]", 3, 1, 2), entry("i", 5, 0, 1), entry("vet[0]", 3, 1, 1)];
+        //lo stesso fault con un altro peso è la stessa entry
+        let b = vec![entry("vet[0]", 3, 1, 5), entry("j", 8, 2, 1), entry("j", 8, 2, 1)];
+
+        //l'unione somma i pesi dello stesso fault sulla prima entry che lo contiene
+        assert_eq!(keys(&union(&a, &b)), keys(&[entry("vet[0]", 3, 1, 7), entry("i", 5, 0, 1), entry("vet[0]", 3, 1, 1), entry("j", 8, 2, 2)]));
+        let total = |entries: &[FaultListEntry]| entries.iter().map(|e| e.weight).sum::<usize>();
+        assert_eq!(total(&union(&a, &b)), total(&a) + total(&b));
+        assert_eq!(keys(&difference(&a, &b)), keys(&[entry("i", 5, 0, 1)]));
+        assert_eq!(keys(&intersection(&a, &b)), keys(&[entry("vet[0]", 3, 1, 2), entry("vet[0]", 3, 1, 1)]));
+
+        let only = |entry_filter: EntryFilter| keys(&super::filter(&a, |e| entry_filter.matches(e)));
+        assert_eq!(only(EntryFilter{ var: Some(Regex::new("vet").unwrap()), ..Default::default() }).len(), 2);
+        //la regex deve coprire l'intero nome: "i" non seleziona "vet[0]"
+        assert_eq!(only(EntryFilter{ var: Some(Regex::new("i").unwrap()), ..Default::default() }), keys(&[entry("i", 5, 0, 1)]));
+        assert_eq!(only(EntryFilter{ var: Some(Regex::new(r"vet\[[1-9]\]").unwrap()), ..Default::default() }).len(), 0);
+        assert_eq!(only(EntryFilter{ time: Some(4..10), bits: Some(0..1), ..Default::default() }), keys(&[entry("i", 5, 0, 1)]));
+    }
+
+    #[test]
+    fn test_set_operations_on_files(){
+        let path = |name: &str| temp_dir().join(name).to_string_lossy().to_string();
+        let (a, b, c) = (path("bubble_sort_FL_set_a.json"), path("bubble_sort_FL_set_b.jsonl"), path("bubble_sort_FL_set_c.csv"));
+        write_fault_list(&a, &FaultList{ metadata: metadata("bubble_sort"),
+            entries: vec![entry("vet[0]", 3, 1, 2), entry("i", 5, 0, 1)] }).unwrap();
+        let mut metadata_b = metadata("bubble_sort");
+        metadata_b.dead_by_var = BTreeMap::from([("j".to_string(), 2)]);
+        write_fault_list(&b, &FaultList{ metadata: metadata_b.clone(),
+            entries: vec![entry("i", 5, 0, 1), entry("j", 8, 2, 1)] }).unwrap();
+        write_fault_list(&c, &FaultList{ metadata: metadata("selection_sort"), entries: vec![] }).unwrap();
+
+        //l'intestazione del primo operando con i contatori del risultato: le entry morte di "k"
+        //non compaiono più, quelle di "vet" sì, e l'unione riporta anche quelle di "j" da 'b'
+        let dest = path("bubble_sort_FL_set_union.csv");
+        assert_eq!(combine_files(SetOp::Union, &a, &b, &dest).unwrap(), 3);
+        let (header, entries) = read_fault_list(&dest).unwrap();
+        let header = header.unwrap();
+        assert_eq!((header.target.as_str(), header.seed, header.num_faults, header.runs, header.dead_faults), ("bubble_sort", Some(7), 8, Some(3), 3));
+        assert_eq!(header.dead_by_var.into_iter().collect::<Vec<_>>(), vec![("j".to_string(), 2), ("vet".to_string(), 1)]);
+        assert_eq!(keys(&entries), keys(&[entry("vet[0]", 3, 1, 2), entry("i", 5, 0, 2), entry("j", 8, 2, 1)]));
+
+        let dest = path("bubble_sort_FL_set_intersection.json");
+        combine_files(SetOp::Intersection, &a, &b, &dest).unwrap();
+        let header = read_fault_list(&dest).unwrap().0.unwrap();
+        assert_eq!((header.num_faults, header.dead_faults), (1, 0));
+        assert!(header.dead_by_var.is_empty());
+
+        //dopo ogni operazione: num_faults = entry rappresentate + entry morte, modalità derivata e
+        //margine ricalcolato sul nuovo numero di fault. Un filtro sugli istanti o sui bit non
+        //riporta le entry morte
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+        let dest = path("bubble_sort_FL_set_header.json");
+        for (command, represented, dead) in [("union", 5, 3), ("difference", 2, 1), ("intersection", 1, 0),
+                                             ("filter --var vet", 2, 1), ("filter --var vet --bits 0..4", 2, 0), ("filter --time 0..4", 2, 0)] {
+            let operands = if command.starts_with("filter") { a.clone() } else { format!("{} {}", a, b) };
+            let (command, options) = command.split_once(' ').unwrap_or((command, ""));
+            run_command(&args(&format!("{} {} {} -o {}", command, operands, options, dest))).unwrap();
+            let (header, entries) = read_fault_list(&dest).unwrap();
+            let header = header.unwrap();
+            assert_eq!(entries.iter().map(|e| e.weight).sum::<usize>(), represented, "{} {}", command, options);
+            assert_eq!((header.dead_faults, header.dead_by_var.values().sum::<usize>()), (dead, dead), "{} {}", command, options);
+            assert_eq!((header.num_faults, header.mode.as_str(), header.space_size, header.confidence), (represented + dead, "derived", 100, 0.95));
+            assert_eq!(header.error_margin, error_margin(100, represented + dead, 0.95).unwrap());
+        }
+
+        //una fault list esaustiva non ha un valore critico: il margine è riportato alla confidenza predefinita
+        let exhaustive = path("bubble_sort_FL_set_exhaustive.json");
+        let mut metadata_e = metadata("bubble_sort");
+        (metadata_e.mode, metadata_e.confidence, metadata_e.error_margin) = ("exhaustive".to_string(), 1.0, 0.0);
+        write_fault_list(&exhaustive, &FaultList{ metadata: metadata_e, entries: vec![entry("vet[0]", 3, 1, 1), entry("i", 5, 0, 1)] }).unwrap();
+        run_command(&args(&format!("filter {} --var i -o {}", exhaustive, dest))).unwrap();
+        let header = read_fault_list(&dest).unwrap().0.unwrap();
+        assert_eq!((header.num_faults, header.confidence, header.error_margin), (1, 0.95, error_margin(100, 1, 0.95).unwrap()));
+
+        //le due fault list devono essere state generate allo stesso modo
+        assert!(matches!(combine_files(SetOp::Difference, &a, &c, &dest), Err(FaultListError::Target{ .. })));
+        let incompatible = |name: &str, change: &dyn Fn(&mut FaultListMetadata)| {
+            let mut metadata = metadata("bubble_sort");
+            change(&mut metadata);
+            let other = path(name);
+            write_fault_list(&other, &FaultList{ metadata, entries: vec![entry("j", 8, 2, 1)] }).unwrap();
+            combine_files(SetOp::Union, &a, &other, &dest)
+        };
+        assert!(matches!(incompatible("bubble_sort_FL_set_schema.json", &|m| m.schema_version += 1), Err(FaultListError::Incompatible(_))));
+        assert!(matches!(incompatible("bubble_sort_FL_set_params.json", &|m| m.params = Some(Sampling::Random(3).into())), Err(FaultListError::Incompatible(_))));
+        assert!(matches!(incompatible("bubble_sort_FL_set_confidence.json", &|m| m.confidence = 0.99), Err(FaultListError::Incompatible(_))));
+        assert!(matches!(incompatible("bubble_sort_FL_set_sites.json", &|m| m.site_weights = BTreeMap::from([("j".to_string(), 2.0)])), Err(FaultListError::Incompatible(_))));
+
+        //pesi di importanza compatibili: ogni variabile conserva il proprio
+        let weighted = |name: &str, weights: &[(&str, f64)], entries: Vec<FaultListEntry>| {
+            let mut metadata = metadata("bubble_sort");
+            metadata.site_weights = weights.iter().map(|&(var, w)| (var.to_string(), w)).collect();
+            let file = path(name);
+            write_fault_list(&file, &FaultList{ metadata, entries }).unwrap();
+            file
+        };
+        let weighted_a = weighted("bubble_sort_FL_set_weighted_a.json", &[("vet", 2.0), ("i", 0.5)], vec![entry("vet[0]", 3, 1, 1)]);
+        let weighted_b = weighted("bubble_sort_FL_set_weighted_b.json", &[("vet", 2.0), ("j", 0.25)], vec![entry("j", 8, 2, 1)]);
+        combine_files(SetOp::Union, &weighted_a, &weighted_b, &dest).unwrap();
+        let header = read_fault_list(&dest).unwrap().0.unwrap();
+        assert_eq!((header.site_weight("vet[0]"), header.site_weight("i"), header.site_weight("j")), (2.0, 0.5, 0.25));
+
+        let dest = path("bubble_sort_FL_set_filter.jsonl");
+        run_command(&args(&format!("filter {} --var vet --bits 0..4 -o {}", a, dest))).unwrap();
+        assert_eq!(keys(&read_fault_list(&dest).unwrap().1), keys(&[entry("vet[0]", 3, 1, 2)]));
+        run_command(&args(&format!("difference {} {} -o {}", a, b, dest))).unwrap();
+        assert_eq!(keys(&read_fault_list(&dest).unwrap().1), keys(&[entry("vet[0]", 3, 1, 2)]));
+        assert!(run_command(&args(&format!("filter {} --time 5 -o {}", a, dest))).is_err());
+        assert!(run_command(&args(&format!("union {} -o {}", a, dest))).is_err());
+    }
+}
@@ -1,197 +1,415 @@
-use syn::{File, ItemFn, Block, Stmt, Pat, Type, Expr, FnArg};
+use syn::{File, Stmt, Pat, Type, Expr, FnArg, Arm, ExprClosure, Item, Local, Block, Signature, ItemImpl, ImplItem, ItemMod, TraitItem, TraitItemFn};
+use syn::visit::{self, Visit};
+use syn::spanned::Spanned;
 use quote::ToTokens;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::fmt::Debug;
-use std::fs::OpenOptions;
-use std::io::Write;
 use serde::{Deserialize, Serialize};
 use serde_json;
+use thiserror::Error;
 use itertools::Itertools;
+use crate::fault_list_manager::dataflow::{Accesses, FlowGraph};
+use crate::fault_list_manager::type_inference::{infer_types, Signatures};
+use crate::fault_list_manager::type_size::{PointerWidth, SizeModel, TypeSize};
 
-//Analizza la funzione
-pub fn analyze_function(func: &ItemFn, file_path_dest: String) {
-    let mut fp= OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .append(false)
-        .create(true)
-        .open(file_path_dest)
-        .unwrap();
+///Informazioni del file che servono all'analisi di ogni sua funzione: le firme delle funzioni
+/// (per l'inferenza dei tipi) e il modello delle dimensioni, con le struct dichiarate nel file
+#[derive(Default)]
+pub struct FileContext{
+    pub signatures: Signatures,
+    pub sizes: SizeModel,
+}
 
-    let body = &func.block;
-    let mut instruction_count = 0;
-    let mut variable_types = HashMap::new();
+impl FileContext{
+    pub fn new(items: &[Item], pointer_width: PointerWidth)->Self{
+        FileContext{ signatures: Signatures::collect(items), sizes: SizeModel::new(items, pointer_width) }
+    }
+}
 
+//Analizza la funzione nel contesto del file che la contiene
+pub fn analyze_function(sig: &Signature, block: &Block, context: &FileContext)->ResultAnalysis{
     // Count the number of instructions and extract variable types
-    count_statements(&body, &mut variable_types,&mut instruction_count);
+    let mut counter = StatementCounter{ types: infer_types(sig, block, &context.signatures), ..StatementCounter::default() };
+    counter.visit_block(block);
 
     // Extract variables
     let mut variables = Vec::new();
-    extract_variables(&func, &variable_types, &mut variables);
-
-    //Formato del file:
-    // <N>
-    // <name1> <type1> <size1>
-    // ...
-    //<nameN> <typeN> >sizeN>
+    extract_variables(sig, &counter, &context.sizes, &mut variables);
+    let StatementCounter{ current: instruction_count, statements, .. } = counter;
 
-    //dove N è il numero di istruzioni
+    //Def-use e liveness di ogni variabile
+    let flow = FlowGraph::build(sig, block);
+    for var in variables.iter_mut() {
+        let Accesses{ defs, uses, dead } = flow.accesses(&var.name, var.start);
+        (var.defs, var.uses, var.dead) = (defs, uses, dead);
+    }
 
     variables=variables.into_iter().sorted_by(|a,b| {a.start.cmp(&b.start)}).collect();
-    //Prima creo la struttura di tipo 'ResultAnalysis', poi la serializzo su file
-    let ris=ResultAnalysis{num_inst: instruction_count, vars: variables};
+    ResultAnalysis{num_inst: instruction_count, vars: variables, statements, ..ResultAnalysis::default()}
+}
 
-    //Creo una stringa JSON dalla struttura dati a cui ho fatto derivare Serialize/Deserialize
-    let ris_json=serde_json::to_string_pretty(&ris);
+//Percorso di un elemento all'interno del file ("modulo::Tipo::metodo")
+fn item_path(prefix: &str, name: impl std::fmt::Display)->String{
+    if prefix.is_empty() { name.to_string() } else { format!("{}::{}", prefix, name) }
+}
 
-    if ris_json.is_ok(){
-        fp.write_all(ris_json.ok().unwrap().as_bytes()).unwrap()
+//Nome del tipo di un blocco impl, senza parametri generici: "<Tipo as Trait>" per le
+//implementazioni di un trait, così i metodi omonimi di trait diversi non si sovrascrivono
+fn impl_name(item: &ItemImpl)->String{
+    let last_segment = |path: &syn::Path| path.segments.last().map_or(String::new(), |segment| segment.ident.to_string());
+    let ty = match &*item.self_ty {
+        Type::Path(type_path) => last_segment(&type_path.path),
+        other => extract_type(other),
+    };
+    match &item.trait_ {
+        Some((_, path, _)) => format!("<{} as {}>", ty, last_segment(path)),
+        None => ty,
     }
 }
 
-fn count_statements(block: &Block, variable_types: &mut HashMap<String, (String, usize)>,
-                    current:&mut usize) {
-    let mut local_variables = HashMap::new();
-
-    for stmt in &block.stmts {
-        match stmt {
-            Stmt::Local(local) => {
-                *current += 1;
-                // Estrazione del nome e del tipo della variabile
-                if let Pat::Type(pat_type) = &local.pat {
-                    if let Pat::Ident(pat_ident) = &*pat_type.pat {
-                        let var_name = pat_ident.ident.to_string();
-                        let var_type = extract_type(&*pat_type.ty);
-                        local_variables.insert(var_name.clone(), (var_type.clone(),*current));
+///Visita ogni funzione con un corpo, con il suo percorso nel file: funzioni libere, metodi dei
+/// blocchi impl, metodi di default dei trait, anche nei moduli annidati
+pub fn visit_functions(items: &[Item], prefix: &str, f: &mut impl FnMut(String, &Signature, &Block)){
+    for item in items {
+        match item {
+            Item::Fn(func) => f(item_path(prefix, &func.sig.ident), &func.sig, &func.block),
+            Item::Impl(item_impl) => {
+                let prefix = item_path(prefix, impl_name(item_impl));
+                for impl_item in &item_impl.items {
+                    if let ImplItem::Fn(method) = impl_item {
+                        f(item_path(&prefix, &method.sig.ident), &method.sig, &method.block);
                     }
-                } else if let Pat::Ident(pat_ident) = &local.pat {
-                    let var_name = pat_ident.ident.to_string();
-                    let var_type = if let Some(init) = &local.init {
-                        infer_type_from_expr(&init.expr)
-                    } else {
-                        "unknown".to_string()
-                    };
-                    local_variables.insert(var_name.clone(), (var_type.clone(), *current));
                 }
             }
-            Stmt::Expr(expr,_) => {
-                *current += 1;                         //Il while/if/for/lo conto come istruzione!
-                //cicli while
-                if let Expr::While(while_expr) = expr {
-                    count_statements(&while_expr.body, &mut local_variables, current);
-                }
-                //if/elseif
-                else if let Expr::If(if_expr) = expr{
-                    count_statements(&if_expr.then_branch, &mut local_variables,current);
-                    if let Some((_, else_branch)) = &if_expr.else_branch {
-                        count_statements_in_expr(else_branch, &mut local_variables,current);
+            Item::Trait(item_trait) => {
+                let prefix = item_path(prefix, &item_trait.ident);
+                for trait_item in &item_trait.items {
+                    if let TraitItem::Fn(TraitItemFn{ sig, default: Some(block), .. }) = trait_item {
+                        f(item_path(&prefix, &sig.ident), sig, block);
                     }
                 }
-                //for
-                else if let Expr::ForLoop(for_expr) = expr{
-                    count_statements(&for_expr.body, &mut local_variables,current);
-                }
+            }
+            Item::Mod(ItemMod{ ident, content: Some((_, items)), .. }) => {
+                visit_functions(items, &item_path(prefix, ident), f);
             }
             _ => {}
         }
     }
+}
 
-    // Aggiornamento delle variabili globali con quelle locali
-    // tramite concatenazione delle due collezioni
-    variable_types.extend(local_variables);
+///Visita del corpo della funzione: conta le istruzioni e registra le variabili locali con
+/// l'istruzione che le dichiara. È un'istruzione ogni Stmt (let, espressione, macro) a qualunque
+/// livello di annidamento: nei rami di if/else if/else, nei cicli while/for/loop, nei blocchi,
+/// nei bracci dei match e nei corpi delle closure. Un braccio o una closure il cui corpo è una
+/// semplice espressione, senza blocco, conta come una istruzione. Il tipo delle variabili senza
+/// annotazione è quello ricavato dall'inferenza ('types')
+#[derive(Default)]
+struct StatementCounter{
+    current: usize,
+    locals: HashMap<String, (String, usize)>,
+    types: HashMap<String, String>,
+    //posizione nel sorgente di ogni istruzione contata e della dichiarazione di ogni variabile
+    statements: Vec<SourceSpan>,
+    declarations: HashMap<String, SourceSpan>,
+    //variabili irrobustite: di tipo Hardened o inizializzate con Hardened::from()
+    hardened: HashSet<String>,
 }
 
-fn count_statements_in_expr(expr: &Expr, variable_types: &mut HashMap<String, (String,usize)>,
-                            current:&mut usize) {
-    match expr {
-        Expr::Block(block_expr) => count_statements(&block_expr.block, variable_types, current),
-        Expr::If(if_expr) => {
-            //let mut count = count_statements(&if_expr.then_branch, variable_types,current);
-            if let Some((_, else_branch)) = &if_expr.else_branch {
-                count_statements_in_expr(else_branch, variable_types, current);
+impl StatementCounter{
+    fn declare(&mut self, local: &Local){
+        let (name, ty) = match &local.pat {
+            Pat::Type(pat_type) => match &*pat_type.pat {
+                Pat::Ident(pat_ident) => (pat_ident.ident.to_string(), extract_type(&pat_type.ty)),
+                _ => return,
+            },
+            Pat::Ident(pat_ident) => {
+                let name = pat_ident.ident.to_string();
+                let ty = self.types.get(&name).cloned().unwrap_or_else(|| "unknown".to_string());
+                (name, ty)
             }
+            _ => return,
+        };
+        //una variabile ridichiarata (shadowing o blocchi diversi) può essere iniettata dalla
+        //prima dichiarazione
+        self.declarations.entry(name.clone()).or_insert_with(|| SourceSpan::of_stmt(&Stmt::Local(local.clone())));
+        if ty.contains("Hardened") || local.init.as_ref().is_some_and(|init| constructs_hardened(&init.expr)) {
+            self.hardened.insert(name.clone());
+        }
+        self.locals.entry(name).or_insert((ty, self.current));
+    }
+
+    //il corpo senza blocco di un braccio o di una closure è un'istruzione a sé
+    fn count_bare_body(&mut self, body: &Expr){
+        if !matches!(body, Expr::Block(_)) {
+            self.current += 1;
+            self.statements.push(SourceSpan::of(body));
         }
-        _ => { },
     }
 }
 
+impl<'ast> Visit<'ast> for StatementCounter{
+    fn visit_stmt(&mut self, stmt: &'ast Stmt){
+        //funzioni, struct o use dichiarati nel corpo non vengono eseguiti
+        if let Stmt::Item(_) = stmt {
+            return;
+        }
+        self.current += 1;                      //Il while/if/for/lo conto come istruzione!
+        self.statements.push(SourceSpan::of_stmt(stmt));
+        if let Stmt::Local(local) = stmt {
+            self.declare(local);
+        }
+        visit::visit_stmt(self, stmt);
+    }
+
+    fn visit_arm(&mut self, arm: &'ast Arm){
+        self.count_bare_body(&arm.body);
+        visit::visit_arm(self, arm);
+    }
+
+    fn visit_expr_closure(&mut self, closure: &'ast ExprClosure){
+        self.count_bare_body(&closure.body);
+        visit::visit_expr_closure(self, closure);
+    }
+
+    fn visit_item(&mut self, _item: &'ast Item){}
+}
+
 fn extract_type(ty: &Type) -> String {
     // Conversione del tipo a stringa
     ty.to_token_stream().to_string()
 }
 
-//Funzione ricorsiva che inferisce il tipo da una certa espressione
-fn infer_type_from_expr(expr: &Expr) -> String {
+//Espressione che costruisce un valore Hardened: Hardened::from(x), Hardened::from_vec(v),
+//vec![Hardened::from(x); n] o un'operazione con uno di questi
+fn constructs_hardened(expr: &Expr)->bool{
     match expr {
-        Expr::Lit(lit) => match &lit.lit {
-            syn::Lit::Int(_) => "i32".to_string(),
-            syn::Lit::Float(_) => "f64".to_string(),
-            syn::Lit::Str(_) => "String".to_string(),
-            syn::Lit::Bool(_) => "bool".to_string(),
-            _ => "unknown".to_string(),
-        },
-        Expr::Assign(binary) => {
-            let left_type = infer_type_from_expr(&binary.left);
-            let right_type = infer_type_from_expr(&binary.right);
-            if left_type == right_type {
-                left_type
-            } else {
-                "unknown".to_string()
+        Expr::Call(call) => matches!(&*call.func, Expr::Path(path) if path.path.segments.first().is_some_and(|segment| segment.ident == "Hardened")),
+        Expr::Macro(mac) => mac.mac.path.is_ident("vec") && mac.mac.tokens.clone().into_iter().next()
+            .is_some_and(|token| token.to_string() == "Hardened"),
+        Expr::Try(inner) => constructs_hardened(&inner.expr),
+        Expr::Paren(inner) => constructs_hardened(&inner.expr),
+        Expr::Binary(binary) => constructs_hardened(&binary.left) || constructs_hardened(&binary.right),
+        _ => false,
+    }
+}
+
+///Tipo del dato su cui si inietta: si tolgono i riferimenti (&T, &mut T), una slice diventa un
+/// vettore ([T] --> Vec<T>: come per i Vec la lunghezza è nota solo durante l'esecuzione) e
+/// Hardened<T> lascia il posto a T, anche negli argomenti generici (&mut Vec<Hardened<i32>> -->
+/// Vec<i32>). Restituisce true se la variabile contiene direttamente dei valori Hardened, anche
+/// come elementi di vettori, array e tuple: dietro altri tipi (Mutex<Hardened<usize>>) i valori
+/// vengono comunque sostituiti ma non sono copie della variabile. I parametri generici (T)
+/// restano tali: la loro dimensione non è nota
+fn payload_type(ty: &mut Type)->bool{
+    match ty {
+        Type::Reference(reference) => {
+            let mut inner = (*reference.elem).clone();
+            let hardened = payload_type(&mut inner);
+            *ty = inner;
+            hardened
+        }
+        Type::Paren(paren) => {
+            let mut inner = (*paren.elem).clone();
+            let hardened = payload_type(&mut inner);
+            *ty = inner;
+            hardened
+        }
+        Type::Slice(slice) => {
+            let mut elem = (*slice.elem).clone();
+            let hardened = payload_type(&mut elem);
+            *ty = syn::parse_quote!(Vec<#elem>);
+            hardened
+        }
+        Type::Array(array) => payload_type(&mut array.elem),
+        Type::Tuple(tuple) => tuple.elems.iter_mut().map(payload_type).fold(false, |a, b| a | b),
+        Type::Path(path) => {
+            let wrapped = path.path.segments.last()
+                .filter(|segment| segment.ident == "Hardened")
+                .and_then(|segment| match &segment.arguments {
+                    syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                        syn::GenericArgument::Type(inner) => Some(inner.clone()),
+                        _ => None,
+                    }),
+                    _ => None,
+                });
+            if let Some(mut inner) = wrapped {
+                payload_type(&mut inner);
+                *ty = inner;
+                return true;
+            }
+            let mut hardened = false;
+            for segment in path.path.segments.iter_mut() {
+                let container = segment.ident == "Vec";
+                if let syn::PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                    for arg in args.args.iter_mut() {
+                        if let syn::GenericArgument::Type(inner) = arg {
+                            hardened |= payload_type(inner) && container;
+                        }
+                    }
+                }
             }
-        },
-        Expr::Unary(unary) => infer_type_from_expr(&unary.expr),
-        _ => "unknown".to_string(),
+            hardened
+        }
+        _ => false,
     }
 }
 
-//Effettua il binding tipo<-->dimensione
-fn type_size(type_str: &str) -> String {
-    match type_str {
-        "i8" | "u8" => "1",
-        "i16" | "u16" => "2",
-        "i32" | "u32" => "4",
-        "i64" | "u64" => "8",
-        "isize" | "usize" => "4", // Assumendo architettura a 32-bit; usare "8" per 64-bit
-        "f32" => "4",
-        "f64" => "8",
-        "bool"=>"1",
-        "Vec < i32 >" => "4*len",
-        "Vec < Vec < i32 > >" => "4*nR*nC",
-        _ => "unknown",
+//Effettua il binding tipo<-->dimensione, con i puntatori dell'architettura su cui vengono
+//eseguiti i target
+pub(crate) fn type_size(type_str: &str) -> TypeSize {
+    SizeModel::default().type_size(type_str)
+}
+
+
+///Posizione nel sorgente di un'istruzione o di una dichiarazione: riga e colonna (da 1) e prima
+/// riga del suo codice, senza indentazione
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SourceSpan{
+    pub line: usize,
+    pub column: usize,
+    pub code: String,
+}
+
+impl SourceSpan{
+    pub fn of(node: &impl Spanned)->Self{
+        let span = node.span();
+        let start = span.start();
+        let code = span.source_text()
+            .and_then(|text| text.lines().next().map(|line| line.trim().to_string()))
+            .unwrap_or_default();
+        SourceSpan{ line: start.line, column: start.column + 1, code }
+    }
+
+    ///Posizione di un'istruzione: gli attributi esterni (es. #[allow(unused_assignments)] su un
+    /// let) non fanno parte del suo codice, che inizia dal let o dall'espressione
+    pub fn of_stmt(stmt: &Stmt)->Self{
+        let mut stmt = stmt.clone();
+        match &mut stmt {
+            Stmt::Local(local) => local.attrs.clear(),
+            Stmt::Macro(mac) => mac.attrs.clear(),
+            _ => {}
+        }
+        SourceSpan::of(&stmt)
     }
-        .to_string()
 }
 
+impl std::fmt::Display for SourceSpan{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "riga {} `{}`", self.line, self.code)
+    }
+}
 
 //Tipo che conserva le informazioni di una certa variabile
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Variable {
     pub name: String,
     pub ty: String,
-    pub size: String,
-    pub start: usize
+    pub size: TypeSize,
+    pub start: usize,
+    //Istruzioni che scrivono la variabile (anche un solo elemento), che la leggono e prima delle
+    //quali il suo valore è morto (vedi dataflow). Assenti nei file di analisi precedenti
+    #[serde(default)]
+    pub defs: Vec<usize>,
+    #[serde(default)]
+    pub uses: Vec<usize>,
+    #[serde(default)]
+    pub dead: Vec<usize>,
+    //dichiarazione nel sorgente (il parametro o il let che la introduce)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub declared: Option<SourceSpan>,
+    //variabile Hardened (Regola 1): le sue due copie sono un unico sito di iniezione, perché
+    //l'iniettore altera sempre la prima
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hardened: bool,
 }
 
 //Implement Serialize/Deserialize for this structure
 //in a way that can be saved on file
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct ResultAnalysis{
     pub num_inst: usize,              //number of instruction
-    pub vars: Vec<Variable>         //list of instruction
+    pub vars: Vec<Variable>,        //list of instruction
+    //file analizzato e posizione di ogni istruzione contata: l'istruzione k (da 1) è
+    //statements[k-1]. Assenti nei file di analisi precedenti e in quelli dei plugin
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub statements: Vec<SourceSpan>,
+}
+
+///File di analisi: una ResultAnalysis per ogni funzione del sorgente, indicizzata per percorso
+/// ("bubble_sort", "modulo::funzione", "Tipo::metodo", "<Tipo as Trait>::metodo")
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AnalysisDocument{
+    //architettura per cui sono calcolate le dimensioni dei tipi
+    #[serde(default)]
+    pub pointer_width: PointerWidth,
+    pub functions: BTreeMap<String, ResultAnalysis>,
 }
 
-fn extract_variables(func: &ItemFn, variable_types: &HashMap<String, (String,usize)>, variables:
-&mut Vec<Variable>) {
+//I file di analisi precedenti contengono una sola funzione
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnalysisFile{
+    Document(AnalysisDocument),
+    Single(ResultAnalysis),
+}
+
+#[derive(Debug, Error)]
+pub enum AnalysisError{
+    #[error("AnalysisError::Io: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("AnalysisError::Parse: {0}")]
+    Parse(String),
+    #[error("AnalysisError::UnknownFunction: nessuna funzione '{name}' tra quelle analizzate ({available})")]
+    UnknownFunction{ name: String, available: String },
+    #[error("AnalysisError::Ambiguous: '{name}' può indicare {candidates}")]
+    Ambiguous{ name: String, candidates: String },
+}
+
+impl AnalysisDocument{
+    pub fn load(path: &str)->Result<Self, AnalysisError>{
+        let raw_info = fs::read_to_string(path)?;
+        match serde_json::from_str(&raw_info).map_err(|e| AnalysisError::Parse(e.to_string()))? {
+            AnalysisFile::Document(document) => Ok(document),
+            AnalysisFile::Single(analysis) => Ok(AnalysisDocument{ functions: BTreeMap::from([(String::new(), analysis)]), ..AnalysisDocument::default() }),
+        }
+    }
+
+    ///Analisi della funzione 'name': il percorso completo oppure solo il nome, se nel file c'è
+    /// una sola funzione con quel nome. Un file con una sola funzione la restituisce comunque
+    pub fn function(&self, name: &str)->Result<&ResultAnalysis, AnalysisError>{
+        if let Some(analysis) = self.functions.get(name) {
+            return Ok(analysis);
+        }
+        if let (1, Some(analysis)) = (self.functions.len(), self.functions.get("")) {
+            return Ok(analysis);
+        }
+        let candidates: Vec<&String> = self.functions.keys()
+            .filter(|path| path.rsplit("::").next() == Some(name))
+            .collect();
+        match candidates.as_slice() {
+            [path] => Ok(&self.functions[*path]),
+            [] => Err(AnalysisError::UnknownFunction{ name: name.to_string(), available: self.functions.keys().join(", ") }),
+            _ => Err(AnalysisError::Ambiguous{ name: name.to_string(), candidates: candidates.iter().join(", ") }),
+        }
+    }
+}
+
+///Analisi della funzione 'function' salvata nel file di analisi 'path'
+pub fn load_analysis(path: &str, function: &str)->Result<ResultAnalysis, AnalysisError>{
+    AnalysisDocument::load(path)?.function(function).cloned()
+}
+
+fn extract_variables(sig: &Signature, counter: &StatementCounter, sizes: &SizeModel, variables: &mut Vec<Variable>) {
 
     // Estrazione dei parametri della funzione
-    for param in &func.sig.inputs {
+    for param in &sig.inputs {
         if let FnArg::Typed(pat_type) = param {
-            let ty = if let Type::Path(type_path) = &*pat_type.ty {
-                type_path.to_token_stream().to_string()
-            } else {
-                "unknown".to_string()
-            };
+            let mut ty = (*pat_type.ty).clone();
+            let hardened = payload_type(&mut ty);
+            let ty = extract_type(&ty);
 
             let name = if let Pat::Ident(pat_ident) = &*pat_type.pat {
                 pat_ident.ident.to_string()
@@ -199,53 +417,268 @@ fn extract_variables(func: &ItemFn, variable_types: &HashMap<String, (String,usi
                 "parameter".to_string()
             };
 
-            let _ = ty.trim();
             variables.push(Variable {
                 name,
-                ty: ty.clone(),
-                size: type_size(&ty),
-                start: 1                    //I parametri possono essere iniettati da subito
+                size: sizes.type_size(&ty),
+                ty,
+                start: 1,                   //I parametri possono essere iniettati da subito
+                declared: Some(SourceSpan::of(pat_type)),
+                hardened,
+                ..Variable::default()
             });
         }
     }
 
     // Estrazione delle variabili locali
-    for (name, (ty, start)) in variable_types {
+    for (name, (ty, start)) in &counter.locals {
+        let (ty, hardened) = match syn::parse_str::<Type>(ty) {
+            Ok(mut parsed) => {
+                let hardened = payload_type(&mut parsed);
+                (extract_type(&parsed), hardened)
+            }
+            Err(_) => (ty.clone(), false),
+        };
         variables.push(Variable {
             name: name.clone(),
-            ty: ty.clone(),
-            size: type_size(&ty),
+            size: sizes.type_size(&ty),
+            ty,
             start: *start,
+            declared: counter.declarations.get(name).cloned(),
+            hardened: hardened || counter.hardened.contains(name),
+            ..Variable::default()
         });
     }
 }
 
-//Funzione 'utente'
-pub fn generate_analysis_file(file_path_src: String, file_path_dest: String)->Result<(),
+//Funzione 'utente': analizza tutte le funzioni del file e le salva in un unico documento. Le
+//dimensioni dei tipi sono quelle di un'architettura con puntatori di 'pointer_width'
+pub fn generate_analysis_file(file_path_src: String, file_path_dest: String, pointer_width: PointerWidth)->Result<(),
     std::io::Error>{
-    let code = fs::read_to_string(file_path_src)?;
+    let code = fs::read_to_string(&file_path_src)?;
     let file: File = syn::parse_str(&code).expect("errore");
 
-    for item in file.items {
-        if let syn::Item::Fn(func) = item {
-            analyze_function(&func, file_path_dest.clone());
+    let mut document = AnalysisDocument{ pointer_width, ..AnalysisDocument::default() };
+    let context = FileContext::new(&file.items, pointer_width);
+    visit_functions(&file.items, "", &mut |path, sig, block| {
+        document.functions.insert(path, analyze_function(sig, block, &context));
+    });
+    document.functions.values_mut().for_each(|analysis| analysis.source = file_path_src.clone());
+
+    //Creo una stringa JSON dalla struttura dati a cui ho fatto derivare Serialize/Deserialize
+    fs::write(file_path_dest, serde_json::to_string_pretty(&document).unwrap())
+}
+
+#[cfg(test)]
+mod tests{
+    use syn::ItemFn;
+    use syn::visit::Visit;
+    use crate::fault_list_manager::type_size::PointerWidth;
+    use crate::fault_list_manager::static_analysis::{analyze_function, generate_analysis_file, load_analysis, AnalysisDocument, AnalysisError, FileContext, StatementCounter, visit_functions};
+
+    fn count(code: &str)->StatementCounter{
+        let func: ItemFn = syn::parse_str(code).unwrap();
+        let mut counter = StatementCounter::default();
+        counter.visit_block(&func.block);
+        counter
+    }
+
+    #[test]
+    fn test_count_statements(){
+        //il ramo then di un else if viene contato come gli altri
+        let counter = count("fn f(n: usize) {
+            let mut x: i32 = 0;
+            if n == 0 { x = 1; } else if n == 1 { x = 2; let y: u8 = 3; } else { x = 3; }
+            x += 1;
+        }");
+        assert_eq!(counter.current, 7);
+        assert_eq!(counter.locals["y"], ("u8".to_string(), 5));
+
+        //loop, blocchi annidati, macro e bracci dei match
+        let counter = count("fn f(n: usize) {
+            let mut i: usize = 0;
+            loop {
+                let v: usize = { let t: usize = i * 2; t + 1 };
+                println!(\"{}\", v);
+                match v {
+                    0 => break,
+                    1 => { i += 2; i += 3; }
+                    _ => i += 1,
+                }
+                if i > n { break; }
+            }
+            let done: bool = true;
+        }");
+        //let i, loop, let v, let t, t + 1, println!, match, break, due nel blocco, i += 1, if, break, let done
+        assert_eq!(counter.current, 14);
+        assert_eq!(counter.locals["t"].1, 4);
+        assert_eq!(counter.locals["done"].1, 14);
+    }
+
+    #[test]
+    fn test_count_closures_and_items(){
+        //il corpo di una closure viene contato, le funzioni annidate no
+        let counter = count("fn f(v: Vec<i32>) {
+            fn helper() -> i32 { let a = 1; a }
+            let total: i32 = v.iter().map(|x| { let d: i32 = x * 2; d }).sum();
+            let check = |x: i32| x > 0;
+            let last: i32 = total;
+        }");
+        assert_eq!(counter.current, 6);
+        assert!(!counter.locals.contains_key("a"));
+        assert_eq!(counter.locals["d"].1, 2);
+        assert_eq!(counter.locals["last"].1, 6);
+    }
+
+    #[test]
+    fn test_worker_locals_in_loop(){
+        //le variabili dei worker sono dichiarate dentro un loop
+        let code = std::fs::read_to_string("src/fault_list_manager/file_fault_list/par_matrix_multiplication/mod.rs").unwrap();
+        let file: syn::File = syn::parse_str(&code).unwrap();
+        let Some(syn::Item::Fn(worker)) = file.items.iter().find(|item| matches!(item, syn::Item::Fn(f) if f.sig.ident == "worker")) else {
+            panic!("worker non trovato")
+        };
+        let mut counter = StatementCounter::default();
+        counter.visit_block(&worker.block);
+        for name in ["size", "row", "j", "acc", "k"] {
+            assert!(counter.locals.contains_key(name), "{}", name);
         }
     }
-    Ok(())
+
+    fn document(code: &str)->AnalysisDocument{
+        let file: syn::File = syn::parse_str(code).unwrap();
+        let mut document = AnalysisDocument::default();
+        visit_functions(&file.items, "", &mut |path, sig, block| {
+            document.functions.insert(path, analyze_function(sig, block, &FileContext::default()));
+        });
+        document
+    }
+
+    fn variables(src: &str, function: &str)->Vec<(String, String, bool)>{
+        let dest = std::env::temp_dir().join(format!("{}_hardened_vars_test.json", function)).to_string_lossy().to_string();
+        generate_analysis_file(src.to_string(), dest.clone(), PointerWidth::HOST).unwrap();
+        let mut vars: Vec<(String, String, bool)> = load_analysis(&dest, function).unwrap().vars.into_iter().map(|var| (var.name, var.ty, var.hardened)).collect();
+        vars.sort();
+        vars
+    }
+
+    #[test]
+    fn test_parameter_types(){
+        let document = document(r#"
+            fn f<T>(a: &mut Vec<Hardened<i32>>, b: &[Hardened<u8>], c: &Vec<Vec<Hardened<i32>>>, d: Hardened<usize>,
+                    e: &[T], f: &Mutex<Hardened<usize>>, g: [Hardened<bool>; 4], h: (usize, &str)){}
+        "#);
+        let vars: Vec<(&str, &str, bool)> = document.functions["f"].vars.iter().map(|var| (var.name.as_str(), var.ty.as_str(), var.hardened)).collect();
+        assert_eq!(vars, [("a", "Vec < i32 >", true), ("b", "Vec < u8 >", true), ("c", "Vec < Vec < i32 > >", true), ("d", "usize", true),
+            ("e", "Vec < T >", false), ("f", "Mutex < usize >", false), ("g", "[bool ; 4]", true), ("h", "(usize , str)", false)]);
+        //locali irrobustite dal tipo o dall'inizializzazione
+        let document = self::document("fn g(v: &mut Vec<Hardened<i32>>){ let mut i = Hardened::from(0); let k: Hardened<u8> = x; let n = 3; v.swap(i.inner().unwrap(), 1); }");
+        let analysis = &document.functions["g"];
+        let var = |name: &str| analysis.vars.iter().find(|var| var.name == name).unwrap();
+        assert!(var("i").hardened && var("k").hardened && !var("n").hardened);
+        assert_eq!((var("i").ty.as_str(), var("k").ty.as_str()), ("i32", "u8"));
+        assert_eq!(var("v").uses, vec![4]);
+    }
+
+    #[test]
+    fn test_hardened_variables(){
+        //le varianti irrobustite hanno le stesse variabili, con lo stesso tipo, delle originali
+        for (target, function, unhardened) in [("bubble_sort", "bubble_sort", vec![]), ("selection_sort", "selection_sort", vec![]),
+                                                ("matrix_multiplication", "matrix_multiplication", vec!["result"])] {
+            let plain = variables(&format!("src/fault_list_manager/file_fault_list/{}/mod.rs", target), function);
+            let hardened = variables(&format!("src/hardened/{}_hardened/mod.rs", target), function);
+            let types = |vars: &[(String, String, bool)]| vars.iter().map(|(name, ty, _)| (name.clone(), ty.clone())).collect::<Vec<_>>();
+            assert_eq!(types(&hardened), types(&plain), "{}", target);
+            assert!(plain.iter().all(|(_, _, hardened)| !hardened), "{}", target);
+            let plain_vars: Vec<&str> = hardened.iter().filter(|(_, _, hardened)| !hardened).map(|(name, _, _)| name.as_str()).collect();
+            assert_eq!(plain_vars, unhardened, "{}", target);
+        }
+        let names = |vars: Vec<(String, String, bool)>| vars.into_iter().map(|(name, _, _)| name).collect::<Vec<_>>();
+        assert_eq!(names(variables("src/hardened/kmp_hardened/mod.rs", "kmp")), names(variables("src/fault_list_manager/file_fault_list/kmp/mod.rs", "kmp")));
+    }
+
+    #[test]
+    fn test_function_paths(){
+        let document = document(r#"
+            fn run(v: Vec<i32>){ let a = 1; }
+            struct S;
+            impl S { fn run(&self){ let b = 2; let c = 3; } }
+            impl Clone for S { fn clone(&self)->S{ S } }
+            trait T { fn def(&self){ let d = true; } fn req(&self); }
+            mod inner { mod deep { fn step(x: u8){} } }
+        "#);
+        let keys: Vec<&str> = document.functions.keys().map(|k| k.as_str()).collect();
+        assert_eq!(keys, ["<S as Clone>::clone", "S::run", "T::def", "inner::deep::step", "run"]);
+        assert_eq!(document.functions["S::run"].num_inst, 2);
+        assert_eq!(document.functions["run"].vars.len(), 2);
+        //ricerca per nome: esatta, univoca sull'ultimo segmento, ambigua o assente
+        assert_eq!(document.function("step").unwrap().vars[0].name, "x");
+        assert!(document.function("clone").is_ok());
+        assert!(matches!(document.function("run"), Ok(r) if r.vars.len() == 2));
+        assert!(matches!(document.function("def"), Ok(r) if r.num_inst == 1));
+        assert!(matches!(document.function("req"), Err(AnalysisError::UnknownFunction{ .. })));
+        let document = self::document("mod a { fn f(){} } mod b { fn f(){} }");
+        assert!(matches!(document.function("f"), Err(AnalysisError::Ambiguous{ .. })));
+    }
+
+    #[test]
+    fn test_legacy_analysis_file(){
+        let dest = std::env::temp_dir().join("legacy_ris_test.json").to_string_lossy().to_string();
+        std::fs::write(&dest, r#"{"num_inst": 3, "vars": [{"name": "i", "ty": "usize", "size": "8", "start": 1}]}"#).unwrap();
+        let analysis = load_analysis(&dest, "qualunque").unwrap();
+        assert_eq!(analysis.num_inst, 3);
+        assert_eq!(analysis.vars[0].name, "i");
+        assert!(analysis.statements.is_empty() && analysis.vars[0].declared.is_none());
+    }
+
+    #[test]
+    fn test_source_spans(){
+        let src = "src/fault_list_manager/file_fault_list/bubble_sort/mod.rs";
+        let dest = std::env::temp_dir().join("bubble_sort_spans_test.json").to_string_lossy().to_string();
+        generate_analysis_file(src.to_string(), dest.clone(), PointerWidth::HOST).unwrap();
+        let analysis = load_analysis(&dest, "bubble_sort").unwrap();
+        assert_eq!(analysis.source, src);
+        //una posizione per ogni istruzione contata
+        assert_eq!(analysis.statements.len(), analysis.num_inst);
+        assert_eq!(analysis.statements[7].to_string(), "riga 11 `vet.swap(j, j + 1);`");
+        assert_eq!(analysis.statements[5].code, "while j < n - i - 1 {");
+        assert_eq!(analysis.statements[5].column, 9);
+
+        let declared = |name: &str| analysis.vars.iter().find(|var| var.name == name).unwrap().declared.clone().unwrap();
+        assert_eq!((declared("vet").line, declared("vet").code.as_str()), (1, "mut vet: Vec<i32>"));
+        assert_eq!(declared("vet").column, 20);
+        assert_eq!((declared("swapped").line, declared("swapped").code.as_str()), (6, "let mut swapped = false;"));
+    }
+
+    #[test]
+    fn test_source_spans_skip_attributes(){
+        //i let preceduti da #[allow(unused_assignments)] iniziano dal let, non dall'attributo
+        let src = "src/fault_list_manager/file_fault_list/selection_sort/mod.rs";
+        let dest = std::env::temp_dir().join("selection_sort_spans_test.json").to_string_lossy().to_string();
+        generate_analysis_file(src.to_string(), dest.clone(), PointerWidth::HOST).unwrap();
+        let analysis = load_analysis(&dest, "selection_sort").unwrap();
+        assert_eq!(analysis.statements[1].to_string(), "riga 4 `let mut j=0;`");
+        assert_eq!((analysis.statements[2].line, analysis.statements[2].column), (6, 5));
+        let declared = analysis.vars.iter().find(|var| var.name == "min").unwrap().declared.clone().unwrap();
+        assert_eq!((declared.line, declared.code.as_str()), (6, "let mut min=0;"));
+        assert!(analysis.statements.iter().all(|span| !span.code.starts_with("#[")));
+    }
 }
 
 /**************************ANALISI STATICA DEL CODICE SORGENTE*************************************
 ---------------------------------------------------------------------------------------------------
 fn generate_analysis_file()                          Genera il file contenente le informazioni
 \                                                    circa l'analisi statica (wrapper)
+-->  fn visit_functions()                            Funzioni, metodi e moduli annidati, ognuno
+     \                                               con il proprio percorso nel documento
+     \                                               (lo stesso per i grafi di control_flow)
 -->  fn analyze_function()                           è a sua volta un wrapper di...
      \
-     --> fn count_statements()                      Two types: Local, Expression (recursion)
-     \        \
-     \        -->  fn infer_type_from_expr()
-     \        \
-     \        -->  fn count_statements_in_expr()    Si occupa di sviscerare blocchi If/Else
-     \                                               annidati (fatto ricorsivamente)
+     -->  fn infer_types()                          (type_inference) Tipi delle variabili senza
+     \                                              annotazione, ricavati dagli usi nel corpo
+     --> struct StatementCounter                    Visitor (syn::visit) che conta ogni Stmt
+     \        \                                      a qualunque livello di annidamento
+     -->  struct FlowGraph                          (dataflow) Def-use e istruzioni in cui ogni
+     \                                              variabile è morta
      -->  fn extract_variables()                    Mette insieme variabili locali e parametri
           \
           -->  fn type_size()                       Tipo<->Dimensione in byte
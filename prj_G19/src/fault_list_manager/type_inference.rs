@@ -0,0 +1,653 @@
+//! Inferenza locale dei tipi delle variabili di una funzione.
+//!
+//! Il tipo di una variabile locale senza annotazione si ricava dall'espressione che la
+//! inizializza e, se questa non basta (es. un letterale intero), dagli usi successivi:
+//!     - confronti e operazioni aritmetiche con un operando di tipo noto
+//!     - assegnamenti da o verso un'espressione di tipo noto
+//!     - indicizzazione (l'indice di un vettore è usize) e metodi come .len() o .swap()
+//!     - argomenti e valore restituito delle funzioni dello stesso file
+//! I vincoli vengono applicati fino al punto fisso; i letterali rimasti senza vincoli hanno il
+//! tipo di default di Rust (i32 per gli interi, f64 per i decimali). I tipi sono stringhe nel
+//! formato dei token di syn ("Vec < i32 >"), come nel resto dell'analisi statica.
+
+use std::collections::HashMap;
+use quote::ToTokens;
+use syn::{Block, Expr, ExprBinary, FnArg, ImplItem, Item, Lit, Local, Pat, ReturnType, Signature, Stmt, Type};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+
+//metodi che restituiscono sempre lo stesso tipo
+const USIZE_METHODS: &[&str] = &["len", "count", "capacity", "count_ones", "count_zeros", "leading_zeros", "trailing_zeros"];
+const BOOL_METHODS: &[&str] = &["is_empty", "contains", "contains_key", "starts_with", "ends_with", "is_some", "is_none",
+    "is_ok", "is_err", "eq", "ne", "lt", "le", "gt", "ge", "any", "all", "is_positive", "is_negative", "is_nan"];
+//metodi che restituiscono il tipo del ricevente
+const SAME_TYPE_METHODS: &[&str] = &["clone", "abs", "pow", "min", "max", "sqrt", "powi", "powf", "clamp", "to_owned",
+    "wrapping_add", "wrapping_sub", "wrapping_mul", "saturating_add", "saturating_sub", "saturating_mul", "rotate_left",
+    "rotate_right", "reverse_bits", "swap_bytes"];
+//metodi i cui argomenti sono indici
+const INDEX_METHODS: &[&str] = &["swap", "remove", "insert", "truncate", "split_at", "rotate_left", "rotate_right"];
+
+///Parametri e tipo restituito di ogni funzione del file, per nome (l'ultimo segmento del
+/// percorso): servono a tipizzare le chiamate e i loro argomenti
+#[derive(Default)]
+pub struct Signatures(HashMap<String, (Vec<String>, Option<String>)>);
+
+impl Signatures{
+    pub fn collect(items: &[Item])->Self{
+        let mut signatures = Signatures::default();
+        signatures.add_items(items);
+        signatures
+    }
+
+    fn add_items(&mut self, items: &[Item]){
+        for item in items {
+            match item {
+                Item::Fn(func) => self.add(&func.sig),
+                Item::Impl(item_impl) => item_impl.items.iter()
+                    .filter_map(|impl_item| if let ImplItem::Fn(method) = impl_item { Some(&method.sig) } else { None })
+                    .for_each(|sig| self.add(sig)),
+                Item::Mod(syn::ItemMod{ content: Some((_, items)), .. }) => self.add_items(items),
+                _ => {}
+            }
+        }
+    }
+
+    fn add(&mut self, sig: &Signature){
+        let params = sig.inputs.iter()
+            .filter_map(|input| if let FnArg::Typed(pat_type) = input { Some(type_string(&pat_type.ty)) } else { None })
+            .collect();
+        let ret = match &sig.output {
+            ReturnType::Type(_, ty) => Some(type_string(ty)),
+            ReturnType::Default => None,
+        };
+        self.0.entry(sig.ident.to_string()).or_insert((params, ret));
+    }
+}
+
+//Tipo di un'espressione: noto, oppure un letterale numerico che si adatta al contesto
+#[derive(Clone, PartialEq, Debug)]
+enum Inferred{
+    Known(String),
+    Int,
+    Float,
+}
+
+fn type_string(ty: &Type)->String{
+    ty.to_token_stream().to_string()
+}
+
+fn parse_type(ty: &str)->Option<Type>{
+    syn::parse_str(ty).ok()
+}
+
+//Tipo degli elementi di un vettore, array o slice (anche dietro un riferimento)
+fn element_type(ty: &str)->Option<String>{
+    fn element(ty: &Type)->Option<&Type>{
+        match ty {
+            Type::Array(array) => Some(&array.elem),
+            Type::Slice(slice) => Some(&slice.elem),
+            Type::Reference(reference) => element(&reference.elem),
+            Type::Paren(paren) => element(&paren.elem),
+            Type::Path(path) => {
+                let segment = path.path.segments.last()?;
+                if !["Vec", "VecDeque"].contains(&segment.ident.to_string().as_str()) {
+                    return None;
+                }
+                match &segment.arguments {
+                    syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+                        syn::GenericArgument::Type(elem) => Some(elem),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+    parse_type(ty).and_then(|ty| element(&ty).map(type_string))
+}
+
+//Tipo riferito da un riferimento ("& T" --> "T"); gli altri tipi restano invariati
+fn deref_type(ty: &str)->String{
+    match parse_type(ty) {
+        Some(Type::Reference(reference)) => type_string(&reference.elem),
+        _ => ty.to_string(),
+    }
+}
+
+//Argomento di un tipo generico con il nome 'wrapper' ("Hardened < usize >" --> "usize")
+fn generic_payload(ty: &str, wrapper: &str)->Option<String>{
+    match parse_type(ty)? {
+        Type::Path(path) => {
+            let segment = path.path.segments.last().filter(|segment| segment.ident == wrapper)?;
+            match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+                    syn::GenericArgument::Type(inner) => Some(type_string(inner)),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn hardened_payload(ty: &str)->Option<String>{
+    generic_payload(ty, "Hardened")
+}
+
+fn result_payload(ty: &str)->Option<String>{
+    generic_payload(ty, "Result").or_else(|| generic_payload(ty, "Option"))
+}
+
+const PRIMITIVES: &[&str] = &["i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    "f32", "f64", "bool", "char"];
+
+//Argomenti di vec![...]: un elenco di elementi oppure un elemento ripetuto
+enum VecMacro{
+    Elems(Vec<Expr>),
+    Repeat(Box<Expr>),
+}
+
+impl Parse for VecMacro{
+    fn parse(input: ParseStream)->syn::Result<Self>{
+        if input.is_empty() {
+            return Ok(VecMacro::Elems(Vec::new()));
+        }
+        let first: Expr = input.parse()?;
+        if input.parse::<syn::Token![;]>().is_ok() {
+            let _len: Expr = input.parse()?;
+            return Ok(VecMacro::Repeat(Box::new(first)));
+        }
+        let mut elems = vec![first];
+        if input.parse::<syn::Token![,]>().is_ok() {
+            elems.extend(Punctuated::<Expr, syn::Token![,]>::parse_terminated(input)?);
+        }
+        Ok(VecMacro::Elems(elems))
+    }
+}
+
+//Variabile locale da tipizzare: l'espressione che la inizializza e, per le variabili dei cicli
+//for, se questa è l'espressione su cui si itera
+struct Declaration{
+    init: Option<Expr>,
+    iterated: bool,
+}
+
+struct Inference<'a>{
+    signatures: &'a Signatures,
+    ret: Option<String>,
+    types: HashMap<String, Inferred>,
+    declarations: Vec<(String, Declaration)>,
+    changed: bool,
+}
+
+///Tipi delle variabili locali e dei parametri della funzione, per nome. Le variabili il cui tipo
+/// non si può ricavare non compaiono
+pub fn infer_types(sig: &Signature, block: &Block, signatures: &Signatures)->HashMap<String, String>{
+    let mut inference = Inference{
+        signatures,
+        ret: match &sig.output {
+            ReturnType::Type(_, ty) => Some(type_string(ty)),
+            ReturnType::Default => None,
+        },
+        types: HashMap::new(),
+        declarations: Vec::new(),
+        changed: false,
+    };
+    for input in &sig.inputs {
+        if let FnArg::Typed(pat_type) = input {
+            if let Pat::Ident(pat_ident) = &*pat_type.pat {
+                inference.types.insert(pat_ident.ident.to_string(), Inferred::Known(type_string(&pat_type.ty)));
+            }
+        }
+    }
+    Declarations(&mut inference).visit_block(block);
+    //valore restituito dal blocco della funzione
+    let tail = match block.stmts.last() {
+        Some(Stmt::Expr(expr, None)) => Some(expr),
+        _ => None,
+    };
+    loop {
+        inference.changed = false;
+        inference.infer_declarations();
+        inference.visit_block(block);
+        if let (Some(tail), Some(ret)) = (tail, inference.ret.clone()) {
+            inference.constrain(tail, &ret);
+        }
+        if !inference.changed {
+            break;
+        }
+    }
+    inference.types.into_iter().map(|(name, ty)| (name, match ty {
+        Inferred::Known(ty) => ty,
+        Inferred::Int => "i32".to_string(),
+        Inferred::Float => "f64".to_string(),
+    })).collect()
+}
+
+//Raccoglie le dichiarazioni: il tipo annotato è noto subito, gli altri vengono inferiti
+struct Declarations<'i, 'a>(&'i mut Inference<'a>);
+
+impl<'ast> Visit<'ast> for Declarations<'_, '_>{
+    fn visit_local(&mut self, local: &'ast Local){
+        match &local.pat {
+            Pat::Type(pat_type) => if let Pat::Ident(pat_ident) = &*pat_type.pat {
+                self.0.types.entry(pat_ident.ident.to_string()).or_insert(Inferred::Known(type_string(&pat_type.ty)));
+            },
+            Pat::Ident(pat_ident) => {
+                let init = local.init.as_ref().map(|init| (*init.expr).clone());
+                self.0.declarations.push((pat_ident.ident.to_string(), Declaration{ init, iterated: false }));
+            }
+            _ => {}
+        }
+        visit::visit_local(self, local);
+    }
+
+    fn visit_expr_for_loop(&mut self, expr_for: &'ast syn::ExprForLoop){
+        if let Pat::Ident(pat_ident) = &*expr_for.pat {
+            let init = Some((*expr_for.expr).clone());
+            self.0.declarations.push((pat_ident.ident.to_string(), Declaration{ init, iterated: true }));
+        }
+        visit::visit_expr_for_loop(self, expr_for);
+    }
+
+    fn visit_item(&mut self, _item: &'ast Item){}
+}
+
+impl Inference<'_>{
+    //assegna un tipo a una variabile: un tipo noto sostituisce un letterale, mai il contrario
+    fn assign(&mut self, name: &str, ty: Inferred){
+        let update = match (self.types.get(name), &ty) {
+            (None, _) => true,
+            (Some(Inferred::Known(_)), _) => false,
+            (Some(_), Inferred::Known(_)) => true,
+            (Some(Inferred::Int), Inferred::Float) => true,
+            _ => false,
+        };
+        if update {
+            self.types.insert(name.to_string(), ty);
+            self.changed = true;
+        }
+    }
+
+    fn infer_declarations(&mut self){
+        let declarations = std::mem::take(&mut self.declarations);
+        for (name, declaration) in &declarations {
+            let Some(init) = &declaration.init else { continue };
+            let ty = if declaration.iterated { self.iterated_type(init) } else { self.expr_type(init) };
+            if let Some(ty) = ty {
+                self.assign(name, ty);
+            }
+        }
+        self.declarations = declarations;
+    }
+
+    //tipo degli elementi prodotti da un for: intervalli, vettori, riferimenti e .iter()
+    fn iterated_type(&self, expr: &Expr)->Option<Inferred>{
+        match expr {
+            Expr::Range(range) => {
+                let bound = |bound: &Option<Box<Expr>>| bound.as_ref().and_then(|bound| self.expr_type(bound));
+                match (bound(&range.start), bound(&range.end)) {
+                    (Some(Inferred::Known(ty)), _) | (_, Some(Inferred::Known(ty))) => Some(Inferred::Known(ty)),
+                    (start, end) => start.or(end),
+                }
+            }
+            Expr::Paren(paren) => self.iterated_type(&paren.expr),
+            Expr::Reference(reference) => self.known_type(&reference.expr)
+                .and_then(|ty| element_type(&ty))
+                .map(|elem| Inferred::Known(format!("& {}", elem))),
+            Expr::MethodCall(call) if call.method == "iter" => self.known_type(&call.receiver)
+                .and_then(|ty| element_type(&ty))
+                .map(|elem| Inferred::Known(format!("& {}", elem))),
+            _ => self.known_type(expr).and_then(|ty| element_type(&ty)).map(Inferred::Known),
+        }
+    }
+
+    fn known_type(&self, expr: &Expr)->Option<String>{
+        match self.expr_type(expr) {
+            Some(Inferred::Known(ty)) => Some(ty),
+            _ => None,
+        }
+    }
+
+    fn expr_type(&self, expr: &Expr)->Option<Inferred>{
+        use syn::BinOp::*;
+        match expr {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Int(int) if !int.suffix().is_empty() => Some(Inferred::Known(int.suffix().to_string())),
+                Lit::Int(_) => Some(Inferred::Int),
+                Lit::Float(float) if !float.suffix().is_empty() => Some(Inferred::Known(float.suffix().to_string())),
+                Lit::Float(_) => Some(Inferred::Float),
+                Lit::Bool(_) => Some(Inferred::Known("bool".to_string())),
+                Lit::Char(_) => Some(Inferred::Known("char".to_string())),
+                Lit::Str(_) => Some(Inferred::Known("& str".to_string())),
+                _ => None,
+            },
+            Expr::Path(path) => {
+                if let Some(ident) = path.path.get_ident() {
+                    return self.types.get(&ident.to_string()).cloned();
+                }
+                //costanti associate dei tipi primitivi (usize::MAX)
+                let segments: Vec<String> = path.path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+                match segments.as_slice() {
+                    [ty, _] if PRIMITIVES.contains(&ty.as_str()) => Some(Inferred::Known(ty.clone())),
+                    _ => None,
+                }
+            }
+            Expr::Binary(binary) => match binary.op {
+                Eq(_) | Ne(_) | Lt(_) | Le(_) | Gt(_) | Ge(_) | And(_) | Or(_) => Some(Inferred::Known("bool".to_string())),
+                Shl(_) | Shr(_) => self.expr_type(&binary.left),
+                Add(_) | Sub(_) | Mul(_) | Div(_) | Rem(_) | BitAnd(_) | BitOr(_) | BitXor(_) => {
+                    match (self.expr_type(&binary.left), self.expr_type(&binary.right)) {
+                        (Some(Inferred::Known(ty)), _) | (_, Some(Inferred::Known(ty))) => Some(Inferred::Known(deref_type(&ty))),
+                        (Some(Inferred::Float), _) | (_, Some(Inferred::Float)) => Some(Inferred::Float),
+                        (left, right) => left.or(right),
+                    }
+                }
+                //gli assegnamenti composti hanno tipo ()
+                _ => None,
+            },
+            Expr::Unary(unary) => match unary.op {
+                syn::UnOp::Deref(_) => self.known_type(&unary.expr).map(|ty| Inferred::Known(deref_type(&ty))),
+                _ => self.expr_type(&unary.expr),
+            },
+            Expr::Paren(paren) => self.expr_type(&paren.expr),
+            Expr::Group(group) => self.expr_type(&group.expr),
+            //le operazioni tra Hardened restituiscono un Result: '?' ne estrae il valore
+            Expr::Try(expr_try) => self.expr_type(&expr_try.expr).map(|ty| match ty {
+                Inferred::Known(ty) => Inferred::Known(result_payload(&ty).unwrap_or(ty)),
+                literal => literal,
+            }),
+            Expr::Cast(cast) => Some(Inferred::Known(type_string(&cast.ty))),
+            Expr::Reference(reference) => self.known_type(&reference.expr).map(|ty| Inferred::Known(match reference.mutability {
+                Some(_) => format!("& mut {}", ty),
+                None => format!("& {}", ty),
+            })),
+            Expr::Index(index) => {
+                if let Expr::Range(_) = &*index.index {
+                    return self.known_type(&index.expr).and_then(|ty| element_type(&ty)).map(|elem| Inferred::Known(format!("[{}]", elem)));
+                }
+                self.known_type(&index.expr).and_then(|ty| element_type(&ty)).map(Inferred::Known)
+            }
+            Expr::MethodCall(call) => {
+                let method = call.method.to_string();
+                if USIZE_METHODS.contains(&method.as_str()) {
+                    Some(Inferred::Known("usize".to_string()))
+                } else if BOOL_METHODS.contains(&method.as_str()) {
+                    Some(Inferred::Known("bool".to_string()))
+                } else if method == "inner" {
+                    //valore controllato di un Hardened
+                    self.known_type(&call.receiver).and_then(|ty| hardened_payload(&deref_type(&ty))).map(Inferred::Known)
+                } else if SAME_TYPE_METHODS.contains(&method.as_str()) {
+                    self.expr_type(&call.receiver).map(|ty| match ty {
+                        Inferred::Known(ty) => Inferred::Known(deref_type(&ty)),
+                        literal => literal,
+                    })
+                } else {
+                    None
+                }
+            }
+            Expr::Call(call) => match &*call.func {
+                Expr::Path(path) => {
+                    let segments: Vec<String> = path.path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+                    match segments.as_slice() {
+                        //conversioni esplicite (usize::from, i64::try_from non è gestita)
+                        [ty, conversion] if PRIMITIVES.contains(&ty.as_str()) && conversion == "from" => Some(Inferred::Known(ty.clone())),
+                        //le due copie di un Hardened hanno il tipo del valore da cui è costruito;
+                        //un letterale prende ancora il tipo dal contesto
+                        [hardened, conversion] if hardened == "Hardened" && conversion == "from" => match self.expr_type(call.args.first()?)? {
+                            Inferred::Known(ty) => Some(Inferred::Known(format!("Hardened < {} >", ty))),
+                            literal => Some(literal),
+                        },
+                        [.., name] => self.signatures.0.get(name).and_then(|(_, ret)| ret.clone()).map(Inferred::Known),
+                        [] => None,
+                    }
+                }
+                _ => None,
+            },
+            Expr::Macro(mac) if mac.mac.path.is_ident("vec") => {
+                let elem = match mac.mac.parse_body::<VecMacro>().ok()? {
+                    VecMacro::Repeat(elem) => self.expr_type(&elem),
+                    VecMacro::Elems(elems) => elems.iter().filter_map(|elem| self.expr_type(elem))
+                        .max_by_key(|ty| matches!(ty, Inferred::Known(_))),
+                };
+                match elem? {
+                    Inferred::Known(ty) => Some(Inferred::Known(format!("Vec < {} >", ty))),
+                    Inferred::Int => Some(Inferred::Known("Vec < i32 >".to_string())),
+                    Inferred::Float => Some(Inferred::Known("Vec < f64 >".to_string())),
+                }
+            }
+            Expr::Block(block) => self.block_type(&block.block),
+            Expr::If(expr_if) => self.block_type(&expr_if.then_branch).or_else(|| {
+                expr_if.else_branch.as_ref().and_then(|(_, else_branch)| self.expr_type(else_branch))
+            }),
+            _ => None,
+        }
+    }
+
+    fn block_type(&self, block: &Block)->Option<Inferred>{
+        match block.stmts.last() {
+            Some(Stmt::Expr(expr, None)) => self.expr_type(expr),
+            _ => None,
+        }
+    }
+
+    //l'espressione deve avere tipo 'ty': le variabili con un tipo ancora da decidere lo prendono
+    fn constrain(&mut self, expr: &Expr, ty: &str){
+        use syn::BinOp::*;
+        match expr {
+            Expr::Path(path) => if let Some(ident) = path.path.get_ident() {
+                let name = ident.to_string();
+                if !matches!(self.types.get(&name), Some(Inferred::Known(_))) && self.is_local(&name) {
+                    self.assign(&name, Inferred::Known(ty.to_string()));
+                }
+            },
+            Expr::Binary(binary) => if let Add(_) | Sub(_) | Mul(_) | Div(_) | Rem(_) | BitAnd(_) | BitOr(_) | BitXor(_) = binary.op {
+                self.constrain(&binary.left, ty);
+                self.constrain(&binary.right, ty);
+            },
+            Expr::Paren(paren) => self.constrain(&paren.expr, ty),
+            Expr::Unary(unary) if matches!(unary.op, syn::UnOp::Neg(_)) => self.constrain(&unary.expr, ty),
+            Expr::Reference(reference) => self.constrain(&reference.expr, &deref_type(ty)),
+            Expr::Block(block) => if let Some(Stmt::Expr(tail, None)) = block.block.stmts.last() {
+                self.constrain(tail, ty);
+            },
+            Expr::If(expr_if) => {
+                if let Some(Stmt::Expr(tail, None)) = expr_if.then_branch.stmts.last() {
+                    self.constrain(tail, ty);
+                }
+                if let Some((_, else_branch)) = &expr_if.else_branch {
+                    self.constrain(else_branch, ty);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn is_local(&self, name: &str)->bool{
+        self.declarations.iter().any(|(declared, _)| declared == name)
+    }
+
+    //due operandi dello stesso tipo: se uno è noto, l'altro lo prende
+    fn unify(&mut self, left: &Expr, right: &Expr){
+        match (self.known_type(left), self.known_type(right)) {
+            (Some(ty), None) => self.constrain(right, &deref_type(&ty)),
+            (None, Some(ty)) => self.constrain(left, &deref_type(&ty)),
+            _ => {}
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Inference<'_>{
+    fn visit_expr_binary(&mut self, binary: &'ast ExprBinary){
+        use syn::BinOp::*;
+        match binary.op {
+            And(_) | Or(_) => {
+                self.constrain(&binary.left, "bool");
+                self.constrain(&binary.right, "bool");
+            }
+            Shl(_) | Shr(_) | ShlAssign(_) | ShrAssign(_) => {}
+            _ => self.unify(&binary.left, &binary.right),
+        }
+        visit::visit_expr_binary(self, binary);
+    }
+
+    fn visit_expr_assign(&mut self, assign: &'ast syn::ExprAssign){
+        self.unify(&assign.left, &assign.right);
+        visit::visit_expr_assign(self, assign);
+    }
+
+    fn visit_local(&mut self, local: &'ast Local){
+        if let (Pat::Type(pat_type), Some(init)) = (&local.pat, &local.init) {
+            self.constrain(&init.expr, &type_string(&pat_type.ty));
+        }
+        visit::visit_local(self, local);
+    }
+
+    fn visit_expr_index(&mut self, index: &'ast syn::ExprIndex){
+        match &*index.index {
+            Expr::Range(range) => {
+                for bound in range.start.iter().chain(range.end.iter()) {
+                    self.constrain(bound, "usize");
+                }
+            }
+            other => self.constrain(other, "usize"),
+        }
+        visit::visit_expr_index(self, index);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall){
+        let method = call.method.to_string();
+        if INDEX_METHODS.contains(&method.as_str()) {
+            if let Some(index) = call.args.first() {
+                self.constrain(index, "usize");
+            }
+            if method == "swap" {
+                if let Some(index) = call.args.iter().nth(1) {
+                    self.constrain(index, "usize");
+                }
+            }
+        }
+        //a.assign(b) è l'assegnamento tra due Hardened
+        if method == "assign" {
+            if let Some(value) = call.args.first() {
+                self.unify(&call.receiver, value);
+            }
+        }
+        if method == "push" {
+            if let (Some(elem), Some(ty)) = (call.args.first(), self.known_type(&call.receiver).and_then(|ty| element_type(&ty))) {
+                self.constrain(elem, &ty);
+            }
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall){
+        if let Expr::Path(path) = &*call.func {
+            let params = path.path.segments.last()
+                .and_then(|segment| self.signatures.0.get(&segment.ident.to_string()))
+                .map(|(params, _)| params.clone());
+            for (arg, ty) in call.args.iter().zip(params.into_iter().flatten()) {
+                self.constrain(arg, &ty);
+            }
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_return(&mut self, expr_return: &'ast syn::ExprReturn){
+        if let (Some(expr), Some(ret)) = (&expr_return.expr, self.ret.clone()) {
+            self.constrain(expr, &ret);
+        }
+        visit::visit_expr_return(self, expr_return);
+    }
+
+    //le closure hanno un proprio tipo restituito
+    fn visit_expr_closure(&mut self, closure: &'ast syn::ExprClosure){
+        let ret = self.ret.take();
+        visit::visit_expr_closure(self, closure);
+        self.ret = ret;
+    }
+
+    fn visit_item(&mut self, _item: &'ast Item){}
+}
+
+#[cfg(test)]
+mod tests{
+    use std::collections::HashMap;
+    use crate::fault_list_manager::type_inference::{infer_types, Signatures};
+
+    fn types(code: &str)->HashMap<String, String>{
+        let file: syn::File = syn::parse_str(code).unwrap();
+        let Some(syn::Item::Fn(func)) = file.items.first() else { panic!("nessuna funzione") };
+        infer_types(&func.sig, &func.block, &Signatures::collect(&file.items))
+    }
+
+    #[test]
+    fn test_infer_from_uses(){
+        let types = types("fn f(vet: Vec<i32>, n: usize, scale: f32)->u64{
+            let mut i = 0;
+            let mut min = 0;
+            let swapped = vet.len() > 0;
+            let len = vet.len();
+            let mut acc = 0;
+            let mut x = 1.5;
+            let mut total = 0;
+            let mut count = 0;
+            let first = vet[0];
+            while i < n {
+                if vet[i] < vet[min] { min = i; }
+                acc += vet[i];
+                x = x * scale;
+                i += 1;
+            }
+            count = helper(count);
+            total = total + 1;
+            total
+        }
+        fn helper(k: u8)->u8 { k }");
+        assert_eq!(types["i"], "usize");
+        assert_eq!(types["min"], "usize");
+        assert_eq!(types["swapped"], "bool");
+        assert_eq!(types["len"], "usize");
+        assert_eq!(types["acc"], "i32");
+        assert_eq!(types["x"], "f32");
+        assert_eq!(types["first"], "i32");
+        assert_eq!(types["count"], "u8");
+        //il valore restituito ha il tipo della firma
+        assert_eq!(types["total"], "u64");
+    }
+
+    #[test]
+    fn test_infer_from_initializers(){
+        let types = types("fn f(m: Vec<Vec<f64>>, v: &[u16]){
+            let row = &m[0];
+            let cell = m[1][2];
+            let part = &v[1..];
+            let big = v[0] as u64;
+            let doubled = vec![0; 4];
+            let flags = vec![true, false];
+            let lim = usize::MAX;
+            let unused = 7;
+            let real = 2.0;
+            for k in 0..v.len() { }
+            for e in v.iter() { }
+            let mut q = 3;
+            q <<= 1;
+        }");
+        assert_eq!(types["row"], "& Vec < f64 >");
+        assert_eq!(types["cell"], "f64");
+        assert_eq!(types["part"], "& [u16]");
+        assert_eq!(types["big"], "u64");
+        assert_eq!(types["doubled"], "Vec < i32 >");
+        assert_eq!(types["flags"], "Vec < bool >");
+        assert_eq!(types["lim"], "usize");
+        //letterali senza vincoli: i tipi di default di Rust
+        assert_eq!(types["unused"], "i32");
+        assert_eq!(types["real"], "f64");
+        assert_eq!(types["q"], "i32");
+        assert_eq!(types["k"], "usize");
+        assert_eq!(types["e"], "& u16");
+    }
+}
@@ -0,0 +1,326 @@
+//! Modello delle dimensioni dei tipi delle variabili analizzate.
+//!
+//! La dimensione di un tipo dipende dall'architettura su cui vengono eseguiti i target (usize,
+//! isize e i puntatori sono larghi quanto un indirizzo) e, per struct, tuple e Option, dal layout
+//! scelto dal compilatore: i campi vengono ordinati per allineamento decrescente, così l'unico
+//! padding è quello finale che rende la dimensione un multiplo dell'allineamento. Vettori e
+//! matrici hanno una lunghezza nota solo durante l'esecuzione: la loro dimensione è espressa per
+//! elemento. Nei file di analisi la dimensione resta una stringa ("8", "4*len", "4*nR*nC",
+//! "unknown"), come nelle versioni precedenti.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use syn::{Expr, Fields, GenericArgument, Item, Lit, PathArguments, Type};
+
+///Larghezza in bit dei puntatori dell'architettura dei target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PointerWidth(pub usize);
+
+impl PointerWidth{
+    ///Architettura su cui è compilato l'ambiente, che esegue anche i target
+    pub const HOST: PointerWidth = PointerWidth(usize::BITS as usize);
+
+    fn bytes(self)->usize{
+        self.0 / 8
+    }
+}
+
+impl Default for PointerWidth{
+    fn default()->Self{
+        PointerWidth::HOST
+    }
+}
+
+///Dimensione in byte di una variabile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum TypeSize{
+    Fixed(usize),               //scalari, array, tuple, struct
+    Vector{ elem: usize },      //Vec<T>: elem byte per ognuno dei len elementi
+    Matrix{ elem: usize },      //Vec<Vec<T>>: elem byte per ognuno degli nR*nC elementi
+    #[default]
+    Unknown,
+}
+
+impl TypeSize{
+    ///Byte di un singolo elemento iniettabile (l'intera variabile per i tipi a dimensione fissa)
+    pub fn element_bytes(&self)->Option<usize>{
+        match self {
+            TypeSize::Fixed(bytes) | TypeSize::Vector{ elem: bytes } | TypeSize::Matrix{ elem: bytes } => Some(*bytes),
+            TypeSize::Unknown => None,
+        }
+    }
+}
+
+impl Display for TypeSize{
+    fn fmt(&self, f: &mut Formatter<'_>)->std::fmt::Result{
+        match self {
+            TypeSize::Fixed(bytes) => write!(f, "{}", bytes),
+            TypeSize::Vector{ elem } => write!(f, "{}*len", elem),
+            TypeSize::Matrix{ elem } => write!(f, "{}*nR*nC", elem),
+            TypeSize::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl FromStr for TypeSize{
+    type Err = String;
+
+    fn from_str(s: &str)->Result<Self, Self::Err>{
+        let parts: Vec<&str> = s.split('*').collect();
+        let elem = || parts[0].parse::<usize>().map_err(|_| format!("dimensione non valida: {}", s));
+        match parts.as_slice() {
+            ["unknown"] => Ok(TypeSize::Unknown),
+            [_] => Ok(TypeSize::Fixed(elem()?)),
+            [_, "nR", "nC"] => Ok(TypeSize::Matrix{ elem: elem()? }),
+            //i file di analisi dei plugin indicavano la lunghezza effettiva del vettore
+            [_, len] if *len == "len" || len.parse::<usize>().is_ok() => Ok(TypeSize::Vector{ elem: elem()? }),
+            _ => Err(format!("dimensione non valida: {}", s)),
+        }
+    }
+}
+
+impl From<TypeSize> for String{
+    fn from(size: TypeSize)->Self{
+        size.to_string()
+    }
+}
+
+impl TryFrom<String> for TypeSize{
+    type Error = String;
+
+    fn try_from(s: String)->Result<Self, Self::Error>{
+        s.parse()
+    }
+}
+
+//Dimensione e allineamento in byte
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Layout{
+    size: usize,
+    align: usize,
+}
+
+impl Layout{
+    fn scalar(size: usize)->Self{
+        Layout{ size, align: size.max(1) }
+    }
+
+    //struct e tuple: campi ordinati per allineamento decrescente, senza padding intermedio
+    fn fields(fields: impl IntoIterator<Item = Layout>)->Self{
+        let (size, align) = fields.into_iter().fold((0, 1), |(size, align), field| (size + field.size, align.max(field.align)));
+        Layout{ size: size.div_ceil(align) * align, align }
+    }
+}
+
+///Modello delle dimensioni per un'architettura, con le struct e le costanti del file analizzato
+#[derive(Default)]
+pub struct SizeModel{
+    pointer_width: PointerWidth,
+    structs: HashMap<String, Vec<Type>>,
+    consts: HashMap<String, usize>,
+}
+
+impl SizeModel{
+    ///Modello per i puntatori di 'pointer_width', con le struct non generiche e le costanti
+    /// intere dichiarate in 'items' (anche nei moduli annidati)
+    pub fn new(items: &[Item], pointer_width: PointerWidth)->Self{
+        let mut model = SizeModel{ pointer_width, ..SizeModel::default() };
+        model.collect(items);
+        model
+    }
+
+    fn collect(&mut self, items: &[Item]){
+        for item in items {
+            match item {
+                Item::Struct(item_struct) if item_struct.generics.params.is_empty() => {
+                    let fields = match &item_struct.fields {
+                        Fields::Named(fields) => fields.named.iter().map(|field| field.ty.clone()).collect(),
+                        Fields::Unnamed(fields) => fields.unnamed.iter().map(|field| field.ty.clone()).collect(),
+                        Fields::Unit => Vec::new(),
+                    };
+                    self.structs.insert(item_struct.ident.to_string(), fields);
+                }
+                Item::Const(item_const) => if let Expr::Lit(syn::ExprLit{ lit: Lit::Int(int), .. }) = &*item_const.expr {
+                    if let Ok(value) = int.base10_parse() {
+                        self.consts.insert(item_const.ident.to_string(), value);
+                    }
+                },
+                Item::Mod(syn::ItemMod{ content: Some((_, items)), .. }) => self.collect(items),
+                _ => {}
+            }
+        }
+    }
+
+    ///Dimensione del tipo 'ty' (nel formato dei token di syn, es. "Vec < i32 >")
+    pub fn type_size(&self, ty: &str)->TypeSize{
+        let Ok(ty) = syn::parse_str::<Type>(ty) else {
+            return TypeSize::Unknown;
+        };
+        let elem_size = |ty: &Type| self.layout(ty).map(|layout| layout.size);
+        match vec_element(&ty) {
+            Some(inner) => match vec_element(inner) {
+                Some(elem) => elem_size(elem).map_or(TypeSize::Unknown, |elem| TypeSize::Matrix{ elem }),
+                None => elem_size(inner).map_or(TypeSize::Unknown, |elem| TypeSize::Vector{ elem }),
+            },
+            None => elem_size(&ty).map_or(TypeSize::Unknown, TypeSize::Fixed),
+        }
+    }
+
+    fn layout(&self, ty: &Type)->Option<Layout>{
+        let pointer = self.pointer_width.bytes();
+        match ty {
+            Type::Paren(paren) => self.layout(&paren.elem),
+            Type::Group(group) => self.layout(&group.elem),
+            Type::Tuple(tuple) => self.fields(tuple.elems.iter()),
+            Type::Array(array) => {
+                let len = match &array.len {
+                    Expr::Lit(syn::ExprLit{ lit: Lit::Int(int), .. }) => int.base10_parse().ok()?,
+                    Expr::Path(path) => *self.consts.get(&path.path.get_ident()?.to_string())?,
+                    _ => return None,
+                };
+                let elem = self.layout(&array.elem)?;
+                Some(Layout{ size: elem.size * len, align: elem.align })
+            }
+            Type::Reference(reference) => Some(self.pointer_to(&reference.elem)),
+            Type::Ptr(ptr) => Some(self.pointer_to(&ptr.elem)),
+            Type::Path(path) if path.qself.is_none() => {
+                let segment = path.path.segments.last()?;
+                let name = segment.ident.to_string();
+                let size = match name.as_str() {
+                    "i8" | "u8" | "bool" => 1,
+                    "i16" | "u16" => 2,
+                    "i32" | "u32" | "f32" | "char" => 4,
+                    "i64" | "u64" | "f64" => 8,
+                    "i128" | "u128" => 16,
+                    "isize" | "usize" => pointer,
+                    "Vec" | "String" => return Some(Layout{ size: 3 * pointer, align: pointer }),
+                    "Box" => return Some(match generic_argument(&segment.arguments) {
+                        Some(inner) => self.pointer_to(inner),
+                        None => Layout::scalar(pointer),
+                    }),
+                    "Rc" | "Arc" => pointer,
+                    "Option" => return self.option(generic_argument(&segment.arguments)?),
+                    _ => return self.fields(self.structs.get(&name)?.iter()),
+                };
+                Some(Layout::scalar(size))
+            }
+            _ => None,
+        }
+    }
+
+    fn fields<'a>(&self, fields: impl Iterator<Item = &'a Type>)->Option<Layout>{
+        Some(Layout::fields(fields.map(|field| self.layout(field)).collect::<Option<Vec<_>>>()?))
+    }
+
+    //i puntatori a slice, str e trait object portano con sé la lunghezza o la vtable
+    fn pointer_to(&self, target: &Type)->Layout{
+        let pointer = self.pointer_width.bytes();
+        let fat = matches!(target, Type::Slice(_) | Type::TraitObject(_))
+            || matches!(target, Type::Path(path) if path.path.is_ident("str"));
+        Layout{ size: if fat { 2 * pointer } else { pointer }, align: pointer }
+    }
+
+    //Option<T> usa i valori non validi di T (puntatori nulli, bool e char fuori intervallo) per
+    //rappresentare None; negli altri casi serve un discriminante in più
+    fn option(&self, inner: &Type)->Option<Layout>{
+        let layout = self.layout(inner)?;
+        let niche = match inner {
+            Type::Reference(_) => true,
+            Type::Path(path) => path.path.segments.last().is_some_and(|segment|
+                ["bool", "char", "Box", "Rc", "Arc", "Vec", "String"].contains(&segment.ident.to_string().as_str())
+                    || segment.ident.to_string().starts_with("NonZero")),
+            _ => false,
+        };
+        if niche {
+            return Some(layout);
+        }
+        Some(Layout::fields([layout, Layout::scalar(1)]))
+    }
+}
+
+//Tipo degli elementi di un Vec<T>
+fn vec_element(ty: &Type)->Option<&Type>{
+    match ty {
+        Type::Path(path) if path.path.segments.last().is_some_and(|segment| segment.ident == "Vec") =>
+            generic_argument(&path.path.segments.last()?.arguments),
+        _ => None,
+    }
+}
+
+fn generic_argument(arguments: &PathArguments)->Option<&Type>{
+    match arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::fault_list_manager::type_size::{PointerWidth, SizeModel, TypeSize};
+    use crate::fault_list_manager::type_size::TypeSize::*;
+
+    fn model(code: &str, bits: usize)->SizeModel{
+        let file: syn::File = syn::parse_str(code).unwrap();
+        SizeModel::new(&file.items, PointerWidth(bits))
+    }
+
+    #[test]
+    fn test_type_sizes(){
+        let code = "
+            const N: usize = 5;
+            struct Point{ x: i32, y: f64, ok: bool }
+            struct Pair(u8, u16);
+            struct Generic<T>{ v: T }
+            mod inner { pub struct Cell{ value: usize } }
+        ";
+        let (m64, m32) = (model(code, 64), model(code, 32));
+        for (ty, size64, size32) in [
+            ("usize", Fixed(8), Fixed(4)),
+            ("i128", Fixed(16), Fixed(16)),
+            ("char", Fixed(4), Fixed(4)),
+            ("[u16 ; 4]", Fixed(8), Fixed(8)),
+            ("[i32 ; N]", Fixed(20), Fixed(20)),
+            ("(u8 , u32 , u8)", Fixed(8), Fixed(8)),
+            ("(usize , i32)", Fixed(16), Fixed(8)),
+            ("()", Fixed(0), Fixed(0)),
+            ("Option < i32 >", Fixed(8), Fixed(8)),
+            ("Option < usize >", Fixed(16), Fixed(8)),
+            ("Option < bool >", Fixed(1), Fixed(1)),
+            ("Option < & i32 >", Fixed(8), Fixed(4)),
+            ("& [i32]", Fixed(16), Fixed(8)),
+            ("String", Fixed(24), Fixed(12)),
+            ("Point", Fixed(16), Fixed(16)),
+            ("Pair", Fixed(4), Fixed(4)),
+            ("Cell", Fixed(8), Fixed(4)),
+            ("[Point ; 2]", Fixed(32), Fixed(32)),
+            ("Vec < usize >", Vector{ elem: 8 }, Vector{ elem: 4 }),
+            ("Vec < Vec < f64 > >", Matrix{ elem: 8 }, Matrix{ elem: 8 }),
+            ("Vec < (usize , i32) >", Vector{ elem: 16 }, Vector{ elem: 8 }),
+            ("Generic < i32 >", Unknown, Unknown),
+            ("HashMap < i32 , i32 >", Unknown, Unknown),
+            ("[i32 ; M]", Unknown, Unknown),
+        ] {
+            assert_eq!(m64.type_size(ty), size64, "{} a 64 bit", ty);
+            assert_eq!(m32.type_size(ty), size32, "{} a 32 bit", ty);
+        }
+    }
+
+    #[test]
+    fn test_size_strings(){
+        for (text, size) in [("8", Fixed(8)), ("4*len", Vector{ elem: 4 }), ("8*nR*nC", Matrix{ elem: 8 }), ("unknown", Unknown)] {
+            assert_eq!(text.parse::<TypeSize>(), Ok(size));
+            assert_eq!(size.to_string(), text);
+            assert_eq!(serde_json::to_string(&size).unwrap(), format!("\"{}\"", text));
+        }
+        //i plugin salvavano la lunghezza effettiva del vettore
+        assert_eq!("1*3".parse::<TypeSize>(), Ok(Vector{ elem: 1 }));
+        assert!("4*rows".parse::<TypeSize>().is_err());
+        assert!(serde_json::from_str::<TypeSize>("\"tanti\"").is_err());
+    }
+}
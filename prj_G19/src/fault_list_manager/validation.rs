@@ -0,0 +1,179 @@
+//! Validazione di una fault list rispetto al registro delle variabili del target.
+//!
+//! Una entry che nomina una variabile inesistente, un elemento fuori dal vettore, un bit oltre
+//! la larghezza del tipo o un istante oltre la fine dell'esecuzione fallirebbe solo dentro
+//! l'iniettore, a campagna avviata. Prima di avviarla ogni entry viene confrontata con lo
+//! spazio dei fault del target e i problemi vengono riportati tutti insieme, con la riga del
+//! file in cui si trovano.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+use crate::fault_list_manager::{injectable_vars, site_of, BitDistribution, DimData, FaultListEntry, FaultListError, FaultListReader, FaultSpace};
+use crate::fault_list_manager::static_analysis::load_analysis;
+
+///Problema di una entry della fault list
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryProblem{
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for EntryProblem{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "riga {}: {}", self.line, self.message)
+    }
+}
+
+///Elementi iniettabili del target per un dato input: per ognuno gli istanti ammessi e il
+/// numero di bit
+pub struct Registry{
+    elements: HashMap<String, (Vec<Range<usize>>, usize)>,
+    vars: HashSet<String>,
+    num_instr_eff: usize,
+}
+
+impl Registry{
+    ///Registro costruito come lo spazio dei fault di create_fault_list, con tutti i bit del tipo,
+    /// dalle variabili della funzione 'function' del file di analisi
+    pub fn new(path_raw_info: &str, function: &str, dims: &DimData, case: &str, num_instr_eff: usize)->Self{
+        let info = load_analysis(path_raw_info, function)
+            .unwrap_or_else(|e| panic!("File di analisi {} non utilizzabile: {}", path_raw_info, e));
+        let vars = injectable_vars(info.vars, dims);
+        let space = FaultSpace::new(&vars, dims, case, num_instr_eff, BitDistribution::Uniform);
+
+        let mut elements: HashMap<String, (Vec<Range<usize>>, usize)> = HashMap::new();
+        for block in &space.blocks {
+            for name in &block.names {
+                let (times, bits) = elements.entry(name.clone()).or_insert((Vec::new(), block.bits));
+                times.push(block.times.clone());
+                *bits = block.bits;
+            }
+        }
+        Registry{ elements, vars: vars.into_iter().map(|var| var.name).collect(), num_instr_eff }
+    }
+
+    ///Problemi della entry, vuoto se la entry è iniettabile
+    pub fn check(&self, entry: &FaultListEntry)->Vec<String>{
+        let mut problems = Vec::new();
+        if entry.weight == 0 {
+            problems.push("peso nullo".to_string());
+        }
+        let Some((times, bits)) = self.elements.get(&entry.var) else {
+            //"vet[57]" o "x@3": la variabile esiste ma l'elemento no
+            let base = site_of(&entry.var);
+            problems.push(if self.vars.contains(base) && base != entry.var {
+                format!("{} fuori dalle dimensioni di {}", entry.var, base)
+            } else {
+                format!("variabile sconosciuta: {}", entry.var)
+            });
+            return problems;
+        };
+        if entry.time >= self.num_instr_eff {
+            problems.push(format!("istante {} oltre la fine dell'esecuzione ({} istruzioni)", entry.time, self.num_instr_eff));
+        } else if !times.iter().any(|range| range.contains(&entry.time)) {
+            problems.push(format!("istante {} non ammesso per {}", entry.time, entry.var));
+        }
+        if entry.flipped_bit >= *bits {
+            problems.push(format!("bit {} oltre la larghezza di {} ({} bit)", entry.flipped_bit, entry.var, bits));
+        }
+        problems
+    }
+}
+
+///Controlla la fault list salvata in 'path': intestazione (schema e target) ed entry, che devono
+/// appartenere allo spazio dei fault del target. Restituisce il numero di entry lette oppure
+/// l'elenco completo dei problemi
+pub fn validate_fault_list(path: &str, target: &str, registry: &Registry)->Result<usize, FaultListError>{
+    let reader = FaultListReader::open(path)?;
+    reader.check(target)?;
+    let mut problems = Vec::new();
+    let mut num_entries = 0;
+    for entry in reader.numbered() {
+        match entry {
+            Ok((line, entry)) => {
+                num_entries += 1;
+                problems.extend(registry.check(&entry).into_iter().map(|message| EntryProblem{ line, message }));
+            }
+            //una riga malformata non impedisce di controllare le successive
+            Err(FaultListError::Parse{ line, message }) => problems.push(EntryProblem{ line, message }),
+            Err(e) => return Err(e),
+        }
+    }
+    if problems.is_empty() { Ok(num_entries) } else { Err(FaultListError::Invalid(problems)) }
+}
+
+#[cfg(test)]
+mod tests{
+    use crate::fault_list_manager::type_size::PointerWidth;
+    use std::env::temp_dir;
+    use std::fs;
+    use crate::fault_env::Data;
+    use crate::fault_list_manager::{create_fault_list, static_analysis, target_function, write_fault_list, DimData, FaultList, FaultListEntry, FaultListError, FaultListReader, Sampling, SCHEMA_VERSION};
+    use crate::fault_list_manager::validation::{validate_fault_list, Registry};
+    use crate::injector::{frame_trace, label_trace};
+    use crate::hardened::par_matrix_multiplication_hardened::PAR_WORKERS;
+
+    #[test]
+    fn test_generated_fault_lists_are_valid(){
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        let matrix = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let frames = frame_trace("quick_sort", Data::Vector(vet.clone()));
+        let cases = [
+            ("bubble_sort", DimData::Vector(vet.len()), label_trace("bubble_sort", Data::Vector(vet.clone())).len()),
+            ("quick_sort", DimData::Frames(vet.len(), frames.clone()), frames.len()),
+            ("par_matrix_multiplication", DimData::Workers((3, 3), PAR_WORKERS),
+             label_trace("par_matrix_multiplication", Data::Matrices(matrix.clone(), matrix.clone())).len()),
+        ];
+        for (case, dims, num_instr) in cases {
+            let analysis = temp_dir().join(format!("{}_ris_validation_test.json", case)).to_string_lossy().to_string();
+            static_analysis::generate_analysis_file(
+                format!("src/fault_list_manager/file_fault_list/{}/mod.rs", case), analysis.clone(), PointerWidth::HOST).unwrap();
+            let registry = Registry::new(&analysis, target_function(case), &dims, case, num_instr);
+            for (sampling, name) in [(Sampling::Random(1000), "random"), (Sampling::Statistical{ margin: 0.05, confidence: 0.95 }, "statistical")] {
+                let dest = temp_dir().join(format!("{}_FL_validation_{}_test.jsonl", case, name)).to_string_lossy().to_string();
+                let fault_list = create_fault_list(sampling, case.to_string(), analysis.clone(), dims.clone(), dest.clone(), num_instr, 5).unwrap();
+                assert_eq!(validate_fault_list(&dest, case, &registry).unwrap(), fault_list.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_entries_reported_with_lines(){
+        let analysis = temp_dir().join("bubble_sort_ris_invalid_test.json").to_string_lossy().to_string();
+        static_analysis::generate_analysis_file(
+            "src/fault_list_manager/file_fault_list/bubble_sort/mod.rs".to_string(), analysis.clone(), PointerWidth::HOST).unwrap();
+        let registry = Registry::new(&analysis, "bubble_sort", &DimData::Vector(10), "bubble_sort", 100);
+
+        let csv = temp_dir().join("bubble_sort_FL_invalid_test.csv").to_string_lossy().to_string();
+        let header = format!("# {{\"schema_version\":{},\"target\":\"bubble_sort\",\"mode\":\"random\",\"space_size\":10,\"num_faults\":6,\"confidence\":0.95,\"error_margin\":0.5}}", SCHEMA_VERSION);
+        let rows = ["vet[3],10,31,1", "vet[57],10,1,1", "k,10,1,1", "i,100,1,1", "vet[0],10,32,1", "j,tre,1,1", "swapped,5,1,1"];
+        fs::write(&csv, format!("{}\nvar,time,flipped_bit,weight\n{}\n", header, rows.join("\n"))).unwrap();
+
+        let Err(FaultListError::Invalid(problems)) = validate_fault_list(&csv, "bubble_sort", &registry) else {
+            panic!("la fault list doveva essere rifiutata")
+        };
+        //tutti i problemi, non solo il primo; le righe contano intestazione e colonne
+        let lines: Vec<usize> = problems.iter().map(|problem| problem.line).collect();
+        assert_eq!(lines, vec![4, 5, 6, 7, 8, 9]);
+        assert!(problems[0].message.contains("fuori dalle dimensioni di vet"));
+        assert!(problems[1].message.contains("variabile sconosciuta"));
+        assert!(problems[2].message.contains("oltre la fine dell'esecuzione"));
+        assert!(problems[3].message.contains("larghezza"));
+        assert!(problems[4].message.contains("time"));
+        //nei booleani conta un solo bit
+        assert!(problems[5].message.contains("larghezza di swapped (1 bit)"));
+
+        //nel formato JSON la riga è quella del campo "var" della entry
+        let json = temp_dir().join("bubble_sort_FL_invalid_test.json").to_string_lossy().to_string();
+        let metadata = FaultListReader::open(&csv).unwrap().header().cloned().unwrap();
+        let entries = vec![FaultListEntry{ var: "vet[3]".to_string(), time: 10, flipped_bit: 3, weight: 1 },
+                           FaultListEntry{ var: "vet[57]".to_string(), time: 10, flipped_bit: 3, weight: 1 }];
+        write_fault_list(&json, &FaultList{ metadata, entries }).unwrap();
+        let line = fs::read_to_string(&json).unwrap().lines().position(|line| line.contains("vet[57]")).unwrap() + 1;
+        let Err(FaultListError::Invalid(problems)) = validate_fault_list(&json, "bubble_sort", &registry) else {
+            panic!("la fault list doveva essere rifiutata")
+        };
+        assert_eq!(problems.iter().map(|problem| problem.line).collect::<Vec<_>>(), vec![line]);
+    }
+}
@@ -0,0 +1,30 @@
+mod tests;
+use crate::hardened::*;
+pub fn bfs(graph: &[Vec<(usize, i32)>], source: usize) -> Result<Vec<Hardened<i32>>, IncoherenceError> {
+    let n = Hardened::from(graph.len());
+    let mut dist = vec![Hardened::from(i32::MAX); n.inner()?];
+    let mut visited = vec![Hardened::from(false); n.inner()?];
+    //La coda FIFO è un vettore che non viene mai svuotato: 'head' indica il prossimo nodo da estrarre
+    let mut queue: Vec<Hardened<usize>> = Vec::new();
+    let mut head = Hardened::from(0);
+
+    dist[source].assign(Hardened::from(0))?;
+    visited[source].assign(Hardened::from(true))?;
+    queue.push(Hardened::from(source));
+    while head < Hardened::from(queue.len()) {
+        let u = queue[head];
+        head.assign((head + 1)?)?;
+        let mut e = Hardened::from(0);
+        while e < Hardened::from(graph[u.inner()?].len()) {
+            let v = Hardened::from(graph[u.inner()?][e.inner()?].0);
+            if !visited[v].inner()? {
+                visited[v].assign(Hardened::from(true))?;
+                let d = (dist[u] + Hardened::from(1))?;
+                dist[v].assign(d)?;
+                queue.push(v);
+            }
+            e.assign((e + 1)?)?;
+        }
+    }
+    Ok(dist)
+}
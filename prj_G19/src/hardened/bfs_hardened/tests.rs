@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use crate::hardened::Hardened;
+    use crate::hardened::bfs_hardened::bfs;
+
+    fn graph() -> Vec<Vec<(usize, i32)>> {
+        vec![
+            vec![(1, 4), (2, 1)],
+            vec![(3, 1)],
+            vec![(1, 2), (3, 5)],
+            vec![(4, 3)],
+            vec![],
+            vec![(0, 1)],
+        ]
+    }
+
+    #[test]
+    fn test_bfs_hardened() {
+        let dist = bfs(&graph(), 0).unwrap();
+        assert_eq!(dist, Hardened::from_vec(vec![0, 1, 1, 2, 3, i32::MAX]));
+    }
+
+    #[test]
+    fn test_bfs_hardened_single_node() {
+        let dist = bfs(&[vec![]], 0).unwrap();
+        assert_eq!(dist, Hardened::from_vec(vec![0]));
+    }
+}
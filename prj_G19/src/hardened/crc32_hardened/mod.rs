@@ -0,0 +1,36 @@
+mod tests;
+use crate::hardened::*;
+///Polinomio generatore del CRC32 (IEEE 802.3) in forma riflessa
+pub const CRC32_POLY: u32 = 0xEDB88320;
+
+pub fn crc32(data: &[Hardened<u8>]) -> Result<Hardened<u32>, IncoherenceError> {
+    //Anche la tabella di lookup è irrobustita: un fault in una sua entry si propaga
+    //a tutti i byte che la utilizzano
+    let mut table = vec![Hardened::from(0u32); 256];
+    let mut n = Hardened::from(0);
+
+    while n < Hardened::from(256) {
+        let mut c = Hardened::from(n.inner()? as u32);
+        let mut k = Hardened::from(0);
+        while k < Hardened::from(8) {
+            if (c & Hardened::from(1))?.inner()? == 1 {
+                c.assign((Hardened::from(CRC32_POLY) ^ (c >> 1)?)?)?;
+            } else {
+                c.assign((c >> 1)?)?;
+            }
+            k.assign((k + 1)?)?;
+        }
+        table[n].assign(c)?;
+        n.assign((n + 1)?)?;
+    }
+
+    let mut crc = Hardened::from(0xFFFFFFFFu32);
+    let mut i = Hardened::from(0);
+    while i < Hardened::from(data.len()) {
+        let byte = data[i.inner()?];
+        let idx = Hardened::from(((crc ^ Hardened::from(byte.inner()? as u32))? & Hardened::from(0xFF))?.inner()? as usize);
+        crc.assign((table[idx] ^ (crc >> 8)?)?)?;
+        i.assign((i + 1)?)?;
+    }
+    crc ^ Hardened::from(0xFFFFFFFF)
+}
@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod tests {
+    use crate::hardened::{Hardened, IncoherenceError};
+    use crate::hardened::crc32_hardened::crc32;
+
+    #[test]
+    fn test_crc32_hardened_check_value() {
+        //Valore di controllo standard del CRC32
+        let data = Hardened::from_vec(b"123456789".to_vec());
+        assert_eq!(crc32(&data).unwrap().inner().unwrap(), 0xCBF43926);
+    }
+
+    #[test]
+    fn test_crc32_hardened_empty() {
+        assert_eq!(crc32(&[]).unwrap().inner().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_crc32_hardened_byte_fail() {
+        let mut data = Hardened::from_vec(b"fault".to_vec());
+        data[2]["cp1"] ^= 1 << 3;
+        assert!(matches!(crc32(&data), Err(IncoherenceError::InnerFail)));
+    }
+}
@@ -0,0 +1,46 @@
+mod tests;
+use crate::hardened::*;
+pub fn dijkstra(graph: &[Vec<(usize, i32)>], source: usize) -> Result<Vec<Hardened<i32>>, IncoherenceError> {
+    let n = Hardened::from(graph.len());
+    let mut dist = vec![Hardened::from(i32::MAX); n.inner()?];
+    let mut visited = vec![Hardened::from(false); n.inner()?];
+    //Coda di priorità: coppie (distanza, nodo) memorizzate in due vettori paralleli
+    let mut pq_dist: Vec<Hardened<i32>> = Vec::new();
+    let mut pq_node: Vec<Hardened<usize>> = Vec::new();
+
+    dist[source].assign(Hardened::from(0))?;
+    pq_dist.push(Hardened::from(0));
+    pq_node.push(Hardened::from(source));
+    while !pq_node.is_empty() {
+        //Estrazione del minimo
+        let mut best = Hardened::from(0);
+        let mut idx = Hardened::from(1);
+        while idx < Hardened::from(pq_node.len()) {
+            if pq_dist[idx] < pq_dist[best] {
+                best.assign(idx)?;
+            }
+            idx.assign((idx + 1)?)?;
+        }
+        let mut d = Hardened::from(0);
+        d.assign(pq_dist.swap_remove(best.inner()?))?;
+        let mut u = Hardened::from(0);
+        u.assign(pq_node.swap_remove(best.inner()?))?;
+
+        if !visited[u].inner()? {
+            visited[u].assign(Hardened::from(true))?;
+            let mut e = Hardened::from(0);
+            while e < Hardened::from(graph[u.inner()?].len()) {
+                let (v, w) = graph[u.inner()?][e.inner()?];
+                let v = Hardened::from(v);
+                let alt = (d + Hardened::from(w))?;
+                if alt < dist[v] {
+                    dist[v].assign(alt)?;
+                    pq_dist.push(alt);
+                    pq_node.push(v);
+                }
+                e.assign((e + 1)?)?;
+            }
+        }
+    }
+    Ok(dist)
+}
@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod tests {
+    use crate::hardened::Hardened;
+    use crate::hardened::dijkstra_hardened::dijkstra;
+
+    fn graph() -> Vec<Vec<(usize, i32)>> {
+        vec![
+            vec![(1, 4), (2, 1)],
+            vec![(3, 1)],
+            vec![(1, 2), (3, 5)],
+            vec![(4, 3)],
+            vec![],
+            vec![(0, 1)],
+        ]
+    }
+
+    #[test]
+    fn test_dijkstra_hardened() {
+        let dist = dijkstra(&graph(), 0).unwrap();
+        assert_eq!(dist, Hardened::from_vec(vec![0, 3, 1, 4, 7, i32::MAX]));
+    }
+
+    #[test]
+    fn test_dijkstra_hardened_other_source() {
+        let dist = dijkstra(&graph(), 5).unwrap();
+        assert_eq!(dist, Hardened::from_vec(vec![1, 4, 2, 5, 8, 0]));
+    }
+
+}
@@ -0,0 +1,16 @@
+mod tests;
+use crate::hardened::*;
+//I kernel numerici sono generici sul tipo dell'elemento: possono essere usati sia con
+//Hardened<f32> che con Hardened<f64>
+pub fn dot_product<T>(x: &[Hardened<T>], y: &[Hardened<T>]) -> Result<Hardened<T>, IncoherenceError>
+where T: Coherent+Debug+Default+Add<Output=T>+Mul<Output=T>{
+    let n = Hardened::from(x.len());
+    let mut acc = Hardened::from(T::default());
+    let mut i = Hardened::from(0);
+
+    while i < n {
+        acc.assign((acc + (x[i.inner()?] * y[i.inner()?])?)?)?;
+        i.assign((i + 1)?)?;
+    }
+    Ok(acc)
+}
@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod tests {
+    use crate::hardened::{Hardened, IncoherenceError};
+    use crate::hardened::dot_product_hardened::dot_product;
+
+    #[test]
+    fn test_dot_product_hardened_f64() {
+        let x = Hardened::from_vec(vec![1.5, -2.0, 0.25, 4.0]);
+        let y = Hardened::from_vec(vec![2.0, 0.5, 8.0, -1.0]);
+        assert_eq!(dot_product(&x, &y).unwrap().inner().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_dot_product_hardened_f32() {
+        let x = Hardened::from_vec(vec![1.0f32, 2.0, 3.0]);
+        let y = Hardened::from_vec(vec![4.0f32, 5.0, 6.0]);
+        assert_eq!(dot_product(&x, &y).unwrap().inner().unwrap(), 32.0);
+    }
+
+    #[test]
+    fn test_dot_product_hardened_sign_flip() {
+        //Il flip del bit di segno su 0.0 produce -0.0: per l'uguaglianza IEEE i due valori
+        //coincidono, ma il confronto sui bit rileva l'incoerenza
+        let mut x = Hardened::from_vec(vec![0.0f64, 1.0]);
+        let y = Hardened::from_vec(vec![3.0f64, 1.0]);
+        x[0]["cp1"] = f64::from_bits(0.0f64.to_bits() ^ (1 << 63));
+        assert!(matches!(dot_product(&x, &y), Err(IncoherenceError::MulFail)));
+    }
+}
@@ -0,0 +1,22 @@
+mod tests;
+use crate::hardened::*;
+pub fn fir_filter<T>(x: &[Hardened<T>], h: &[Hardened<T>]) -> Result<Vec<Hardened<T>>, IncoherenceError>
+where T: Coherent+Debug+Default+Add<Output=T>+Mul<Output=T>{
+    let n = Hardened::from(x.len());
+    let m = Hardened::from(h.len());
+    let mut y = vec![Hardened::from(T::default()); n.inner()?];
+    let mut i = Hardened::from(0);
+
+    while i < n {
+        let mut acc = Hardened::from(T::default());
+        let mut k = Hardened::from(0);
+        while k < m && k <= i {
+            let sample = x[(i - k)?.inner()?];
+            acc.assign((acc + (h[k.inner()?] * sample)?)?)?;
+            k.assign((k + 1)?)?;
+        }
+        y[i].assign(acc)?;
+        i.assign((i + 1)?)?;
+    }
+    Ok(y)
+}
@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod tests {
+    use crate::hardened::Hardened;
+    use crate::hardened::fir_filter_hardened::fir_filter;
+
+    #[test]
+    fn test_fir_filter_hardened() {
+        //Media mobile su due campioni
+        let x = Hardened::from_vec(vec![2.0, 4.0, 6.0, 8.0]);
+        let h = Hardened::from_vec(vec![0.5, 0.5]);
+        let y = fir_filter(&x, &h).unwrap();
+        assert_eq!(y, Hardened::from_vec(vec![1.0, 3.0, 5.0, 7.0]));
+    }
+
+    #[test]
+    fn test_fir_filter_hardened_impulse() {
+        //La risposta all'impulso riproduce i coefficienti del filtro
+        let x = Hardened::from_vec(vec![1.0f32, 0.0, 0.0, 0.0, 0.0]);
+        let h = Hardened::from_vec(vec![0.25f32, -0.5, 0.75]);
+        let y = fir_filter(&x, &h).unwrap();
+        assert_eq!(y, Hardened::from_vec(vec![0.25f32, -0.5, 0.75, 0.0, 0.0]));
+    }
+}
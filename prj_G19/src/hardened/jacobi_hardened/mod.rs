@@ -0,0 +1,33 @@
+mod tests;
+use crate::hardened::*;
+///Numero di iterazioni eseguite dal caso di studio (deve coincidere con quello della versione non irrobustita)
+pub const JACOBI_ITERATIONS: usize = 25;
+
+pub fn jacobi<T>(a: &[Vec<Hardened<T>>], b: &[Hardened<T>], iterations: usize) -> Result<Vec<Hardened<T>>, IncoherenceError>
+where T: Coherent+Debug+Default+Add<Output=T>+Sub<Output=T>+Mul<Output=T>+Div<Output=T>{
+    let n = Hardened::from(b.len());
+    let iterations = Hardened::from(iterations);
+    let mut x = vec![Hardened::from(T::default()); n.inner()?];
+    let mut x_new = vec![Hardened::from(T::default()); n.inner()?];
+    let mut it = Hardened::from(0);
+
+    while it < iterations {
+        let mut i = Hardened::from(0);
+        while i < n {
+            let mut sigma = Hardened::from(T::default());
+            let mut j = Hardened::from(0);
+            while j < n {
+                if j != i {
+                    sigma.assign((sigma + (a[i.inner()?][j.inner()?] * x[j])?)?)?;
+                }
+                j.assign((j + 1)?)?;
+            }
+            let diag = a[i.inner()?][i.inner()?];
+            x_new[i].assign(((b[i.inner()?] - sigma)? / diag)?)?;
+            i.assign((i + 1)?)?;
+        }
+        x.clone_from(&x_new);
+        it.assign((it + 1)?)?;
+    }
+    Ok(x)
+}
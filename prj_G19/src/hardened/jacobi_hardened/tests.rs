@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod tests {
+    use crate::hardened::{Hardened, IntoNestedVec};
+    use crate::hardened::jacobi_hardened::jacobi;
+
+    #[test]
+    fn test_jacobi_hardened() {
+        //Sistema a diagonale dominante con soluzione (1, 2, -1)
+        let a = Hardened::from_mat(vec![vec![4.0, 1.0, 1.0], vec![1.0, 5.0, 2.0], vec![1.0, 2.0, 6.0]]);
+        let b = Hardened::from_vec(vec![5.0, 9.0, -1.0]);
+        let x: Vec<f64> = jacobi(&a, &b, 50).unwrap().into_nested_vec();
+        for (xi, expected) in x.iter().zip([1.0, 2.0, -1.0]) {
+            assert!((xi - expected).abs() < 1e-9, "{:?}", x);
+        }
+    }
+
+    #[test]
+    fn test_jacobi_hardened_f32() {
+        let a = Hardened::from_mat(vec![vec![2.0f32, 0.0], vec![0.0, 4.0]]);
+        let b = Hardened::from_vec(vec![1.0f32, 1.0]);
+        let x = jacobi(&a, &b, 1).unwrap();
+        assert_eq!(x, Hardened::from_vec(vec![0.5f32, 0.25]));
+    }
+}
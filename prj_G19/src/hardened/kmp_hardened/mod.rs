@@ -0,0 +1,42 @@
+mod tests;
+use crate::hardened::*;
+///Restituisce le posizioni di tutte le occorrenze (anche sovrapposte) di 'pattern' in 'text'
+pub fn kmp(text: &[Hardened<u8>], pattern: &[Hardened<u8>]) -> Result<Vec<Hardened<usize>>, IncoherenceError> {
+    let n = Hardened::from(text.len());
+    let m = Hardened::from(pattern.len());
+    if m == Hardened::from(0) {
+        return Ok(Vec::new());
+    }
+    let mut fail = vec![Hardened::from(0); m.inner()?];
+    let mut k = Hardened::from(0);
+    let mut q = Hardened::from(1);
+
+    while q < m {
+        while k > Hardened::from(0) && pattern[k.inner()?] != pattern[q.inner()?] {
+            k.assign(fail[(k - 1)?])?;
+        }
+        if pattern[k.inner()?] == pattern[q.inner()?] {
+            k.assign((k + 1)?)?;
+        }
+        fail[q].assign(k)?;
+        q.assign((q + 1)?)?;
+    }
+
+    let mut matches: Vec<Hardened<usize>> = Vec::new();
+    let mut j = Hardened::from(0);
+    let mut i = Hardened::from(0);
+    while i < n {
+        while j > Hardened::from(0) && pattern[j.inner()?] != text[i.inner()?] {
+            j.assign(fail[(j - 1)?])?;
+        }
+        if pattern[j.inner()?] == text[i.inner()?] {
+            j.assign((j + 1)?)?;
+        }
+        if j == m {
+            matches.push(((i + 1)? - m)?);
+            j.assign(fail[(j - 1)?])?;
+        }
+        i.assign((i + 1)?)?;
+    }
+    Ok(matches)
+}
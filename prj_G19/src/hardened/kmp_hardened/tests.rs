@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use crate::hardened::{Hardened, IntoNestedVec};
+    use crate::hardened::kmp_hardened::kmp;
+
+    #[test]
+    fn test_kmp_hardened() {
+        let text = Hardened::from_vec(b"abababcabab".to_vec());
+        let pattern = Hardened::from_vec(b"abab".to_vec());
+        //le occorrenze sovrapposte sono tutte riportate
+        assert_eq!(kmp(&text, &pattern).unwrap().into_nested_vec(), vec![0, 2, 7]);
+    }
+
+    #[test]
+    fn test_kmp_hardened_no_match() {
+        let text = Hardened::from_vec(b"aaaa".to_vec());
+        assert!(kmp(&text, &Hardened::from_vec(b"ab".to_vec())).unwrap().is_empty());
+        assert!(kmp(&text, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "PartialEq::eq")]
+    fn test_kmp_hardened_text_fail() {
+        //il carattere corrotto del testo è il secondo operando del confronto con il pattern
+        let mut text = Hardened::from_vec(b"needle".to_vec());
+        let pattern = Hardened::from_vec(b"dle".to_vec());
+        text[3]["cp1"] ^= 1;
+        let _ = kmp(&text, &pattern);
+    }
+
+    #[test]
+    fn test_kmp_hardened_fallback() {
+        //dopo il mismatch su 'a' != 'b' la ricerca riparte da fail[1] = 1 senza tornare indietro nel testo
+        let text = Hardened::from_vec(b"aaab".to_vec());
+        let pattern = Hardened::from_vec(b"aab".to_vec());
+        assert_eq!(kmp(&text, &pattern).unwrap().into_nested_vec(), vec![1]);
+        assert!(kmp(&[], &pattern).unwrap().is_empty());
+    }
+}
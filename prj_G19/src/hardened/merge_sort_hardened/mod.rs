@@ -0,0 +1,49 @@
+mod tests;
+use crate::hardened::*;
+pub fn merge_sort(vet: &mut Vec<Hardened<i32>>) -> Result<(), IncoherenceError> {
+    let n = Hardened::from(vet.len());
+    if n < Hardened::from(2) {
+        return Ok(());
+    }
+    let mut aux = vet.clone();
+    let mut depth = Hardened::from(0);
+    merge_sort_rec(vet, &mut aux, Hardened::from(0), (n - 1)?, &mut depth, n)
+}
+
+fn merge_sort_rec(vet: &mut Vec<Hardened<i32>>, aux: &mut Vec<Hardened<i32>>, lo: Hardened<usize>,
+                  hi: Hardened<usize>, depth: &mut Hardened<usize>, max_depth: Hardened<usize>)
+    -> Result<(), IncoherenceError> {
+    depth.assign((*depth + 1)?)?;
+    if *depth > max_depth {
+        return Err(IncoherenceError::DepthFail);
+    }
+
+    if lo < hi {
+        //Hardened<usize> non implementa la divisione: si calcola sui valori controllati
+        let mid = Hardened::from((lo.inner()? + hi.inner()?) / 2);
+        merge_sort_rec(vet, aux, lo, mid, depth, max_depth)?;
+        merge_sort_rec(vet, aux, (mid + 1)?, hi, depth, max_depth)?;
+
+        let mut i = lo;
+        let mut j = (mid + 1)?;
+        let mut k = lo;
+        while k <= hi {
+            if j > hi || (i <= mid && vet[i] <= vet[j]) {
+                aux[k].assign(vet[i])?;
+                i.assign((i + 1)?)?;
+            } else {
+                aux[k].assign(vet[j])?;
+                j.assign((j + 1)?)?;
+            }
+            k.assign((k + 1)?)?;
+        }
+        k.assign(lo)?;
+        while k <= hi {
+            vet[k].assign(aux[k])?;
+            k.assign((k + 1)?)?;
+        }
+    }
+
+    depth.assign((*depth - 1)?)?;
+    Ok(())
+}
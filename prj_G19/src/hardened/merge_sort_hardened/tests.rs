@@ -0,0 +1,22 @@
+#[cfg(test)]
+mod tests {
+    use crate::hardened::{Hardened, IncoherenceError};
+    use crate::hardened::merge_sort_hardened::merge_sort;
+    #[test]
+    fn test_merge_sort_hardened() {
+        let mut vec = Hardened::from_vec(vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32]);
+
+        assert!(merge_sort(&mut vec).is_ok());
+
+        let sorted_vec = Hardened::from_vec(vec![-32, -9, 1, 3, 10, 15, 16, 19, 20, 27]);
+        assert_eq!(vec, sorted_vec);
+    }
+
+    #[test]
+    fn test_merge_sort_hardened_assign_fail() {
+        let mut vec = Hardened::from_vec(vec![5, 1, 4, 2]);
+        vec[0]["cp1"] = 9;          //Injection: l'elemento viene copiato in aux prima di essere letto
+
+        assert!(matches!(merge_sort(&mut vec), Err(IncoherenceError::AssignFail)));
+    }
+}
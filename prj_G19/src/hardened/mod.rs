@@ -1,12 +1,21 @@
 pub(crate) mod bubble_sort_hardened;
 pub(crate) mod matrix_multiplication_hardened;
 pub(crate) mod selection_sort_hardened;
+pub(crate) mod quick_sort_hardened;
+pub(crate) mod merge_sort_hardened;
+pub(crate) mod bfs_hardened;
+pub(crate) mod dijkstra_hardened;
+pub(crate) mod dot_product_hardened;
+pub(crate) mod fir_filter_hardened;
+pub(crate) mod jacobi_hardened;
+pub(crate) mod crc32_hardened;
+pub(crate) mod kmp_hardened;
+pub(crate) mod par_matrix_multiplication_hardened;
 
 use std::cmp::Ordering;
 use std::fmt::{Debug, Formatter};
-use std::ops::{Add, Index, IndexMut, Sub, Mul};
+use std::ops::{Add, Index, IndexMut, Sub, Mul, Div, BitAnd, BitXor, Shr};
 use thiserror::Error;
-use crate::fault_env::Data;
 
 //-------------------------------------------------------------
 #[derive(Clone, Copy)]
@@ -20,14 +29,38 @@ pub struct Hardened<T>{
     cp2: T,
 }
 
+///Tipi di cui si può controllare la coerenza delle due copie. Il confronto è fatto sulla
+/// rappresentazione in bit: per i reali l'uguaglianza IEEE non basta, perché NaN != NaN
+/// segnalerebbe un fault inesistente e 0.0 == -0.0 nasconderebbe il flip del bit di segno.
+pub trait Coherent: Copy {
+    fn same_bits(&self, other: &Self) -> bool;
+}
+
+macro_rules! impl_coherent_eq {
+    ($($t:ty),*) => {
+        $(impl Coherent for $t {
+            fn same_bits(&self, other: &Self) -> bool { self == other }
+        })*
+    };
+}
+impl_coherent_eq!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, bool);
+
+impl Coherent for f32 {
+    fn same_bits(&self, other: &Self) -> bool { self.to_bits() == other.to_bits() }
+}
+
+impl Coherent for f64 {
+    fn same_bits(&self, other: &Self) -> bool { self.to_bits() == other.to_bits() }
+}
+
 impl<T> Hardened<T>
-where T: Debug+PartialEq+Eq+Copy+Clone{
+where T: Debug+Coherent{
     ///Controllo di coerenza: si controlla che le due copie del valore della
     /// variabile siano uguali. E' la funzione utilizzata affinché venga rispettata
     /// la 'Regola 3' secondo cui ogni lettura deve essere preceduta dal controllo delle
     /// due copie, nel caso in cui questo fallisse, è stato trovato un fault!
     fn incoherent(&self)->bool{
-        self.cp1 != self.cp2
+        !self.cp1.same_bits(&self.cp2)
     }
 
     /// L'operazione di assegnazione non può essere ridefinita (cioè non posso ridefinire '='
@@ -72,11 +105,11 @@ impl<T> From<T> for Hardened<T> where T:Copy{
     }
 }
 
-pub trait IntoNestedVec {
-    fn into_nested_vec(self) -> Vec<i32>;
+pub trait IntoNestedVec<T> {
+    fn into_nested_vec(self) -> Vec<T>;
 }
-impl IntoNestedVec for Vec<Hardened<i32>> {
-    fn into_nested_vec(self) -> Vec<i32> {
+impl<T: Copy> IntoNestedVec<T> for Vec<Hardened<T>> {
+    fn into_nested_vec(self) -> Vec<T> {
         self.into_iter()
             .map(|hardened| hardened.cp1) // Estrai solo il campo cp1
             .collect()
@@ -88,7 +121,7 @@ impl IntoNestedVec for Vec<Hardened<i32>> {
 //di tipo IncoherenceError, implementato usando il crate thiserror.
 // a = b+c
 impl<T> Add for Hardened<T>
-where T: Add<Output=T>+PartialEq+Eq+Debug+Copy+Clone{
+where T: Add<Output=T>+Coherent+Debug{
     type Output = Result<Hardened<T>, IncoherenceError>;
     fn add(self, rhs: Self) -> Self::Output {
         if self.incoherent() || rhs.incoherent(){
@@ -115,7 +148,7 @@ impl Add<usize> for Hardened<usize>{
 }
 
 impl<T> Sub for Hardened<T>
-where T:Sub<Output=T>+PartialEq+Eq+Debug+Copy+Clone{
+where T:Sub<Output=T>+Coherent+Debug{
     type Output=Result<Hardened<T>,IncoherenceError>;
     fn sub(self, rhs: Self) -> Self::Output {
         if self.incoherent() || rhs.incoherent(){
@@ -143,7 +176,7 @@ impl Sub<usize> for Hardened<usize>{
 
 // Mul per Hardened<T> per supportare la moltiplicazione elementare
 impl<T> Mul for Hardened<T>
-where T: Mul<Output = T> + PartialEq + Eq + Debug + Copy + Clone {
+where T: Mul<Output = T> + Coherent + Debug {
     type Output = Result<Hardened<T>, IncoherenceError>;
 
     fn mul(self, rhs: Self) -> Self::Output {
@@ -158,11 +191,80 @@ where T: Mul<Output = T> + PartialEq + Eq + Debug + Copy + Clone {
     }
 }
 
+impl<T> Div for Hardened<T>
+where T: Div<Output = T> + Coherent + Debug {
+    type Output = Result<Hardened<T>, IncoherenceError>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        if self.incoherent() || rhs.incoherent() {
+            return Err(IncoherenceError::DivFail);
+        }
+
+        Ok(Self {
+            cp1: self.cp1 / rhs.cp1,
+            cp2: self.cp2 / rhs.cp2,
+        })
+    }
+}
+
+//------------------------------------------------------------------------
+
+//---------------------OPERAZIONI BIT A BIT-------------------------------
+//Usate dai casi di studio che lavorano su byte e parole (es. CRC32)
+impl<T> BitXor for Hardened<T>
+where T: BitXor<Output = T> + Coherent + Debug {
+    type Output = Result<Hardened<T>, IncoherenceError>;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        if self.incoherent() || rhs.incoherent() {
+            return Err(IncoherenceError::BitFail);
+        }
+
+        Ok(Self {
+            cp1: self.cp1 ^ rhs.cp1,
+            cp2: self.cp2 ^ rhs.cp2,
+        })
+    }
+}
+
+impl<T> BitAnd for Hardened<T>
+where T: BitAnd<Output = T> + Coherent + Debug {
+    type Output = Result<Hardened<T>, IncoherenceError>;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        if self.incoherent() || rhs.incoherent() {
+            return Err(IncoherenceError::BitFail);
+        }
+
+        Ok(Self {
+            cp1: self.cp1 & rhs.cp1,
+            cp2: self.cp2 & rhs.cp2,
+        })
+    }
+}
+
+//Lo scorrimento è sempre di una quantità costante: il secondo operando non è irrobustito
+impl<T> Shr<u32> for Hardened<T>
+where T: Shr<u32, Output = T> + Coherent + Debug {
+    type Output = Result<Hardened<T>, IncoherenceError>;
+
+    fn shr(self, rhs: u32) -> Self::Output {
+        if self.incoherent() {
+            return Err(IncoherenceError::BitFail);
+        }
+
+        Ok(Self {
+            cp1: self.cp1 >> rhs,
+            cp2: self.cp2 >> rhs,
+        })
+    }
+}
+
 //------------------------------------------------------------------------
 
 //------------------------OPERAZIONI DI CONFRONTO-------------------------
 impl<T> PartialEq for Hardened<T>
-where T:PartialEq+Eq+Debug+Copy+Clone{
+where T:PartialEq+Coherent+Debug{
     fn eq(&self, other: &Self) -> bool {
         if  other.incoherent(){
             panic!("PartialEq::eq")
@@ -172,10 +274,10 @@ where T:PartialEq+Eq+Debug+Copy+Clone{
 }
 
 impl<T> Eq for Hardened<T>
-where T:PartialEq+Eq+Debug+Copy+Clone{      }
+where T:Eq+Coherent+Debug{      }
 
 impl<T> PartialOrd for Hardened<T>
-where T:PartialEq+PartialOrd+Eq+Debug+Copy+Clone{
+where T:PartialOrd+Coherent+Debug{
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         if other.incoherent(){
             panic!("PartialOrd::partial_cmp")
@@ -185,7 +287,7 @@ where T:PartialEq+PartialOrd+Eq+Debug+Copy+Clone{
 }
 
 impl<T> Ord for Hardened<T>
-where T:PartialEq+PartialOrd+Ord+Eq+Debug+Copy+Clone{
+where T:Ord+Coherent+Debug{
     fn cmp(&self, other: &Self) -> Ordering {
         if other.incoherent(){
             panic!("Ord::cmp");
@@ -238,7 +340,7 @@ impl<T> IndexMut<&str> for Hardened<T>{
 
 //Per poter stampare il tipo Hardened<T> con la macro println!() e il
 // modificatore {:?}
-impl<T> Debug for Hardened<T> where T:Debug+PartialEq+Eq+Copy+Clone{
+impl<T> Debug for Hardened<T> where T:Debug+Coherent{
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         if self.incoherent(){
             panic!("Debug::fmt");
@@ -263,6 +365,10 @@ pub enum IncoherenceError{
     SubFail,
     #[error("IncoherenceError::MulFail: due to incoherence mul failed")]
     MulFail,
+    #[error("IncoherenceError::DivFail: due to incoherence div failed")]
+    DivFail,
+    #[error("IncoherenceError::BitFail: due to incoherence bitwise op failed")]
+    BitFail,
     #[error("IncoherenceError::IndexMutFail ")]
     IndexMutFail,
     #[error("IncoherenceError::IndexFail ")]
@@ -275,124 +381,18 @@ pub enum IncoherenceError{
     PartialEqFail,
     #[error("IncoherenceError::InnerFail")]
     InnerFail,
+    #[error("IncoherenceError::DepthFail: recursion depth limit exceeded")]
+    DepthFail,
 }
 
-//Funzioni per il conteggio 'passivo' delle istruzioni eseguite
-
-pub fn run_for_count_selection_sort(vettore: Data<i32>) ->usize{
-
-    let mut vet = vettore.into_vector();
-    let n:usize = vet.len();
-    let mut j;
-    let mut min;
-
-    let mut count=5;
-    //-----------------------SELECTION SORT-------------------------
-    let mut i=0;
-    while i< n -1{
-        count=count+1;
-        min=i;
-        count=count+1;
-        j=i+1;
-        //Ricerca del minimo
-        count=count+1;
-        while j< n {
-            count=count+1;
-            if vet[j] < vet[min]{
-                count=count+1;
-                min=j;  }
-            count=count+1;
-            j = j+1;
-        }
-        count=count+1;
-        //Scambio il minimo
-        vet.swap(min,i);
-        //Vado avanti
-        count=count+1;
-        i=i+1;
-
-        //conto il while di dopo (se necessario)
-        if i< n -1 {count=count+1}
-    }
-    count
-}
-pub fn run_for_count_bubble_sort(vettore: Data<i32>) ->usize{
-    let mut count=2;
-    let mut vet=vettore.into_vector();
-    let n = vet.len();
-    let mut i = 0;
-
-    while i < n {
-        count+=1;
-        let mut swapped = false;
-        count+=1;
-        let mut j = 0;
-        count+=1;
-
-        while j < n - i - 1 {
-            count+=1;
-            if vet[j] > vet[j + 1] {
-                count+=1;
-                vet.swap(j, j + 1);
-                count+=1;
-                swapped = true;
-                count+=1;
-            }
-            j += 1;
-            count+=1;
-        }
-        if !swapped {
-            count+=1;
-            break;
-        }
-        i += 1;
-        count+=1;
-    }
-    count
-}
-//a: &Vec<Vec<i32>>, b: &Vec<Vec<i32>>
-pub fn run_for_count_matrix_mul(matrici: Data<i32>, size:usize)->usize{
-
-    let matrices = matrici.into_matrices();
-    let a = matrices.0;
-    let b = matrices.1;
-
-    let mut result: Vec<Vec<i32>> = Vec::new();
-    let mut count=3;
-    for i in 0..size {
-        count+=1;
-        let mut row: Vec<i32> = Vec::new(); // Crea una nuova riga
-        count+=1;
-        for j in 0..size {
-            count+=1;
-            let mut acc = 0;
-            count+=1;
-            for k in 0..size {
-                count+=1;
-                acc += a[i][k] * b[k][j];
-                count+=1;
-            }
-            row.push(acc); // Aggiunge il valore calcolato alla riga
-            count+=1;
-        }
-        result.push(row); // Aggiunge la riga alla matrice risultante
-        count+=1;
-    }
-    //result
-    count
-}
-
-
-
-
 //------------------------------------------------------
 
 #[cfg(test)]
 mod tests{
     use std::panic::catch_unwind;
-    use crate::Hardened;
+    use crate::hardened::Hardened;
 
-    use crate::IncoherenceError;
+    use crate::hardened::IncoherenceError;
     #[test]
     fn test_add_ok(){
         //Arrange
@@ -426,6 +426,23 @@ mod tests{
         assert!(ris.is_ok());
         assert_eq!(ris.unwrap().inner().unwrap(), 9);
     }
+    #[test]
+    fn test_bit_ops(){
+        let a = Hardened::from(0xF0F0u32);
+        let b = Hardened::from(0x0FF0u32);
+        assert_eq!((a ^ b).unwrap().inner().unwrap(), 0xFF00);
+        assert_eq!((a & b).unwrap().inner().unwrap(), 0x00F0);
+        assert_eq!((a >> 4).unwrap().inner().unwrap(), 0x0F0F);
+    }
+    #[test]
+    fn test_bit_ops_err(){
+        let mut a = Hardened::from(0xFFu8);
+        let b = Hardened::from(0x0Fu8);
+        a["cp1"] ^= 1 << 7;      //Injection
+        assert!(matches!(a ^ b, Err(IncoherenceError::BitFail)));
+        assert!(matches!(b & a, Err(IncoherenceError::BitFail)));
+        assert!(matches!(a >> 1, Err(IncoherenceError::BitFail)));
+    }
 
     #[test]
     //Test per verificare il corretto funzionamento di from_mat
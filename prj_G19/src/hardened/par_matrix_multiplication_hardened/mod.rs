@@ -0,0 +1,58 @@
+mod tests;
+use std::sync::Mutex;
+use std::thread;
+use crate::hardened::*;
+///Numero di thread worker usati dalla moltiplicazione parallela
+pub const PAR_WORKERS: usize = 3;
+
+///Moltiplicazione tra matrici parallela per righe: ogni worker preleva la prossima riga libera
+/// dal contatore condiviso e la scrive nella matrice risultato condivisa.
+/// In caso di incoerenza viene restituito il primo errore nell'ordine dei worker.
+pub fn par_matrix_multiplication(a: &[Vec<Hardened<i32>>], b: &[Vec<Hardened<i32>>], workers: usize) -> Result<Vec<Vec<Hardened<i32>>>, IncoherenceError> {
+    let size = Hardened::from(a.len());
+    let result = Mutex::new(vec![vec![Hardened::from(0); size.inner()?]; size.inner()?]);
+    let next_row = Mutex::new(Hardened::from(0));
+
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| s.spawn(|| worker(a, b, &result, &next_row)))
+            .collect();
+        handles.into_iter().try_for_each(|h| h.join().unwrap())
+    })?;
+
+    //La lettura finale della matrice condivisa è fatta da un thread diverso da chi l'ha scritta
+    let result = result.into_inner().unwrap();
+    for row in &result {
+        for el in row {
+            el.inner()?;
+        }
+    }
+    Ok(result)
+}
+
+fn worker(a: &[Vec<Hardened<i32>>], b: &[Vec<Hardened<i32>>],
+          result: &Mutex<Vec<Vec<Hardened<i32>>>>, next_row: &Mutex<Hardened<usize>>) -> Result<(), IncoherenceError> {
+    let size = Hardened::from(a.len());
+    loop {
+        let row = {
+            let mut next = next_row.lock().unwrap();
+            let row = Hardened::from(next.inner()?);
+            if row >= size {
+                return Ok(());
+            }
+            next.assign((row + 1)?)?;
+            row
+        };
+        let mut j = Hardened::from(0);
+        while j < size {
+            let mut acc = Hardened::from(0);
+            let mut k = Hardened::from(0);
+            while k < size {
+                acc.assign((acc + (a[row.inner()?][k.inner()?] * b[k.inner()?][j.inner()?])?)?)?;
+                k.assign((k + 1)?)?;
+            }
+            result.lock().unwrap()[row.inner()?][j.inner()?].assign(acc)?;
+            j.assign((j + 1)?)?;
+        }
+    }
+}
@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod tests {
+    use crate::hardened::{Hardened, IncoherenceError};
+    use crate::hardened::par_matrix_multiplication_hardened::{par_matrix_multiplication, PAR_WORKERS};
+    use crate::hardened::matrix_multiplication_hardened::matrix_multiplication;
+
+    #[test]
+    fn test_par_matrix_multiplication_hardened() {
+        let a = Hardened::from_mat(vec![vec![1, 2, 3, 1], vec![4, 5, 6, 1], vec![7, 8, 9, 1], vec![1, 0, 1, 1]]);
+        let b = Hardened::from_mat(vec![vec![1, 0, 2, 1], vec![0, 1, 0, 3], vec![2, 1, 1, 0], vec![-1, 4, 0, 2]]);
+        let expected = matrix_multiplication(&a, &b).unwrap();
+        //con qualunque numero di worker (anche maggiore del numero di righe) il risultato non cambia
+        for workers in [1, 2, PAR_WORKERS, 8] {
+            assert_eq!(par_matrix_multiplication(&a, &b, workers).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_par_matrix_multiplication_hardened_shared_fail() {
+        let a = Hardened::from_mat(vec![vec![1, 2], vec![3, 4]]);
+        let mut b = Hardened::from_mat(vec![vec![5, 6], vec![7, 8]]);
+        //b è letta da tutti i worker: il primo che la usa rileva l'incoerenza
+        b[1][0]["cp1"] ^= 1 << 4;
+        assert!(matches!(par_matrix_multiplication(&a, &b, PAR_WORKERS), Err(IncoherenceError::MulFail)));
+    }
+}
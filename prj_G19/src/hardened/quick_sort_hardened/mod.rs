@@ -0,0 +1,42 @@
+mod tests;
+use crate::hardened::*;
+pub fn quick_sort(vet: &mut Vec<Hardened<i32>>) -> Result<(), IncoherenceError> {
+    let n = Hardened::from(vet.len());
+    if n < Hardened::from(2) {
+        return Ok(());
+    }
+    //La profondità della ricorsione è anch'essa una variabile irrobustita:
+    //nel caso peggiore il quick sort annida al più n chiamate
+    let mut depth = Hardened::from(0);
+    quick_sort_rec(vet, Hardened::from(0), (n - 1)?, &mut depth, n)
+}
+
+fn quick_sort_rec(vet: &mut Vec<Hardened<i32>>, lo: Hardened<usize>, hi: Hardened<usize>,
+                  depth: &mut Hardened<usize>, max_depth: Hardened<usize>) -> Result<(), IncoherenceError> {
+    depth.assign((*depth + 1)?)?;
+    if *depth > max_depth {
+        return Err(IncoherenceError::DepthFail);
+    }
+
+    let pivot = vet[hi];
+    let mut i = lo;
+    let mut j = lo;
+
+    while j < hi {
+        if vet[j] < pivot {
+            vet.swap(i.inner()?, j.inner()?);
+            i.assign((i + 1)?)?;
+        }
+        j.assign((j + 1)?)?;
+    }
+    vet.swap(i.inner()?, hi.inner()?);
+    if i > (lo + 1)? {
+        quick_sort_rec(vet, lo, (i - 1)?, depth, max_depth)?;
+    }
+    if (i + 1)? < hi {
+        quick_sort_rec(vet, (i + 1)?, hi, depth, max_depth)?;
+    }
+
+    depth.assign((*depth - 1)?)?;
+    Ok(())
+}
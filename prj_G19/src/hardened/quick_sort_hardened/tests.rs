@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod tests {
+    use crate::hardened::Hardened;
+    use crate::hardened::quick_sort_hardened::quick_sort;
+    #[test]
+    fn test_quick_sort_hardened() {
+        let mut vec = Hardened::from_vec(vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32]);
+
+        assert!(quick_sort(&mut vec).is_ok());
+
+        let sorted_vec = Hardened::from_vec(vec![-32, -9, 1, 3, 10, 15, 16, 19, 20, 27]);
+        assert_eq!(vec, sorted_vec);
+    }
+
+    #[test]
+    fn test_quick_sort_hardened_already_sorted() {
+        //Caso peggiore: la ricorsione raggiunge la profondità massima
+        let mut vec = Hardened::from_vec(vec![1, 2, 3, 4, 5, 6]);
+        assert!(quick_sort(&mut vec).is_ok());
+        assert_eq!(vec, Hardened::from_vec(vec![1, 2, 3, 4, 5, 6]));
+    }
+
+    #[test]
+    #[should_panic(expected = "PartialOrd::partial_cmp")]
+    fn test_quick_sort_hardened_pivot_fail() {
+        let mut vec = Hardened::from_vec(vec![4, 3, 2, 1]);
+        vec[3]["cp2"] = 7;          //Injection sul pivot della prima chiamata
+        let _ = quick_sort(&mut vec);
+    }
+}
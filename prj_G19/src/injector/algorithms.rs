@@ -1,6 +1,15 @@
-use std::sync::mpsc::{Receiver, Sender};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::TryLockError;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use crate::plugin::PluginInstance;
 use crate::hardened::{Hardened, IncoherenceError};
-use crate::injector::{BubbleSortVariables, MatrixMultiplicationVariables, SelectionSortVariables};
+use crate::injector::{BubbleSortVariables, MatrixMultiplicationVariables, SelectionSortVariables,
+                      QuickSortVariables, QuickSortFrame, MergeSortVariables, MergeSortFrame,
+                      BfsVariables, DijkstraVariables, DotProductVariables, FirFilterVariables, JacobiVariables,
+                      Crc32Variables, KmpVariables, ParMatrixMultiplicationVariables, Actor};
+use crate::hardened::jacobi_hardened::JACOBI_ITERATIONS;
+use crate::hardened::crc32_hardened::CRC32_POLY;
 
 pub fn runner_selection_sort(variables: &SelectionSortVariables, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<i32>>, IncoherenceError> {
 
@@ -262,23 +271,1064 @@ pub fn runner_matrix_multiplication(variables: &MatrixMultiplicationVariables, t
     Ok(variables.result.read().unwrap().clone().into_iter().clone().flatten().collect::<Vec<Hardened<i32>>>())
 }
 
+pub fn runner_quick_sort(variables: &QuickSortVariables, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<i32>>, IncoherenceError> {
+    *variables.n.write().unwrap() = Hardened::from(variables.vet.read().unwrap().len());
+    tx_runner.send("i1").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.depth.write().unwrap() = Hardened::from(0);
+    tx_runner.send("i2").unwrap();
+    rx_runner.recv().unwrap();
+
+    if *variables.n.read().unwrap() >= Hardened::from(2) {
+        let hi = (*variables.n.read().unwrap() - 1)?;
+        quick_sort_call(variables, Hardened::from(0), hi, &tx_runner, &rx_runner)?;
+    }
+
+    Ok(variables.vet.read().unwrap().clone())
+}
+
+//Una chiamata ricorsiva del quick sort: il frame con lo, hi e le variabili locali viene messo
+//in cima allo stack all'ingresso e rimosso all'uscita; d è la sua posizione nello stack.
+fn quick_sort_call(variables: &QuickSortVariables, lo: Hardened<usize>, hi: Hardened<usize>,
+                   tx_runner: &Sender<&str>, rx_runner: &Receiver<&str>) -> Result<(), IncoherenceError> {
+    let tmp = (*variables.depth.read().unwrap() + 1)?;
+    variables.depth.write().unwrap().assign(tmp)?;
+    tx_runner.send("i3").unwrap();
+    rx_runner.recv().unwrap();
+
+    if *variables.depth.read().unwrap() > *variables.n.read().unwrap() {
+        return Err(IncoherenceError::DepthFail);
+    }
+
+    let d = variables.frames.read().unwrap().len();
+    variables.frames.write().unwrap().push(QuickSortFrame::new(lo, hi));
+    tx_runner.send("i4").unwrap();
+    rx_runner.recv().unwrap();
+
+    let pivot = variables.vet.read().unwrap()[variables.frame(d).hi];
+    variables.frames.write().unwrap()[d].pivot.assign(pivot)?;
+    tx_runner.send("i5").unwrap();
+    rx_runner.recv().unwrap();
+
+    let tmp = variables.frame(d).lo;
+    variables.frames.write().unwrap()[d].i.assign(tmp)?;
+    tx_runner.send("i6").unwrap();
+    rx_runner.recv().unwrap();
+
+    let tmp = variables.frame(d).lo;
+    variables.frames.write().unwrap()[d].j.assign(tmp)?;
+    tx_runner.send("i7").unwrap();
+    rx_runner.recv().unwrap();
+
+    while variables.frame(d).j < variables.frame(d).hi {
+        tx_runner.send("i8").unwrap();
+        rx_runner.recv().unwrap();
+
+        let frame = variables.frame(d);
+        if variables.vet.read().unwrap()[frame.j] < frame.pivot {
+            tx_runner.send("i9").unwrap();
+            rx_runner.recv().unwrap();
+
+            let frame = variables.frame(d);
+            variables.vet.write().unwrap().swap(frame.i.inner()?, frame.j.inner()?);
+            tx_runner.send("i10").unwrap();
+            rx_runner.recv().unwrap();
+
+            let tmp = (variables.frame(d).i + 1)?;
+            variables.frames.write().unwrap()[d].i.assign(tmp)?;
+            tx_runner.send("i11").unwrap();
+            rx_runner.recv().unwrap();
+        }
+
+        let tmp = (variables.frame(d).j + 1)?;
+        variables.frames.write().unwrap()[d].j.assign(tmp)?;
+        tx_runner.send("i12").unwrap();
+        rx_runner.recv().unwrap();
+    }
+
+    let frame = variables.frame(d);
+    variables.vet.write().unwrap().swap(frame.i.inner()?, frame.hi.inner()?);
+    tx_runner.send("i13").unwrap();
+    rx_runner.recv().unwrap();
+
+    let frame = variables.frame(d);
+    if frame.i > (frame.lo + 1)? {
+        quick_sort_call(variables, frame.lo, (frame.i - 1)?, tx_runner, rx_runner)?;
+    }
+    let frame = variables.frame(d);
+    if (frame.i + 1)? < frame.hi {
+        quick_sort_call(variables, (frame.i + 1)?, frame.hi, tx_runner, rx_runner)?;
+    }
+
+    variables.frames.write().unwrap().pop();
+    tx_runner.send("i14").unwrap();
+    rx_runner.recv().unwrap();
+
+    let tmp = (*variables.depth.read().unwrap() - 1)?;
+    variables.depth.write().unwrap().assign(tmp)?;
+    tx_runner.send("i15").unwrap();
+    rx_runner.recv().unwrap();
+
+    Ok(())
+}
+
+
+pub fn runner_merge_sort(variables: &MergeSortVariables, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<i32>>, IncoherenceError> {
+    *variables.n.write().unwrap() = Hardened::from(variables.vet.read().unwrap().len());
+    tx_runner.send("i1").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.depth.write().unwrap() = Hardened::from(0);
+    tx_runner.send("i2").unwrap();
+    rx_runner.recv().unwrap();
+
+    let tmp = variables.vet.read().unwrap().clone();
+    *variables.aux.write().unwrap() = tmp;
+    tx_runner.send("i3").unwrap();
+    rx_runner.recv().unwrap();
+
+    if *variables.n.read().unwrap() >= Hardened::from(2) {
+        let hi = (*variables.n.read().unwrap() - 1)?;
+        merge_sort_call(variables, Hardened::from(0), hi, &tx_runner, &rx_runner)?;
+    }
+
+    Ok(variables.vet.read().unwrap().clone())
+}
+
+fn merge_sort_call(variables: &MergeSortVariables, lo: Hardened<usize>, hi: Hardened<usize>,
+                   tx_runner: &Sender<&str>, rx_runner: &Receiver<&str>) -> Result<(), IncoherenceError> {
+    let tmp = (*variables.depth.read().unwrap() + 1)?;
+    variables.depth.write().unwrap().assign(tmp)?;
+    tx_runner.send("i4").unwrap();
+    rx_runner.recv().unwrap();
+
+    if *variables.depth.read().unwrap() > *variables.n.read().unwrap() {
+        return Err(IncoherenceError::DepthFail);
+    }
+
+    let d = variables.frames.read().unwrap().len();
+    variables.frames.write().unwrap().push(MergeSortFrame::new(lo, hi));
+    tx_runner.send("i5").unwrap();
+    rx_runner.recv().unwrap();
+
+    if variables.frame(d).lo < variables.frame(d).hi {
+        let frame = variables.frame(d);
+        variables.frames.write().unwrap()[d].mid.assign(Hardened::from((frame.lo.inner()? + frame.hi.inner()?) / 2))?;
+        tx_runner.send("i6").unwrap();
+        rx_runner.recv().unwrap();
+
+        let frame = variables.frame(d);
+        merge_sort_call(variables, frame.lo, frame.mid, tx_runner, rx_runner)?;
+        let frame = variables.frame(d);
+        merge_sort_call(variables, (frame.mid + 1)?, frame.hi, tx_runner, rx_runner)?;
+
+        let tmp = variables.frame(d).lo;
+        variables.frames.write().unwrap()[d].i.assign(tmp)?;
+        tx_runner.send("i7").unwrap();
+        rx_runner.recv().unwrap();
+
+        let tmp = (variables.frame(d).mid + 1)?;
+        variables.frames.write().unwrap()[d].j.assign(tmp)?;
+        tx_runner.send("i8").unwrap();
+        rx_runner.recv().unwrap();
+
+        let tmp = variables.frame(d).lo;
+        variables.frames.write().unwrap()[d].k.assign(tmp)?;
+        tx_runner.send("i9").unwrap();
+        rx_runner.recv().unwrap();
+
+        while variables.frame(d).k <= variables.frame(d).hi {
+            tx_runner.send("i10").unwrap();
+            rx_runner.recv().unwrap();
+
+            let frame = variables.frame(d);
+            if frame.j > frame.hi || (frame.i <= frame.mid && variables.vet.read().unwrap()[frame.i] <= variables.vet.read().unwrap()[frame.j]) {
+                let tmp = variables.vet.read().unwrap()[frame.i];
+                variables.aux.write().unwrap()[frame.k].assign(tmp)?;
+                tx_runner.send("i11").unwrap();
+                rx_runner.recv().unwrap();
+
+                let tmp = (variables.frame(d).i + 1)?;
+                variables.frames.write().unwrap()[d].i.assign(tmp)?;
+                tx_runner.send("i12").unwrap();
+                rx_runner.recv().unwrap();
+            } else {
+                let tmp = variables.vet.read().unwrap()[frame.j];
+                variables.aux.write().unwrap()[frame.k].assign(tmp)?;
+                tx_runner.send("i13").unwrap();
+                rx_runner.recv().unwrap();
+
+                let tmp = (variables.frame(d).j + 1)?;
+                variables.frames.write().unwrap()[d].j.assign(tmp)?;
+                tx_runner.send("i14").unwrap();
+                rx_runner.recv().unwrap();
+            }
+
+            let tmp = (variables.frame(d).k + 1)?;
+            variables.frames.write().unwrap()[d].k.assign(tmp)?;
+            tx_runner.send("i15").unwrap();
+            rx_runner.recv().unwrap();
+        }
+
+        let tmp = variables.frame(d).lo;
+        variables.frames.write().unwrap()[d].k.assign(tmp)?;
+        tx_runner.send("i16").unwrap();
+        rx_runner.recv().unwrap();
+
+        while variables.frame(d).k <= variables.frame(d).hi {
+            let k = variables.frame(d).k;
+            let tmp = variables.aux.read().unwrap()[k];
+            variables.vet.write().unwrap()[k].assign(tmp)?;
+            tx_runner.send("i17").unwrap();
+            rx_runner.recv().unwrap();
+
+            let tmp = (variables.frame(d).k + 1)?;
+            variables.frames.write().unwrap()[d].k.assign(tmp)?;
+            tx_runner.send("i18").unwrap();
+            rx_runner.recv().unwrap();
+        }
+    }
+
+    variables.frames.write().unwrap().pop();
+    tx_runner.send("i19").unwrap();
+    rx_runner.recv().unwrap();
+
+    let tmp = (*variables.depth.read().unwrap() - 1)?;
+    variables.depth.write().unwrap().assign(tmp)?;
+    tx_runner.send("i20").unwrap();
+    rx_runner.recv().unwrap();
+
+    Ok(())
+}
+
+pub fn runner_bfs(variables: &BfsVariables, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<i32>>, IncoherenceError> {
+    let graph = &variables.graph;
+
+    *variables.n.write().unwrap() = Hardened::from(graph.len());
+    tx_runner.send("i1").unwrap();
+    rx_runner.recv().unwrap();
+
+    let n = variables.n.read().unwrap().inner()?;
+    *variables.dist.write().unwrap() = vec![Hardened::from(i32::MAX); n];
+    tx_runner.send("i2").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.visited.write().unwrap() = vec![Hardened::from(false); n];
+    tx_runner.send("i3").unwrap();
+    rx_runner.recv().unwrap();
+
+    variables.queue.write().unwrap().clear();
+    tx_runner.send("i4").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.head.write().unwrap() = Hardened::from(0);
+    tx_runner.send("i5").unwrap();
+    rx_runner.recv().unwrap();
+
+    let source = *variables.source.read().unwrap();
+    variables.dist.write().unwrap()[source].assign(Hardened::from(0))?;
+    tx_runner.send("i6").unwrap();
+    rx_runner.recv().unwrap();
+
+    let source = *variables.source.read().unwrap();
+    variables.visited.write().unwrap()[source].assign(Hardened::from(true))?;
+    tx_runner.send("i7").unwrap();
+    rx_runner.recv().unwrap();
+
+    let source = *variables.source.read().unwrap();
+    variables.queue.write().unwrap().push(source);
+    tx_runner.send("i8").unwrap();
+    rx_runner.recv().unwrap();
+
+    while *variables.head.read().unwrap() < Hardened::from(variables.queue.read().unwrap().len()) {
+        tx_runner.send("i9").unwrap();
+        rx_runner.recv().unwrap();
+
+        let head = *variables.head.read().unwrap();
+        let tmp = variables.queue.read().unwrap()[head];
+        variables.u.write().unwrap().assign(tmp)?;
+        tx_runner.send("i10").unwrap();
+        rx_runner.recv().unwrap();
+
+        let tmp = (*variables.head.read().unwrap() + 1)?;
+        variables.head.write().unwrap().assign(tmp)?;
+        tx_runner.send("i11").unwrap();
+        rx_runner.recv().unwrap();
+
+        variables.e.write().unwrap().assign(Hardened::from(0))?;
+        tx_runner.send("i12").unwrap();
+        rx_runner.recv().unwrap();
+
+        while *variables.e.read().unwrap() < Hardened::from(graph[variables.u.read().unwrap().inner()?].len()) {
+            tx_runner.send("i13").unwrap();
+            rx_runner.recv().unwrap();
+
+            let edge = graph[variables.u.read().unwrap().inner()?][variables.e.read().unwrap().inner()?];
+            variables.v.write().unwrap().assign(Hardened::from(edge.0))?;
+            tx_runner.send("i14").unwrap();
+            rx_runner.recv().unwrap();
+
+            let v = *variables.v.read().unwrap();
+            if !variables.visited.read().unwrap()[v].inner()? {
+                tx_runner.send("i15").unwrap();
+                rx_runner.recv().unwrap();
+
+                let v = *variables.v.read().unwrap();
+                variables.visited.write().unwrap()[v].assign(Hardened::from(true))?;
+                tx_runner.send("i16").unwrap();
+                rx_runner.recv().unwrap();
+
+                let u = *variables.u.read().unwrap();
+                let tmp = (variables.dist.read().unwrap()[u] + Hardened::from(1))?;
+                let v = *variables.v.read().unwrap();
+                variables.dist.write().unwrap()[v].assign(tmp)?;
+                tx_runner.send("i17").unwrap();
+                rx_runner.recv().unwrap();
+
+                let v = *variables.v.read().unwrap();
+                variables.queue.write().unwrap().push(v);
+                tx_runner.send("i18").unwrap();
+                rx_runner.recv().unwrap();
+            }
+
+            let tmp = (*variables.e.read().unwrap() + 1)?;
+            variables.e.write().unwrap().assign(tmp)?;
+            tx_runner.send("i19").unwrap();
+            rx_runner.recv().unwrap();
+        }
+    }
+
+    Ok(variables.dist.read().unwrap().clone())
+}
+
+pub fn runner_dijkstra(variables: &DijkstraVariables, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<i32>>, IncoherenceError> {
+    let graph = &variables.graph;
+
+    *variables.n.write().unwrap() = Hardened::from(graph.len());
+    tx_runner.send("i1").unwrap();
+    rx_runner.recv().unwrap();
+
+    let n = variables.n.read().unwrap().inner()?;
+    *variables.dist.write().unwrap() = vec![Hardened::from(i32::MAX); n];
+    tx_runner.send("i2").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.visited.write().unwrap() = vec![Hardened::from(false); n];
+    tx_runner.send("i3").unwrap();
+    rx_runner.recv().unwrap();
+
+    variables.pq_dist.write().unwrap().clear();
+    tx_runner.send("i4").unwrap();
+    rx_runner.recv().unwrap();
+
+    variables.pq_node.write().unwrap().clear();
+    tx_runner.send("i5").unwrap();
+    rx_runner.recv().unwrap();
+
+    let source = *variables.source.read().unwrap();
+    variables.dist.write().unwrap()[source].assign(Hardened::from(0))?;
+    tx_runner.send("i6").unwrap();
+    rx_runner.recv().unwrap();
+
+    variables.pq_dist.write().unwrap().push(Hardened::from(0));
+    tx_runner.send("i7").unwrap();
+    rx_runner.recv().unwrap();
+
+    let source = *variables.source.read().unwrap();
+    variables.pq_node.write().unwrap().push(source);
+    tx_runner.send("i8").unwrap();
+    rx_runner.recv().unwrap();
+
+    while !variables.pq_node.read().unwrap().is_empty() {
+        tx_runner.send("i9").unwrap();
+        rx_runner.recv().unwrap();
+
+        //Estrazione del minimo dalla coda di priorità
+        variables.best.write().unwrap().assign(Hardened::from(0))?;
+        tx_runner.send("i10").unwrap();
+        rx_runner.recv().unwrap();
+
+        variables.idx.write().unwrap().assign(Hardened::from(1))?;
+        tx_runner.send("i11").unwrap();
+        rx_runner.recv().unwrap();
+
+        while *variables.idx.read().unwrap() < Hardened::from(variables.pq_node.read().unwrap().len()) {
+            tx_runner.send("i12").unwrap();
+            rx_runner.recv().unwrap();
+
+            let idx = *variables.idx.read().unwrap();
+            let best = *variables.best.read().unwrap();
+            if variables.pq_dist.read().unwrap()[idx] < variables.pq_dist.read().unwrap()[best] {
+                tx_runner.send("i13").unwrap();
+                rx_runner.recv().unwrap();
+
+                let tmp = *variables.idx.read().unwrap();
+                variables.best.write().unwrap().assign(tmp)?;
+                tx_runner.send("i14").unwrap();
+                rx_runner.recv().unwrap();
+            }
+
+            let tmp = (*variables.idx.read().unwrap() + 1)?;
+            variables.idx.write().unwrap().assign(tmp)?;
+            tx_runner.send("i15").unwrap();
+            rx_runner.recv().unwrap();
+        }
+
+        let best = variables.best.read().unwrap().inner()?;
+        let tmp = variables.pq_dist.write().unwrap().swap_remove(best);
+        variables.d.write().unwrap().assign(tmp)?;
+        tx_runner.send("i16").unwrap();
+        rx_runner.recv().unwrap();
+
+        let best = variables.best.read().unwrap().inner()?;
+        let tmp = variables.pq_node.write().unwrap().swap_remove(best);
+        variables.u.write().unwrap().assign(tmp)?;
+        tx_runner.send("i17").unwrap();
+        rx_runner.recv().unwrap();
+
+        let u = *variables.u.read().unwrap();
+        if !variables.visited.read().unwrap()[u].inner()? {
+            tx_runner.send("i18").unwrap();
+            rx_runner.recv().unwrap();
+
+            let u = *variables.u.read().unwrap();
+            variables.visited.write().unwrap()[u].assign(Hardened::from(true))?;
+            tx_runner.send("i19").unwrap();
+            rx_runner.recv().unwrap();
+
+            variables.e.write().unwrap().assign(Hardened::from(0))?;
+            tx_runner.send("i20").unwrap();
+            rx_runner.recv().unwrap();
+
+            while *variables.e.read().unwrap() < Hardened::from(graph[variables.u.read().unwrap().inner()?].len()) {
+                tx_runner.send("i21").unwrap();
+                rx_runner.recv().unwrap();
+
+                let edge = graph[variables.u.read().unwrap().inner()?][variables.e.read().unwrap().inner()?];
+                variables.v.write().unwrap().assign(Hardened::from(edge.0))?;
+                tx_runner.send("i22").unwrap();
+                rx_runner.recv().unwrap();
+
+                let tmp = (*variables.d.read().unwrap() + Hardened::from(edge.1))?;
+                variables.alt.write().unwrap().assign(tmp)?;
+                tx_runner.send("i23").unwrap();
+                rx_runner.recv().unwrap();
+
+                let v = *variables.v.read().unwrap();
+                if *variables.alt.read().unwrap() < variables.dist.read().unwrap()[v] {
+                    tx_runner.send("i24").unwrap();
+                    rx_runner.recv().unwrap();
+
+                    let tmp = *variables.alt.read().unwrap();
+                    let v = *variables.v.read().unwrap();
+                    variables.dist.write().unwrap()[v].assign(tmp)?;
+                    tx_runner.send("i25").unwrap();
+                    rx_runner.recv().unwrap();
+
+                    let tmp = *variables.alt.read().unwrap();
+                    variables.pq_dist.write().unwrap().push(tmp);
+                    tx_runner.send("i26").unwrap();
+                    rx_runner.recv().unwrap();
+
+                    let v = *variables.v.read().unwrap();
+                    variables.pq_node.write().unwrap().push(v);
+                    tx_runner.send("i27").unwrap();
+                    rx_runner.recv().unwrap();
+                }
+
+                let tmp = (*variables.e.read().unwrap() + 1)?;
+                variables.e.write().unwrap().assign(tmp)?;
+                tx_runner.send("i28").unwrap();
+                rx_runner.recv().unwrap();
+            }
+        }
+    }
+
+    Ok(variables.dist.read().unwrap().clone())
+}
+
+pub fn runner_dot_product(variables: &DotProductVariables, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<f64>>, IncoherenceError> {
+    *variables.n.write().unwrap() = Hardened::from(variables.x.read().unwrap().len());
+    tx_runner.send("i1").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.acc.write().unwrap() = Hardened::from(0.0);
+    tx_runner.send("i2").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.i.write().unwrap() = Hardened::from(0);
+    tx_runner.send("i3").unwrap();
+    rx_runner.recv().unwrap();
+
+    while *variables.i.read().unwrap() < *variables.n.read().unwrap() {
+        tx_runner.send("i4").unwrap();
+        rx_runner.recv().unwrap();
+
+        let i = *variables.i.read().unwrap();
+        let prod = (variables.x.read().unwrap()[i] * variables.y.read().unwrap()[i])?;
+        let tmp = (*variables.acc.read().unwrap() + prod)?;
+        variables.acc.write().unwrap().assign(tmp)?;
+        tx_runner.send("i5").unwrap();
+        rx_runner.recv().unwrap();
+
+        let tmp = (*variables.i.read().unwrap() + 1)?;
+        variables.i.write().unwrap().assign(tmp)?;
+        tx_runner.send("i6").unwrap();
+        rx_runner.recv().unwrap();
+    }
+
+    Ok(vec![*variables.acc.read().unwrap()])
+}
+
+pub fn runner_fir_filter(variables: &FirFilterVariables, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<f64>>, IncoherenceError> {
+    *variables.n.write().unwrap() = Hardened::from(variables.x.read().unwrap().len());
+    tx_runner.send("i1").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.m.write().unwrap() = Hardened::from(variables.h.read().unwrap().len());
+    tx_runner.send("i2").unwrap();
+    rx_runner.recv().unwrap();
+
+    let n = variables.n.read().unwrap().inner()?;
+    *variables.y.write().unwrap() = vec![Hardened::from(0.0); n];
+    tx_runner.send("i3").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.i.write().unwrap() = Hardened::from(0);
+    tx_runner.send("i4").unwrap();
+    rx_runner.recv().unwrap();
+
+    while *variables.i.read().unwrap() < *variables.n.read().unwrap() {
+        tx_runner.send("i5").unwrap();
+        rx_runner.recv().unwrap();
+
+        variables.acc.write().unwrap().assign(Hardened::from(0.0))?;
+        tx_runner.send("i6").unwrap();
+        rx_runner.recv().unwrap();
+
+        variables.k.write().unwrap().assign(Hardened::from(0))?;
+        tx_runner.send("i7").unwrap();
+        rx_runner.recv().unwrap();
+
+        while *variables.k.read().unwrap() < *variables.m.read().unwrap() && *variables.k.read().unwrap() <= *variables.i.read().unwrap() {
+            tx_runner.send("i8").unwrap();
+            rx_runner.recv().unwrap();
+
+            let k = *variables.k.read().unwrap();
+            let sample = variables.x.read().unwrap()[(*variables.i.read().unwrap() - k)?];
+            let prod = (variables.h.read().unwrap()[k] * sample)?;
+            let tmp = (*variables.acc.read().unwrap() + prod)?;
+            variables.acc.write().unwrap().assign(tmp)?;
+            tx_runner.send("i9").unwrap();
+            rx_runner.recv().unwrap();
+
+            let tmp = (*variables.k.read().unwrap() + 1)?;
+            variables.k.write().unwrap().assign(tmp)?;
+            tx_runner.send("i10").unwrap();
+            rx_runner.recv().unwrap();
+        }
+
+        let i = *variables.i.read().unwrap();
+        let tmp = *variables.acc.read().unwrap();
+        variables.y.write().unwrap()[i].assign(tmp)?;
+        tx_runner.send("i11").unwrap();
+        rx_runner.recv().unwrap();
+
+        let tmp = (*variables.i.read().unwrap() + 1)?;
+        variables.i.write().unwrap().assign(tmp)?;
+        tx_runner.send("i12").unwrap();
+        rx_runner.recv().unwrap();
+    }
+
+    Ok(variables.y.read().unwrap().clone())
+}
+
+pub fn runner_jacobi(variables: &JacobiVariables, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<f64>>, IncoherenceError> {
+    *variables.n.write().unwrap() = Hardened::from(variables.b.read().unwrap().len());
+    tx_runner.send("i1").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.iterations.write().unwrap() = Hardened::from(JACOBI_ITERATIONS);
+    tx_runner.send("i2").unwrap();
+    rx_runner.recv().unwrap();
+
+    let n = variables.n.read().unwrap().inner()?;
+    *variables.x.write().unwrap() = vec![Hardened::from(0.0); n];
+    tx_runner.send("i3").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.x_new.write().unwrap() = vec![Hardened::from(0.0); n];
+    tx_runner.send("i4").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.it.write().unwrap() = Hardened::from(0);
+    tx_runner.send("i5").unwrap();
+    rx_runner.recv().unwrap();
+
+    while *variables.it.read().unwrap() < *variables.iterations.read().unwrap() {
+        tx_runner.send("i6").unwrap();
+        rx_runner.recv().unwrap();
+
+        variables.i.write().unwrap().assign(Hardened::from(0))?;
+        tx_runner.send("i7").unwrap();
+        rx_runner.recv().unwrap();
+
+        while *variables.i.read().unwrap() < *variables.n.read().unwrap() {
+            tx_runner.send("i8").unwrap();
+            rx_runner.recv().unwrap();
+
+            variables.sigma.write().unwrap().assign(Hardened::from(0.0))?;
+            tx_runner.send("i9").unwrap();
+            rx_runner.recv().unwrap();
+
+            variables.j.write().unwrap().assign(Hardened::from(0))?;
+            tx_runner.send("i10").unwrap();
+            rx_runner.recv().unwrap();
+
+            while *variables.j.read().unwrap() < *variables.n.read().unwrap() {
+                tx_runner.send("i11").unwrap();
+                rx_runner.recv().unwrap();
+
+                if *variables.j.read().unwrap() != *variables.i.read().unwrap() {
+                    tx_runner.send("i12").unwrap();
+                    rx_runner.recv().unwrap();
+
+                    let i = variables.i.read().unwrap().inner()?;
+                    let j = *variables.j.read().unwrap();
+                    let prod = (variables.a.read().unwrap()[i][j.inner()?] * variables.x.read().unwrap()[j])?;
+                    let tmp = (*variables.sigma.read().unwrap() + prod)?;
+                    variables.sigma.write().unwrap().assign(tmp)?;
+                    tx_runner.send("i13").unwrap();
+                    rx_runner.recv().unwrap();
+                }
+
+                let tmp = (*variables.j.read().unwrap() + 1)?;
+                variables.j.write().unwrap().assign(tmp)?;
+                tx_runner.send("i14").unwrap();
+                rx_runner.recv().unwrap();
+            }
+
+            let i = *variables.i.read().unwrap();
+            let diag = variables.a.read().unwrap()[i.inner()?][i.inner()?];
+            let tmp = ((variables.b.read().unwrap()[i] - *variables.sigma.read().unwrap())? / diag)?;
+            variables.x_new.write().unwrap()[i].assign(tmp)?;
+            tx_runner.send("i15").unwrap();
+            rx_runner.recv().unwrap();
+
+            let tmp = (*variables.i.read().unwrap() + 1)?;
+            variables.i.write().unwrap().assign(tmp)?;
+            tx_runner.send("i16").unwrap();
+            rx_runner.recv().unwrap();
+        }
+
+        let tmp = variables.x_new.read().unwrap().clone();
+        *variables.x.write().unwrap() = tmp;
+        tx_runner.send("i17").unwrap();
+        rx_runner.recv().unwrap();
+
+        let tmp = (*variables.it.read().unwrap() + 1)?;
+        variables.it.write().unwrap().assign(tmp)?;
+        tx_runner.send("i18").unwrap();
+        rx_runner.recv().unwrap();
+    }
+
+    Ok(variables.x.read().unwrap().clone())
+}
+
+pub fn runner_crc32(variables: &Crc32Variables, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<u32>>, IncoherenceError> {
+    *variables.table.write().unwrap() = vec![Hardened::from(0); 256];
+    tx_runner.send("i1").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.n.write().unwrap() = Hardened::from(0);
+    tx_runner.send("i2").unwrap();
+    rx_runner.recv().unwrap();
+
+    //Costruzione della tabella di lookup
+    while *variables.n.read().unwrap() < Hardened::from(256) {
+        tx_runner.send("i3").unwrap();
+        rx_runner.recv().unwrap();
+
+        let n = variables.n.read().unwrap().inner()?;
+        variables.c.write().unwrap().assign(Hardened::from(n as u32))?;
+        tx_runner.send("i4").unwrap();
+        rx_runner.recv().unwrap();
+
+        variables.k.write().unwrap().assign(Hardened::from(0))?;
+        tx_runner.send("i5").unwrap();
+        rx_runner.recv().unwrap();
+
+        while *variables.k.read().unwrap() < Hardened::from(8) {
+            tx_runner.send("i6").unwrap();
+            rx_runner.recv().unwrap();
+
+            let c = *variables.c.read().unwrap();
+            let tmp = if (c & Hardened::from(1))?.inner()? == 1 {
+                (Hardened::from(CRC32_POLY) ^ (c >> 1)?)?
+            } else {
+                (c >> 1)?
+            };
+            variables.c.write().unwrap().assign(tmp)?;
+            tx_runner.send("i7").unwrap();
+            rx_runner.recv().unwrap();
+
+            let tmp = (*variables.k.read().unwrap() + 1)?;
+            variables.k.write().unwrap().assign(tmp)?;
+            tx_runner.send("i8").unwrap();
+            rx_runner.recv().unwrap();
+        }
+
+        let n = *variables.n.read().unwrap();
+        let c = *variables.c.read().unwrap();
+        variables.table.write().unwrap()[n].assign(c)?;
+        tx_runner.send("i9").unwrap();
+        rx_runner.recv().unwrap();
+
+        let tmp = (*variables.n.read().unwrap() + 1)?;
+        variables.n.write().unwrap().assign(tmp)?;
+        tx_runner.send("i10").unwrap();
+        rx_runner.recv().unwrap();
+    }
+
+    *variables.crc.write().unwrap() = Hardened::from(0xFFFFFFFF);
+    tx_runner.send("i11").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.i.write().unwrap() = Hardened::from(0);
+    tx_runner.send("i12").unwrap();
+    rx_runner.recv().unwrap();
+
+    while *variables.i.read().unwrap() < Hardened::from(variables.data.read().unwrap().len()) {
+        tx_runner.send("i13").unwrap();
+        rx_runner.recv().unwrap();
+
+        let i = *variables.i.read().unwrap();
+        let byte = variables.data.read().unwrap()[i];
+        variables.byte.write().unwrap().assign(byte)?;
+        tx_runner.send("i14").unwrap();
+        rx_runner.recv().unwrap();
+
+        let byte = Hardened::from(variables.byte.read().unwrap().inner()? as u32);
+        let low = ((*variables.crc.read().unwrap() ^ byte)? & Hardened::from(0xFF))?;
+        variables.idx.write().unwrap().assign(Hardened::from(low.inner()? as usize))?;
+        tx_runner.send("i15").unwrap();
+        rx_runner.recv().unwrap();
+
+        let idx = *variables.idx.read().unwrap();
+        let tmp = (variables.table.read().unwrap()[idx] ^ (*variables.crc.read().unwrap() >> 8)?)?;
+        variables.crc.write().unwrap().assign(tmp)?;
+        tx_runner.send("i16").unwrap();
+        rx_runner.recv().unwrap();
+
+        let tmp = (*variables.i.read().unwrap() + 1)?;
+        variables.i.write().unwrap().assign(tmp)?;
+        tx_runner.send("i17").unwrap();
+        rx_runner.recv().unwrap();
+    }
+
+    let crc = (*variables.crc.read().unwrap() ^ Hardened::from(0xFFFFFFFF))?;
+    Ok(vec![crc])
+}
+
+pub fn runner_kmp(variables: &KmpVariables, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<usize>>, IncoherenceError> {
+    *variables.n.write().unwrap() = Hardened::from(variables.text.read().unwrap().len());
+    tx_runner.send("i1").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.m.write().unwrap() = Hardened::from(variables.pattern.read().unwrap().len());
+    tx_runner.send("i2").unwrap();
+    rx_runner.recv().unwrap();
+
+    if *variables.m.read().unwrap() == Hardened::from(0) {
+        return Ok(Vec::new());
+    }
+
+    let m = variables.m.read().unwrap().inner()?;
+    *variables.fail.write().unwrap() = vec![Hardened::from(0); m];
+    tx_runner.send("i3").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.k.write().unwrap() = Hardened::from(0);
+    tx_runner.send("i4").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.q.write().unwrap() = Hardened::from(1);
+    tx_runner.send("i5").unwrap();
+    rx_runner.recv().unwrap();
+
+    //Costruzione della failure function
+    while *variables.q.read().unwrap() < *variables.m.read().unwrap() {
+        tx_runner.send("i6").unwrap();
+        rx_runner.recv().unwrap();
+
+        while *variables.k.read().unwrap() > Hardened::from(0) &&
+            variables.pattern.read().unwrap()[*variables.k.read().unwrap()] != variables.pattern.read().unwrap()[*variables.q.read().unwrap()] {
+            let prev = (*variables.k.read().unwrap() - 1)?;
+            let tmp = variables.fail.read().unwrap()[prev];
+            variables.k.write().unwrap().assign(tmp)?;
+            tx_runner.send("i7").unwrap();
+            rx_runner.recv().unwrap();
+        }
+
+        if variables.pattern.read().unwrap()[*variables.k.read().unwrap()] == variables.pattern.read().unwrap()[*variables.q.read().unwrap()] {
+            let tmp = (*variables.k.read().unwrap() + 1)?;
+            variables.k.write().unwrap().assign(tmp)?;
+            tx_runner.send("i8").unwrap();
+            rx_runner.recv().unwrap();
+        }
+
+        let q = *variables.q.read().unwrap();
+        let k = *variables.k.read().unwrap();
+        variables.fail.write().unwrap()[q].assign(k)?;
+        tx_runner.send("i9").unwrap();
+        rx_runner.recv().unwrap();
+
+        let tmp = (*variables.q.read().unwrap() + 1)?;
+        variables.q.write().unwrap().assign(tmp)?;
+        tx_runner.send("i10").unwrap();
+        rx_runner.recv().unwrap();
+    }
+
+    *variables.matches.write().unwrap() = Vec::new();
+    tx_runner.send("i11").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.j.write().unwrap() = Hardened::from(0);
+    tx_runner.send("i12").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.i.write().unwrap() = Hardened::from(0);
+    tx_runner.send("i13").unwrap();
+    rx_runner.recv().unwrap();
+
+    //Scansione del testo
+    while *variables.i.read().unwrap() < *variables.n.read().unwrap() {
+        tx_runner.send("i14").unwrap();
+        rx_runner.recv().unwrap();
+
+        while *variables.j.read().unwrap() > Hardened::from(0) &&
+            variables.pattern.read().unwrap()[*variables.j.read().unwrap()] != variables.text.read().unwrap()[*variables.i.read().unwrap()] {
+            let prev = (*variables.j.read().unwrap() - 1)?;
+            let tmp = variables.fail.read().unwrap()[prev];
+            variables.j.write().unwrap().assign(tmp)?;
+            tx_runner.send("i15").unwrap();
+            rx_runner.recv().unwrap();
+        }
+
+        if variables.pattern.read().unwrap()[*variables.j.read().unwrap()] == variables.text.read().unwrap()[*variables.i.read().unwrap()] {
+            let tmp = (*variables.j.read().unwrap() + 1)?;
+            variables.j.write().unwrap().assign(tmp)?;
+            tx_runner.send("i16").unwrap();
+            rx_runner.recv().unwrap();
+        }
+
+        if *variables.j.read().unwrap() == *variables.m.read().unwrap() {
+            let start = ((*variables.i.read().unwrap() + 1)? - *variables.m.read().unwrap())?;
+            variables.matches.write().unwrap().push(start);
+            tx_runner.send("i17").unwrap();
+            rx_runner.recv().unwrap();
+
+            let prev = (*variables.j.read().unwrap() - 1)?;
+            let tmp = variables.fail.read().unwrap()[prev];
+            variables.j.write().unwrap().assign(tmp)?;
+            tx_runner.send("i18").unwrap();
+            rx_runner.recv().unwrap();
+        }
+
+        let tmp = (*variables.i.read().unwrap() + 1)?;
+        variables.i.write().unwrap().assign(tmp)?;
+        tx_runner.send("i19").unwrap();
+        rx_runner.recv().unwrap();
+    }
+
+    Ok(variables.matches.read().unwrap().clone())
+}
+
+///Moltiplicazione parallela per righe. Il thread del runner inizializza lo stato condiviso e
+/// poi fa avanzare i worker a turno (round-robin), un'istruzione alla volta: solo il worker che
+/// ha il turno esegue codice, quindi l'interleaving e gli istanti di iniezione sono deterministici.
+pub fn runner_par_matrix_multiplication(variables: &ParMatrixMultiplicationVariables, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<i32>>, IncoherenceError> {
+    let size = variables.a.read().unwrap().len();
+    *variables.result.write().unwrap() = vec![vec![Hardened::from(0); size]; size];
+    *variables.row_writer.write().unwrap() = vec![Actor::Main; size];
+    tx_runner.send("i1").unwrap();
+    rx_runner.recv().unwrap();
+
+    *variables.next_row.write().unwrap() = Hardened::from(0);
+    *variables.next_row_writer.write().unwrap() = Actor::Main;
+    tx_runner.send("i2").unwrap();
+    rx_runner.recv().unwrap();
+
+    let outcomes = thread::scope(|s| {
+        let mut turns = Vec::new();
+        let mut handles = Vec::new();
+        for w in 0..variables.workers.len() {
+            let (tx_go, rx_go) = channel::<()>();
+            let (tx_step, rx_step) = channel::<Option<&'static str>>();
+            turns.push((tx_go, rx_step));
+            handles.push(s.spawn(move || {
+                //ogni istruzione termina cedendo il turno: si notifica l'etichetta e si attende il prossimo turno.
+                //Un worker in attesa della sezione critica cede il turno senza eseguire istruzioni
+                let step = |label: Option<&'static str>| {
+                    tx_step.send(label).unwrap();
+                    rx_go.recv().unwrap();
+                };
+                rx_go.recv().unwrap();
+                let outcome = panic::catch_unwind(AssertUnwindSafe(|| worker_par_matrix_multiplication(variables, w, step)));
+                if !matches!(outcome, Ok(Ok(()))) {
+                    variables.detected(Actor::Worker(w));
+                }
+                outcome
+            }));
+        }
+
+        while !turns.is_empty() {
+            turns.retain(|(tx_go, rx_step)| {
+                tx_go.send(()).unwrap();
+                match rx_step.recv() {
+                    Ok(Some(label)) => {
+                        tx_runner.send(label).unwrap();
+                        rx_runner.recv().unwrap();
+                        true
+                    }
+                    Ok(None) => true,
+                    //il worker ha terminato (normalmente o per un fault rilevato)
+                    Err(_) => false
+                }
+            });
+        }
+        handles.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>()
+    });
+
+    //Si restituisce l'esito del primo worker che ha rilevato un fault
+    if let Some(Actor::Worker(w)) = *variables.detected_by.read().unwrap() {
+        match outcomes.into_iter().nth(w).unwrap() {
+            Ok(res) => res?,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+
+    //La raccolta del risultato è una lettura del thread principale sui dati scritti dai worker
+    let result = variables.result.read().unwrap().clone().into_iter().flatten().collect::<Vec<Hardened<i32>>>();
+    if let Some(err) = result.iter().find_map(|el| el.inner().err()) {
+        variables.detected(Actor::Main);
+        return Err(err);
+    }
+    Ok(result)
+}
+
+fn worker_par_matrix_multiplication(variables: &ParMatrixMultiplicationVariables, w: usize, yield_turn: impl Fn(Option<&'static str>)) -> Result<(), IncoherenceError> {
+    let me = Actor::Worker(w);
+    let locals = &variables.workers[w];
+    let step = |label| yield_turn(Some(label));
+
+    *locals.size.write().unwrap() = Hardened::from(variables.a.read().unwrap().len());
+    step("i3");
+
+    loop {
+        //Prelievo della prossima riga dal contatore condiviso: il lock resta acquisito fino all'aggiornamento
+        let guard = loop {
+            match variables.row_lock.try_lock() {
+                Ok(guard) => break guard,
+                Err(TryLockError::Poisoned(poisoned)) => break poisoned.into_inner(),
+                Err(TryLockError::WouldBlock) => yield_turn(None),
+            }
+        };
+        let next = variables.next_row.read().unwrap().inner()?;
+        locals.row.write().unwrap().assign(Hardened::from(next))?;
+        step("i4");
+
+        if *locals.row.read().unwrap() >= *locals.size.read().unwrap() {
+            return Ok(());
+        }
+
+        let row = *locals.row.read().unwrap();
+        variables.next_row.write().unwrap().assign((row + 1)?)?;
+        *variables.next_row_writer.write().unwrap() = me;
+        variables.row_writer.write().unwrap()[row.inner()?] = me;
+        step("i5");
+        drop(guard);
+
+        locals.j.write().unwrap().assign(Hardened::from(0))?;
+        step("i6");
+
+        while *locals.j.read().unwrap() < *locals.size.read().unwrap() {
+            step("i7");
+
+            locals.acc.write().unwrap().assign(Hardened::from(0))?;
+            step("i8");
+
+            locals.k.write().unwrap().assign(Hardened::from(0))?;
+            step("i9");
+
+            while *locals.k.read().unwrap() < *locals.size.read().unwrap() {
+                step("i10");
+
+                let (row, j, k) = (locals.row.read().unwrap().inner()?, locals.j.read().unwrap().inner()?, locals.k.read().unwrap().inner()?);
+                let prod = (variables.a.read().unwrap()[row][k] * variables.b.read().unwrap()[k][j])?;
+                let tmp = (*locals.acc.read().unwrap() + prod)?;
+                locals.acc.write().unwrap().assign(tmp)?;
+                step("i11");
+
+                let tmp = (*locals.k.read().unwrap() + 1)?;
+                locals.k.write().unwrap().assign(tmp)?;
+                step("i12");
+            }
+
+            let (row, j) = (locals.row.read().unwrap().inner()?, locals.j.read().unwrap().inner()?);
+            let acc = *locals.acc.read().unwrap();
+            variables.result.write().unwrap()[row][j].assign(acc)?;
+            step("i13");
+
+            let tmp = (*locals.j.read().unwrap() + 1)?;
+            locals.j.write().unwrap().assign(tmp)?;
+            step("i14");
+        }
+    }
+}
+
+///Il plugin esegue l'algoritmo e chiama la callback dopo ogni istruzione: la callback segue lo
+/// stesso protocollo dei runner interni, senza unwrap perché un panic non può attraversare il
+/// confine con la libreria dinamica
+pub fn runner_plugin(instance: &PluginInstance, tx_runner: Sender<&str>, rx_runner: Receiver<&str>) -> Result<Vec<Hardened<i32>>, IncoherenceError> {
+    instance.run(|| {
+        let _ = tx_runner.send("step");
+        let _ = rx_runner.recv();
+    })?;
+    Ok(Hardened::from_vec(instance.output()))
+}
+
 #[cfg(test)]
     mod tests{
     use std::thread;
     use std::sync::Arc;
-    use std::sync::mpsc::channel;
-    use crate::fault_env::Data::Matrices;
-    use crate::fault_list_manager::fault_manager;
-    use crate::injector::{injector, runner, AlgorithmVariables};
+    use std::sync::mpsc::{channel, sync_channel};
+    use crate::fault_env::{Data, PIPELINE_BOUND};
+    use crate::fault_env::Data::{Matrices, Vector};
+    use crate::fault_list_manager::{fault_manager, FaultListEntry, FaultSource};
+    use crate::hardened::{Hardened, IncoherenceError, IntoNestedVec};
+    use crate::injector::{frame_trace, injector, injector_manager, label_trace, runner, INJECTIONS_IN_FLIGHT, Actor, AlgorithmVariables, Detection, Output, TestResult};
+    use crate::fault_list_manager::{create_fault_list, static_analysis, DimData, Sampling};
+    use crate::fault_list_manager::type_size::PointerWidth;
+    use crate::fault_list_manager::liveness::{DefUse, Liveness};
+    use crate::fault_list_manager::static_analysis::load_analysis;
+    use crate::fault_list_manager::target_function;
+    use crate::fault_list_manager::validation::Registry;
+    use std::collections::HashMap;
+    use std::env::temp_dir;
 
     #[test]
         fn test_run_matrix_multiplication(){
             let fault_list = "src/fault_list_manager/file_fault_list/matrix_multiplication/matrix_mul_FL.json".to_string();
             let target = "matrix_multiplication".to_string();
             let data = Matrices(vec![vec![5, 7, 6, 5], vec![7, 10, 8, 7], vec![6, 8, 10, 9], vec![5, 7, 9, 10]], vec![vec![68, -41, -17, 10], vec![-41, 25, 10, -6], vec![-17, 10, 5, -3], vec![10, -6, -3, 2]]);
-            let (tx_chan_fm_inj, rx_chan_fm_inj) = channel();
+            let (tx_chan_fm_inj, rx_chan_fm_inj) = sync_channel(PIPELINE_BOUND);
             let (tx_chan_inj_anl, rx_chan_inj_anl) = channel();
-            fault_manager(tx_chan_fm_inj,fault_list);
+            let registry = Registry::new("src/fault_list_manager/file_fault_list/matrix_multiplication/matrix_mul_ris.json", "matrix_multiplication",
+                                         &DimData::Matrices((4, 4)), &target, label_trace(&target, data.clone()).len());
+            let (_, entries) = FaultSource::File(fault_list, registry).open(&target).unwrap();
+            let fault_manager = thread::spawn(move || fault_manager(tx_chan_fm_inj, entries).unwrap());
 
 
 
@@ -320,13 +1370,341 @@ pub fn runner_matrix_multiplication(variables: &MatrixMultiplicationVariables, t
 
 
         drop(tx_chan_inj_anl);
+        //il fault manager è fermo sul canale pieno: chiudendolo termina
+        drop(rx_chan_fm_inj);
+        fault_manager.join().unwrap();
+
+
 
 
+        }
+
+    #[test]
+    fn test_injector_manager_pipeline(){
+        //più entry delle iniezioni contemporanee e canali minimi: l'injector manager deve
+        //alternare ricezione e consegna, restituendo i risultati nell'ordine delle entry
+        let num_entries = INJECTIONS_IN_FLIGHT * 2 + 3;
+        let (tx_entries, rx_entries) = sync_channel(1);
+        let (tx_results, rx_results) = sync_channel(1);
+        let manager = thread::spawn(move || injector_manager(rx_entries, tx_results, "bubble_sort".to_string(), Vector(vec![3, 1, 2])));
+        let sender = thread::spawn(move || {
+            for time in 0..num_entries {
+                let entry = FaultListEntry{ var: "vet[0]".to_string(), time, flipped_bit: 0, weight: 1 };
+                tx_entries.send(entry).unwrap();
+            }
+        });
+        let times: Vec<usize> = rx_results.iter().map(|result| result.get_fault_list_entry().time).collect();
+        sender.join().unwrap();
+        manager.join().unwrap();
+        assert_eq!(times, (0..num_entries).collect::<Vec<_>>());
+    }
+
+    //Esegue una singola iniezione sul target e restituisce il risultato del runner
+    #[test]
+    fn test_liveness_matches_injection(){
+        //Ogni entry morta deve dare l'output corretto e tutte le entry di una stessa classe di
+        //equivalenza lo stesso esito: la potatura non cambia le statistiche della campagna
+        let lengths = |names: &[(&str, usize)]| DimData::Lengths(names.iter().map(|&(name, len)| (name.to_string(), len)).collect());
+        let cases = [
+            ("sel_sort", "selection_sort", Vector(vec![3, 1, 2]), DimData::Vector(3)),
+            ("bubble_sort", "bubble_sort", Vector(vec![3, 1, 2]), DimData::Vector(3)),
+            ("matrix_multiplication", "matrix_multiplication", Matrices(vec![vec![1, 2], vec![3, 4]], vec![vec![5, 6], vec![7, 8]]), DimData::Matrices((2, 2))),
+            ("bfs", "bfs", Data::Graph(vec![vec![(1, 1)], vec![(2, 1)], vec![]], 0), DimData::Graph(3, 2)),
+            ("dijkstra", "dijkstra", Data::Graph(vec![vec![(1, 4), (2, 1)], vec![], vec![(1, 2)]], 0), DimData::Graph(3, 3)),
+            ("dot_product", "dot_product", Data::Reals(vec![1.0, -2.0], vec![0.5, 3.0]), lengths(&[("x", 2), ("y", 2)])),
+            ("fir_filter", "fir_filter", Data::Reals(vec![1.0, -2.0, 0.5], vec![0.5, 0.25]), lengths(&[("x", 3), ("h", 2), ("y", 3)])),
+            ("jacobi", "jacobi", Data::LinearSystem(vec![vec![2.0]], vec![4.0]), lengths(&[("a", 1), ("b", 1), ("x", 1), ("x_new", 1)])),
+            ("kmp", "kmp", Data::Text("aaba".to_string(), "ab".to_string()), lengths(&[("text", 4), ("pattern", 2), ("fail", 2), ("matches", 3)])),
+        ];
+        for (target, dir, data, dims) in cases {
+            let analysis = temp_dir().join(format!("{}_ris_liveness_test.json", target)).to_string_lossy().to_string();
+            let dest = temp_dir().join(format!("{}_FL_liveness_test.json", target)).to_string_lossy().to_string();
+            static_analysis::generate_analysis_file(format!("src/fault_list_manager/file_fault_list/{}/mod.rs", dir), analysis.clone(), PointerWidth::HOST).unwrap();
+            let trace = label_trace(target, data.clone());
+            let entries: Vec<FaultListEntry> = create_fault_list(Sampling::Exhaustive, target.to_string(), analysis.clone(), dims, dest, trace.len(), 0).unwrap()
+                .into_iter()
+                .filter(|entry| entry.flipped_bit < 2)
+                //dei vettori bastano i primi due elementi
+                .filter(|entry| entry.var.split(['[', ']']).filter_map(|i| i.parse::<usize>().ok()).all(|i| i < 2))
+                .collect();
+
+            //un output non rilevato può contenere elementi incoerenti: si confronta la copia cp1
+            let cp1 = |output: Output| match output {
+                Output::Int(vet) => vet.iter().map(|el| format!("{:?}", el["cp1"])).collect::<Vec<String>>(),
+                Output::Real(vet) => vet.iter().map(|el| format!("{:?}", el["cp1"])).collect(),
+                Output::Word(vet) => vet.iter().map(|el| format!("{:?}", el["cp1"])).collect(),
+                Output::Index(vet) => vet.iter().map(|el| format!("{:?}", el["cp1"])).collect(),
+            };
+            let outcome = |entry: FaultListEntry| run_single_injection(target, data.clone(), entry).get_result()
+                .map(cp1)
+                .map_err(|err| format!("{:?}", err));
+            let golden = outcome(FaultListEntry{ var: String::new(), time: 0, flipped_bit: 0, weight: 1 });
+            let def_use = DefUse::new(target, Some(&load_analysis(&analysis, target_function(target)).unwrap()), trace);
+            let mut outcomes = HashMap::new();
+            let mut dead = 0;
+            for entry in entries {
+                let liveness = def_use.liveness(&entry.var, entry.time);
+                let result = outcome(entry.clone());
+                if liveness == Liveness::Dead {
+                    dead += 1;
+                    assert_eq!(result, golden, "{} {:?}", target, entry);
+                } else {
+                    let expected = outcomes.entry((entry.var.clone(), entry.flipped_bit, liveness)).or_insert(result.clone());
+                    assert_eq!(*expected, result, "{} {:?}", target, entry);
+                }
+            }
+            assert!(dead > 0, "{}", target);
+        }
+    }
+
+    fn run_single_injection(target: &str, data: Data<i32>, fault_list_entry: FaultListEntry) -> TestResult {
+        let variables = AlgorithmVariables::from_target(target, data);
+        let (tx_1, rx_1) = channel();
+        let (tx_2, rx_2) = channel();
+        let runner_variables = Arc::clone(&variables);
+        let entry_runner = fault_list_entry.clone();
+        let handle_runner = thread::spawn(move || runner(runner_variables, entry_runner, tx_1, rx_2));
+        let handle_injector = thread::spawn(move || injector(variables, fault_list_entry, tx_2, rx_1));
+        let result = handle_runner.join().unwrap();
+        handle_injector.join().unwrap();
+        result
+    }
+
+    #[test]
+    fn test_frame_trace(){
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        for target in ["quick_sort", "merge_sort"] {
+            //un conteggio per ogni etichetta; i frame si aprono e si chiudono uno alla volta
+            let frames = frame_trace(target, Vector(vet.clone()));
+            assert_eq!(frames.len(), label_trace(target, Vector(vet.clone())).len(), "{}", target);
+            assert_eq!((frames[0], frames[frames.len() - 1]), (0, 0), "{}", target);
+            assert!(frames.windows(2).all(|w| w[0].abs_diff(w[1]) <= 1), "{}", target);
+            assert!(frames.iter().max().unwrap() > &1, "{}", target);
+            //un vettore di un solo elemento non apre frame
+            assert!(frame_trace(target, Vector(vec![4])).iter().all(|&d| d == 0), "{}", target);
+        }
+    }
+
+    #[test]
+    fn test_inject_frame_variable(){
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        //Istante 5: il frame 0 esiste e 'hi' viene letto subito dopo dal ciclo di partizionamento
+        let entry = FaultListEntry{ var: "hi@0".to_string(), time: 5, flipped_bit: 3, weight: 1 };
+        let result = run_single_injection("quick_sort", Vector(vet.clone()), entry);
+        assert!(result.get_result().is_err());
+
+        //Un frame non ancora attivo non esiste: l'iniezione non ha effetto
+        let entry = FaultListEntry{ var: "hi@7".to_string(), time: 5, flipped_bit: 3, weight: 1 };
+        let result = run_single_injection("merge_sort", Vector(vet), entry);
+        assert_eq!(result.get_result().unwrap(), Output::Int(Hardened::from_vec(vec![-32, -9, 1, 3, 10, 15, 16, 19, 20, 27])));
+    }
+
+    fn graph() -> Data<i32> {
+        Data::Graph(vec![vec![(1, 4), (2, 1)], vec![(3, 1)], vec![(1, 2), (3, 5)], vec![(4, 3)], vec![], vec![(0, 1)]], 0)
+    }
+
+    #[test]
+    fn test_graph_count_matches_runner(){
+        for (target, count, expected) in [("bfs", label_trace("bfs", graph()).len(), vec![0, 1, 1, 2, 3, i32::MAX]),
+                                          ("dijkstra", label_trace("dijkstra", graph()).len(), vec![0, 3, 1, 4, 7, i32::MAX])] {
+            let variables = AlgorithmVariables::from_target(target, graph());
+            let (tx_1, rx_1) = channel();
+            let (tx_2, rx_2) = channel();
+            let entry = FaultListEntry{ var: "n".to_string(), time: 0, flipped_bit: 0, weight: 1 };
+            let handle = thread::spawn(move || runner(variables, entry, tx_1, rx_2));
+            let mut observed = 0;
+            while rx_1.recv().is_ok() {
+                observed += 1;
+                tx_2.send("ricevuto").unwrap();
+            }
+            assert_eq!(handle.join().unwrap().get_result().unwrap(), Output::Int(Hardened::from_vec(expected)), "{}", target);
+            assert_eq!(observed, count, "{}", target);
+        }
+    }
+
+    #[test]
+    fn test_inject_graph_variable(){
+        //Istante 8: la sorgente è appena entrata nella coda di priorità e viene estratta subito dopo
+        let entry = FaultListEntry{ var: "pq_node[0]".to_string(), time: 8, flipped_bit: 1, weight: 1 };
+        let result = run_single_injection("dijkstra", graph(), entry);
+        assert!(matches!(result.get_result(), Err(IncoherenceError::AssignFail)));
+
+        //Un flag di visita corrotto viene rilevato alla lettura successiva
+        let entry = FaultListEntry{ var: "visited[3]".to_string(), time: 3, flipped_bit: 0, weight: 1 };
+        let result = run_single_injection("bfs", graph(), entry);
+        assert!(result.get_result().is_err());
+
+        //All'istante 3 la coda contiene ancora solo la sorgente: l'elemento 5 non esiste
+        let entry = FaultListEntry{ var: "queue[5]".to_string(), time: 3, flipped_bit: 2, weight: 1 };
+        let result = run_single_injection("bfs", graph(), entry);
+        assert_eq!(result.get_result().unwrap(), Output::Int(Hardened::from_vec(vec![0, 1, 1, 2, 3, i32::MAX])));
+    }
+
+    #[test]
+    fn test_real_count_matches_runner(){
+        let vectors = Data::Reals(vec![1.0, -2.0, 0.5, 4.0], vec![0.5, 0.25, 3.0, -1.0]);
+        let signals = Data::Reals(vec![1.0, -2.0, 0.5, 4.0, 3.0], vec![0.5, 0.25, 0.25]);
+        let system = Data::LinearSystem(vec![vec![4.0, 1.0, 1.0], vec![1.0, 5.0, 2.0], vec![1.0, 2.0, 6.0]], vec![5.0, 9.0, -1.0]);
+        for (target, data, count) in [("dot_product", vectors.clone(), label_trace("dot_product", vectors).len()),
+                                      ("fir_filter", signals.clone(), label_trace("fir_filter", signals.clone()).len()),
+                                      ("jacobi", system.clone(), label_trace("jacobi", system).len())] {
+            let variables = AlgorithmVariables::from_target(target, data);
+            let (tx_1, rx_1) = channel();
+            let (tx_2, rx_2) = channel();
+            let entry = FaultListEntry{ var: "n".to_string(), time: 0, flipped_bit: 0, weight: 1 };
+            let handle = thread::spawn(move || runner(variables, entry, tx_1, rx_2));
+            let mut observed = 0;
+            while rx_1.recv().is_ok() {
+                observed += 1;
+                tx_2.send("ricevuto").unwrap();
+            }
+            assert!(matches!(handle.join().unwrap().get_result(), Ok(Output::Real(_))), "{}", target);
+            assert_eq!(observed, count, "{}", target);
+        }
+    }
+
+    #[test]
+    fn test_inject_real_variable(){
+        let vectors = Data::Reals(vec![1.0, -2.0, 0.5, 4.0], vec![0.5, 0.25, 3.0, -1.0]);
+        let signals = Data::Reals(vec![1.0, -2.0, 0.5, 4.0, 3.0], vec![0.5, 0.25, 0.25]);
+        //Flip del bit di segno di acc subito dopo la sua inizializzazione a 0.0: rilevato al primo accumulo
+        let entry = FaultListEntry{ var: "acc".to_string(), time: 2, flipped_bit: 63, weight: 1 };
+        let result = run_single_injection("dot_product", vectors, entry);
+        assert!(matches!(result.get_result(), Err(IncoherenceError::AddFail)));
+
+        //Un campione di ingresso già consumato non viene più letto: il fault non ha effetto
+        let entry = FaultListEntry{ var: "x[0]".to_string(), time: 40, flipped_bit: 60, weight: 1 };
+        let result = run_single_injection("fir_filter", signals, entry);
+        assert_eq!(result.get_result().unwrap().into_reals(), vec![0.5, -0.75, 0.0, 1.625, 2.625]);
+    }
+
+    #[test]
+    fn test_bytes_count_matches_runner(){
+        let message = Data::Bytes(b"123456789".to_vec());
+        let text = Data::Text("abababcabab".to_string(), "abab".to_string());
+        for (target, data, count) in [("crc32", message.clone(), label_trace("crc32", message).len()),
+                                      ("kmp", text.clone(), label_trace("kmp", text).len())] {
+            let variables = AlgorithmVariables::from_target(target, data);
+            let (tx_1, rx_1) = channel();
+            let (tx_2, rx_2) = channel();
+            let entry = FaultListEntry{ var: "n".to_string(), time: 0, flipped_bit: 0, weight: 1 };
+            let handle = thread::spawn(move || runner(variables, entry, tx_1, rx_2));
+            let mut observed = 0;
+            while rx_1.recv().is_ok() {
+                observed += 1;
+                tx_2.send("ricevuto").unwrap();
+            }
+            match handle.join().unwrap().get_result() {
+                Ok(Output::Word(crc)) => assert_eq!(crc, Hardened::from_vec(vec![0xCBF43926])),
+                Ok(Output::Index(pos)) => assert_eq!(pos, Hardened::from_vec(vec![0, 2, 7])),
+                other => panic!("{}: {:?}", target, other),
+            }
+            assert_eq!(observed, count, "{}", target);
+        }
+    }
 
+    #[test]
+    fn test_inject_bytes_variable(){
+        let message = Data::Bytes(b"abc".to_vec());
+        //Istante in cui la tabella di lookup è completa e il CRC non è ancora inizializzato
+        let table_done = 2 + 256 * 29;
+        //La prima entry letta è quella di indice (0xFF ^ 'a'): il fault viene rilevato
+        let entry = FaultListEntry{ var: "table[158]".to_string(), time: table_done, flipped_bit: 31, weight: 1 };
+        let result = run_single_injection("crc32", message.clone(), entry);
+        assert!(matches!(result.get_result(), Err(IncoherenceError::BitFail)));
+
+        //Un'entry mai letta durante il calcolo non altera il risultato
+        let entry = FaultListEntry{ var: "table[0]".to_string(), time: table_done, flipped_bit: 31, weight: 1 };
+        let result = run_single_injection("crc32", message, entry);
+        assert_eq!(result.get_result().unwrap(), Output::Word(Hardened::from_vec(vec![0x352441C2])));
+
+        //Il confronto tra un carattere del pattern e uno del testo corrotto fallisce
+        let text = Data::Text("abababcabab".to_string(), "abab".to_string());
+        let entry = FaultListEntry{ var: "text[2]".to_string(), time: 1, flipped_bit: 1, weight: 1 };
+        let result = run_single_injection("kmp", text.clone(), entry);
+        assert!(matches!(result.get_result(), Err(IncoherenceError::PartialEqFail)));
+
+        //Una posizione già scritta nell'output non viene più letta: il fault è silenzioso
+        //ma l'output è errato
+        let entry = FaultListEntry{ var: "matches[0]".to_string(), time: label_trace("kmp", text.clone()).len(), flipped_bit: 3, weight: 1 };
+        let result = run_single_injection("kmp", text, entry);
+        match result.get_result() {
+            Ok(Output::Index(pos)) => assert_eq!(pos.into_nested_vec(), vec![8, 2, 7]),
+            other => panic!("{:?}", other),
+        }
+    }
 
+    #[test]
+    fn test_par_count_matches_runner(){
+        //con 4 righe e 3 worker almeno un worker calcola più di una riga
+        for size in [2, 4] {
+            let a: Vec<Vec<i32>> = (0..size).map(|i| (0..size).map(|j| (i * size + j) as i32 - 3).collect()).collect();
+            let b: Vec<Vec<i32>> = (0..size).map(|i| (0..size).map(|j| (i + 2 * j) as i32).collect()).collect();
+            let expected: Vec<i32> = (0..size).flat_map(|i| (0..size).map(|j| (0..size).map(|k| a[i][k] * b[k][j]).sum::<i32>()).collect::<Vec<_>>()).collect();
+            let data = Matrices(a, b);
+            let count = label_trace("par_matrix_multiplication", data.clone()).len();
+            let variables = AlgorithmVariables::from_target("par_matrix_multiplication", data);
+            let (tx_1, rx_1) = channel();
+            let (tx_2, rx_2) = channel();
+            let entry = FaultListEntry{ var: "next_row".to_string(), time: 0, flipped_bit: 0, weight: 1 };
+            let handle = thread::spawn(move || runner(variables, entry, tx_1, rx_2));
+            let mut observed = 0;
+            while rx_1.recv().is_ok() {
+                observed += 1;
+                tx_2.send("ricevuto").unwrap();
+            }
+            let result = handle.join().unwrap();
+            assert!(result.get_detection().is_none());
+            assert_eq!(result.get_result().unwrap(), Output::Int(Hardened::from_vec(expected)));
+            assert_eq!(observed, count, "{}x{}", size, size);
         }
     }
 
+    #[test]
+    fn test_inject_par_variable(){
+        //2x2 con 3 worker: i1, i2, size dei tre worker (3..5), il worker 0 preleva la riga 0 (6)
+        //e aggiorna il contatore (7), poi il worker 1 preleva la riga 1 (8)
+        let data = Matrices(vec![vec![1, 2], vec![3, 4]], vec![vec![5, 6], vec![7, 8]]);
+
+        //Il contatore scritto dal worker 0 viene letto dal worker 1
+        let entry = FaultListEntry{ var: "next_row".to_string(), time: 7, flipped_bit: 0, weight: 1 };
+        let result = run_single_injection("par_matrix_multiplication", data.clone(), entry);
+        assert!(matches!(result.get_result(), Err(IncoherenceError::InnerFail)));
+        let detection = result.get_detection().unwrap();
+        assert_eq!(detection, Detection{ writer: Actor::Worker(0), reader: Actor::Worker(1) });
+        assert!(detection.is_cross_thread());
+
+        //Una variabile locale viene letta solo dal worker che la possiede (acc = 0 all'istante 14)
+        let entry = FaultListEntry{ var: "acc@0".to_string(), time: 14, flipped_bit: 2, weight: 1 };
+        let result = run_single_injection("par_matrix_multiplication", data.clone(), entry);
+        assert!(result.get_result().is_err());
+        let detection = result.get_detection().unwrap();
+        assert_eq!(detection, Detection{ writer: Actor::Worker(0), reader: Actor::Worker(0) });
+        assert!(!detection.is_cross_thread());
+
+        //Un elemento del risultato corrotto dopo l'ultima istruzione viene rilevato dal thread principale
+        let entry = FaultListEntry{ var: "result[0][0]".to_string(), time: label_trace("par_matrix_multiplication", data.clone()).len(), flipped_bit: 1, weight: 1 };
+        let result = run_single_injection("par_matrix_multiplication", data, entry);
+        assert!(matches!(result.get_result(), Err(IncoherenceError::InnerFail)));
+        assert_eq!(result.get_detection(), Some(Detection{ writer: Actor::Worker(0), reader: Actor::Main }));
+    }
+
+    #[test]
+    fn test_plugin_runner_injection(){
+        let plugin = crate::plugin::sample_plugin();
+        let vet = vec![10, 15, 27, -9, 19];
+        //un elemento del vettore corrotto prima del primo confronto viene rilevato
+        let entry = FaultListEntry{ var: "vet[0]".to_string(), time: 5, flipped_bit: 2, weight: 1 };
+        let result = run_single_injection(plugin.name(), Vector(vet.clone()), entry);
+        assert!(matches!(result.get_result(), Err(IncoherenceError::InnerFail)));
+
+        //dopo l'ultima istruzione il fault non viene più rilevato ma altera l'output
+        let entry = FaultListEntry{ var: "vet[0]".to_string(), time: plugin.count_steps(&vet).unwrap(), flipped_bit: 0, weight: 1 };
+        let result = run_single_injection(plugin.name(), Vector(vet.clone()), entry);
+        assert_eq!(result.get_result().unwrap().into_ints(), vec![-10, 10, 15, 19, 27]);
+    }
+    }
+
 /*
 let size = Hardened::from(a.len());
     let mut result = vec![vec![Hardened::from(0); size.inner()?]; size.inner()?];
@@ -0,0 +1,223 @@
+//! Corrispondenza tra le etichette inviate dai runner e le istruzioni del sorgente analizzato.
+//!
+//! I runner inviano "iK" dopo ogni passo, ma K non è sempre il numero dell'istruzione assegnato
+//! da StatementCounter: alcune istruzioni (un break, la condizione falsa di un if) vengono
+//! eseguite senza etichetta e alcune etichette non hanno un'istruzione corrispondente (la
+//! gestione dei frame delle chiamate ricorsive). Le tabelle sono scritte a mano per ogni caso di
+//! studio e verificate dai test sul grafo di controllo di flusso della funzione analizzata.
+
+use crate::fault_list_manager::control_flow::ControlFlowGraph;
+
+///Etichette del runner di un caso di studio
+#[derive(Debug, Clone, Copy)]
+pub struct RunnerLabels{
+    ///per ogni etichetta, l'istruzione della funzione analizzata (vedi target_function) appena
+    /// eseguita; None per le etichette di servizio del runner
+    pub statements: &'static [(&'static str, Option<usize>)],
+    ///true se la traccia alterna le etichette di più thread: due etichette consecutive possono
+    /// appartenere a esecuzioni diverse della funzione
+    pub interleaved: bool,
+}
+
+impl RunnerLabels{
+    ///Istruzione eseguita dal runner prima di inviare 'label'
+    pub fn statement(&self, label: &str)->Option<usize>{
+        self.statements.iter().find(|(l, _)| *l == label).and_then(|&(_, k)| k)
+    }
+
+    ///true se nessuna etichetta del runner corrisponde all'istruzione k
+    pub fn silent(&self, k: usize)->bool{
+        self.statements.iter().all(|&(_, s)| s != Some(k))
+    }
+
+    ///Istruzioni eseguite dopo 'from' fino a 'to' compresa (None: ingresso e uscita della
+    /// funzione). Tra due etichette il runner può eseguire solo istruzioni senza etichetta o
+    /// condizioni di if e cicli, che inviano l'etichetta solo quando sono vere
+    pub fn path(&self, cfg: &ControlFlowGraph, from: Option<usize>, to: Option<usize>)->Option<Vec<usize>>{
+        cfg.path(from, to, |k| self.silent(k) || cfg.branches(k))
+    }
+}
+
+///Tabella delle etichette del caso di studio, None per i target senza runner dedicato
+pub fn runner_labels(target: &str)->Option<RunnerLabels>{
+    let statements = match target {
+        "sel_sort" => SELECTION_SORT,
+        "bubble_sort" => BUBBLE_SORT,
+        "matrix_multiplication" => MATRIX_MULTIPLICATION,
+        "quick_sort" => QUICK_SORT,
+        "merge_sort" => MERGE_SORT,
+        "bfs" => BFS,
+        "dijkstra" => DIJKSTRA,
+        "dot_product" => DOT_PRODUCT,
+        "fir_filter" => FIR_FILTER,
+        "jacobi" => JACOBI,
+        "crc32" => CRC32,
+        "kmp" => KMP,
+        "par_matrix_multiplication" => PAR_MATRIX_MULTIPLICATION,
+        _ => return None,
+    };
+    Some(RunnerLabels{ statements, interleaved: target == "par_matrix_multiplication" })
+}
+
+const SELECTION_SORT: &[(&str, Option<usize>)] = &[
+    ("i1", Some(1)), ("i2", Some(2)), ("i3", Some(3)), ("i4", Some(4)), ("i5", Some(5)), ("i6", Some(6)),
+    ("i7", Some(7)), ("i8", Some(8)), ("i9", Some(9)), ("i10", Some(10)), ("i11", Some(11)), ("i12", Some(12)),
+    ("i13", Some(13)),
+];
+
+//il break (istruzione 12) esce dal ciclo senza etichetta
+const BUBBLE_SORT: &[(&str, Option<usize>)] = &[
+    ("i1", Some(1)), ("i2", Some(2)), ("i3", Some(3)), ("i4", Some(4)), ("i5", Some(5)), ("i6", Some(6)),
+    ("i7", Some(7)), ("i8", Some(8)), ("i9", Some(9)), ("i10", Some(10)), ("i11", Some(11)), ("i12", Some(13)),
+];
+
+const MATRIX_MULTIPLICATION: &[(&str, Option<usize>)] = &[
+    ("i1", Some(1)), ("i2", Some(2)), ("i3", Some(3)), ("i4", Some(4)), ("i5", Some(5)), ("i6", Some(6)),
+    ("i7", Some(7)), ("i8", Some(8)), ("i9", Some(9)), ("i10", Some(10)), ("i11", Some(11)), ("i12", Some(12)),
+    ("i13", Some(13)), ("i14", Some(14)), ("i15", Some(15)), ("i16", Some(16)),
+];
+
+//i1-i4 preparano i frame in quick_sort, i14-i15 chiudono le chiamate ricorsive
+const QUICK_SORT: &[(&str, Option<usize>)] = &[
+    ("i1", None), ("i2", None), ("i3", None), ("i4", None), ("i5", Some(1)), ("i6", Some(2)),
+    ("i7", Some(3)), ("i8", Some(4)), ("i9", Some(5)), ("i10", Some(6)), ("i11", Some(7)), ("i12", Some(8)),
+    ("i13", Some(9)), ("i14", None), ("i15", None),
+];
+
+//i1-i5 e i19-i20 gestiscono vettore ausiliario e frame; l'if dell'istruzione 9 e il while
+//dell'istruzione 16 non hanno etichetta
+const MERGE_SORT: &[(&str, Option<usize>)] = &[
+    ("i1", None), ("i2", None), ("i3", None), ("i4", None), ("i5", None), ("i6", Some(2)),
+    ("i7", Some(5)), ("i8", Some(6)), ("i9", Some(7)), ("i10", Some(8)), ("i11", Some(10)), ("i12", Some(11)),
+    ("i13", Some(12)), ("i14", Some(13)), ("i15", Some(14)), ("i16", Some(15)), ("i17", Some(17)), ("i18", Some(18)),
+    ("i19", None), ("i20", None),
+];
+
+const BFS: &[(&str, Option<usize>)] = &[
+    ("i1", Some(1)), ("i2", Some(2)), ("i3", Some(3)), ("i4", Some(4)), ("i5", Some(5)), ("i6", Some(6)),
+    ("i7", Some(7)), ("i8", Some(8)), ("i9", Some(9)), ("i10", Some(10)), ("i11", Some(11)), ("i12", Some(12)),
+    ("i13", Some(13)), ("i14", Some(14)), ("i15", Some(15)), ("i16", Some(16)), ("i17", Some(17)), ("i18", Some(18)),
+    ("i19", Some(19)),
+];
+
+const DIJKSTRA: &[(&str, Option<usize>)] = &[
+    ("i1", Some(1)), ("i2", Some(2)), ("i3", Some(3)), ("i4", Some(4)), ("i5", Some(5)), ("i6", Some(6)),
+    ("i7", Some(7)), ("i8", Some(8)), ("i9", Some(9)), ("i10", Some(10)), ("i11", Some(11)), ("i12", Some(12)),
+    ("i13", Some(13)), ("i14", Some(14)), ("i15", Some(15)), ("i16", Some(16)), ("i17", Some(17)), ("i18", Some(18)),
+    ("i19", Some(19)), ("i20", Some(20)), ("i21", Some(21)), ("i22", Some(22)), ("i23", Some(23)), ("i24", Some(24)),
+    ("i25", Some(25)), ("i26", Some(26)), ("i27", Some(27)), ("i28", Some(28)),
+];
+
+const DOT_PRODUCT: &[(&str, Option<usize>)] = &[
+    ("i1", Some(1)), ("i2", Some(2)), ("i3", Some(3)), ("i4", Some(4)), ("i5", Some(5)), ("i6", Some(6)),
+];
+
+const FIR_FILTER: &[(&str, Option<usize>)] = &[
+    ("i1", Some(1)), ("i2", Some(2)), ("i3", Some(3)), ("i4", Some(4)), ("i5", Some(5)), ("i6", Some(6)),
+    ("i7", Some(7)), ("i8", Some(8)), ("i9", Some(9)), ("i10", Some(10)), ("i11", Some(11)), ("i12", Some(12)),
+];
+
+const JACOBI: &[(&str, Option<usize>)] = &[
+    ("i1", Some(1)), ("i2", Some(2)), ("i3", Some(3)), ("i4", Some(4)), ("i5", Some(5)), ("i6", Some(6)),
+    ("i7", Some(7)), ("i8", Some(8)), ("i9", Some(9)), ("i10", Some(10)), ("i11", Some(11)), ("i12", Some(12)),
+    ("i13", Some(13)), ("i14", Some(14)), ("i15", Some(15)), ("i16", Some(16)), ("i17", Some(17)), ("i18", Some(18)),
+];
+
+//i7 è l'intero if/else di aggiornamento di c (istruzioni 7-9)
+const CRC32: &[(&str, Option<usize>)] = &[
+    ("i1", Some(1)), ("i2", Some(2)), ("i3", Some(3)), ("i4", Some(4)), ("i5", Some(5)), ("i6", Some(6)),
+    ("i7", Some(7)), ("i8", Some(10)), ("i9", Some(11)), ("i10", Some(12)), ("i11", Some(13)), ("i12", Some(14)),
+    ("i13", Some(15)), ("i14", Some(16)), ("i15", Some(17)), ("i16", Some(18)), ("i17", Some(19)),
+];
+
+//l'if di m == 0, i cicli sui fallimenti e i confronti senza ramo else non hanno etichetta
+const KMP: &[(&str, Option<usize>)] = &[
+    ("i1", Some(1)), ("i2", Some(2)), ("i3", Some(5)), ("i4", Some(6)), ("i5", Some(7)), ("i6", Some(8)),
+    ("i7", Some(10)), ("i8", Some(12)), ("i9", Some(13)), ("i10", Some(14)), ("i11", Some(15)), ("i12", Some(16)),
+    ("i13", Some(17)), ("i14", Some(18)), ("i15", Some(20)), ("i16", Some(22)), ("i17", Some(24)), ("i18", Some(25)),
+    ("i19", Some(26)),
+];
+
+//i1-i2 sono del thread principale; i4 legge la prossima riga da calcolare, i5 la prenota
+const PAR_MATRIX_MULTIPLICATION: &[(&str, Option<usize>)] = &[
+    ("i1", None), ("i2", None), ("i3", Some(1)), ("i4", Some(4)), ("i5", Some(7)), ("i6", Some(9)),
+    ("i7", Some(10)), ("i8", Some(11)), ("i9", Some(12)), ("i10", Some(13)), ("i11", Some(14)), ("i12", Some(15)),
+    ("i13", Some(16)), ("i14", Some(17)),
+];
+
+#[cfg(test)]
+mod tests{
+    use std::collections::BTreeSet;
+    use std::fs;
+    use crate::fault_env::Data;
+    use crate::fault_list_manager::control_flow::control_flow_graphs;
+    use crate::fault_list_manager::target_function;
+    use crate::injector::label_trace;
+    use crate::injector::labels::runner_labels;
+
+    fn cases()->Vec<(&'static str, &'static str, Data<i32>)>{
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        let matrix = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let graph = Data::Graph(vec![vec![(1, 4), (2, 1)], vec![(3, 1)], vec![(1, 2), (3, 5)], vec![(4, 3)], vec![], vec![(0, 1)]], 0);
+        vec![
+            ("sel_sort", "selection_sort", Data::Vector(vet.clone())),
+            ("bubble_sort", "bubble_sort", Data::Vector(vet.clone())),
+            ("matrix_multiplication", "matrix_multiplication", Data::Matrices(matrix.clone(), matrix.clone())),
+            ("quick_sort", "quick_sort", Data::Vector(vet.clone())),
+            ("merge_sort", "merge_sort", Data::Vector(vet)),
+            ("bfs", "bfs", graph.clone()),
+            ("dijkstra", "dijkstra", graph),
+            ("dot_product", "dot_product", Data::Reals(vec![1.0, -2.0, 0.5, 4.0], vec![0.5, 0.25, 3.0, -1.0])),
+            ("fir_filter", "fir_filter", Data::Reals(vec![1.0, -2.0, 0.5, 4.0, 3.0], vec![0.5, 0.25, 0.25])),
+            ("jacobi", "jacobi", Data::LinearSystem(vec![vec![4.0, 1.0, 1.0], vec![1.0, 5.0, 2.0], vec![1.0, 2.0, 6.0]], vec![5.0, 9.0, -1.0])),
+            ("crc32", "crc32", Data::Bytes(b"123456789".to_vec())),
+            ("kmp", "kmp", Data::Text("abaababaab".to_string(), "abaab".to_string())),
+            ("par_matrix_multiplication", "par_matrix_multiplication", Data::Matrices(matrix.clone(), matrix)),
+        ]
+    }
+
+    #[test]
+    fn test_labels_follow_control_flow(){
+        for (target, dir, data) in cases() {
+            let labels = runner_labels(target).unwrap();
+            let code = fs::read_to_string(format!("src/fault_list_manager/file_fault_list/{}/mod.rs", dir)).unwrap();
+            let cfg = control_flow_graphs(&code).unwrap().remove(target_function(target)).unwrap();
+            for &(label, k) in labels.statements {
+                assert!(k.is_none_or(|k| k <= cfg.statements.len()), "{} {}", target, label);
+            }
+
+            let trace = label_trace(target, data);
+            let fired: BTreeSet<&str> = trace.iter().copied().collect();
+            let listed: BTreeSet<&str> = labels.statements.iter().map(|&(label, _)| label).collect();
+            assert_eq!(fired, listed, "{}", target);
+            if labels.interleaved {
+                continue;
+            }
+            //ogni etichetta deve essere raggiungibile dalla precedente attraverso istruzioni
+            //eseguite senza etichetta; dopo un'etichetta di servizio si riparte da capo
+            let mut prev: Option<Option<usize>> = Some(None);
+            for (t, label) in trace.iter().enumerate() {
+                let k = labels.statement(label);
+                if let (Some(from), Some(_)) = (prev, k) {
+                    assert!(labels.path(&cfg, from, k).is_some(), "{} istante {}: {:?} -> {}", target, t + 1, from, label);
+                }
+                prev = k.map(Some);
+            }
+            if let Some(from) = prev {
+                assert!(labels.path(&cfg, from, None).is_some(), "{}: {:?} -> uscita", target, from);
+            }
+        }
+    }
+
+    #[test]
+    fn test_identity_map_breaks_bubble_sort(){
+        //con la numerazione di StatementCounter l'etichetta i12 sarebbe il break, da cui non si
+        //torna all'inizio del ciclo esterno
+        let code = fs::read_to_string("src/fault_list_manager/file_fault_list/bubble_sort/mod.rs").unwrap();
+        let cfg = control_flow_graphs(&code).unwrap().remove("bubble_sort").unwrap();
+        let labels = runner_labels("bubble_sort").unwrap();
+        assert_eq!(labels.statement("i12"), Some(13));
+        assert!(cfg.path(Some(12), Some(3), |k| labels.silent(k) || cfg.branches(k)).is_none());
+        assert!(labels.path(&cfg, Some(13), Some(3)).is_some());
+    }
+}
@@ -1,34 +1,109 @@
 mod algorithms;
+pub mod labels;
 
-use std::sync::{Arc, RwLock};
-use std::sync::mpsc::{channel, Receiver, Sender};
-use std::{panic, thread, vec};
+use std::sync::{Arc, Mutex, RwLock};
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, Sender, SyncSender};
+use std::thread::JoinHandle;
+use std::{panic, thread};
+use std::fmt::Debug;
 use crate::fault_list_manager::FaultListEntry;
-use crate::hardened::{Hardened, IncoherenceError};
+use crate::hardened::{Coherent, Hardened, IncoherenceError, IntoNestedVec};
 use algorithms::{runner_selection_sort};
-use crate::fault_env::Data;
-use crate::injector::algorithms::{runner_bubble_sort, runner_matrix_multiplication};
+use crate::fault_env::{AdjacencyList, Data};
+use crate::injector::algorithms::{runner_bubble_sort, runner_matrix_multiplication, runner_quick_sort, runner_merge_sort, runner_bfs, runner_dijkstra,
+                                        runner_dot_product, runner_fir_filter, runner_jacobi, runner_crc32, runner_kmp,
+                                        runner_par_matrix_multiplication, runner_plugin};
+use crate::plugin::{self, PluginInstance};
+use crate::hardened::par_matrix_multiplication_hardened::PAR_WORKERS;
 use crate::VERBOSE;
 
+///Output prodotto da un runner: i casi di studio interi restituiscono un vettore di i32,
+/// i kernel numerici un vettore di f64, il CRC32 una parola a 32 bit e la ricerca di
+/// sottostringhe le posizioni delle occorrenze
+#[derive(Debug, Clone, PartialEq)]
+pub enum Output {
+    Int(Vec<Hardened<i32>>),
+    Real(Vec<Hardened<f64>>),
+    Word(Vec<Hardened<u32>>),
+    Index(Vec<Hardened<usize>>),
+}
+
+#[allow(dead_code)]
+impl Output {
+    pub fn into_ints(self) -> Vec<i32> {
+        match self {
+            Output::Int(v) => v.into_nested_vec(),
+            _ => panic!("Not an integer output"),
+        }
+    }
+
+    pub fn into_reals(self) -> Vec<f64> {
+        match self {
+            Output::Real(v) => v.into_nested_vec(),
+            _ => panic!("Not a real output"),
+        }
+    }
+}
+
+///Thread di un target parallelo: il thread principale (che inizializza e raccoglie i dati
+/// condivisi) oppure uno dei worker
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Actor {
+    Main,
+    Worker(usize),
+}
+
+///Per i target paralleli: thread che aveva scritto per ultimo la variabile iniettata e thread
+/// che ha rilevato l'incoerenza leggendola
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection {
+    pub writer: Actor,
+    pub reader: Actor,
+}
+
+impl Detection {
+    pub fn is_cross_thread(&self) -> bool {
+        self.writer != self.reader
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub struct TestResult {
     fault_list_entry: FaultListEntry,
-    result: Result<Vec<Hardened<i32>>, IncoherenceError>
+    result: Result<Output, IncoherenceError>,
+    detection: Option<Detection>,       //solo per i target paralleli con fault rilevato
 }
 impl TestResult {
-    pub fn get_result(&self) -> Result<Vec<Hardened<i32>>, IncoherenceError> {
+    pub fn get_result(&self) -> Result<Output, IncoherenceError> {
         self.result.clone()
     }
 
     pub fn get_fault_list_entry(&self) -> FaultListEntry {
         self.fault_list_entry.clone()
     }
+
+    pub fn get_detection(&self) -> Option<Detection> {
+        self.detection
+    }
 }
 enum AlgorithmVariables {
     SelectionSort(SelectionSortVariables),
     BubbleSort(BubbleSortVariables),
     MatrixMultiplication(MatrixMultiplicationVariables),
+    QuickSort(QuickSortVariables),
+    MergeSort(MergeSortVariables),
+    Bfs(BfsVariables),
+    Dijkstra(DijkstraVariables),
+    DotProduct(DotProductVariables),
+    FirFilter(FirFilterVariables),
+    Jacobi(JacobiVariables),
+    Crc32(Crc32Variables),
+    Kmp(KmpVariables),
+    ParMatrixMultiplication(ParMatrixMultiplicationVariables),
+    //target esterno: le variabili vivono nello stato del plugin
+    Plugin(PluginInstance),
 }
 
 struct SelectionSortVariables {
@@ -58,6 +133,221 @@ struct MatrixMultiplicationVariables {
     result: RwLock<Vec<Vec<Hardened<i32>>>>
 }
 
+//Negli algoritmi ricorsivi le variabili locali esistono una volta per ogni chiamata attiva:
+//ogni chiamata aggiunge un frame in cima allo stack e lo rimuove al ritorno.
+//Nella fault list la variabile 'x' del frame di profondità d è indicata come "x@d".
+#[derive(Clone, Copy)]
+struct QuickSortFrame {
+    lo: Hardened<usize>,
+    hi: Hardened<usize>,
+    pivot: Hardened<i32>,
+    i: Hardened<usize>,
+    j: Hardened<usize>,
+}
+
+struct QuickSortVariables {
+    n: RwLock<Hardened<usize>>,
+    depth: RwLock<Hardened<usize>>,
+    frames: RwLock<Vec<QuickSortFrame>>,
+    vet: RwLock<Vec<Hardened<i32>>>,
+}
+
+#[derive(Clone, Copy)]
+struct MergeSortFrame {
+    lo: Hardened<usize>,
+    hi: Hardened<usize>,
+    mid: Hardened<usize>,
+    i: Hardened<usize>,
+    j: Hardened<usize>,
+    k: Hardened<usize>,
+}
+
+struct MergeSortVariables {
+    n: RwLock<Hardened<usize>>,
+    depth: RwLock<Hardened<usize>>,
+    frames: RwLock<Vec<MergeSortFrame>>,
+    vet: RwLock<Vec<Hardened<i32>>>,
+    aux: RwLock<Vec<Hardened<i32>>>,
+}
+
+//Negli algoritmi su grafo le liste di adiacenza sono un dato di sola lettura: le injection
+//riguardano le distanze, i flag di visita e il contenuto della coda (o della coda di priorità).
+struct BfsVariables {
+    graph: AdjacencyList<i32>,
+    source: RwLock<Hardened<usize>>,
+    n: RwLock<Hardened<usize>>,
+    head: RwLock<Hardened<usize>>,
+    u: RwLock<Hardened<usize>>,
+    e: RwLock<Hardened<usize>>,
+    v: RwLock<Hardened<usize>>,
+    dist: RwLock<Vec<Hardened<i32>>>,
+    visited: RwLock<Vec<Hardened<bool>>>,
+    queue: RwLock<Vec<Hardened<usize>>>,
+}
+
+struct DijkstraVariables {
+    graph: AdjacencyList<i32>,
+    source: RwLock<Hardened<usize>>,
+    n: RwLock<Hardened<usize>>,
+    best: RwLock<Hardened<usize>>,
+    idx: RwLock<Hardened<usize>>,
+    d: RwLock<Hardened<i32>>,
+    u: RwLock<Hardened<usize>>,
+    e: RwLock<Hardened<usize>>,
+    v: RwLock<Hardened<usize>>,
+    alt: RwLock<Hardened<i32>>,
+    dist: RwLock<Vec<Hardened<i32>>>,
+    visited: RwLock<Vec<Hardened<bool>>>,
+    pq_dist: RwLock<Vec<Hardened<i32>>>,
+    pq_node: RwLock<Vec<Hardened<usize>>>,
+}
+
+//Nei kernel numerici i dati sono reali: il bit-flip agisce sulla rappresentazione IEEE 754
+struct DotProductVariables {
+    n: RwLock<Hardened<usize>>,
+    i: RwLock<Hardened<usize>>,
+    acc: RwLock<Hardened<f64>>,
+    x: RwLock<Vec<Hardened<f64>>>,
+    y: RwLock<Vec<Hardened<f64>>>,
+}
+
+struct FirFilterVariables {
+    n: RwLock<Hardened<usize>>,
+    m: RwLock<Hardened<usize>>,
+    i: RwLock<Hardened<usize>>,
+    k: RwLock<Hardened<usize>>,
+    acc: RwLock<Hardened<f64>>,
+    x: RwLock<Vec<Hardened<f64>>>,
+    h: RwLock<Vec<Hardened<f64>>>,
+    y: RwLock<Vec<Hardened<f64>>>,
+}
+
+struct JacobiVariables {
+    n: RwLock<Hardened<usize>>,
+    iterations: RwLock<Hardened<usize>>,
+    it: RwLock<Hardened<usize>>,
+    i: RwLock<Hardened<usize>>,
+    j: RwLock<Hardened<usize>>,
+    sigma: RwLock<Hardened<f64>>,
+    a: RwLock<Vec<Vec<Hardened<f64>>>>,
+    b: RwLock<Vec<Hardened<f64>>>,
+    x: RwLock<Vec<Hardened<f64>>>,
+    x_new: RwLock<Vec<Hardened<f64>>>,
+}
+
+//Nei casi di studio su byte il bit-flip agisce su u8 (dati e pattern) e su u32 (tabella e CRC)
+struct Crc32Variables {
+    n: RwLock<Hardened<usize>>,
+    c: RwLock<Hardened<u32>>,
+    k: RwLock<Hardened<usize>>,
+    crc: RwLock<Hardened<u32>>,
+    i: RwLock<Hardened<usize>>,
+    byte: RwLock<Hardened<u8>>,
+    idx: RwLock<Hardened<usize>>,
+    data: RwLock<Vec<Hardened<u8>>>,
+    table: RwLock<Vec<Hardened<u32>>>,
+}
+
+struct KmpVariables {
+    n: RwLock<Hardened<usize>>,
+    m: RwLock<Hardened<usize>>,
+    k: RwLock<Hardened<usize>>,
+    q: RwLock<Hardened<usize>>,
+    j: RwLock<Hardened<usize>>,
+    i: RwLock<Hardened<usize>>,
+    text: RwLock<Vec<Hardened<u8>>>,
+    pattern: RwLock<Vec<Hardened<u8>>>,
+    fail: RwLock<Vec<Hardened<usize>>>,
+    matches: RwLock<Vec<Hardened<usize>>>,
+}
+
+//Variabili locali di un worker della moltiplicazione parallela
+struct WorkerVariables {
+    size: RwLock<Hardened<usize>>,
+    row: RwLock<Hardened<usize>>,
+    j: RwLock<Hardened<usize>>,
+    k: RwLock<Hardened<usize>>,
+    acc: RwLock<Hardened<i32>>,
+}
+
+struct ParMatrixMultiplicationVariables {
+    //stato condiviso tra i worker
+    next_row: RwLock<Hardened<usize>>,
+    a: RwLock<Vec<Vec<Hardened<i32>>>>,
+    b: RwLock<Vec<Vec<Hardened<i32>>>>,
+    result: RwLock<Vec<Vec<Hardened<i32>>>>,
+    workers: Vec<WorkerVariables>,
+    //sezione critica che rende atomici prelievo e aggiornamento di next_row
+    row_lock: Mutex<()>,
+    //tracciamento degli accessi: ultimo thread che ha scritto il contatore e ogni riga del
+    //risultato, thread che ha scritto la variabile iniettata e thread che ha rilevato il fault
+    next_row_writer: RwLock<Actor>,
+    row_writer: RwLock<Vec<Actor>>,
+    injected_writer: RwLock<Option<Actor>>,
+    detected_by: RwLock<Option<Actor>>,
+}
+
+impl ParMatrixMultiplicationVariables {
+    ///Registra il primo thread che rileva un'incoerenza: i worker avanzano a turno, quindi
+    /// il primo a registrarsi è anche il primo in ordine di tempo
+    fn detected(&self, reader: Actor) {
+        self.detected_by.write().unwrap().get_or_insert(reader);
+    }
+
+    fn detection(&self) -> Option<Detection> {
+        let writer = (*self.injected_writer.read().unwrap())?;
+        let reader = (*self.detected_by.read().unwrap())?;
+        Some(Detection { writer, reader })
+    }
+}
+
+impl QuickSortFrame {
+    fn new(lo: Hardened<usize>, hi: Hardened<usize>) -> Self {
+        QuickSortFrame { lo, hi, pivot: Hardened::from(0), i: Hardened::from(0), j: Hardened::from(0) }
+    }
+
+    fn var_mut(&mut self, name: &str) -> Option<&mut Hardened<usize>> {
+        match name {
+            "lo" => Some(&mut self.lo),
+            "hi" => Some(&mut self.hi),
+            "i" => Some(&mut self.i),
+            "j" => Some(&mut self.j),
+            _ => None,
+        }
+    }
+}
+
+impl MergeSortFrame {
+    fn new(lo: Hardened<usize>, hi: Hardened<usize>) -> Self {
+        MergeSortFrame { lo, hi, mid: Hardened::from(0), i: Hardened::from(0), j: Hardened::from(0), k: Hardened::from(0) }
+    }
+
+    fn var_mut(&mut self, name: &str) -> Option<&mut Hardened<usize>> {
+        match name {
+            "lo" => Some(&mut self.lo),
+            "hi" => Some(&mut self.hi),
+            "mid" => Some(&mut self.mid),
+            "i" => Some(&mut self.i),
+            "j" => Some(&mut self.j),
+            "k" => Some(&mut self.k),
+            _ => None,
+        }
+    }
+}
+
+impl QuickSortVariables {
+    ///Copia del frame di profondità d (le copie cp1/cp2 vengono preservate)
+    fn frame(&self, d: usize) -> QuickSortFrame {
+        self.frames.read().unwrap()[d]
+    }
+}
+
+impl MergeSortVariables {
+    fn frame(&self, d: usize) -> MergeSortFrame {
+        self.frames.read().unwrap()[d]
+    }
+}
+
 // Common initialization trait
 trait VariableSet {
     type Input;
@@ -106,6 +396,176 @@ impl VariableSet for MatrixMultiplicationVariables {
     }
 }
 
+impl VariableSet for QuickSortVariables {
+    type Input = Vec<i32>;
+    fn new(vet: Vec<i32>) -> Self {
+        QuickSortVariables {
+            n: RwLock::new(Hardened::from(0)),
+            depth: RwLock::new(Hardened::from(0)),
+            frames: RwLock::new(Vec::new()),
+            vet: RwLock::new(Hardened::from_vec(vet))
+        }
+    }
+}
+
+impl VariableSet for MergeSortVariables {
+    type Input = Vec<i32>;
+    fn new(vet: Vec<i32>) -> Self {
+        MergeSortVariables {
+            n: RwLock::new(Hardened::from(0)),
+            depth: RwLock::new(Hardened::from(0)),
+            frames: RwLock::new(Vec::new()),
+            vet: RwLock::new(Hardened::from_vec(vet)),
+            aux: RwLock::new(Vec::new())
+        }
+    }
+}
+
+impl VariableSet for BfsVariables {
+    type Input = (AdjacencyList<i32>, usize);
+    fn new((graph, source): (AdjacencyList<i32>, usize)) -> Self {
+        BfsVariables {
+            graph,
+            source: RwLock::new(Hardened::from(source)),
+            n: RwLock::new(Hardened::from(0)),
+            head: RwLock::new(Hardened::from(0)),
+            u: RwLock::new(Hardened::from(0)),
+            e: RwLock::new(Hardened::from(0)),
+            v: RwLock::new(Hardened::from(0)),
+            dist: RwLock::new(Vec::new()),
+            visited: RwLock::new(Vec::new()),
+            queue: RwLock::new(Vec::new())
+        }
+    }
+}
+
+impl VariableSet for DijkstraVariables {
+    type Input = (AdjacencyList<i32>, usize);
+    fn new((graph, source): (AdjacencyList<i32>, usize)) -> Self {
+        DijkstraVariables {
+            graph,
+            source: RwLock::new(Hardened::from(source)),
+            n: RwLock::new(Hardened::from(0)),
+            best: RwLock::new(Hardened::from(0)),
+            idx: RwLock::new(Hardened::from(0)),
+            d: RwLock::new(Hardened::from(0)),
+            u: RwLock::new(Hardened::from(0)),
+            e: RwLock::new(Hardened::from(0)),
+            v: RwLock::new(Hardened::from(0)),
+            alt: RwLock::new(Hardened::from(0)),
+            dist: RwLock::new(Vec::new()),
+            visited: RwLock::new(Vec::new()),
+            pq_dist: RwLock::new(Vec::new()),
+            pq_node: RwLock::new(Vec::new())
+        }
+    }
+}
+
+impl VariableSet for DotProductVariables {
+    type Input = (Vec<f64>, Vec<f64>);
+    fn new((x, y): (Vec<f64>, Vec<f64>)) -> Self {
+        DotProductVariables {
+            n: RwLock::new(Hardened::from(0)),
+            i: RwLock::new(Hardened::from(0)),
+            acc: RwLock::new(Hardened::from(0.0)),
+            x: RwLock::new(Hardened::from_vec(x)),
+            y: RwLock::new(Hardened::from_vec(y))
+        }
+    }
+}
+
+impl VariableSet for FirFilterVariables {
+    type Input = (Vec<f64>, Vec<f64>);
+    fn new((x, h): (Vec<f64>, Vec<f64>)) -> Self {
+        FirFilterVariables {
+            n: RwLock::new(Hardened::from(0)),
+            m: RwLock::new(Hardened::from(0)),
+            i: RwLock::new(Hardened::from(0)),
+            k: RwLock::new(Hardened::from(0)),
+            acc: RwLock::new(Hardened::from(0.0)),
+            x: RwLock::new(Hardened::from_vec(x)),
+            h: RwLock::new(Hardened::from_vec(h)),
+            y: RwLock::new(Vec::new())
+        }
+    }
+}
+
+impl VariableSet for JacobiVariables {
+    type Input = (Vec<Vec<f64>>, Vec<f64>);
+    fn new((a, b): (Vec<Vec<f64>>, Vec<f64>)) -> Self {
+        JacobiVariables {
+            n: RwLock::new(Hardened::from(0)),
+            iterations: RwLock::new(Hardened::from(0)),
+            it: RwLock::new(Hardened::from(0)),
+            i: RwLock::new(Hardened::from(0)),
+            j: RwLock::new(Hardened::from(0)),
+            sigma: RwLock::new(Hardened::from(0.0)),
+            a: RwLock::new(Hardened::from_mat(a)),
+            b: RwLock::new(Hardened::from_vec(b)),
+            x: RwLock::new(Vec::new()),
+            x_new: RwLock::new(Vec::new())
+        }
+    }
+}
+
+impl VariableSet for ParMatrixMultiplicationVariables {
+    type Input = (Vec<Vec<i32>>, Vec<Vec<i32>>);
+    fn new((a, b): (Vec<Vec<i32>>, Vec<Vec<i32>>)) -> Self {
+        ParMatrixMultiplicationVariables {
+            next_row: RwLock::new(Hardened::from(0)),
+            a: RwLock::new(Hardened::from_mat(a)),
+            b: RwLock::new(Hardened::from_mat(b)),
+            result: RwLock::new(Vec::new()),
+            workers: (0..PAR_WORKERS).map(|_| WorkerVariables {
+                size: RwLock::new(Hardened::from(0)),
+                row: RwLock::new(Hardened::from(0)),
+                j: RwLock::new(Hardened::from(0)),
+                k: RwLock::new(Hardened::from(0)),
+                acc: RwLock::new(Hardened::from(0))
+            }).collect(),
+            row_lock: Mutex::new(()),
+            next_row_writer: RwLock::new(Actor::Main),
+            row_writer: RwLock::new(Vec::new()),
+            injected_writer: RwLock::new(None),
+            detected_by: RwLock::new(None)
+        }
+    }
+}
+
+impl VariableSet for Crc32Variables {
+    type Input = Vec<u8>;
+    fn new(data: Vec<u8>) -> Self {
+        Crc32Variables {
+            n: RwLock::new(Hardened::from(0)),
+            c: RwLock::new(Hardened::from(0)),
+            k: RwLock::new(Hardened::from(0)),
+            crc: RwLock::new(Hardened::from(0)),
+            i: RwLock::new(Hardened::from(0)),
+            byte: RwLock::new(Hardened::from(0)),
+            idx: RwLock::new(Hardened::from(0)),
+            data: RwLock::new(Hardened::from_vec(data)),
+            table: RwLock::new(Vec::new())
+        }
+    }
+}
+
+impl VariableSet for KmpVariables {
+    type Input = (String, String);
+    fn new((text, pattern): (String, String)) -> Self {
+        KmpVariables {
+            n: RwLock::new(Hardened::from(0)),
+            m: RwLock::new(Hardened::from(0)),
+            k: RwLock::new(Hardened::from(0)),
+            q: RwLock::new(Hardened::from(0)),
+            j: RwLock::new(Hardened::from(0)),
+            i: RwLock::new(Hardened::from(0)),
+            text: RwLock::new(Hardened::from_vec(text.into_bytes())),
+            pattern: RwLock::new(Hardened::from_vec(pattern.into_bytes())),
+            fail: RwLock::new(Vec::new()),
+            matches: RwLock::new(Vec::new())
+        }
+    }
+}
 
 impl AlgorithmVariables {
     fn from_target(target: &str, data: Data<i32>) -> Arc<AlgorithmVariables> {
@@ -113,7 +573,20 @@ impl AlgorithmVariables {
             "sel_sort" => Arc::new(AlgorithmVariables::SelectionSort(SelectionSortVariables::new(data.into_vector()))),
             "bubble_sort" => Arc::new(AlgorithmVariables::BubbleSort(BubbleSortVariables::new(data.into_vector()))),
             "matrix_multiplication" => Arc::new(AlgorithmVariables::MatrixMultiplication(MatrixMultiplicationVariables::new(data.into_matrices()))),
-            _ => panic!("Unknown target algorithm"),
+            "quick_sort" => Arc::new(AlgorithmVariables::QuickSort(QuickSortVariables::new(data.into_vector()))),
+            "merge_sort" => Arc::new(AlgorithmVariables::MergeSort(MergeSortVariables::new(data.into_vector()))),
+            "bfs" => Arc::new(AlgorithmVariables::Bfs(BfsVariables::new(data.into_graph()))),
+            "dijkstra" => Arc::new(AlgorithmVariables::Dijkstra(DijkstraVariables::new(data.into_graph()))),
+            "dot_product" => Arc::new(AlgorithmVariables::DotProduct(DotProductVariables::new(data.into_reals()))),
+            "fir_filter" => Arc::new(AlgorithmVariables::FirFilter(FirFilterVariables::new(data.into_reals()))),
+            "jacobi" => Arc::new(AlgorithmVariables::Jacobi(JacobiVariables::new(data.into_linear_system()))),
+            "crc32" => Arc::new(AlgorithmVariables::Crc32(Crc32Variables::new(data.into_bytes()))),
+            "kmp" => Arc::new(AlgorithmVariables::Kmp(KmpVariables::new(data.into_text()))),
+            "par_matrix_multiplication" => Arc::new(AlgorithmVariables::ParMatrixMultiplication(ParMatrixMultiplicationVariables::new(data.into_matrices()))),
+            name => match plugin::get(name) {
+                Some(plugin) => Arc::new(AlgorithmVariables::Plugin(plugin.instance(&data.into_vector()))),
+                None => panic!("Unknown target algorithm"),
+            },
         }
     }
 }
@@ -123,37 +596,74 @@ fn runner(variables: Arc<AlgorithmVariables>, fault_list_entry: FaultListEntry,
     let result = panic::catch_unwind(|| {
         match &*variables {
             AlgorithmVariables::SelectionSort(var) => {
-                runner_selection_sort(var, tx_runner, rx_runner)
+                runner_selection_sort(var, tx_runner, rx_runner).map(Output::Int)
             }
             AlgorithmVariables::BubbleSort(var) => {
-                runner_bubble_sort(var, tx_runner, rx_runner)
+                runner_bubble_sort(var, tx_runner, rx_runner).map(Output::Int)
             }
             AlgorithmVariables::MatrixMultiplication(var) => {
-                runner_matrix_multiplication(var, tx_runner, rx_runner)
+                runner_matrix_multiplication(var, tx_runner, rx_runner).map(Output::Int)
+            }
+            AlgorithmVariables::QuickSort(var) => {
+                runner_quick_sort(var, tx_runner, rx_runner).map(Output::Int)
+            }
+            AlgorithmVariables::MergeSort(var) => {
+                runner_merge_sort(var, tx_runner, rx_runner).map(Output::Int)
+            }
+            AlgorithmVariables::Bfs(var) => {
+                runner_bfs(var, tx_runner, rx_runner).map(Output::Int)
+            }
+            AlgorithmVariables::Dijkstra(var) => {
+                runner_dijkstra(var, tx_runner, rx_runner).map(Output::Int)
+            }
+            AlgorithmVariables::DotProduct(var) => {
+                runner_dot_product(var, tx_runner, rx_runner).map(Output::Real)
+            }
+            AlgorithmVariables::FirFilter(var) => {
+                runner_fir_filter(var, tx_runner, rx_runner).map(Output::Real)
+            }
+            AlgorithmVariables::Jacobi(var) => {
+                runner_jacobi(var, tx_runner, rx_runner).map(Output::Real)
+            }
+            AlgorithmVariables::Crc32(var) => {
+                runner_crc32(var, tx_runner, rx_runner).map(Output::Word)
+            }
+            AlgorithmVariables::Kmp(var) => {
+                runner_kmp(var, tx_runner, rx_runner).map(Output::Index)
+            }
+            AlgorithmVariables::ParMatrixMultiplication(var) => {
+                runner_par_matrix_multiplication(var, tx_runner, rx_runner).map(Output::Int)
+            }
+            AlgorithmVariables::Plugin(instance) => {
+                runner_plugin(instance, tx_runner, rx_runner).map(Output::Int)
             }
         }
     });
 
-
+    let detection = match &*variables {
+        AlgorithmVariables::ParMatrixMultiplication(var) => var.detection(),
+        _ => None
+    };
 
     match result {
-        Ok(Ok(v)) => TestResult {result: Ok(v), fault_list_entry},
+        Ok(Ok(v)) => TestResult {result: Ok(v), fault_list_entry, detection},
         Ok(Err(err)) => {
             if VERBOSE {
                 println!("Error found - {:?}", err);
             }
-            TestResult {result: Err(err), fault_list_entry}
+            TestResult {result: Err(err), fault_list_entry, detection}
         },
         Err(panic) => {
             let msg = panic_message::panic_message(&panic);
 
-            return match msg {
-                m if m.contains("IndexMut") => TestResult { result: Err(IncoherenceError::IndexMutFail), fault_list_entry },
-                m if m.contains("Index") => TestResult { result: Err(IncoherenceError::IndexFail), fault_list_entry },
-                m if m.contains("PartialOrd") => TestResult { result: Err(IncoherenceError::PartialOrdFail), fault_list_entry },
-                m if m.contains("Ord") => TestResult { result: Err(IncoherenceError::OrdFail), fault_list_entry },
-                _ => TestResult { result: Err(IncoherenceError::PartialEqFail), fault_list_entry },
-            }
+            let err = match msg {
+                m if m.contains("IndexMut") => IncoherenceError::IndexMutFail,
+                m if m.contains("Index") => IncoherenceError::IndexFail,
+                m if m.contains("PartialOrd") => IncoherenceError::PartialOrdFail,
+                m if m.contains("Ord") => IncoherenceError::OrdFail,
+                _ => IncoherenceError::PartialEqFail,
+            };
+            TestResult { result: Err(err), fault_list_entry, detection }
         }
     }
 }
@@ -166,6 +676,8 @@ fn injector(variables: Arc<AlgorithmVariables>, fault_list_entry: FaultListEntry
 
     // dato che fault_mask mi dice la posizione del bit da modificare, per ottenere la maschera devo calcolare 2^fault_mask
     let mask = 1 << (fault_list_entry.flipped_bit);
+    // per i reali il bit-flip si applica alla rappresentazione IEEE 754 a 64 bit
+    let flip_real = |val: f64| f64::from_bits(val.to_bits() ^ (1u64 << fault_list_entry.flipped_bit));
 
     //println!("mask: {}", 1 << (fault_list_entry.fault_mask));       // ottengo la maschera
 
@@ -369,6 +881,280 @@ fn injector(variables: Arc<AlgorithmVariables>, fault_list_entry: FaultListEntry
                     }
 
                 }
+                AlgorithmVariables::QuickSort(var) => {
+                    match fault_list_entry.var.as_str() {
+                        "n" => {
+                            let val = var.n.read().unwrap().inner().unwrap();
+                            var.n.write().unwrap()["cp1"] = val ^ mask;
+                        },
+                        "depth" => {
+                            let val = var.depth.read().unwrap().inner().unwrap();
+                            var.depth.write().unwrap()["cp1"] = val ^ mask;
+                        },
+                        name if name.contains('@') => {
+                            let (var_name, d) = split_frame_var(name);
+                            let mut frames = var.frames.write().unwrap();
+                            //Se il frame non è attivo in questo istante la variabile non esiste: fault mascherato
+                            if let Some(frame) = frames.get_mut(d) {
+                                if var_name == "pivot" {
+                                    let val = frame.pivot.inner().unwrap();
+                                    frame.pivot["cp1"] = val ^ (mask as i32);
+                                } else if let Some(hd) = frame.var_mut(var_name) {
+                                    let val = hd.inner().unwrap();
+                                    hd["cp1"] = val ^ mask;
+                                }
+                            }
+                        },
+                        _ => {
+                            let index = fault_list_entry.var
+                                .split(['[', ']'])
+                                .collect::<Vec<_>>()[1]
+                                .parse::<usize>().unwrap();
+
+                            let val = var.vet.read().unwrap()[index].inner().unwrap();
+                            var.vet.write().unwrap()[index]["cp1"] = val ^ (mask as i32);
+                        }
+                    }
+                }
+                AlgorithmVariables::MergeSort(var) => {
+                    match fault_list_entry.var.as_str() {
+                        "n" => {
+                            let val = var.n.read().unwrap().inner().unwrap();
+                            var.n.write().unwrap()["cp1"] = val ^ mask;
+                        },
+                        "depth" => {
+                            let val = var.depth.read().unwrap().inner().unwrap();
+                            var.depth.write().unwrap()["cp1"] = val ^ mask;
+                        },
+                        name if name.contains('@') => {
+                            let (var_name, d) = split_frame_var(name);
+                            let mut frames = var.frames.write().unwrap();
+                            if let Some(hd) = frames.get_mut(d).and_then(|frame| frame.var_mut(var_name)) {
+                                let val = hd.inner().unwrap();
+                                hd["cp1"] = val ^ mask;
+                            }
+                        },
+                        _ => {
+                            let parts = fault_list_entry.var
+                                .split(['[', ']'])
+                                .collect::<Vec<_>>();
+                            let index = parts[1].parse::<usize>().unwrap();
+                            let vector = if parts[0] == "aux" { &var.aux } else { &var.vet };
+
+                            //'aux' viene allocato dal runner: prima di allora l'elemento non esiste
+                            inject_element(vector, index, |val| val ^ (mask as i32));
+                        }
+                    }
+                }
+                AlgorithmVariables::Bfs(var) => {
+                    match fault_list_entry.var.as_str() {
+                        "source" => inject_scalar(&var.source, |val| val ^ mask),
+                        "n" => inject_scalar(&var.n, |val| val ^ mask),
+                        "head" => inject_scalar(&var.head, |val| val ^ mask),
+                        "u" => inject_scalar(&var.u, |val| val ^ mask),
+                        "e" => inject_scalar(&var.e, |val| val ^ mask),
+                        "v" => inject_scalar(&var.v, |val| val ^ mask),
+                        _ => {
+                            let parts = fault_list_entry.var
+                                .split(['[', ']'])
+                                .collect::<Vec<_>>();
+                            let index = parts[1].parse::<usize>().unwrap();
+                            match parts[0] {
+                                "dist" => inject_element(&var.dist, index, |val| val ^ (mask as i32)),
+                                "visited" => inject_element(&var.visited, index, |val| !val),
+                                "queue" => inject_element(&var.queue, index, |val| val ^ mask),
+                                _ => println!("non e' un vettore che conosco")
+                            }
+                        }
+                    }
+                }
+                AlgorithmVariables::Dijkstra(var) => {
+                    match fault_list_entry.var.as_str() {
+                        "source" => inject_scalar(&var.source, |val| val ^ mask),
+                        "n" => inject_scalar(&var.n, |val| val ^ mask),
+                        "best" => inject_scalar(&var.best, |val| val ^ mask),
+                        "idx" => inject_scalar(&var.idx, |val| val ^ mask),
+                        "d" => inject_scalar(&var.d, |val| val ^ (mask as i32)),
+                        "u" => inject_scalar(&var.u, |val| val ^ mask),
+                        "e" => inject_scalar(&var.e, |val| val ^ mask),
+                        "v" => inject_scalar(&var.v, |val| val ^ mask),
+                        "alt" => inject_scalar(&var.alt, |val| val ^ (mask as i32)),
+                        _ => {
+                            let parts = fault_list_entry.var
+                                .split(['[', ']'])
+                                .collect::<Vec<_>>();
+                            let index = parts[1].parse::<usize>().unwrap();
+                            match parts[0] {
+                                "dist" => inject_element(&var.dist, index, |val| val ^ (mask as i32)),
+                                "visited" => inject_element(&var.visited, index, |val| !val),
+                                "pq_dist" => inject_element(&var.pq_dist, index, |val| val ^ (mask as i32)),
+                                "pq_node" => inject_element(&var.pq_node, index, |val| val ^ mask),
+                                _ => println!("non e' un vettore che conosco")
+                            }
+                        }
+                    }
+                }
+                AlgorithmVariables::DotProduct(var) => {
+                    match fault_list_entry.var.as_str() {
+                        "n" => inject_scalar(&var.n, |val| val ^ mask),
+                        "i" => inject_scalar(&var.i, |val| val ^ mask),
+                        "acc" => inject_scalar(&var.acc, flip_real),
+                        _ => {
+                            let parts = fault_list_entry.var
+                                .split(['[', ']'])
+                                .collect::<Vec<_>>();
+                            let index = parts[1].parse::<usize>().unwrap();
+                            match parts[0] {
+                                "x" => inject_element(&var.x, index, flip_real),
+                                "y" => inject_element(&var.y, index, flip_real),
+                                _ => println!("non e' un vettore che conosco")
+                            }
+                        }
+                    }
+                }
+                AlgorithmVariables::FirFilter(var) => {
+                    match fault_list_entry.var.as_str() {
+                        "n" => inject_scalar(&var.n, |val| val ^ mask),
+                        "m" => inject_scalar(&var.m, |val| val ^ mask),
+                        "i" => inject_scalar(&var.i, |val| val ^ mask),
+                        "k" => inject_scalar(&var.k, |val| val ^ mask),
+                        "acc" => inject_scalar(&var.acc, flip_real),
+                        _ => {
+                            let parts = fault_list_entry.var
+                                .split(['[', ']'])
+                                .collect::<Vec<_>>();
+                            let index = parts[1].parse::<usize>().unwrap();
+                            match parts[0] {
+                                "x" => inject_element(&var.x, index, flip_real),
+                                "h" => inject_element(&var.h, index, flip_real),
+                                "y" => inject_element(&var.y, index, flip_real),
+                                _ => println!("non e' un vettore che conosco")
+                            }
+                        }
+                    }
+                }
+                AlgorithmVariables::Jacobi(var) => {
+                    match fault_list_entry.var.as_str() {
+                        "n" => inject_scalar(&var.n, |val| val ^ mask),
+                        "iterations" => inject_scalar(&var.iterations, |val| val ^ mask),
+                        "it" => inject_scalar(&var.it, |val| val ^ mask),
+                        "i" => inject_scalar(&var.i, |val| val ^ mask),
+                        "j" => inject_scalar(&var.j, |val| val ^ mask),
+                        "sigma" => inject_scalar(&var.sigma, flip_real),
+                        _ => {
+                            let parts = fault_list_entry.var
+                                .split(['[', ']'])
+                                .filter(|s| !s.is_empty())
+                                .collect::<Vec<_>>();
+                            let index = parts[1].parse::<usize>().unwrap();
+                            match parts[0] {
+                                "a" => {
+                                    let col = parts[2].parse::<usize>().unwrap();
+                                    let val = var.a.read().unwrap()[index][col].inner().unwrap();
+                                    var.a.write().unwrap()[index][col]["cp1"] = flip_real(val);
+                                }
+                                "b" => inject_element(&var.b, index, flip_real),
+                                "x" => inject_element(&var.x, index, flip_real),
+                                "x_new" => inject_element(&var.x_new, index, flip_real),
+                                _ => println!("non e' un vettore che conosco")
+                            }
+                        }
+                    }
+                }
+                AlgorithmVariables::ParMatrixMultiplication(var) => {
+                    //oltre al bit-flip si registra quale thread aveva scritto per ultimo la variabile
+                    let writer = match fault_list_entry.var.as_str() {
+                        "next_row" => {
+                            inject_scalar(&var.next_row, |val| val ^ mask);
+                            *var.next_row_writer.read().unwrap()
+                        }
+                        name if name.contains('@') => {
+                            let (local, w) = split_frame_var(name);
+                            let worker = &var.workers[w];
+                            match local {
+                                "size" => inject_scalar(&worker.size, |val| val ^ mask),
+                                "row" => inject_scalar(&worker.row, |val| val ^ mask),
+                                "j" => inject_scalar(&worker.j, |val| val ^ mask),
+                                "k" => inject_scalar(&worker.k, |val| val ^ mask),
+                                "acc" => inject_scalar(&worker.acc, |val| val ^ (mask as i32)),
+                                _ => println!("non e' una variabile che conosco")
+                            }
+                            Actor::Worker(w)
+                        }
+                        name => {
+                            let parts = name
+                                .split(['[', ']'])
+                                .filter(|s| !s.is_empty())
+                                .collect::<Vec<_>>();
+                            let (row, col) = (parts[1].parse::<usize>().unwrap(), parts[2].parse::<usize>().unwrap());
+                            let matrix = match parts[0] {
+                                "a" => &var.a,
+                                "b" => &var.b,
+                                _ => &var.result,
+                            };
+                            //la matrice risultato viene allocata dal thread principale alla prima istruzione
+                            let val = matrix.read().unwrap().get(row).map(|r| r[col].inner().unwrap());
+                            if let Some(val) = val {
+                                matrix.write().unwrap()[row][col]["cp1"] = val ^ (mask as i32);
+                            }
+                            match parts[0] {
+                                "result" => var.row_writer.read().unwrap().get(row).copied().unwrap_or(Actor::Main),
+                                _ => Actor::Main
+                            }
+                        }
+                    };
+                    *var.injected_writer.write().unwrap() = Some(writer);
+                }
+                AlgorithmVariables::Plugin(instance) => {
+                    if !instance.inject(&fault_list_entry.var, fault_list_entry.flipped_bit) {
+                        println!("non e' una variabile che conosco")
+                    }
+                }
+                AlgorithmVariables::Crc32(var) => {
+                    match fault_list_entry.var.as_str() {
+                        "n" => inject_scalar(&var.n, |val| val ^ mask),
+                        "c" => inject_scalar(&var.c, |val| val ^ (mask as u32)),
+                        "k" => inject_scalar(&var.k, |val| val ^ mask),
+                        "crc" => inject_scalar(&var.crc, |val| val ^ (mask as u32)),
+                        "i" => inject_scalar(&var.i, |val| val ^ mask),
+                        "byte" => inject_scalar(&var.byte, |val| val ^ (mask as u8)),
+                        "idx" => inject_scalar(&var.idx, |val| val ^ mask),
+                        _ => {
+                            let parts = fault_list_entry.var
+                                .split(['[', ']'])
+                                .collect::<Vec<_>>();
+                            let index = parts[1].parse::<usize>().unwrap();
+                            match parts[0] {
+                                "data" => inject_element(&var.data, index, |val| val ^ (mask as u8)),
+                                "table" => inject_element(&var.table, index, |val| val ^ (mask as u32)),
+                                _ => println!("non e' un vettore che conosco")
+                            }
+                        }
+                    }
+                }
+                AlgorithmVariables::Kmp(var) => {
+                    match fault_list_entry.var.as_str() {
+                        "n" => inject_scalar(&var.n, |val| val ^ mask),
+                        "m" => inject_scalar(&var.m, |val| val ^ mask),
+                        "k" => inject_scalar(&var.k, |val| val ^ mask),
+                        "q" => inject_scalar(&var.q, |val| val ^ mask),
+                        "j" => inject_scalar(&var.j, |val| val ^ mask),
+                        "i" => inject_scalar(&var.i, |val| val ^ mask),
+                        _ => {
+                            let parts = fault_list_entry.var
+                                .split(['[', ']'])
+                                .collect::<Vec<_>>();
+                            let index = parts[1].parse::<usize>().unwrap();
+                            match parts[0] {
+                                "text" => inject_element(&var.text, index, |val| val ^ (mask as u8)),
+                                "pattern" => inject_element(&var.pattern, index, |val| val ^ (mask as u8)),
+                                "fail" => inject_element(&var.fail, index, |val| val ^ mask),
+                                "matches" => inject_element(&var.matches, index, |val| val ^ mask),
+                                _ => println!("non e' un vettore che conosco")
+                            }
+                        }
+                    }
+                }
             }
         }
         tx_injector.send("ricevuto").unwrap();
@@ -377,8 +1163,73 @@ fn injector(variables: Arc<AlgorithmVariables>, fault_list_entry: FaultListEntry
 
 
 
+///Applica il bit-flip alla copia cp1 di una variabile scalare
+fn inject_scalar<T>(var: &RwLock<Hardened<T>>, flip: impl Fn(T) -> T)
+where T: Debug+Coherent{
+    let val = var.read().unwrap().inner().unwrap();
+    var.write().unwrap()["cp1"] = flip(val);
+}
+
+///Applica il bit-flip all'elemento 'index' di un vettore. I vettori che crescono durante
+/// l'esecuzione (code, vettori allocati dal runner) possono non contenere ancora l'elemento:
+/// in quel caso la variabile non esiste e il fault è mascherato.
+fn inject_element<T>(vector: &RwLock<Vec<Hardened<T>>>, index: usize, flip: impl Fn(T) -> T)
+where T: Debug+Coherent{
+    let val = vector.read().unwrap().get(index).map(|el| el.inner().unwrap());
+    if let Some(val) = val {
+        vector.write().unwrap()[index]["cp1"] = flip(val);
+    }
+}
+
+///Separa il nome di una variabile di frame ("lo@2") nel nome e nella profondità del frame
+fn split_frame_var(name: &str) -> (&str, usize) {
+    let (var_name, depth) = name.split_once('@').unwrap();
+    (var_name, depth.parse::<usize>().unwrap())
+}
+
+//Esecuzione senza fault del runner: dopo ogni etichetta 'observe' riceve l'etichetta e le
+//variabili del runner, prima che questo prosegua
+fn fault_free_run<T>(target: &str, data: Data<i32>, mut observe: impl FnMut(&'static str, &AlgorithmVariables) -> T) -> Vec<T> {
+    let variables = AlgorithmVariables::from_target(target, data);
+    let (tx_1, rx_1) = channel();
+    let (tx_2, rx_2) = channel();
+    //all'istante 0 l'iniettore non interviene mai: la entry non ha effetto
+    let entry = FaultListEntry{ var: String::new(), time: 0, flipped_bit: 0, weight: 1 };
+    let runner_variables = Arc::clone(&variables);
+    let handle = thread::spawn(move || runner(runner_variables, entry, tx_1, rx_2));
+
+    let mut observed = Vec::new();
+    while let Ok(label) = rx_1.recv() {
+        observed.push(observe(label, &variables));
+        tx_2.send("ricevuto").unwrap();
+    }
+    handle.join().unwrap();
+    observed
+}
+
+///Sequenza delle etichette inviate dal runner in un'esecuzione senza fault: la posizione k
+/// (da 0) corrisponde all'istante k+1 delle entry della fault list
+pub fn label_trace(target: &str, data: Data<i32>) -> Vec<&'static str> {
+    fault_free_run(target, data, |label, _| label)
+}
+
+///Numero di frame attivi dopo ogni etichetta di un'esecuzione senza fault di un target ricorsivo,
+/// con le stesse posizioni di label_trace: la fault list inietta le variabili di frame solo nei
+/// frame presenti in quell'istante
+pub fn frame_trace(target: &str, data: Data<i32>) -> Vec<usize> {
+    fault_free_run(target, data, |_, variables| match variables {
+        AlgorithmVariables::QuickSort(var) => var.frames.read().unwrap().len(),
+        AlgorithmVariables::MergeSort(var) => var.frames.read().unwrap().len(),
+        _ => panic!("Target {} senza frame", target),
+    })
+}
+
+///Numero massimo di iniezioni in corso: quando è raggiunto l'injector manager attende la più
+/// vecchia prima di prelevare altre entry, e la pressione risale fino al fault list manager
+pub const INJECTIONS_IN_FLIGHT: usize = 64;
+
 pub fn injector_manager(rx_chan_fm_inj: Receiver<FaultListEntry>,
-                        tx_chan_inj_anl: Sender<TestResult>,
+                        tx_chan_inj_anl: SyncSender<TestResult>,
                         target: String,
                         data: Data<i32>){
 
@@ -390,9 +1241,14 @@ pub fn injector_manager(rx_chan_fm_inj: Receiver<FaultListEntry>,
     }));
 
 
-
-    let mut handles_runner = vec![];
-    let mut handles_injector = vec![];
+    //coppie (runner, iniettore) in corso, nell'ordine delle entry: i risultati arrivano
+    //all'analyzer nello stesso ordine della fault list
+    let mut in_flight = VecDeque::new();
+    let complete = |(handle_runner, handle_injector): (JoinHandle<TestResult>, JoinHandle<()>)| {
+        let result = handle_runner.join().unwrap();
+        handle_injector.join().unwrap();
+        result
+    };
 
     while let Ok(fault_list_entry) = rx_chan_fm_inj.recv(){
 
@@ -409,19 +1265,21 @@ pub fn injector_manager(rx_chan_fm_inj: Receiver<FaultListEntry>,
 
         let fault_list_entry_runner = fault_list_entry.clone();
 
-        handles_runner.push(thread::spawn(move || runner(runner_variables, fault_list_entry_runner, tx_1, rx_2)));     // lancio il thread che esegue l'algoritmo
-        handles_injector.push(thread::spawn(move || injector(injector_variables, fault_list_entry, tx_2, rx_1)));      // lancio il thread iniettore
-    }
-
+        in_flight.push_back((
+            thread::spawn(move || runner(runner_variables, fault_list_entry_runner, tx_1, rx_2)),     // lancio il thread che esegue l'algoritmo
+            thread::spawn(move || injector(injector_variables, fault_list_entry, tx_2, rx_1)),        // lancio il thread iniettore
+        ));
 
-    for handle in handles_runner {
-        let result = handle.join().unwrap();
-        tx_chan_inj_anl.send(result).unwrap();
+        if in_flight.len() == INJECTIONS_IN_FLIGHT {
+            //se l'analyzer ha chiuso il canale la campagna è interrotta
+            if tx_chan_inj_anl.send(complete(in_flight.pop_front().unwrap())).is_err() {
+                break;
+            }
+        }
     }
 
-
-    for handle in handles_injector {
-        handle.join().unwrap();
+    for handles in in_flight {
+        let _ = tx_chan_inj_anl.send(complete(handles));
     }
 
 
@@ -4,6 +4,7 @@ mod fault_env;
 mod injector;
 mod analyzer;
 mod pdf_generator;
+mod plugin;
 
 use fault_list_manager::static_analysis;
 use std::io::{BufRead, Error};
@@ -11,9 +12,15 @@ use std::io;
 use std::path::Path;
 use std::fs::File;
 use std::time::Instant;
-use crate::fault_env::{Data, fault_injection_env};
-use crate::fault_list_manager::DimData;
-use crate::hardened::*;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use crate::fault_env::{AdjacencyList, Data, fault_injection_env};
+use crate::fault_list_manager::{campaign_rng, target_function, BitDistribution, DimData, FaultListFormat, FaultSource, ExecutionProfile, FaultListParams, Sampling, SiteSelection, INPUT_STREAM};
+use crate::fault_list_manager::validation::Registry;
+use crate::fault_list_manager::{compliance, control_flow, set_ops};
+use crate::fault_list_manager::type_size::PointerWidth;
+use crate::plugin::Plugin;
+use crate::hardened::par_matrix_multiplication_hardened::PAR_WORKERS;
 use dialoguer::{Select, Input};
 use rand::Rng;
 use regex::Regex;
@@ -21,18 +28,92 @@ use regex::Regex;
 
 ///Ambiente di Fault Injection per applicazione ridondata
 pub static VERBOSE: bool = false; //Settare true per messaggi di iniezione
+const GRAPH_PATH: &str = "src/data/graph.txt";
+const NUMERIC_PATH: &str = "src/data/numeric.txt";
+const TEXT_PATH: &str = "src/data/text.txt";
+const SITE_WEIGHTS_PATH: &str = "src/data/site_weights.json";
 #[derive(Debug)]
 pub struct InputData {
     pub vector: Vec<i32>,
     pub matrix_size: usize,
     pub matrix1: Vec<Vec<i32>>,
     pub matrix2: Vec<Vec<i32>>,
+    pub graph: Option<(AdjacencyList<i32>, usize)>, //grafo e sorgente, caricati solo per BFS e Dijkstra
+    pub reals: Option<RealInputs>,  //ingressi dei kernel numerici, caricati solo per i loro target
+    pub text: Option<TextInputs>,   //ingressi di CRC32 e KMP, caricati solo per i loro target
+}
+
+///Impostazioni comuni a tutti i casi di studio di una campagna
+#[derive(Debug, Clone, Copy)]
+pub struct Campaign {
+    pub seed: u64,
+    pub fl_format: FaultListFormat,  //formato dei file delle fault list generate
+    pub delivery: Delivery,
+    pub pointer_width: PointerWidth, //architettura dei target, per le dimensioni di usize e puntatori
+}
+
+///Come le entry della fault list arrivano all'iniettore
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Delivery {
+    File,                   //fault list salvata, potata e validata prima della campagna
+    Stream { tee: bool },   //entry generate durante la campagna, senza potatura; con tee
+                            //vengono anche salvate nel file della fault list
+}
+
+///Ingressi dei kernel numerici
+#[derive(Debug)]
+pub struct RealInputs {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub h: Vec<f64>,
+    pub a: Vec<Vec<f64>>,
+    pub b: Vec<f64>,
+}
+
+///Ingressi dei casi di studio su stringhe e byte
+#[derive(Debug)]
+pub struct TextInputs {
+    pub message: String,
+    pub text: String,
+    pub pattern: String,
 }
 impl InputData {
+    ///Carica il grafo dei casi di studio BFS e Dijkstra: gli altri target non ne hanno bisogno,
+    /// quindi un file del grafo mancante o non valido non li blocca
+    fn load_graph(&mut self, file_path: &str) -> Result<(), Error> {
+        self.graph = Some(load_graph_from_file(file_path)?);
+        Ok(())
+    }
+    ///Carica gli ingressi dei kernel numerici (prodotto scalare, filtro FIR e Jacobi), come
+    /// load_graph per il grafo
+    fn load_reals(&mut self, file_path: &str) -> Result<(), Error> {
+        self.reals = Some(load_reals_from_file(file_path)?);
+        Ok(())
+    }
+    fn reals(&self) -> &RealInputs {
+        self.reals.as_ref().expect("Ingressi reali non caricati (vedi InputData::load_reals)")
+    }
+    ///Carica gli ingressi di CRC32 e KMP, come load_graph per il grafo
+    fn load_text(&mut self, file_path: &str) -> Result<(), Error> {
+        self.text = Some(load_text_from_file(file_path)?);
+        Ok(())
+    }
+    fn text(&self) -> &TextInputs {
+        self.text.as_ref().expect("Ingressi testuali non caricati (vedi InputData::load_text)")
+    }
     fn into_data(&self, ty:&str) -> Data<i32> {
         match ty {
             "vector" => Data::Vector(self.vector.clone()),
             "matrices" => Data::Matrices(self.matrix1.clone(), self.matrix2.clone()),
+            "graph" => {
+                let (graph, source) = self.graph.clone().expect("Grafo non caricato (vedi InputData::load_graph)");
+                Data::Graph(graph, source)
+            }
+            "dot_product" => Data::Reals(self.reals().x.clone(), self.reals().y.clone()),
+            "fir_filter" => Data::Reals(self.reals().x.clone(), self.reals().h.clone()),
+            "jacobi" => Data::LinearSystem(self.reals().a.clone(), self.reals().b.clone()),
+            "crc32" => Data::Bytes(self.text().message.clone().into_bytes()),
+            "kmp" => Data::Text(self.text().text.clone(), self.text().pattern.clone()),
             _ => panic!("Unknown input type {}", ty),
         }
     }
@@ -40,12 +121,36 @@ impl InputData {
         match ty {
             "vector" => DimData::Vector(self.vector.len()),
             "matrices" => DimData::Matrices((self.matrix1.len(), self.matrix2.len())),
+            "workers" => DimData::Workers((self.matrix1.len(), self.matrix2.len()), PAR_WORKERS),
+            "frames_quick_sort" => DimData::Frames(self.vector.len(), injector::frame_trace("quick_sort", self.into_data("vector"))),
+            "frames_merge_sort" => DimData::Frames(self.vector.len(), injector::frame_trace("merge_sort", self.into_data("vector"))),
+            "graph" => {
+                let (graph, _) = self.graph.as_ref().expect("Grafo non caricato (vedi InputData::load_graph)");
+                DimData::Graph(graph.len(), graph.iter().map(Vec::len).sum())
+            }
+            "dot_product" => DimData::Lengths(HashMap::from([
+                ("x".to_string(), self.reals().x.len()), ("y".to_string(), self.reals().y.len())])),
+            "fir_filter" => DimData::Lengths(HashMap::from([
+                ("x".to_string(), self.reals().x.len()), ("h".to_string(), self.reals().h.len()),
+                ("y".to_string(), self.reals().x.len())])),
+            "jacobi" => DimData::Lengths(["a", "b", "x", "x_new"].iter()
+                .map(|name| (name.to_string(), self.reals().b.len()))
+                .collect()),
+            "crc32" => DimData::Lengths(HashMap::from([
+                ("data".to_string(), self.text().message.len()), ("table".to_string(), 256)])),
+            //le occorrenze sono al più n-m+1; il vettore può restare vuoto
+            "kmp" => DimData::Lengths(HashMap::from([
+                ("text".to_string(), self.text().text.len()), ("pattern".to_string(), self.text().pattern.len()),
+                ("fail".to_string(), self.text().pattern.len()),
+                ("matches".to_string(), (self.text().text.len() + 1).saturating_sub(self.text().pattern.len()).max(1))])),
             _ => panic!("Unknown input type {}", ty),
         }
     }
 }
 
-pub fn load_data_from_dataset()-> Result<InputData, Error> {
+///Carica un vettore e una coppia di matrici scelti a caso dal dataset: la scelta dipende solo
+/// dal seed della campagna
+pub fn load_data_from_dataset(seed: u64)-> Result<InputData, Error> {
     // Apri il file
     let file = File::open("src/data/dataset/dataset_vector.txt")?;
     let reader = io::BufReader::new(file);
@@ -54,7 +159,7 @@ pub fn load_data_from_dataset()-> Result<InputData, Error> {
     let lines: Vec<String> = reader.lines().filter_map(Result::ok).collect();
 
     // Genera un indice casuale per selezionare una linea
-    let mut rng = rand::thread_rng();
+    let mut rng = campaign_rng(seed, INPUT_STREAM);
     let random_index = rng.gen_range(0..lines.len());
 
     // Seleziona la linea e convertila in un vettore di i32
@@ -74,7 +179,7 @@ pub fn load_data_from_dataset()-> Result<InputData, Error> {
     let lines: Vec<String> = reader2.lines().filter_map(Result::ok).collect();
 
     // Genera un indice casuale per selezionare una linea
-    let random_index = rand::thread_rng().gen_range(0..16) * 4;
+    let random_index = rng.gen_range(0..16) * 4;
 
     let matrix1: Vec<Vec<i32>> = (random_index..random_index + 3)
         .filter_map(|idx| lines.get(idx)) // Recupera la linea, se esiste
@@ -103,13 +208,31 @@ pub fn load_data_from_dataset()-> Result<InputData, Error> {
 
     let matrix_size = 3;
 
+
     Ok(InputData {
         vector,
         matrix_size,
         matrix1,
         matrix2,
+        graph: None,
+        reals: None,
+        text: None,
     })
 }
+///Carica i pesi delle variabili per la selezione personalizzata dei siti di iniezione
+pub fn load_site_weights(file_path: &str) -> Result<BTreeMap<String, f64>, Error> {
+    let content = std::fs::read_to_string(file_path)?;
+    let weights: BTreeMap<String, f64> = serde_json::from_str(&content)
+        .map_err(|e| Error::new(io::ErrorKind::InvalidData, format!("Formato invalido nel file dei pesi: {}", e)))?;
+    if weights.values().any(|&w| !w.is_finite() || w < 0.0) {
+        return Err(Error::new(io::ErrorKind::InvalidData, "I pesi devono essere numeri non negativi"));
+    }
+    if !weights.values().any(|&w| w > 0.0) {
+        return Err(Error::new(io::ErrorKind::InvalidData, "Almeno una variabile deve avere un peso positivo"));
+    }
+    Ok(weights)
+}
+
 pub fn load_data_from_file(file_path: &str) -> Result<InputData, Error> {
     let path = Path::new(file_path);
     let file = File::open(path)?;
@@ -223,16 +346,148 @@ pub fn load_data_from_file(file_path: &str) -> Result<InputData, Error> {
         }
         matrix2.push(row);
     }
-    
+
+
     Ok(InputData {
         vector,
         matrix_size,
         matrix1,
         matrix2,
+        graph: None,
+        reals: None,
+        text: None,
     })
 }
+
+///Legge un grafo orientato pesato: una riga "nodi archi sorgente" seguita da un arco per riga
+/// nel formato "origine destinazione peso". Restituisce le liste di adiacenza e la sorgente.
+pub fn load_graph_from_file(file_path: &str) -> Result<(AdjacencyList<i32>, usize), Error> {
+    let file = File::open(Path::new(file_path))?;
+    //Si scartano l'intestazione testuale e le righe vuote: restano solo le righe numeriche
+    let mut lines = io::BufReader::new(file).lines()
+        .map_while(Result::ok)
+        .map(|line| line.trim().to_string())
+        .filter(|line| line.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false));
+
+    let header: Vec<usize> = lines.next()
+        .ok_or_else(|| Error::new(io::ErrorKind::InvalidData, "Intestazione del grafo mancante"))?
+        .split_whitespace()
+        .map(|n| n.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| Error::new(io::ErrorKind::InvalidData, "Formato invalido nell'intestazione del grafo"))?;
+    if header.len() != 3 {
+        return Err(Error::new(io::ErrorKind::InvalidData, "L'intestazione del grafo deve contenere nodi, archi e sorgente"));
+    }
+    let (num_nodes, num_edges, source) = (header[0], header[1], header[2]);
+    if source >= num_nodes {
+        return Err(Error::new(io::ErrorKind::InvalidData, format!("Il nodo sorgente {} non appartiene al grafo", source)));
+    }
+
+    let mut graph = vec![Vec::new(); num_nodes];
+    for _ in 0..num_edges {
+        let edge_line = lines.next()
+            .ok_or_else(|| Error::new(io::ErrorKind::InvalidData, "Archi del grafo mancanti"))?;
+        let edge: Vec<&str> = edge_line.split_whitespace().collect();
+        let parsed = match edge.as_slice() {
+            [u, v, w] => u.parse::<usize>().ok().zip(v.parse::<usize>().ok()).zip(w.parse::<i32>().ok()),
+            _ => None
+        };
+        let ((u, v), w) = parsed
+            .ok_or_else(|| Error::new(io::ErrorKind::InvalidData, format!("Formato invalido nell'arco \"{}\"", edge_line)))?;
+        if u >= num_nodes || v >= num_nodes || w <= 0 {
+            return Err(Error::new(io::ErrorKind::InvalidData, format!("Arco non valido: \"{}\"", edge_line)));
+        }
+        graph[u].push((v, w));
+    }
+    Ok((graph, source))
+}
+///Legge gli ingressi dei kernel numerici. Sono considerate solo le righe composte interamente
+/// da numeri (separati da virgole o da spazi): l'intestazione testuale viene scartata.
+pub fn load_reals_from_file(file_path: &str) -> Result<RealInputs, Error> {
+    let file = File::open(Path::new(file_path))?;
+    let mut rows = io::BufReader::new(file).lines()
+        .map_while(Result::ok)
+        .filter_map(|line| {
+            let values = line.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|n| !n.is_empty())
+                .map(|n| n.parse::<f64>())
+                .collect::<Result<Vec<_>, _>>();
+            values.ok().filter(|v| !v.is_empty())
+        });
+    let mut next_row = |what: &str| rows.next()
+        .ok_or_else(|| Error::new(io::ErrorKind::InvalidData, format!("Dati mancanti: {}", what)));
+
+    let x = next_row("vettore x")?;
+    let y = next_row("vettore y")?;
+    if x.len() != y.len() {
+        return Err(Error::new(io::ErrorKind::InvalidData, "I vettori x e y devono avere la stessa lunghezza"));
+    }
+    let h = next_row("coefficienti del filtro")?;
+
+    let size = next_row("dimensione del sistema")?;
+    if size.len() != 1 || size[0] < 1.0 || size[0].fract() != 0.0 {
+        return Err(Error::new(io::ErrorKind::InvalidData, "Formato invalido per la dimensione del sistema"));
+    }
+    let n = size[0] as usize;
+    let mut a = Vec::new();
+    for _ in 0..n {
+        let row = next_row("righe della matrice del sistema")?;
+        if row.len() != n {
+            return Err(Error::new(io::ErrorKind::InvalidData, "La dimensione della matrice del sistema non corrisponde ai dati forniti"));
+        }
+        a.push(row);
+    }
+    let b = next_row("termini noti")?;
+    if b.len() != n {
+        return Err(Error::new(io::ErrorKind::InvalidData, "Il numero di termini noti non corrisponde alla dimensione del sistema"));
+    }
+
+    Ok(RealInputs { x, y, h, a, b })
+}
+
+///Legge gli ingressi dei casi di studio su stringhe: il testo che segue l'intestazione
+/// (delimitata da due righe di trattini) contiene messaggio, testo e pattern, una riga ciascuno.
+pub fn load_text_from_file(file_path: &str) -> Result<TextInputs, Error> {
+    let file = File::open(Path::new(file_path))?;
+    let mut rows = io::BufReader::new(file).lines()
+        .map_while(Result::ok)
+        .skip_while(|line| !line.starts_with("---"))
+        .skip(1)
+        .skip_while(|line| !line.starts_with("---"))
+        .skip(1)
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty());
+    let mut next_row = |what: &str| rows.next()
+        .ok_or_else(|| Error::new(io::ErrorKind::InvalidData, format!("Dati mancanti: {}", what)));
+
+    let message = next_row("messaggio per il CRC32")?;
+    let text = next_row("testo per la ricerca")?;
+    let pattern = next_row("pattern da cercare")?;
+
+    Ok(TextInputs { message, text, pattern })
+}
+
 fn main() {
 
+    // Sottocomandi non interattivi (es. "fault-list union a.json b.json -o c.json")
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some((command, args)) = args.split_first() {
+        let result = match command.as_str() {
+            "fault-list" => set_ops::run_command(args),
+            "lint" => compliance::run_command(args),
+            "cfg" => control_flow::run_command(args),
+            _ => Err(format!("sottocomando sconosciuto: {}\n{}\n{}\n{}", command, set_ops::USAGE, compliance::USAGE, control_flow::USAGE)),
+        };
+        match result {
+            Ok(message) => println!("{}", message),
+            Err(e) => {
+                eprintln!("Errore: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     //IMPLEMENTAZIONE MENU UTENTE---------------------------
 
     // Descrizione iniziale
@@ -263,6 +518,43 @@ fn main() {
     }
     file_path.push_str(&nome_file);
 
+    // Seed della campagna: riusando lo stesso seed si ottengono lo stesso input e le stesse fault list
+    let seed: u64 = Input::new()
+        .with_prompt("Inserisci il seed della campagna")
+        .default(rand::thread_rng().gen())
+        .interact_text()
+        .unwrap();
+
+    // Formato dei file delle fault list
+    let fl_formats = [FaultListFormat::Json, FaultListFormat::JsonLines, FaultListFormat::Csv];
+    let fl_format = fl_formats[Select::new()
+        .with_prompt("Seleziona il formato della fault list")
+        .default(0)
+        .items(&["JSON", "JSON Lines", "CSV"])
+        .interact()
+        .unwrap()];
+
+    // Fault list su file oppure generata durante l'iniezione
+    let deliveries = [Delivery::File, Delivery::Stream { tee: true }, Delivery::Stream { tee: false }];
+    let delivery = deliveries[Select::new()
+        .with_prompt("Seleziona come generare la fault list")
+        .default(0)
+        .items(&["Su file, potata e validata prima della campagna",
+                 "In streaming durante la campagna, salvata anche su file",
+                 "In streaming durante la campagna, senza salvarla"])
+        .interact()
+        .unwrap()];
+
+    // Architettura dei target: decide la dimensione di usize, isize e dei puntatori
+    let pointer_widths = [PointerWidth::HOST, PointerWidth(32), PointerWidth(16)];
+    let pointer_width = pointer_widths[Select::new()
+        .with_prompt("Seleziona l'architettura dei target (larghezza dei puntatori)")
+        .default(0)
+        .items(&[format!("{} bit (questa macchina)", PointerWidth::HOST.0), "32 bit".to_string(), "16 bit".to_string()])
+        .interact()
+        .unwrap()];
+    let campaign = Campaign { seed, fl_format, delivery, pointer_width };
+
     // Sorgente dei dati
     let data_sources = vec!["Data file", "Dataset"];
     let data_source_selection = Select::new()
@@ -273,7 +565,7 @@ fn main() {
         .unwrap();
 
     // Caricamento dati in base alla sorgente scelta
-    let input_data: InputData = match data_source_selection {
+    let mut input_data: InputData = match data_source_selection {
         0 => match load_data_from_file(&input_path) {
             Ok(data) => data,
             Err(e) => {
@@ -281,7 +573,7 @@ fn main() {
                 std::process::exit(1);
             }
         },
-        1 => match load_data_from_dataset() {
+        1 => match load_data_from_dataset(campaign.seed) {
             Ok(data) => data,
             Err(e) => {
                 eprintln!("Errore: {}", e);
@@ -308,7 +600,18 @@ fn main() {
             let options = vec![
                 "Selection Sort",
                 "Bubble Sort",
-                "Matrix Multiplication"
+                "Matrix Multiplication",
+                "Quick Sort (ricorsivo)",
+                "Merge Sort (ricorsivo)",
+                "BFS (grafo)",
+                "Dijkstra (grafo)",
+                "Prodotto scalare (f64)",
+                "Filtro FIR (f64)",
+                "Metodo di Jacobi (f64)",
+                "CRC32 (byte)",
+                "Ricerca KMP (testo)",
+                "Matrix Multiplication parallela (thread)",
+                "Plugin esterno (libreria dinamica)"
             ];
 
             // Menu di selezione
@@ -318,6 +621,27 @@ fn main() {
                 .items(&options)
                 .interact()
                 .unwrap();
+            //BFS e Dijkstra
+            if let 5 | 6 = algo_selection {
+                if let Err(e) = input_data.load_graph(GRAPH_PATH) {
+                    eprintln!("Errore nel file del grafo {}: {}", GRAPH_PATH, e);
+                    std::process::exit(1);
+                }
+            }
+            //prodotto scalare, filtro FIR e Jacobi
+            if let 7..=9 = algo_selection {
+                if let Err(e) = input_data.load_reals(NUMERIC_PATH) {
+                    eprintln!("Errore nel file dei dati numerici {}: {}", NUMERIC_PATH, e);
+                    std::process::exit(1);
+                }
+            }
+            //CRC32 e KMP
+            if let 10 | 11 = algo_selection {
+                if let Err(e) = input_data.load_text(TEXT_PATH) {
+                    eprintln!("Errore nel file dei dati testuali {}: {}", TEXT_PATH, e);
+                    std::process::exit(1);
+                }
+            }
 
             //--------------------------------------------------------------------------
 
@@ -337,11 +661,7 @@ fn main() {
             match single_algo_anlysis_selection {
                 //single run su fault entries desiderate
                 0 => {
-                        let num_faults = Input::new()
-                        .with_prompt("Inserisci il numero di fault entries desiderate")
-                        .default(2000)
-                        .interact_text()
-                        .unwrap();
+                        let num_faults = select_sampling().with_bits(select_bit_distribution()).with_sites(select_site_selection());
 
                         file_path.push_str(".pdf");
 
@@ -351,6 +671,7 @@ fn main() {
                             run_case_study(
                                 0,
                                 num_faults,
+                                campaign,
                                 "sel_sort",
                                 &file_path,
                                 input_data.into_data("vector"),
@@ -358,8 +679,7 @@ fn main() {
                                 Instant::now(),
                                 "src/fault_list_manager/file_fault_list/selection_sort/mod.rs",
                                 "src/fault_list_manager/file_fault_list/selection_sort/sel_sort_ris.json",
-                                "src/fault_list_manager/file_fault_list/selection_sort/sel_sort_FL.json",
-                                |vettore| run_for_count_selection_sort(vettore));
+                                "src/fault_list_manager/file_fault_list/selection_sort/sel_sort_FL.json");
                         }
 
                         //single run bubble sort
@@ -367,6 +687,7 @@ fn main() {
                             run_case_study(
                                 1,
                                 num_faults,
+                                campaign,
                                 "bubble_sort",
                                 &file_path,
                                 input_data.into_data("vector"),
@@ -374,8 +695,7 @@ fn main() {
                                 Instant::now(),
                                 "src/fault_list_manager/file_fault_list/bubble_sort/mod.rs",
                                 "src/fault_list_manager/file_fault_list/bubble_sort/bubble_sort_ris.json",
-                                "src/fault_list_manager/file_fault_list/bubble_sort/bubble_sort_FL.json",
-                                |vettore| run_for_count_bubble_sort(vettore)
+                                "src/fault_list_manager/file_fault_list/bubble_sort/bubble_sort_FL.json"
                             );
                         }
 
@@ -384,6 +704,7 @@ fn main() {
                             run_case_study(
                                 2,
                                 num_faults,
+                                campaign,
                                 "matrix_multiplication",
                                 &file_path,
                                 input_data.into_data("matrices"),
@@ -391,11 +712,186 @@ fn main() {
                                 Instant::now(),
                                 "src/fault_list_manager/file_fault_list/matrix_multiplication/mod.rs",
                                 "src/fault_list_manager/file_fault_list/matrix_multiplication/matrix_mul_ris.json",
-                                "src/fault_list_manager/file_fault_list/matrix_multiplication/matrix_mul_FL.json",
-                                |matrici| run_for_count_matrix_mul(matrici,input_data.matrix_size)
+                                "src/fault_list_manager/file_fault_list/matrix_multiplication/matrix_mul_FL.json"
+                            );
+                        }
+
+                        //single run quick sort
+                        3 => {
+                            run_case_study(
+                                0,
+                                num_faults,
+                                campaign,
+                                "quick_sort",
+                                &file_path,
+                                input_data.into_data("vector"),
+                                input_data.into_dimdata("frames_quick_sort"),
+                                Instant::now(),
+                                "src/fault_list_manager/file_fault_list/quick_sort/mod.rs",
+                                "src/fault_list_manager/file_fault_list/quick_sort/quick_sort_ris.json",
+                                "src/fault_list_manager/file_fault_list/quick_sort/quick_sort_FL.json"
+                            );
+                        }
+
+                        //single run merge sort
+                        4 => {
+                            run_case_study(
+                                0,
+                                num_faults,
+                                campaign,
+                                "merge_sort",
+                                &file_path,
+                                input_data.into_data("vector"),
+                                input_data.into_dimdata("frames_merge_sort"),
+                                Instant::now(),
+                                "src/fault_list_manager/file_fault_list/merge_sort/mod.rs",
+                                "src/fault_list_manager/file_fault_list/merge_sort/merge_sort_ris.json",
+                                "src/fault_list_manager/file_fault_list/merge_sort/merge_sort_FL.json"
+                            );
+                        }
+
+                        //single run bfs
+                        5 => {
+                            run_case_study(
+                                0,
+                                num_faults,
+                                campaign,
+                                "bfs",
+                                &file_path,
+                                input_data.into_data("graph"),
+                                input_data.into_dimdata("graph"),
+                                Instant::now(),
+                                "src/fault_list_manager/file_fault_list/bfs/mod.rs",
+                                "src/fault_list_manager/file_fault_list/bfs/bfs_ris.json",
+                                "src/fault_list_manager/file_fault_list/bfs/bfs_FL.json"
+                            );
+                        }
+
+                        //single run dijkstra
+                        6 => {
+                            run_case_study(
+                                0,
+                                num_faults,
+                                campaign,
+                                "dijkstra",
+                                &file_path,
+                                input_data.into_data("graph"),
+                                input_data.into_dimdata("graph"),
+                                Instant::now(),
+                                "src/fault_list_manager/file_fault_list/dijkstra/mod.rs",
+                                "src/fault_list_manager/file_fault_list/dijkstra/dijkstra_ris.json",
+                                "src/fault_list_manager/file_fault_list/dijkstra/dijkstra_FL.json"
+                            );
+                        }
+
+                        //single run prodotto scalare
+                        7 => {
+                            run_case_study(
+                                0,
+                                num_faults,
+                                campaign,
+                                "dot_product",
+                                &file_path,
+                                input_data.into_data("dot_product"),
+                                input_data.into_dimdata("dot_product"),
+                                Instant::now(),
+                                "src/fault_list_manager/file_fault_list/dot_product/mod.rs",
+                                "src/fault_list_manager/file_fault_list/dot_product/dot_product_ris.json",
+                                "src/fault_list_manager/file_fault_list/dot_product/dot_product_FL.json"
+                            );
+                        }
+
+                        //single run filtro FIR
+                        8 => {
+                            run_case_study(
+                                0,
+                                num_faults,
+                                campaign,
+                                "fir_filter",
+                                &file_path,
+                                input_data.into_data("fir_filter"),
+                                input_data.into_dimdata("fir_filter"),
+                                Instant::now(),
+                                "src/fault_list_manager/file_fault_list/fir_filter/mod.rs",
+                                "src/fault_list_manager/file_fault_list/fir_filter/fir_filter_ris.json",
+                                "src/fault_list_manager/file_fault_list/fir_filter/fir_filter_FL.json"
+                            );
+                        }
+
+                        //single run metodo di Jacobi
+                        9 => {
+                            run_case_study(
+                                0,
+                                num_faults,
+                                campaign,
+                                "jacobi",
+                                &file_path,
+                                input_data.into_data("jacobi"),
+                                input_data.into_dimdata("jacobi"),
+                                Instant::now(),
+                                "src/fault_list_manager/file_fault_list/jacobi/mod.rs",
+                                "src/fault_list_manager/file_fault_list/jacobi/jacobi_ris.json",
+                                "src/fault_list_manager/file_fault_list/jacobi/jacobi_FL.json"
+                            );
+                        }
+
+                        //single run CRC32
+                        10 => {
+                            run_case_study(
+                                0,
+                                num_faults,
+                                campaign,
+                                "crc32",
+                                &file_path,
+                                input_data.into_data("crc32"),
+                                input_data.into_dimdata("crc32"),
+                                Instant::now(),
+                                "src/fault_list_manager/file_fault_list/crc32/mod.rs",
+                                "src/fault_list_manager/file_fault_list/crc32/crc32_ris.json",
+                                "src/fault_list_manager/file_fault_list/crc32/crc32_FL.json"
+                            );
+                        }
+
+                        //single run ricerca KMP
+                        11 => {
+                            run_case_study(
+                                0,
+                                num_faults,
+                                campaign,
+                                "kmp",
+                                &file_path,
+                                input_data.into_data("kmp"),
+                                input_data.into_dimdata("kmp"),
+                                Instant::now(),
+                                "src/fault_list_manager/file_fault_list/kmp/mod.rs",
+                                "src/fault_list_manager/file_fault_list/kmp/kmp_ris.json",
+                                "src/fault_list_manager/file_fault_list/kmp/kmp_FL.json"
+                            );
+                        }
+
+                        //single run matrix multiplication parallela
+                        12 => {
+                            run_case_study(
+                                0,
+                                num_faults,
+                                campaign,
+                                "par_matrix_multiplication",
+                                &file_path,
+                                input_data.into_data("matrices"),
+                                input_data.into_dimdata("workers"),
+                                Instant::now(),
+                                "src/fault_list_manager/file_fault_list/par_matrix_multiplication/mod.rs",
+                                "src/fault_list_manager/file_fault_list/par_matrix_multiplication/par_matrix_mul_ris.json",
+                                "src/fault_list_manager/file_fault_list/par_matrix_multiplication/par_matrix_mul_FL.json"
                             );
                         }
 
+                        //single run di un target caricato da una libreria dinamica
+                        13 => {
+                            let plugin = select_plugin();
+                            run_plugin_case_study(0, num_faults, campaign, &plugin, &file_path, input_data.into_data("vector"), Instant::now());
+                        }
+
                         _ => println!("Invalid selection."),
                     }
                 }
@@ -413,6 +909,7 @@ fn main() {
                                 run_case_study(
                                     esecuzione,
                                     cardinality,
+                                    campaign,
                                     "sel_sort",
                                     &file_path,
                                     input_data.into_data("vector"),
@@ -420,8 +917,7 @@ fn main() {
                                     Instant::now(),
                                     "src/fault_list_manager/file_fault_list/selection_sort/mod.rs",
                                     "src/fault_list_manager/file_fault_list/selection_sort/sel_sort_ris.json",
-                                    "src/fault_list_manager/file_fault_list/selection_sort/sel_sort_FL.json",
-                                    |vettore| run_for_count_selection_sort(vettore)
+                                    "src/fault_list_manager/file_fault_list/selection_sort/sel_sort_FL.json"
                                 );
                                 esecuzione += 1;
                             }
@@ -434,6 +930,7 @@ fn main() {
                                 run_case_study(
                                     esecuzione,
                                     cardinality,
+                                    campaign,
                                     "bubble_sort",
                                     &file_path,
                                     input_data.into_data("vector"),
@@ -441,8 +938,7 @@ fn main() {
                                     Instant::now(),
                                     "src/fault_list_manager/file_fault_list/bubble_sort/mod.rs",
                                     "src/fault_list_manager/file_fault_list/bubble_sort/bubble_sort_ris.json",
-                                    "src/fault_list_manager/file_fault_list/bubble_sort/bubble_sort_FL.json",
-                                    |vettore| run_for_count_bubble_sort(vettore)
+                                    "src/fault_list_manager/file_fault_list/bubble_sort/bubble_sort_FL.json"
                                 );
                                 esecuzione += 1;
                             }
@@ -455,6 +951,7 @@ fn main() {
                                 run_case_study(
                                     esecuzione,
                                     cardinality,
+                                    campaign,
                                     "matrix_multiplication",
                                     &file_path,
                                     input_data.into_data("matrices"),
@@ -462,12 +959,209 @@ fn main() {
                                     Instant::now(),
                                     "src/fault_list_manager/file_fault_list/matrix_multiplication/mod.rs",
                                     "src/fault_list_manager/file_fault_list/matrix_multiplication/matrix_mul_ris.json",
-                                    "src/fault_list_manager/file_fault_list/matrix_multiplication/matrix_mul_FL.json",
-                                    |matrici| run_for_count_matrix_mul(matrici,input_data.matrix_size)
+                                    "src/fault_list_manager/file_fault_list/matrix_multiplication/matrix_mul_FL.json"
                                 );
                                 esecuzione += 1;
                             }
                         }
+                        3 => {
+                            // Caso studio 4: Quick Sort
+                            for (esecuzione, cardinality) in (0..).zip(cardinalities) {
+                                println!("Esecuzione Quick Sort con cardinalità:{}",cardinality);
+                                run_case_study(
+                                    esecuzione,
+                                    cardinality,
+                                    campaign,
+                                    "quick_sort",
+                                    &file_path,
+                                    input_data.into_data("vector"),
+                                    input_data.into_dimdata("frames_quick_sort"),
+                                    Instant::now(),
+                                    "src/fault_list_manager/file_fault_list/quick_sort/mod.rs",
+                                    "src/fault_list_manager/file_fault_list/quick_sort/quick_sort_ris.json",
+                                    "src/fault_list_manager/file_fault_list/quick_sort/quick_sort_FL.json"
+                                );
+                            }
+                        }
+                        4 => {
+                            // Caso studio 5: Merge Sort
+                            for (esecuzione, cardinality) in (0..).zip(cardinalities) {
+                                println!("Esecuzione Merge Sort con cardinalità:{}",cardinality);
+                                run_case_study(
+                                    esecuzione,
+                                    cardinality,
+                                    campaign,
+                                    "merge_sort",
+                                    &file_path,
+                                    input_data.into_data("vector"),
+                                    input_data.into_dimdata("frames_merge_sort"),
+                                    Instant::now(),
+                                    "src/fault_list_manager/file_fault_list/merge_sort/mod.rs",
+                                    "src/fault_list_manager/file_fault_list/merge_sort/merge_sort_ris.json",
+                                    "src/fault_list_manager/file_fault_list/merge_sort/merge_sort_FL.json"
+                                );
+                            }
+                        }
+                        5 => {
+                            // Caso studio 6: BFS
+                            for (esecuzione, cardinality) in (0..).zip(cardinalities) {
+                                println!("Esecuzione BFS con cardinalità:{}",cardinality);
+                                run_case_study(
+                                    esecuzione,
+                                    cardinality,
+                                    campaign,
+                                    "bfs",
+                                    &file_path,
+                                    input_data.into_data("graph"),
+                                    input_data.into_dimdata("graph"),
+                                    Instant::now(),
+                                    "src/fault_list_manager/file_fault_list/bfs/mod.rs",
+                                    "src/fault_list_manager/file_fault_list/bfs/bfs_ris.json",
+                                    "src/fault_list_manager/file_fault_list/bfs/bfs_FL.json"
+                                );
+                            }
+                        }
+                        6 => {
+                            // Caso studio 7: Dijkstra
+                            for (esecuzione, cardinality) in (0..).zip(cardinalities) {
+                                println!("Esecuzione Dijkstra con cardinalità:{}",cardinality);
+                                run_case_study(
+                                    esecuzione,
+                                    cardinality,
+                                    campaign,
+                                    "dijkstra",
+                                    &file_path,
+                                    input_data.into_data("graph"),
+                                    input_data.into_dimdata("graph"),
+                                    Instant::now(),
+                                    "src/fault_list_manager/file_fault_list/dijkstra/mod.rs",
+                                    "src/fault_list_manager/file_fault_list/dijkstra/dijkstra_ris.json",
+                                    "src/fault_list_manager/file_fault_list/dijkstra/dijkstra_FL.json"
+                                );
+                            }
+                        }
+                        7 => {
+                            // Caso studio 8: Prodotto scalare
+                            for (esecuzione, cardinality) in (0..).zip(cardinalities) {
+                                println!("Esecuzione Prodotto scalare con cardinalità:{}",cardinality);
+                                run_case_study(
+                                    esecuzione,
+                                    cardinality,
+                                    campaign,
+                                    "dot_product",
+                                    &file_path,
+                                    input_data.into_data("dot_product"),
+                                    input_data.into_dimdata("dot_product"),
+                                    Instant::now(),
+                                    "src/fault_list_manager/file_fault_list/dot_product/mod.rs",
+                                    "src/fault_list_manager/file_fault_list/dot_product/dot_product_ris.json",
+                                    "src/fault_list_manager/file_fault_list/dot_product/dot_product_FL.json"
+                                );
+                            }
+                        }
+                        8 => {
+                            // Caso studio 9: Filtro FIR
+                            for (esecuzione, cardinality) in (0..).zip(cardinalities) {
+                                println!("Esecuzione Filtro FIR con cardinalità:{}",cardinality);
+                                run_case_study(
+                                    esecuzione,
+                                    cardinality,
+                                    campaign,
+                                    "fir_filter",
+                                    &file_path,
+                                    input_data.into_data("fir_filter"),
+                                    input_data.into_dimdata("fir_filter"),
+                                    Instant::now(),
+                                    "src/fault_list_manager/file_fault_list/fir_filter/mod.rs",
+                                    "src/fault_list_manager/file_fault_list/fir_filter/fir_filter_ris.json",
+                                    "src/fault_list_manager/file_fault_list/fir_filter/fir_filter_FL.json"
+                                );
+                            }
+                        }
+                        9 => {
+                            // Caso studio 10: Metodo di Jacobi
+                            for (esecuzione, cardinality) in (0..).zip(cardinalities) {
+                                println!("Esecuzione Metodo di Jacobi con cardinalità:{}",cardinality);
+                                run_case_study(
+                                    esecuzione,
+                                    cardinality,
+                                    campaign,
+                                    "jacobi",
+                                    &file_path,
+                                    input_data.into_data("jacobi"),
+                                    input_data.into_dimdata("jacobi"),
+                                    Instant::now(),
+                                    "src/fault_list_manager/file_fault_list/jacobi/mod.rs",
+                                    "src/fault_list_manager/file_fault_list/jacobi/jacobi_ris.json",
+                                    "src/fault_list_manager/file_fault_list/jacobi/jacobi_FL.json"
+                                );
+                            }
+                        }
+                        10 => {
+                            // Caso studio 11: CRC32
+                            for (esecuzione, cardinality) in (0..).zip(cardinalities) {
+                                println!("Esecuzione CRC32 con cardinalità:{}",cardinality);
+                                run_case_study(
+                                    esecuzione,
+                                    cardinality,
+                                    campaign,
+                                    "crc32",
+                                    &file_path,
+                                    input_data.into_data("crc32"),
+                                    input_data.into_dimdata("crc32"),
+                                    Instant::now(),
+                                    "src/fault_list_manager/file_fault_list/crc32/mod.rs",
+                                    "src/fault_list_manager/file_fault_list/crc32/crc32_ris.json",
+                                    "src/fault_list_manager/file_fault_list/crc32/crc32_FL.json"
+                                );
+                            }
+                        }
+                        11 => {
+                            // Caso studio 12: Ricerca KMP
+                            for (esecuzione, cardinality) in (0..).zip(cardinalities) {
+                                println!("Esecuzione Ricerca KMP con cardinalità:{}",cardinality);
+                                run_case_study(
+                                    esecuzione,
+                                    cardinality,
+                                    campaign,
+                                    "kmp",
+                                    &file_path,
+                                    input_data.into_data("kmp"),
+                                    input_data.into_dimdata("kmp"),
+                                    Instant::now(),
+                                    "src/fault_list_manager/file_fault_list/kmp/mod.rs",
+                                    "src/fault_list_manager/file_fault_list/kmp/kmp_ris.json",
+                                    "src/fault_list_manager/file_fault_list/kmp/kmp_FL.json"
+                                );
+                            }
+                        }
+                        12 => {
+                            // Caso studio 13: Matrix Multiplication parallela
+                            for (esecuzione, cardinality) in (0..).zip(cardinalities) {
+                                println!("Esecuzione Matrix Multiplication parallela con cardinalità:{}",cardinality);
+                                run_case_study(
+                                    esecuzione,
+                                    cardinality,
+                                    campaign,
+                                    "par_matrix_multiplication",
+                                    &file_path,
+                                    input_data.into_data("matrices"),
+                                    input_data.into_dimdata("workers"),
+                                    Instant::now(),
+                                    "src/fault_list_manager/file_fault_list/par_matrix_multiplication/mod.rs",
+                                    "src/fault_list_manager/file_fault_list/par_matrix_multiplication/par_matrix_mul_ris.json",
+                                    "src/fault_list_manager/file_fault_list/par_matrix_multiplication/par_matrix_mul_FL.json"
+                                );
+                            }
+                        }
+                        13 => {
+                            // Target esterno caricato come plugin
+                            let plugin = select_plugin();
+                            for (esecuzione, cardinality) in (0..).zip(cardinalities) {
+                                println!("Esecuzione del plugin {} con cardinalità:{}", plugin.name(), cardinality);
+                                run_plugin_case_study(esecuzione, cardinality, campaign, &plugin, &file_path, input_data.into_data("vector"), Instant::now());
+                            }
+                        }
                         _ => println!("Invalid selection."),
                     }
                 }
@@ -493,6 +1187,7 @@ fn main() {
             run_case_study(
                 esecuzione,
                 num_faults,
+                campaign,
                 "sel_sort",
                 &file_path,
                 input_data.into_data("vector"),
@@ -500,8 +1195,7 @@ fn main() {
                 Instant::now(),
                 "src/fault_list_manager/file_fault_list/selection_sort/mod.rs",
                 "src/fault_list_manager/file_fault_list/selection_sort/sel_sort_ris.json",
-                "src/fault_list_manager/file_fault_list/selection_sort/sel_sort_FL.json",
-                |vettore| run_for_count_selection_sort(vettore)
+                "src/fault_list_manager/file_fault_list/selection_sort/sel_sort_FL.json"
             );
 
             esecuzione += 1;
@@ -510,6 +1204,7 @@ fn main() {
             run_case_study(
                 esecuzione,
                 num_faults,
+                campaign,
                 "bubble_sort",
                 &file_path,
                 input_data.into_data("vector"),
@@ -517,8 +1212,7 @@ fn main() {
                 Instant::now(),
                 "src/fault_list_manager/file_fault_list/bubble_sort/mod.rs",
                 "src/fault_list_manager/file_fault_list/bubble_sort/bubble_sort_ris.json",
-                "src/fault_list_manager/file_fault_list/bubble_sort/bubble_sort_FL.json",
-                |vettore| run_for_count_bubble_sort(vettore)
+                "src/fault_list_manager/file_fault_list/bubble_sort/bubble_sort_FL.json"
             );
 
             esecuzione += 1;
@@ -527,6 +1221,7 @@ fn main() {
             run_case_study(
                 esecuzione,
                 num_faults,
+                campaign,
                 "matrix_multiplication",
                 &file_path,
                 input_data.into_data("matrices"),
@@ -534,8 +1229,7 @@ fn main() {
                 Instant::now(),
                 "src/fault_list_manager/file_fault_list/matrix_multiplication/mod.rs",
                 "src/fault_list_manager/file_fault_list/matrix_multiplication/matrix_mul_ris.json",
-                "src/fault_list_manager/file_fault_list/matrix_multiplication/matrix_mul_FL.json",
-                |matrici| run_for_count_matrix_mul(matrici,input_data.matrix_size)
+                "src/fault_list_manager/file_fault_list/matrix_multiplication/matrix_mul_FL.json"
             );
         }
 
@@ -544,9 +1238,169 @@ fn main() {
     println!("Operazione completata. Report salvato in: {}", file_path);
 
 
+    //Chiede come generare la fault list: numero di entry casuali, enumerazione esaustiva oppure
+    //campione dimensionato per il margine di errore desiderato
+    fn select_sampling() -> Sampling {
+        let modes = vec!["Numero di fault entries casuali", "Enumerazione esaustiva dello spazio dei fault", "Campione statistico (margine di errore)"];
+        let mode = Select::new()
+            .with_prompt("Scegli come generare la fault list")
+            .items(&modes)
+            .default(0)
+            .interact()
+            .unwrap();
+        match mode {
+            0 => {
+                let num_faults: i32 = Input::new()
+                    .with_prompt("Inserisci il numero di fault entries desiderate")
+                    .default(2000)
+                    .interact_text()
+                    .unwrap();
+                num_faults.into()
+            }
+            1 => Sampling::Exhaustive,
+            _ => {
+                let margin: f64 = Input::new()
+                    .with_prompt("Inserisci il margine di errore (es. 0.01 per l'1%)")
+                    .default(0.01)
+                    .interact_text()
+                    .unwrap();
+                let levels = [0.90, 0.95, 0.99];
+                let level = Select::new()
+                    .with_prompt("Scegli il livello di confidenza")
+                    .items(&["90%", "95%", "99%"])
+                    .default(1)
+                    .interact()
+                    .unwrap();
+                Sampling::Statistical{ margin, confidence: levels[level] }
+            }
+        }
+    }
+
+    //Chiede come scegliere la posizione del bit invertito in ogni entry
+    fn select_bit_distribution() -> BitDistribution {
+        let distributions = [BitDistribution::Uniform, BitDistribution::MsbWeighted, BitDistribution::LsbOnly];
+        let selection = Select::new()
+            .with_prompt("Scegli la distribuzione dei bit da invertire")
+            .items(&["Uniforme", "Pesata verso il bit più significativo", "Solo il bit meno significativo"])
+            .default(0)
+            .interact()
+            .unwrap();
+        distributions[selection]
+    }
+
+    //Chiede come scegliere la variabile di ogni entry casuale; i pesi personalizzati sono letti
+    //da un file JSON che associa a ogni variabile il suo peso
+    fn select_site_selection() -> SiteSelection {
+        let selection = Select::new()
+            .with_prompt("Scegli come selezionare le variabili da iniettare")
+            .items(&["Uniforme per variabile", "Proporzionale ai bit di stato", "Proporzionale alla durata di vita", "Pesi da file di configurazione"])
+            .default(0)
+            .interact()
+            .unwrap();
+        match selection {
+            0 => SiteSelection::PerVariable,
+            1 => SiteSelection::Area,
+            2 => SiteSelection::Lifetime,
+            _ => {
+                let path: String = Input::new()
+                    .with_prompt("Inserisci il percorso del file dei pesi")
+                    .default(SITE_WEIGHTS_PATH.to_string())
+                    .interact_text()
+                    .unwrap();
+                match load_site_weights(&path) {
+                    Ok(weights) => SiteSelection::Custom(weights),
+                    Err(e) => {
+                        eprintln!("Errore: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+    }
+
+    //Chiede il percorso della libreria e carica il plugin
+    fn select_plugin() -> Arc<Plugin> {
+        let default_path = Path::new("target/debug").join(libloading::library_filename("bubble_sort_plugin"));
+        let path: String = Input::new()
+            .with_prompt("Inserisci il percorso della libreria del plugin")
+            .default(default_path.to_string_lossy().into_owned())
+            .interact_text()
+            .unwrap();
+        match plugin::load(&path) {
+            Ok(plugin) => plugin,
+            Err(e) => {
+                eprintln!("Errore: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    //Come run_case_study, ma il file di analisi è costruito dal registro delle variabili del
+    //plugin e il numero di istruzioni da una sua esecuzione senza fault
+    fn run_plugin_case_study(esecuzione:i8,
+                             num_faults: impl Into<FaultListParams>,
+                             campaign: Campaign,
+                             plugin: &Arc<Plugin>,
+                             file_path: &str,
+                             input_data: Data<i32>,
+                             start: Instant){
+        let analysis_output_file = format!("results/{}_ris.json", plugin.name());
+        let fault_list_file = campaign.fl_format.apply(&format!("results/{}_FL.json", plugin.name()));
+        let input = input_data.clone().into_vector();
+
+        let lengths = plugin.generate_analysis_file(&input, &analysis_output_file)
+            .expect("Generazione del file di analisi del plugin fallita");
+        let trace = injector::label_trace(plugin.name(), input_data.clone());
+        let profile = ExecutionProfile::from_trace(&trace);
+        let (dim_data, num_instr) = (DimData::Lengths(lengths), profile.steps);
+        if let Delivery::Stream { tee } = campaign.delivery {
+            let stream = match fault_list_manager::generate_fault_list(
+                num_faults, plugin.name().to_string(), analysis_output_file, dim_data, profile, campaign.seed) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("Errore nella generazione della fault list: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            fault_injection_env(
+                FaultSource::Stream(Box::new(stream), tee.then_some(fault_list_file)),
+                plugin.name().to_string(),
+                file_path.to_string(),
+                input_data,
+                start,
+                esecuzione
+            );
+            return;
+        }
+        if let Err(e) = fault_list_manager::create_fault_list(
+            num_faults,
+            plugin.name().to_string(),
+            analysis_output_file.clone(),
+            dim_data.clone(),
+            fault_list_file.clone(),
+            profile,
+            campaign.seed,
+        ) {
+            eprintln!("Errore nella generazione della fault list: {}", e);
+            std::process::exit(1);
+        }
+        fault_list_manager::liveness::prune_fault_list(&fault_list_file, plugin.name(), &analysis_output_file, trace);
+        //la fault list viene validata all'apertura, prima di avviare la campagna
+        let registry = Registry::new(&analysis_output_file, plugin.name(), &dim_data, plugin.name(), num_instr);
+        fault_injection_env(
+            FaultSource::File(fault_list_file, registry),
+            plugin.name().to_string(),
+            file_path.to_string(),
+            input_data,
+            start,
+            esecuzione
+        );
+    }
+
     // Avvia la pipeline
     fn run_case_study(esecuzione:i8,
-                      num_faults: i32,
+                      num_faults: impl Into<FaultListParams>,
+                      campaign: Campaign,
                       case_name: &str,
                       file_path: &str,
                       input_data: Data<i32>,
@@ -554,28 +1408,64 @@ fn main() {
                       start: Instant,
                       analysis_input_file: &str,
                       analysis_output_file: &str,
-                      fault_list_file: &str,
-                      fault_list_run: impl FnOnce(Data<i32>) -> usize){
+                      fault_list_file: &str){
         // 1. Analisi statica del codice
 
         static_analysis::generate_analysis_file(
             analysis_input_file.to_string(),
             analysis_output_file.to_string(),
+            campaign.pointer_width,
         ).expect("Analisi statica del codice fallita");
 
-        // 2. Generazione della fault list (FL)
-        fault_list_manager::create_fault_list(
+        // 2. Esecuzione senza fault del runner: numero di istruzioni ed etichette eseguite
+        let trace = injector::label_trace(case_name, input_data.clone());
+        let profile = ExecutionProfile::from_trace(&trace);
+        let num_instr = profile.steps;
+
+        // 3. Generazione della fault list (FL), nel formato scelto per la campagna
+        let fault_list_file = &campaign.fl_format.apply(fault_list_file);
+        //in streaming le entry vanno direttamente all'iniettore: non c'è una fault list
+        //completa da potare, e le entry generate appartengono allo spazio dei fault per costruzione
+        if let Delivery::Stream { tee } = campaign.delivery {
+            let stream = match fault_list_manager::generate_fault_list(
+                num_faults, case_name.to_string(), analysis_output_file.to_string(), dim_data, profile, campaign.seed) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("Errore nella generazione della fault list: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            fault_injection_env(
+                FaultSource::Stream(Box::new(stream), tee.then(|| fault_list_file.to_string())),
+                case_name.to_string(),
+                file_path.to_string(),
+                input_data,
+                start,
+                esecuzione
+            );
+            return;
+        }
+        if let Err(e) = fault_list_manager::create_fault_list(
             num_faults,
             case_name.to_string(),
             analysis_output_file.to_string(),
-            dim_data,
+            dim_data.clone(),
             fault_list_file.to_string(),
-            fault_list_run(input_data.clone()),
-        );
+            profile,
+            campaign.seed,
+        ) {
+            eprintln!("Errore nella generazione della fault list: {}", e);
+            std::process::exit(1);
+        }
 
-        // 3. Faccio partire l'ambiente di fault injection
+        // 4. Potatura della FL: si eliminano le iniezioni morte e si riuniscono quelle equivalenti
+        fault_list_manager::liveness::prune_fault_list(fault_list_file, case_name, analysis_output_file, trace);
+
+        // 5. Faccio partire l'ambiente di fault injection: la FL viene validata all'apertura rispetto
+        //    alle variabili del target e alle dimensioni dell'input
+        let registry = Registry::new(analysis_output_file, target_function(case_name), &dim_data, case_name, num_instr);
         fault_injection_env(
-            fault_list_file.to_string(),
+            FaultSource::File(fault_list_file.to_string(), registry),
             case_name.to_string(),
             file_path.to_string(),
             input_data.clone(),
@@ -583,4 +1473,38 @@ fn main() {
             esecuzione
         );
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+    use std::fs;
+    use crate::fault_env::AdjacencyList;
+    use crate::{load_graph_from_file, GRAPH_PATH};
+
+    fn load(name: &str, content: &str) -> Result<(AdjacencyList<i32>, usize), std::io::Error> {
+        let path = temp_dir().join(name);
+        fs::write(&path, content).unwrap();
+        load_graph_from_file(&path.to_string_lossy())
+    }
+
+    #[test]
+    fn test_load_graph_from_file() {
+        //l'intestazione testuale viene scartata
+        let (graph, source) = load("graph_ok_test.txt", "** GRAPH **\n- nodi archi sorgente\n\n3 3 1\n0 1 4\n1 2 1\n1 0 2\n").unwrap();
+        assert_eq!(graph, vec![vec![(1, 4)], vec![(2, 1), (0, 2)], vec![]]);
+        assert_eq!(source, 1);
+        let (graph, source) = load_graph_from_file(GRAPH_PATH).unwrap();
+        assert!(source < graph.len());
+
+        for (name, content) in [("graph_source_test.txt", "2 1 2\n0 1 1\n"),      //sorgente fuori dal grafo
+                                ("graph_edges_test.txt", "3 2 0\n0 1 1\n"),      //archi mancanti
+                                ("graph_node_test.txt", "2 1 0\n0 5 1\n"),       //nodo inesistente
+                                ("graph_weight_test.txt", "2 1 0\n0 1 0\n"),     //peso non positivo
+                                ("graph_header_test.txt", "2 1\n0 1 1\n"),       //intestazione incompleta
+                                ("graph_empty_test.txt", "nessun dato\n")] {
+            assert_eq!(load(name, content).unwrap_err().kind(), std::io::ErrorKind::InvalidData, "{}", name);
+        }
+        assert!(load_graph_from_file("src/data/nessun_grafo.txt").is_err());
+    }
+}
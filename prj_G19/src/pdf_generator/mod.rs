@@ -23,6 +23,8 @@ use genpdf::{elements, fonts};
 use genpdf::elements::{FrameCellDecorator, LinearLayout, PageBreak, Paragraph, TableLayout, UnorderedList};
 use genpdf::style::{Color, Style};
 use crate::analyzer::Analyzer;
+use crate::plugin;
+use crate::fault_list_manager::{BitDistribution, Sampling, SiteSelection};
 
 const FONT_DIRS: &[&str] = &[
     "src/pdf_generator/fonts/times_new_roman"
@@ -45,6 +47,9 @@ pub fn print_pdf_all(file_path: &String, data_list: Vec<Analyzer>){
         doc.push(Paragraph::default().styled_string("Algoritmo eseguito: ", bold_italic).styled_string(side_headers[i].to_string(),italic).padded(text_margins));
         doc.push(elements::Break::new(0.3));
         doc.push(Paragraph::default().styled_string("Numero di faults: ",bold_italic).styled_string(data_list[i].faults.total_fault.to_string(),italic).padded(text_margins));
+        if let Some(paragraph) = error_margin_paragraph(&data_list[i], "Margine di errore: ") {
+            doc.push(paragraph);
+        }
         doc.push(elements::Break::new(0.3));
         doc.push(list_input_output.pop().unwrap().padded(Margins::trbl(0, 0,0,-10)));
         doc.push(elements::Break::new(0.5));
@@ -99,6 +104,17 @@ pub fn print_pdf_diffcard(file_path: &String, data_list: Vec<Analyzer>){
         "sel_sort"=> for _ in 0..3 {chart_headers.push("SELECTION SORT")} ,
         "bubble_sort"=> for _ in 0..3 {chart_headers.push("BUBBLE SORT")},
         "matrix_multiplication"=> for _ in 0..3 {chart_headers.push("MATRIX MULTIPLICATION")},
+        "quick_sort"=> for _ in 0..3 {chart_headers.push("QUICK SORT")},
+        "merge_sort"=> for _ in 0..3 {chart_headers.push("MERGE SORT")},
+        "bfs"=> for _ in 0..3 {chart_headers.push("BFS")},
+        "dijkstra"=> for _ in 0..3 {chart_headers.push("DIJKSTRA")},
+        "dot_product"=> for _ in 0..3 {chart_headers.push("DOT PRODUCT")},
+        "fir_filter"=> for _ in 0..3 {chart_headers.push("FIR FILTER")},
+        "jacobi"=> for _ in 0..3 {chart_headers.push("JACOBI")},
+        "crc32"=> for _ in 0..3 {chart_headers.push("CRC32")},
+        "kmp"=> for _ in 0..3 {chart_headers.push("KMP")},
+        "par_matrix_multiplication"=> for _ in 0..3 {chart_headers.push("PARALLEL MATRIX MULTIPLICATION")},
+        name if plugin::get(name).is_some() => for _ in 0..3 {chart_headers.push("PLUGIN")},
         _ => {}
     }
 
@@ -110,6 +126,11 @@ pub fn print_pdf_diffcard(file_path: &String, data_list: Vec<Analyzer>){
     doc.push(Paragraph::default().styled_string("Algoritmo eseguito: ", bold_italic).styled_string(side_headers[0].to_string(),italic).padded(text_margins));
     doc.push(elements::Break::new(0.3));
     doc.push(Paragraph::default().styled_string("Numero di faults: ",bold_italic).styled_string(format!("[{},{},{}]",data_list[0].faults.total_fault,data_list[1].faults.total_fault,data_list[2].faults.total_fault),italic).padded(text_margins));
+    for (anl, cardinality) in data_list.iter().zip(["1000", "2000", "3000"]) {
+        if let Some(paragraph) = error_margin_paragraph(anl, &format!("Margine di errore su {} iniezioni: ", cardinality)) {
+            doc.push(paragraph);
+        }
+    }
     doc.push(elements::Break::new(0.3));
     doc.push(list_input_output.pop().unwrap().padded(Margins::trbl(0, 0,0,-10)));
     doc.push(elements::Break::new(0.5));
@@ -148,6 +169,16 @@ pub fn print_pdf_diffcard(file_path: &String, data_list: Vec<Analyzer>){
     doc.push(Paragraph::default().styled_string("Fault fatatali su 1000 iniezioni: ",bold_italic).styled_string(format!("{} %",f64::trunc((data_list[0].faults.n_fatal_fault as f64/data_list[0].faults.total_fault as f64)*10000.0)/100.0),italic).styled_string(" (percentuale di fault iniettati che hanno provocato un output errato)",italic).padded(text_margins));
     doc.push(Paragraph::default().styled_string("Fault fatatali su 2000 iniezioni: ",bold_italic).styled_string(format!("{} %",f64::trunc((data_list[1].faults.n_fatal_fault as f64/data_list[1].faults.total_fault as f64)*10000.0)/100.0),italic).styled_string(" (percentuale di fault iniettati che hanno provocato un output errato)",italic).padded(text_margins));
     doc.push(Paragraph::default().styled_string("Fault fatatali su 3000 iniezioni: ",bold_italic).styled_string(format!("{} %",f64::trunc((data_list[2].faults.n_fatal_fault as f64/data_list[2].faults.total_fault as f64)*10000.0)/100.0),italic).styled_string(" (percentuale di fault iniettati che hanno provocato un output errato)",italic).padded(text_margins));
+    if is_real_target(&data_list[0].target_program) {
+        for (anl, cardinality) in data_list.iter().zip(["1000", "2000", "3000"]) {
+            doc.push(tolerated_paragraph(anl, &format!("Fault entro la tolleranza su {} iniezioni: ", cardinality)));
+        }
+    }
+    if is_parallel_target(&data_list[0].target_program) {
+        for (anl, cardinality) in data_list.iter().zip(["1000", "2000", "3000"]) {
+            doc.push(cross_thread_paragraph(anl, &format!("Rilevazioni cross-thread su {} iniezioni: ", cardinality)));
+        }
+    }
 
     doc.render_to_file(file_path)
         .expect("Failed to write output file");
@@ -171,6 +202,10 @@ pub fn print_pdf_singolo(file_path: &String, analyzer: Analyzer) {
     doc.push(Paragraph::default().styled_string("Algoritmo scelto: ", bold_italic).styled_string(side_headers[0].to_string(),italic).padded(text_margins));
     doc.push(elements::Break::new(0.3));
     doc.push(Paragraph::default().styled_string("Numero di faults: ",bold_italic).styled_string(analyzer.faults.total_fault.to_string(),italic).padded(text_margins));
+    if let Some(paragraph) = error_margin_paragraph(&analyzer, "Margine di errore: ") {
+        doc.push(elements::Break::new(0.3));
+        doc.push(paragraph);
+    }
     doc.push(elements::Break::new(0.3));
     doc.push(list_input_output.pop().unwrap().padded(Margins::trbl(0, 0,0,-10)));
     doc.push(elements::Break::new(0.5));
@@ -201,12 +236,90 @@ pub fn print_pdf_singolo(file_path: &String, analyzer: Analyzer) {
     doc.push(Paragraph::default().styled_string("Percentuale di detected:",bold_italic).styled_string(format!("{}",f64::trunc(((data_list[0].faults.total_fault as f64 - data_list[0].faults.n_silent_fault as f64)/data_list[0].faults.total_fault as f64)*10000.0)/100.0),italic).styled_string(" %",italic).padded(text_margins));
     doc.push(elements::Break::new(0.5));
     doc.push(Paragraph::default().styled_string("Fault fatatali: ",bold_italic).styled_string(format!("{} %",f64::trunc((data_list[0].faults.n_fatal_fault as f64/data_list[0].faults.total_fault as f64)*10000.0)/100.0),italic).styled_string(" (percentuale di fault iniettati che hanno provocato un output errato)",italic).padded(text_margins));
+    if is_real_target(&data_list[0].target_program) {
+        doc.push(tolerated_paragraph(&data_list[0], "Fault entro la tolleranza: "));
+    }
+    if is_parallel_target(&data_list[0].target_program) {
+        doc.push(cross_thread_paragraph(&data_list[0], "Rilevazioni cross-thread: "));
+    }
+    for paragraph in sdc_origin_paragraphs(&data_list[0]) {
+        doc.push(elements::Break::new(0.3));
+        doc.push(paragraph);
+    }
 
     doc.render_to_file(file_path)
         .expect("Failed to write output file");
 }
 
 
+///Righe del sorgente da cui hanno origine più fault con output errato (nessuna se la fault list
+/// non riporta la posizione delle istruzioni)
+fn sdc_origin_paragraphs(analyzer: &Analyzer) -> Vec<elements::PaddedElement<Paragraph>> {
+    let italic = Style::new().italic().with_font_size(10);
+    let bold_italic = Style::new().bold().italic().with_font_size(10);
+    analyzer.sdc_origins(3).into_iter().map(|(share, line)| {
+        Paragraph::default().styled_string("Origine degli SDC: ", bold_italic)
+            .styled_string(format!("il {:.0} % dei fault con output errato ha origine alla riga {} `{}`", share * 100.0, line.line, line.code), italic)
+            .padded(Margins::trbl(0, 70,0,0))
+    }).collect()
+}
+
+///I kernel numerici producono output reali, classificati anche in base alla tolleranza
+fn is_real_target(target: &str) -> bool {
+    matches!(target, "dot_product" | "fir_filter" | "jacobi")
+}
+
+fn tolerated_paragraph(analyzer: &Analyzer, label: &str) -> elements::PaddedElement<Paragraph> {
+    let italic = Style::new().italic().with_font_size(10);
+    let bold_italic = Style::new().bold().italic().with_font_size(10);
+    Paragraph::default().styled_string(label, bold_italic)
+        .styled_string(format!("{} %",f64::trunc((analyzer.faults.n_tolerated_fault as f64/analyzer.faults.total_fault as f64)*10000.0)/100.0),italic)
+        .styled_string(" (percentuale di fault iniettati con output diverso da quello atteso ma entro la tolleranza)",italic)
+        .padded(Margins::trbl(0, 70,0,0))
+}
+
+///Margine di errore della fault list, se la fault list ne riporta l'intestazione
+fn error_margin_paragraph(analyzer: &Analyzer, label: &str) -> Option<elements::PaddedElement<Paragraph>> {
+    let italic = Style::new().italic().with_font_size(10);
+    let bold_italic = Style::new().bold().italic().with_font_size(10);
+    let info = analyzer.fault_list_info.as_ref()?;
+    Some(Paragraph::default().styled_string(label, bold_italic)
+        .styled_string(format!("{:.2} %", info.error_margin * 100.0), italic)
+        .styled_string(format!(" (confidenza {} %, campionamento {}, spazio dei fault di {} elementi", info.confidence * 100.0, info.mode, info.space_size), italic)
+        .styled_string(info.seed.map(|seed| format!(", seed {}", seed)).unwrap_or_default(), italic)
+        .styled_string(match info.params.as_ref().map(|params| params.bits).unwrap_or_default() {
+            BitDistribution::Uniform => "",
+            BitDistribution::MsbWeighted => ", bit pesati verso il più significativo",
+            BitDistribution::LsbOnly => ", solo il bit meno significativo",
+        }, italic)
+        .styled_string(match info.params.as_ref().filter(|params| matches!(params.sampling, Sampling::Random(_))).map(|params| &params.sites) {
+            Some(SiteSelection::Area) => ", variabili scelte in proporzione ai bit di stato",
+            Some(SiteSelection::Lifetime) => ", variabili scelte in proporzione alla durata di vita",
+            Some(SiteSelection::Custom(_)) => ", variabili scelte con i pesi indicati dall'utente",
+            _ => "",
+        }, italic)
+        .styled_string(info.runs.map(|runs| format!(", {} esecuzioni dopo la potatura di {} iniezioni morte", runs, info.dead_faults)).unwrap_or_default(), italic)
+        .styled_string(")", italic)
+        .styled_string(analyzer.space_estimate.as_ref().map(|estimate| format!(". Stima sullo spazio dei fault: {:.2} % rilevati, {:.2} % silenti, {:.2} % con output errato",
+            estimate.detected * 100.0, estimate.silent * 100.0, estimate.corrupted * 100.0)).unwrap_or_default(), italic)
+        .padded(Margins::trbl(0, 70,0,0)))
+}
+
+///Nei target multi-thread si distingue se il fault è stato rilevato dallo stesso thread che aveva
+///scritto il dato corrotto oppure da un altro
+fn is_parallel_target(target: &str) -> bool {
+    matches!(target, "par_matrix_multiplication")
+}
+
+fn cross_thread_paragraph(analyzer: &Analyzer, label: &str) -> elements::PaddedElement<Paragraph> {
+    let italic = Style::new().italic().with_font_size(10);
+    let bold_italic = Style::new().bold().italic().with_font_size(10);
+    Paragraph::default().styled_string(label, bold_italic)
+        .styled_string(format!("{} (stesso thread: {})", analyzer.faults.n_cross_thread_detected, analyzer.faults.n_same_thread_detected), italic)
+        .styled_string(" (fault rilevati da un thread diverso da quello che aveva scritto il dato corrotto)",italic)
+        .padded(Margins::trbl(0, 70,0,0))
+}
+
 pub fn gen_bar_chart(data_list: &Vec<Analyzer>, side_headers:&Vec<&str>, x_axis_label: &str)-> &'static str {
     let mut percentages = Vec::new();
     for anl in data_list{
@@ -381,11 +494,87 @@ fn get_list_input_output(analyzer: &Analyzer, side_headers: &mut Vec<&str>) -> V
             list_input.push(p_input);
             list_output.push(p_output);
         },
-        "matrix_multiplication"=> {
+        "quick_sort" | "merge_sort"=> {
+            side_headers.push(if analyzer.target_program == "quick_sort" {"QUICK SORT"} else {"MERGE SORT"});
+            let p_input = Paragraph::default().styled_string("Vettore di input: ", bold_italic)
+                .styled_string(format!("{:?}",data_input.into_vector()),italic).padded(text_margins);
+            let  p_output = Paragraph::default().styled_string("Vettore ordinato: ", bold_italic)
+                .styled_string(format!("{:?}",data_output.into_vector()),italic).padded(text_margins);
+            list_input.push(p_input);
+            list_output.push(p_output);
+        },
+        "bfs" | "dijkstra"=> {
+            side_headers.push(if analyzer.target_program == "bfs" {"BFS"} else {"DIJKSTRA"});
+            let (graph, source) = data_input.into_graph();
+            let edges = graph.iter().enumerate()
+                .flat_map(|(u, adj)| adj.iter().map(move |(v, w)| format!("{}->{} ({})", u, v, w)))
+                .collect::<Vec<_>>();
+            //i nodi non raggiungibili dalla sorgente hanno distanza infinita
+            let dist = data_output.into_vector().iter()
+                .map(|&d| if d == i32::MAX { "inf".to_string() } else { d.to_string() })
+                .collect::<Vec<_>>();
+            let p_input = Paragraph::default().styled_string("Archi del grafo di input: ", bold_italic)
+                .styled_string(edges.join(", "),italic).padded(text_margins);
+            let  p_output = Paragraph::default().styled_string(format!("Distanze dal nodo {}: ", source), bold_italic)
+                .styled_string(format!("[{}]", dist.join(", ")),italic).padded(text_margins);
+            list_input.push(p_input);
+            list_output.push(p_output);
+        },
+        "dot_product" | "fir_filter"=> {
+            side_headers.push(if analyzer.target_program == "dot_product" {"DOT PRODUCT"} else {"FIR FILTER"});
+            let (x, second) = data_input.into_reals();
+            let second_label = if analyzer.target_program == "dot_product" {"Vettore y: "} else {"Coefficienti del filtro: "};
+            let p_input = Paragraph::default().styled_string("Vettore x: ", bold_italic)
+                .styled_string(format!("{:?}",x),italic)
+                .styled_string(format!("    {}", second_label), bold_italic)
+                .styled_string(format!("{:?}",second),italic).padded(text_margins);
+            let  p_output = Paragraph::default().styled_string("Output: ", bold_italic)
+                .styled_string(format!("{:?}",data_output.into_reals()),italic).padded(text_margins);
+            list_input.push(p_input);
+            list_output.push(p_output);
+        },
+        "jacobi"=> {
+            side_headers.push("JACOBI");
+            let (a, b) = data_input.into_linear_system();
+            let p_input = Paragraph::default().styled_string("Sistema lineare (A | b): ", bold_italic).padded(text_margins);
+            list_input.push(p_input);
+            for (row, bi) in a.iter().zip(&b) {
+                list_input.push(Paragraph::default()
+                    .styled_string(format!(" {} | {}", row.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(" "), bi),italic)
+                    .padded(text_margins));
+            }
+            let  p_output = Paragraph::default().styled_string("Soluzione approssimata: ", bold_italic)
+                .styled_string(format!("{:?}",data_output.into_reals()),italic).padded(text_margins);
+            list_output.push(p_output);
+        },
+        "crc32"=> {
+            side_headers.push("CRC32");
+            let message = data_input.into_bytes();
+            let p_input = Paragraph::default().styled_string("Messaggio: ", bold_italic)
+                .styled_string(format!("\"{}\" ({} byte)", String::from_utf8_lossy(&message), message.len()),italic).padded(text_margins);
+            //il CRC è memorizzato come i32: si stampa la sua rappresentazione a 32 bit
+            let  p_output = Paragraph::default().styled_string("CRC32: ", bold_italic)
+                .styled_string(format!("0x{:08X}", data_output.into_vector()[0] as u32),italic).padded(text_margins);
+            list_input.push(p_input);
+            list_output.push(p_output);
+        },
+        "kmp"=> {
+            side_headers.push("KMP");
+            let (text, pattern) = data_input.into_text();
+            let p_input = Paragraph::default().styled_string("Testo: ", bold_italic)
+                .styled_string(format!("\"{}\"", text),italic)
+                .styled_string("    Pattern: ", bold_italic)
+                .styled_string(format!("\"{}\"", pattern),italic).padded(text_margins);
+            let  p_output = Paragraph::default().styled_string("Posizioni delle occorrenze: ", bold_italic)
+                .styled_string(format!("{:?}",data_output.into_vector()),italic).padded(text_margins);
+            list_input.push(p_input);
+            list_output.push(p_output);
+        },
+        "matrix_multiplication" | "par_matrix_multiplication"=> {
             let (a,b) = data_input.clone().into_matrices();
-            let (output,_) = data_output.clone().into_matrices();
+            let output = data_output.clone().into_matrix();
             let matrix_len = data_input.into_matrices().0.len();
-            side_headers.push("MATRIX MULTIPLICATION");
+            side_headers.push(if analyzer.target_program == "par_matrix_multiplication" {"PARALLEL MATRIX MULTIPLICATION"} else {"MATRIX MULTIPLICATION"});
 
             for i in 0..matrix_len {
                 let mut p_input = Paragraph::default();
@@ -401,7 +590,16 @@ fn get_list_input_output(analyzer: &Analyzer, side_headers: &mut Vec<&str>) -> V
 
             }
         },
-        _ => {}
+        name => if let Some(plugin) = plugin::get(name) {
+            side_headers.push("PLUGIN");
+            let p_input = Paragraph::default().styled_string("Vettore di input: ", bold_italic)
+                .styled_string(format!("{:?}",data_input.into_vector()),italic)
+                .styled_string(format!(" (target {} da {})", plugin.name(), plugin.path().display()),italic).padded(text_margins);
+            let p_output = Paragraph::default().styled_string("Output atteso: ", bold_italic)
+                .styled_string(format!("{:?}",data_output.into_vector()),italic).padded(text_margins);
+            list_input.push(p_input);
+            list_output.push(p_output);
+        }
     }
     vec![list_output,list_input]
 }
@@ -461,13 +659,19 @@ mod tests {
             n_inner_fault: 60,
             n_sub_fault: 35,
             n_mul_fault: 30,
+            n_div_fault: 0,
+            n_bit_fault: 0,
             n_add_fault: 50,
             n_indexmut_fault: 60,
             n_index_fault: 70,
             n_ord_fault: 80,
             n_partialord_fault: 90,
             n_partialeq_fault: 100,
+            n_depth_fault: 0,
             n_fatal_fault: 22,
+            n_tolerated_fault: 0,
+            n_cross_thread_detected: 0,
+            n_same_thread_detected: 0,
             total_fault: 550,
         }, 324.98, 1,  "sel_sort".to_string());
         let mut anl2 = anl.clone();
@@ -497,13 +701,19 @@ mod tests {
             n_inner_fault: 35,
             n_sub_fault: 30,
             n_mul_fault: 30,
+            n_div_fault: 0,
+            n_bit_fault: 0,
             n_add_fault: 50,
             n_indexmut_fault: 60,
             n_index_fault: 70,
             n_ord_fault: 80,
             n_partialord_fault: 90,
             n_partialeq_fault: 100,
+            n_depth_fault: 0,
             n_fatal_fault: 22,
+            n_tolerated_fault: 0,
+            n_cross_thread_detected: 0,
+            n_same_thread_detected: 0,
             total_fault: 550,
         }, 324.98, 1,  "sel_sort".to_string());
         let mut anl2 = anl.clone();
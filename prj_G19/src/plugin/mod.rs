@@ -0,0 +1,334 @@
+//Target esterni caricati a runtime da una libreria dinamica (cdylib) che implementa l'ABI di
+//fault_plugin_api. Un plugin caricato viene registrato con il nome dichiarato nel suo
+//descrittore e da quel momento si usa come un qualsiasi target interno.
+use std::collections::HashMap;
+use std::ffi::{c_void, CStr};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use fault_plugin_api::*;
+use libloading::Library;
+use thiserror::Error;
+use crate::fault_list_manager::static_analysis::{AnalysisDocument, ResultAnalysis, Variable};
+use crate::fault_list_manager::type_size::TypeSize;
+use crate::hardened::IncoherenceError;
+
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("PluginError::Load: {0}")]
+    Load(#[from] libloading::Error),
+    #[error("PluginError::AbiVersion: il plugin usa l'ABI {found}, l'host la {expected}")]
+    AbiVersion { found: u32, expected: u32 },
+    #[error("PluginError::InvalidName: il nome del target non è UTF-8 valido")]
+    InvalidName,
+}
+
+///Variabile del registro di un'istanza del plugin
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginVar {
+    pub name: String,
+    pub width: u32,     //bit di un elemento
+    pub len: usize,     //0 per gli scalari
+    pub signed: bool,
+}
+
+pub struct Plugin {
+    name: String,
+    path: PathBuf,
+    descriptor: &'static PluginDescriptor,
+    //la libreria deve restare caricata finché si usa il descrittore: i plugin registrati
+    //non vengono mai scaricati
+    _library: Library,
+}
+
+///Plugin caricati, indicizzati per nome del target
+fn registry() -> &'static Mutex<HashMap<String, Arc<Plugin>>> {
+    static PLUGINS: OnceLock<Mutex<HashMap<String, Arc<Plugin>>>> = OnceLock::new();
+    PLUGINS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+///Carica il plugin dalla libreria in 'path' e lo registra con il nome dichiarato dal plugin
+pub fn load(path: impl AsRef<Path>) -> Result<Arc<Plugin>, PluginError> {
+    let path = path.as_ref();
+    //Il caricamento esegue il codice di inizializzazione della libreria: ci si fida del plugin
+    let library = unsafe { Library::new(path)? };
+    let descriptor = unsafe {
+        let entry = library.get::<EntryFn>(ENTRY_SYMBOL)?;
+        &*entry()
+    };
+    if descriptor.abi_version != ABI_VERSION {
+        return Err(PluginError::AbiVersion { found: descriptor.abi_version, expected: ABI_VERSION });
+    }
+    let name = unsafe { CStr::from_ptr(descriptor.name) }.to_str().map_err(|_| PluginError::InvalidName)?.to_string();
+
+    let plugin = Arc::new(Plugin { name: name.clone(), path: path.to_path_buf(), descriptor, _library: library });
+    registry().lock().unwrap().insert(name, Arc::clone(&plugin));
+    Ok(plugin)
+}
+
+///Plugin già caricato con il nome 'target', se esiste
+pub fn get(target: &str) -> Option<Arc<Plugin>> {
+    registry().lock().unwrap().get(target).cloned()
+}
+
+impl Plugin {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn instance(self: &Arc<Self>, input: &[i32]) -> PluginInstance {
+        let state = unsafe { (self.descriptor.create)(input.as_ptr(), input.len()) };
+        PluginInstance { plugin: Arc::clone(self), state }
+    }
+
+    ///Output della versione non irrobustita dell'algoritmo
+    pub fn golden(&self, input: &[i32]) -> Vec<i32> {
+        let mut out = vec![0; input.len()];
+        let len = unsafe { (self.descriptor.golden)(input.as_ptr(), input.len(), out.as_mut_ptr(), out.len()) };
+        if len > out.len() {
+            out.resize(len, 0);
+            unsafe { (self.descriptor.golden)(input.as_ptr(), input.len(), out.as_mut_ptr(), out.len()) };
+        }
+        out.truncate(len);
+        out
+    }
+
+    ///Numero di istruzioni eseguite da una run senza iniezioni: è l'equivalente del
+    /// conteggio passivo dei target interni. Una run senza fault fallisce solo se il plugin è
+    /// difettoso, e in quel caso il conteggio non è valido
+    pub fn count_steps(self: &Arc<Self>, input: &[i32]) -> Result<usize, IncoherenceError> {
+        let mut count = 0;
+        self.instance(input).run(|| count += 1)?;
+        Ok(count)
+    }
+
+    ///Scrive in 'dest' l'equivalente del file di analisi statica, costruito dal registro delle
+    /// variabili. Restituisce la lunghezza di ogni vettore, da usare come DimData::Lengths
+    pub fn generate_analysis_file(self: &Arc<Self>, input: &[i32], dest: &str) -> Result<HashMap<String, usize>, String> {
+        let vars = self.instance(input).variables();
+        let analysis = ResultAnalysis {
+            num_inst: self.count_steps(input).map_err(|e| format!("run senza fault del plugin fallita: {}", e))?,
+            vars: vars.iter().map(|var| {
+                let bytes = var.width.div_ceil(8) as usize;
+                let ty = match (var.width, var.signed) {
+                    (1, _) => "bool".to_string(),
+                    (width, true) => format!("i{}", width),
+                    (width, false) => format!("u{}", width),
+                };
+                Variable {
+                    name: var.name.clone(),
+                    ty: if var.len > 0 { format!("Vec < {} >", ty) } else { ty },
+                    size: if var.len > 0 { TypeSize::Vector { elem: bytes } } else { TypeSize::Fixed(bytes) },
+                    start: 1,
+                    //il sorgente del plugin non è disponibile: nessuna informazione def-use
+                    ..Variable::default()
+                }
+            }).collect(),
+            ..ResultAnalysis::default()
+        };
+        //il plugin ha una sola funzione, registrata con il suo nome
+        let document = AnalysisDocument { functions: [(self.name().to_string(), analysis)].into(), ..AnalysisDocument::default() };
+        let json = serde_json::to_string_pretty(&document).map_err(|e| e.to_string())?;
+        fs::write(dest, json).map_err(|e| e.to_string())?;
+        Ok(vars.into_iter().filter(|var| var.len > 0).map(|var| (var.name, var.len)).collect())
+    }
+}
+
+///Stato di un'esecuzione del plugin. Il runner lo usa tramite run() e l'iniettore tramite
+/// inject(): il protocollo a passi garantisce che le due chiamate non siano mai contemporanee
+pub struct PluginInstance {
+    plugin: Arc<Plugin>,
+    state: *mut c_void,
+}
+
+unsafe impl Send for PluginInstance {}
+unsafe impl Sync for PluginInstance {}
+
+impl Drop for PluginInstance {
+    fn drop(&mut self) {
+        unsafe { (self.plugin.descriptor.destroy)(self.state) }
+    }
+}
+
+unsafe extern "C" fn step_trampoline<F: FnMut()>(ctx: *mut c_void, _label: u32) {
+    let step = &mut *(ctx as *mut F);
+    step();
+}
+
+impl PluginInstance {
+    pub fn variables(&self) -> Vec<PluginVar> {
+        let descriptor = self.plugin.descriptor;
+        (0..unsafe { (descriptor.var_count)(self.state) }).filter_map(|index| {
+            let info = unsafe { (descriptor.var_info)(self.state, index) };
+            //descrizione nulla: il plugin non conosce la variabile
+            if info.name.is_null() {
+                return None;
+            }
+            Some(PluginVar {
+                name: unsafe { CStr::from_ptr(info.name) }.to_string_lossy().into_owned(),
+                width: info.width,
+                len: info.len,
+                signed: info.signed,
+            })
+        }).collect()
+    }
+
+    ///Esegue il target chiamando 'step' dopo ogni istruzione. 'step' non deve andare in panic:
+    /// non può attraversare il confine extern "C"
+    pub fn run<F: FnMut()>(&self, mut step: F) -> Result<(), IncoherenceError> {
+        let ctx = &mut step as *mut F as *mut c_void;
+        into_result(unsafe { (self.plugin.descriptor.run)(self.state, step_trampoline::<F>, ctx) })
+    }
+
+    pub fn output(&self) -> Vec<i32> {
+        let len = unsafe { (self.plugin.descriptor.output)(self.state, std::ptr::null_mut(), 0) };
+        let mut out = vec![0; len];
+        unsafe { (self.plugin.descriptor.output)(self.state, out.as_mut_ptr(), out.len()) };
+        out
+    }
+
+    ///Inverte il bit 'bit' della variabile 'var' ("nome" oppure "nome[indice]").
+    /// Restituisce false se la variabile o l'elemento non esistono
+    pub fn inject(&self, var: &str, bit: usize) -> bool {
+        let (name, element) = match var.split_once('[') {
+            Some((name, index)) => match index.trim_end_matches(']').parse() {
+                Ok(element) => (name, element),
+                Err(_) => return false,
+            },
+            None => (var, 0),
+        };
+        let Some(index) = self.variables().iter().position(|v| v.name == name) else {
+            return false;
+        };
+        let status = unsafe { (self.plugin.descriptor.flip)(self.state, index, element, bit as u32) };
+        status == STATUS_OK
+    }
+}
+
+fn into_result(status: Status) -> Result<(), IncoherenceError> {
+    match status {
+        STATUS_OK => Ok(()),
+        STATUS_ASSIGN_FAIL => Err(IncoherenceError::AssignFail),
+        STATUS_ADD_FAIL => Err(IncoherenceError::AddFail),
+        STATUS_SUB_FAIL => Err(IncoherenceError::SubFail),
+        STATUS_MUL_FAIL => Err(IncoherenceError::MulFail),
+        STATUS_DIV_FAIL => Err(IncoherenceError::DivFail),
+        STATUS_BIT_FAIL => Err(IncoherenceError::BitFail),
+        STATUS_INDEX_MUT_FAIL => Err(IncoherenceError::IndexMutFail),
+        STATUS_INDEX_FAIL => Err(IncoherenceError::IndexFail),
+        STATUS_ORD_FAIL => Err(IncoherenceError::OrdFail),
+        STATUS_PARTIAL_ORD_FAIL => Err(IncoherenceError::PartialOrdFail),
+        STATUS_PARTIAL_EQ_FAIL => Err(IncoherenceError::PartialEqFail),
+        STATUS_DEPTH_FAIL => Err(IncoherenceError::DepthFail),
+        //il messaggio del panic resta nel plugin: si classifica come i panic dei target interni
+        //non riconducibili a un'operazione (vedi injector::runner)
+        STATUS_PANIC => Err(IncoherenceError::PartialEqFail),
+        //STATUS_INNER_FAIL e qualsiasi codice sconosciuto: incoerenza generica
+        _ => Err(IncoherenceError::InnerFail),
+    }
+}
+
+///Compila il plugin di esempio del workspace (una sola volta) e lo carica. La cartella di
+/// destinazione è separata per non contendere il lock della build che sta eseguendo i test
+#[cfg(test)]
+pub(crate) fn sample_plugin() -> Arc<Plugin> {
+    use std::process::Command;
+    static BUILT: OnceLock<PathBuf> = OnceLock::new();
+    let path = BUILT.get_or_init(|| {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let target_dir = root.join("target").join("plugin-test");
+        let status = Command::new(env!("CARGO"))
+            .args(["build", "--quiet", "-p", "bubble_sort_plugin", "--manifest-path"])
+            .arg(root.join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .status()
+            .expect("impossibile eseguire cargo");
+        assert!(status.success(), "compilazione del plugin di esempio fallita");
+        target_dir.join("debug").join(libloading::library_filename("bubble_sort_plugin"))
+    });
+    load(path).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+    use crate::fault_list_manager::static_analysis::load_analysis;
+    use crate::hardened::IncoherenceError;
+    use crate::injector::label_trace;
+    use crate::fault_env::Data;
+    use crate::plugin::{get, load, sample_plugin, PluginError, PluginVar};
+
+    #[test]
+    fn test_plugin_registry(){
+        let plugin = sample_plugin();
+        assert_eq!(plugin.name(), "plugin_bubble_sort");
+        assert!(get("plugin_bubble_sort").is_some());
+        let vars = plugin.instance(&[3, 1, 2]).variables();
+        assert_eq!(vars[0], PluginVar{ name: "vet".to_string(), width: 32, len: 3, signed: true });
+        assert!(!vars[1].signed);
+        assert_eq!(vars.iter().map(|v| v.name.as_str()).collect::<Vec<_>>(), vec!["vet", "n", "i", "j", "swapped"]);
+        //fuori dal registro il plugin restituisce la descrizione nulla invece di andare in panic
+        let instance = plugin.instance(&[3, 1, 2]);
+        let info = unsafe { (plugin.descriptor.var_info)(instance.state, vars.len()) };
+        assert!(info.name.is_null() && info.width == 0);
+    }
+
+    #[test]
+    fn test_plugin_matches_bubble_sort(){
+        let plugin = sample_plugin();
+        let vet = vec![10, 15, 27, -9, 19, 20, 16, 1, 3, -32];
+        let mut sorted = vet.clone();
+        sorted.sort();
+        //stesse istruzioni del target interno: stesso conteggio
+        assert_eq!(plugin.count_steps(&vet).unwrap(), label_trace("bubble_sort", Data::Vector(vet.clone())).len());
+        assert_eq!(plugin.golden(&vet), sorted);
+        let instance = plugin.instance(&vet);
+        assert!(instance.run(|| {}).is_ok());
+        assert_eq!(instance.output(), sorted);
+    }
+
+    #[test]
+    fn test_plugin_inject(){
+        let plugin = sample_plugin();
+        let instance = plugin.instance(&[4, 3, 2, 1]);
+        let mut steps = 0;
+        //dopo l'istruzione 2 (i = 0) si inietta su i: il confronto i < n fallisce
+        let result = instance.run(|| {
+            steps += 1;
+            if steps == 2 {
+                assert!(instance.inject("i", 5));
+            }
+        });
+        assert!(matches!(result, Err(IncoherenceError::PartialOrdFail)));
+
+        let instance = plugin.instance(&[4, 3, 2, 1]);
+        assert!(!instance.inject("vet[4]", 0));
+        assert!(!instance.inject("sconosciuta", 0));
+        assert!(!instance.inject("n", 64));
+        assert!(instance.inject("vet[3]", 0));
+    }
+
+    #[test]
+    fn test_plugin_analysis_file(){
+        let plugin = sample_plugin();
+        let dest = temp_dir().join("plugin_ris_test.json").to_string_lossy().to_string();
+        let lengths = plugin.generate_analysis_file(&[5, 4, 3], &dest).unwrap();
+        assert_eq!(lengths.get("vet"), Some(&3));
+        let analysis = load_analysis(&dest, plugin.name()).unwrap();
+        assert_eq!(analysis.num_inst, plugin.count_steps(&[5, 4, 3]).unwrap());
+        let ty = |name: &str| analysis.vars.iter().find(|v| v.name == name).unwrap().ty.clone();
+        assert_eq!(ty("vet"), "Vec < i32 >");
+        assert_eq!(ty("n"), "u64");
+        assert_eq!(ty("swapped"), "bool");
+    }
+
+    #[test]
+    fn test_plugin_load_missing(){
+        assert!(matches!(load("non/esiste/libplugin.so"), Err(PluginError::Load(_))));
+    }
+}